@@ -0,0 +1,246 @@
+use crate::hashing::sha256_hex;
+use crate::scanner::{FileNode, ScanOptions, ScanProgress, scan_directory_live};
+use crate::snapshot::{read_snapshot, write_snapshot};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Line protocol over TCP. The agent opens with `SPACEVIEW-AGENT 1 <nonce>`, the client
+// answers `AUTH <sha256(nonce:token)>` (the token itself never crosses the wire) and
+// then `SCAN <path>`. While scanning the agent streams
+//   PROGRESS <files> <bytes>
+//   TREE <complete 0|1> <length>\n<snapshot text of that many bytes>
+// and ends after the complete TREE, or with `ERR <message>` at any point.
+
+pub const AGENT_PORT: u16 = 7878;
+const PROTOCOL: &str = "SPACEVIEW-AGENT 1";
+/// Time allowed for the handshake and for any single read on the client side.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Agent heartbeat while scanning; also how quickly a client cancel is noticed.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+/// Minimum time between partial trees streamed to the client.
+const TREE_INTERVAL_SECS: u64 = 5;
+/// Largest TREE frame a client accepts (guards against a bogus length).
+const MAX_TREE_BYTES: u64 = 512 << 20;
+
+/// `:7878` or `7878` goes to `default_host`; `host` without a port uses AGENT_PORT.
+fn with_default_port(addr: &str, default_host: &str) -> String {
+    let addr = addr.trim();
+    if let Some(port) = addr.strip_prefix(':') {
+        format!("{}:{}", default_host, port)
+    } else if addr.parse::<u16>().is_ok() {
+        format!("{}:{}", default_host, addr)
+    } else if addr.rsplit_once(':').is_some_and(|(_, p)| p.parse::<u16>().is_ok()) {
+        addr.to_string()
+    } else {
+        format!("{}:{}", addr, AGENT_PORT)
+    }
+}
+
+fn auth_proof(nonce: &str, token: &str) -> String {
+    sha256_hex(format!("{}:{}", nonce, token).as_bytes())
+}
+
+/// Unpredictable per-connection challenge. Only needs to be unique, not secret.
+fn new_nonce() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    hasher.write_u128(now.as_nanos());
+    hasher.write_u32(std::process::id());
+    sha256_hex(&hasher.finish().to_le_bytes())
+}
+
+fn read_line(reader: &mut impl BufRead) -> Result<String, String> {
+    let mut line = String::new();
+    match reader.read_line(&mut line) {
+        Ok(0) => Err("Connection closed".to_string()),
+        Ok(_) => Ok(line.trim_end_matches(['\r', '\n']).to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// ===================== Agent =====================
+
+/// Serve scan requests until the process is stopped. Each connection gets its own thread.
+/// Without a host in `listen` only this machine can connect: trees go out unencrypted,
+/// so reaching the agent from elsewhere takes an explicit `--listen 0.0.0.0:port`.
+pub fn run_agent(listen: &str, token: &str) -> Result<(), String> {
+    let addr = with_default_port(listen, "127.0.0.1");
+    let listener = TcpListener::bind(&addr).map_err(|e| format!("Could not listen on {}: {}", addr, e))?;
+    println!("SpaceView agent listening on {}", addr);
+    let token = Arc::new(token.to_string());
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let token = token.clone();
+        std::thread::spawn(move || {
+            let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+            match serve(stream, &token) {
+                Ok(path) => println!("{}: sent {}", peer, path.to_string_lossy()),
+                Err(e) => eprintln!("{}: {}", peer, e),
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Handle one client: authenticate, scan the requested folder, stream the tree.
+fn serve(stream: TcpStream, token: &str) -> Result<PathBuf, String> {
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut writer = stream;
+    let send = |w: &mut TcpStream, line: &str| writeln!(w, "{}", line).map_err(|e| e.to_string());
+
+    let nonce = new_nonce();
+    send(&mut writer, &format!("{} {}", PROTOCOL, nonce))?;
+    let proof = read_line(&mut reader)?;
+    let expected = format!("AUTH {}", auth_proof(&nonce, token));
+    // Compare every byte so timing doesn't reveal how much of the proof matched
+    let matches = proof.len() == expected.len()
+        && proof.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+    if !matches {
+        let _ = send(&mut writer, "ERR Authentication failed");
+        return Err("authentication failed".to_string());
+    }
+    send(&mut writer, "OK")?;
+
+    let request = read_line(&mut reader)?;
+    let Some(path) = request.strip_prefix("SCAN ").map(PathBuf::from) else {
+        let _ = send(&mut writer, "ERR Expected SCAN <path>");
+        return Err(format!("bad request: {}", request));
+    };
+    if !path.is_dir() {
+        let _ = send(&mut writer, &format!("ERR Not a folder on the agent: {}", path.to_string_lossy()));
+        return Err(format!("not a folder: {}", path.to_string_lossy()));
+    }
+
+    let progress = Arc::new(ScanProgress::new());
+    let (tx, rx) = std::sync::mpsc::channel::<(bool, Vec<u8>)>();
+    let scan_progress = progress.clone();
+    let scan_path = path.clone();
+    std::thread::spawn(move || {
+        let mut last_tree: Option<std::time::Instant> = None;
+        let cancel_flag = scan_progress.clone();
+        let result = scan_directory_live(&scan_path, scan_progress, None, &ScanOptions::default(), |node| {
+            if last_tree.is_none_or(|t| t.elapsed().as_secs() >= TREE_INTERVAL_SECS) {
+                let mut buf = Vec::new();
+                if write_snapshot(&mut buf, node, false).is_ok() {
+                    let _ = tx.send((false, buf));
+                }
+                last_tree = Some(std::time::Instant::now());
+            }
+        });
+        // Cancelled means the client is gone; its partial tree has nowhere to go
+        if let Some(root) = result.filter(|_| !cancel_flag.cancel.load(Ordering::Relaxed)) {
+            let mut buf = Vec::new();
+            if write_snapshot(&mut buf, &root, true).is_ok() {
+                let _ = tx.send((true, buf));
+            }
+        }
+    });
+
+    // Stream until the final tree; a failed write means the client went away
+    let result = loop {
+        let sent = match rx.recv_timeout(PROGRESS_INTERVAL) {
+            Ok((complete, tree)) => {
+                let frame = writeln!(writer, "TREE {} {}", complete as u8, tree.len())
+                    .and_then(|_| writer.write_all(&tree))
+                    .map_err(|e| e.to_string());
+                if complete {
+                    break frame.map(|_| path);
+                }
+                frame
+            }
+            Err(RecvTimeoutError::Timeout) => send(&mut writer, &format!(
+                "PROGRESS {} {}",
+                progress.files_scanned.load(Ordering::Relaxed),
+                progress.bytes_scanned.load(Ordering::Relaxed),
+            )),
+            Err(RecvTimeoutError::Disconnected) => {
+                let _ = send(&mut writer, "ERR Scan failed");
+                break Err("scan failed".to_string());
+            }
+        };
+        if let Err(e) = sent {
+            break Err(e);
+        }
+    };
+    if result.is_err() {
+        progress.cancel.store(true, Ordering::Relaxed);
+    }
+    result
+}
+
+// ===================== Client =====================
+
+/// Ask the agent at `addr` to scan `path`, mirroring its progress into `progress` and
+/// handing each partial tree to `on_partial`. Ok(None) if cancelled via `progress.cancel`.
+pub fn request_scan(
+    addr: &str,
+    token: &str,
+    path: &Path,
+    progress: &ScanProgress,
+    mut on_partial: impl FnMut(FileNode),
+) -> Result<Option<FileNode>, String> {
+    let addr = with_default_port(addr, "localhost");
+    let socket = addr.to_socket_addrs()
+        .map_err(|e| format!("{}: {}", addr, e))?
+        .next()
+        .ok_or_else(|| format!("{}: no address", addr))?;
+    let stream = TcpStream::connect_timeout(&socket, CONNECT_TIMEOUT).map_err(|e| format!("{}: {}", addr, e))?;
+    // The agent sends at least a heartbeat per PROGRESS_INTERVAL once scanning
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut writer = stream;
+
+    let greeting = read_line(&mut reader)?;
+    let nonce = greeting.strip_prefix(PROTOCOL)
+        .map(str::trim)
+        .ok_or_else(|| "Not a SpaceView agent".to_string())?;
+    writeln!(writer, "AUTH {}", auth_proof(nonce, token)).map_err(|e| e.to_string())?;
+    let reply = read_line(&mut reader)?;
+    if reply != "OK" {
+        return Err(reply.strip_prefix("ERR ").unwrap_or(&reply).to_string());
+    }
+    writeln!(writer, "SCAN {}", path.to_string_lossy()).map_err(|e| e.to_string())?;
+
+    loop {
+        if progress.cancel.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let line = read_line(&mut reader)?;
+        let mut parts = line.split(' ');
+        match parts.next() {
+            Some("PROGRESS") => {
+                let mut next = || parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+                progress.files_scanned.store(next(), Ordering::Relaxed);
+                progress.bytes_scanned.store(next(), Ordering::Relaxed);
+            }
+            Some("TREE") => {
+                let complete = parts.next() == Some("1");
+                let len: u64 = parts.next().and_then(|v| v.parse().ok()).ok_or("Bad TREE frame")?;
+                if len > MAX_TREE_BYTES {
+                    return Err("Tree too large".to_string());
+                }
+                // Grows as bytes arrive, so a bogus length can't allocate up front
+                let mut buf = Vec::new();
+                (&mut reader).take(len).read_to_end(&mut buf).map_err(|e| e.to_string())?;
+                if buf.len() as u64 != len {
+                    return Err("Connection closed".to_string());
+                }
+                let (_, root) = read_snapshot(&buf[..])?;
+                if complete {
+                    return Ok(Some(root));
+                }
+                on_partial(root);
+            }
+            Some("ERR") => return Err(line["ERR".len()..].trim().to_string()),
+            _ => return Err(format!("Unexpected reply: {}", line)),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::scanner::FileNode;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// User names shorter than this are not replaced inside other names.
+const MIN_REPLACED_USER_LEN: usize = 3;
+
+/// What to hide in a tree before it is shared outside the organization.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Anonymize {
+    /// Profile folders under `Users` / `home` become `user-1`, `user-2`... and
+    /// those names are also replaced wherever else they appear.
+    pub user_names: bool,
+    /// Every file and folder name becomes `folder-N` / `file-N.ext`.
+    pub file_names: bool,
+}
+
+impl Anonymize {
+    pub fn any(self) -> bool {
+        self.user_names || self.file_names
+    }
+}
+
+/// Pseudonyms handed out so far. The same name always gets the same pseudonym,
+/// so repeated folders (`node_modules` in every project) still look repeated.
+#[derive(Default)]
+struct Pseudonyms {
+    options: Anonymize,
+    /// Lowercased real user name -> pseudonym
+    users: HashMap<String, String>,
+    /// `users` longest first, for replacing inside other names
+    replacements: Vec<(String, String)>,
+    names: HashMap<(bool, String), String>,
+    folders: usize,
+    files: usize,
+}
+
+impl Pseudonyms {
+    fn user(&mut self, name: &str) -> String {
+        let next = self.users.len() + 1;
+        self.users.entry(name.to_ascii_lowercase()).or_insert_with(|| format!("user-{}", next)).clone()
+    }
+
+    /// The shared name for `name`. `in_profiles` marks the children of a
+    /// `Users` / `home` folder, whose names are user names.
+    fn rename(&mut self, name: &str, is_dir: bool, in_profiles: bool) -> String {
+        if self.options.user_names && in_profiles && is_dir {
+            return self.user(name);
+        }
+        // Aggregates and tiles (`<Small Files>`) carry no private data
+        if name.starts_with('<') {
+            return name.to_string();
+        }
+        if self.options.file_names {
+            let key = (is_dir, name.to_string());
+            if let Some(pseudonym) = self.names.get(&key) {
+                return pseudonym.clone();
+            }
+            let pseudonym = if is_dir {
+                self.folders += 1;
+                format!("folder-{}", self.folders)
+            } else {
+                self.files += 1;
+                match Path::new(name).extension() {
+                    Some(ext) if !name.starts_with('.') => format!("file-{}.{}", self.files, ext.to_string_lossy()),
+                    _ => format!("file-{}", self.files),
+                }
+            };
+            self.names.insert(key, pseudonym.clone());
+            return pseudonym;
+        }
+        self.replace_users(name)
+    }
+
+    /// Freeze the user names found so far as the ones `replace_users` looks for.
+    /// Very short names (`a`, `jo`) would mangle unrelated names, so only whole
+    /// profile folders are renamed for those.
+    fn finish_users(&mut self) {
+        self.replacements = self.users.iter()
+            .filter(|(user, _)| user.len() >= MIN_REPLACED_USER_LEN)
+            .map(|(user, pseudonym)| (user.clone(), pseudonym.clone()))
+            .collect();
+        self.replacements.sort_by_key(|(user, _)| std::cmp::Reverse(user.len()));
+    }
+
+    /// `name` with every known user name in it replaced (ignoring ASCII case).
+    fn replace_users(&self, name: &str) -> String {
+        let mut out = name.to_string();
+        for (user, pseudonym) in &self.replacements {
+            let mut start = 0;
+            while let Some(found) = out.to_ascii_lowercase()[start..].find(user.as_str()) {
+                let at = start + found;
+                out.replace_range(at..at + user.len(), pseudonym);
+                start = at + pseudonym.len();
+            }
+        }
+        out
+    }
+}
+
+fn is_profiles(name: &str) -> bool {
+    name.eq_ignore_ascii_case("Users") || name == "home"
+}
+
+/// A copy of the tree with names replaced as `options` asks, paths rebuilt to
+/// match. Sizes, counts, dates, extensions and the shape of the tree are kept.
+pub fn anonymize(root: &FileNode, options: Anonymize) -> FileNode {
+    let mut names = Pseudonyms { options, ..Default::default() };
+    if options.user_names {
+        collect_users(root, &mut names);
+        // A scan of a single profile: the user name is in the root path
+        let mut parts = root.path.components().map(|c| c.as_os_str().to_string_lossy().to_string());
+        while let Some(part) = parts.next() {
+            if is_profiles(&part) {
+                if let Some(user) = parts.next() {
+                    names.user(&user);
+                }
+            }
+        }
+        names.finish_users();
+    }
+
+    // Root path: drive and separators stay, each named part is renamed
+    let mut path = PathBuf::new();
+    let mut in_profiles = false;
+    for component in root.path.components() {
+        match component {
+            Component::Normal(part) => {
+                let part = part.to_string_lossy();
+                path.push(names.rename(&part, true, in_profiles));
+                in_profiles = is_profiles(&part);
+            }
+            other => path.push(other.as_os_str()),
+        }
+    }
+    let name = path.file_name().map_or_else(|| path.to_string_lossy().to_string(), |n| n.to_string_lossy().to_string());
+    anonymize_node(root, name, path, &mut names)
+}
+
+/// Register the profile folder names first, so `replace_users` knows them all
+/// before any other name is renamed.
+fn collect_users(node: &FileNode, names: &mut Pseudonyms) {
+    if is_profiles(&node.name) {
+        for profile in node.children.iter().filter(|c| c.is_dir) {
+            names.user(&profile.name);
+        }
+    }
+    for child in node.children.iter().filter(|c| c.is_dir) {
+        collect_users(child, names);
+    }
+}
+
+fn anonymize_node(node: &FileNode, name: String, path: PathBuf, names: &mut Pseudonyms) -> FileNode {
+    let in_profiles = is_profiles(&node.name);
+    let children = node.children.iter()
+        .map(|child| {
+            let child_name = names.rename(&child.name, child.is_dir, in_profiles);
+            // Aggregates have no path of their own
+            let child_path = if child.path.as_os_str().is_empty() { PathBuf::new() } else { path.join(&child_name) };
+            anonymize_node(child, child_name, child_path, names)
+        })
+        .collect();
+    FileNode {
+        name,
+        path,
+        size: node.size,
+        is_dir: node.is_dir,
+        file_count: node.file_count,
+        modified: node.modified,
+        attrs: node.attrs,
+        allocated: node.allocated,
+        children,
+    }
+}
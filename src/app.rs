@@ -1,18 +1,166 @@
+use crate::agent::request_scan;
+use crate::anonymize::{Anonymize, anonymize};
+use crate::archive::{zip_destination, zip_folder};
 use crate::camera::Camera;
-use crate::scanner::{FileNode, ScanProgress, get_free_space, scan_directory_live};
+use crate::clipboard::copy_files;
+use crate::baseline::{create_baseline, load_baseline, save_baseline, verify_baseline};
+use crate::compare::{
+    DiffKind, DiffReport, DIFF_KINDS, GrowthMap, HeatMap, compare_snapshots, compare_trees, compare_with_snapshot, heat_map,
+};
+use crate::demo::{DEMO_ROOT, demo_tree};
+use crate::handles::{InUse, MAX_FILES_CHECKED, processes_using};
+use crate::hashing::{CHECKSUMS, Checksum, checksum_file, hash_file_full, hash_file_partial};
+use crate::history::{
+    DEFAULT_SNAPSHOT_KEEP, clear_history, disk_usage, history_dir, prune_all, root_history, save_to_history, snapshot_before,
+};
+use crate::migrate::{MigrationSource, migration_script, script_extension};
+use crate::memory::{node_count, process_rss};
+use crate::ledger::{DeleteMethod, Deletion, append_ledger, clear_ledger, load_ledger};
+use crate::manifest::{Manifest, PlannedRemoval, unchanged};
+use crate::node_actions::{ActionEnv, BUSY_HINT, NodeAction, NodeFacts, NodeKind, action_buttons};
+use crate::paths::{open_in_explorer, recycle_files, reveal_in_explorer};
+use crate::quota::{QUOTA_WARN, Quota, match_quotas, parse_quota_csv};
+use crate::power::{fullscreen_app, on_battery};
+use crate::remote_fs::{is_scan_url, scan_url, split_credentials};
+use crate::display::{fit_to_monitors, monitor_work_areas};
+use crate::export::{SvgPart, print_html, tree_to_dot, tree_to_mermaid, treemap_to_svg};
+use crate::keymap::{ACTIONS, Action, Keymap};
+use crate::reports::{
+    AppCache, ArchiveCandidate, GitRepoInfo, MatchingFolders, NameIssues, SHORT_NAME_BYTES, SLACK_REPORT_ROWS, SlackFolder,
+    SystemItem, UserProfile, clear_dir_contents, find_archive_candidates, find_cluster_slack, find_git_repos,
+    find_matching_folders, find_name_issues, find_user_profiles, measure_app_caches, measure_system,
+};
+use crate::scanner::{
+    ALL_DRIVES, ATTR_CASE_SENSITIVE, ATTR_COMPRESSED, ATTR_GIT, ATTR_LINK, ATTR_SYNCED, ATTR_SYSTEM, DriveSpace,
+    FileNode, ScanOptions, ScanProgress, NOT_SCANNED, SMALL_FILES, UNKNOWN_SPACE, attr_labels, cluster_size,
+    drive_space, get_free_space, is_all_drives, is_network_path, is_offline, is_partial, is_space_tile,
+    local_drive_roots, merge_drive, parse_exclusions, parse_include_names, scan_all_drives, scan_directory, scan_directory_live,
+};
+use crate::selection::Selection;
+use crate::view_state::{ViewState, load_view_states, remember_view_state, same_root};
+use crate::watcher::{WatchEvent, Watcher, watch, watch_supported};
+use crate::shred::{SHRED_PASSES_HDD, shred_file};
+use crate::snapshot::{SnapshotInfo, load_snapshot, read_snapshot_info, save_scan_snapshot, save_snapshot};
+use crate::throttle::RateLimiter;
 use crate::treemap;
 use crate::world_layout::{LayoutNode, WorldLayout};
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 const ZOOM_FRAME_WIDTH: f32 = 4.0;
+// Default cell metrics (user-adjustable via Appearance)
 const MIN_SCREEN_PX: f32 = 2.0;
 const HEADER_PX: f32 = 16.0;
 const PAD_PX: f32 = 3.0;
 const BORDER_PX: f32 = 1.5;
+const HIGH_CONTRAST_BORDER_PX: f32 = 2.5;
+const SELECTION_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 40);
+/// Height of the quota use strip under a directory header
+const QUOTA_STRIP_PX: f32 = 3.0;
+/// Corner badges for special folders (and links), drawn right to left in this order
+const ATTR_BADGES: [(u16, &str, egui::Color32); 6] = [
+    (ATTR_GIT, "git", egui::Color32::from_rgb(220, 90, 50)),
+    (ATTR_CASE_SENSITIVE, "Aa", egui::Color32::from_rgb(190, 140, 30)),
+    (ATTR_SYNCED, "sync", egui::Color32::from_rgb(40, 120, 215)),
+    (ATTR_LINK, "link", LINK_COLOR),
+    (ATTR_COMPRESSED, "zip", egui::Color32::from_rgb(35, 145, 120)),
+    (ATTR_SYSTEM, "sys", egui::Color32::from_rgb(105, 105, 105)),
+];
+/// Links (and folders reached through one) get a badge and a dashed outline
+const LINK_COLOR: egui::Color32 = egui::Color32::from_rgb(125, 90, 200);
+/// Heat overlay: growth is measured from a history snapshot at least this much older
+/// than the scan, so a rescan minutes later doesn't make everything look idle.
+const HEAT_MIN_AGE_SECS: u64 = 3600;
+const HEAT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 90, 20);
+/// Largest items listed when confirming a folder delete
+const DELETE_PREVIEW_ITEMS: usize = 5;
+/// Default and longest "untouched for" period of the Archive Candidates report
+const DEFAULT_ARCHIVE_MONTHS: u32 = 12;
+const MAX_ARCHIVE_MONTHS: u32 = 120;
+/// Cluster sizes offered by the Cluster Slack report, for "what if" comparisons
+const CLUSTER_SIZES: [u64; 11] = [512, 4 << 10, 8 << 10, 16 << 10, 32 << 10, 64 << 10, 128 << 10, 256 << 10, 512 << 10, 1 << 20, 2 << 20];
+/// Cluster size assumed when the volume's can't be read (remote trees)
+const DEFAULT_CLUSTER_SIZE: u64 = 4096;
+/// An average calendar month
+const ARCHIVE_MONTH_SECS: u64 = 2_629_746;
+/// Width of the children bar in a breadcrumb's hover card, and the children it names
+const CRUMB_BAR_WIDTH: f32 = 220.0;
+const CRUMB_BAR_CHILDREN: usize = 8;
+/// Longest hover delay offered in Settings
+const MAX_HOVER_DELAY_MS: u64 = 3000;
+/// Smallest on-screen side (px) at which a selected block counts as visible
+const REVEAL_MIN_PX: f32 = 6.0;
+/// Fill of the `<Unknown>` drive tile: neutral, so it doesn't read as a folder.
+const UNKNOWN_SPACE_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 110, 110);
+/// Depth levels shown in the Depth color legend
+const LEGEND_DEPTHS: usize = 10;
+/// Bands in the Age legend; each swatch filters the map to one
+const AGE_BANDS: usize = 3;
+/// Seconds a legend click highlights every block at that depth
+const FLASH_SECS: f64 = 1.2;
+const BOOKMARK_KEYS: [egui::Key; 9] = [
+    egui::Key::Num1, egui::Key::Num2, egui::Key::Num3, egui::Key::Num4, egui::Key::Num5,
+    egui::Key::Num6, egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+];
+/// Views reachable with Alt+1..5 (plain 1..5 outside the Map) and Ctrl+Tab, in tab order
+const TAB_VIEWS: [ViewMode; 5] = [
+    ViewMode::Treemap, ViewMode::List, ViewMode::LargestFiles, ViewMode::Extensions, ViewMode::Duplicates,
+];
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Width in px of exported SVG treemaps (height follows the on-screen aspect ratio).
+const SVG_EXPORT_WIDTH: f32 = 1600.0;
+/// Rows in the "largest folders" table of the print page.
+const PRINT_TOP_FOLDERS: usize = 40;
+/// Types listed in a folder's Type Breakdown before the rest are summed up
+const TYPE_BREAKDOWN_ROWS: usize = 25;
+/// Files kept for the Top Files view.
+const LARGEST_FILES: usize = 1000;
+/// Extension filter chips above the Top Files table (largest types by total size).
+const LARGEST_EXT_CHIPS: usize = 8;
+/// Smallest capacity gap shown as an `<Unknown>` tile; below this it's rounding noise.
+const UNKNOWN_MIN_BYTES: u64 = 64 << 20;
+/// UI zoom while presenting, so labels read from across a room.
+const PRESENT_ZOOM: f32 = 1.5;
+/// Touch mode: smallest height of a button or other control, about a fingertip
+const TOUCH_TARGET_PX: f32 = 40.0;
+/// Touch mode: text size relative to egui's defaults
+const TOUCH_TEXT_SCALE: f32 = 1.25;
+/// Touch mode: smallest treemap folder header, so headers can be tapped
+const TOUCH_HEADER_PX: f32 = 28.0;
+/// Wheel notches one press of the on-screen zoom buttons is worth
+const TOUCH_ZOOM_STEPS: f32 = 3.0;
+/// How long the "Esc to exit" hint shows after entering presentation mode.
+const PRESENT_HINT_SECS: u64 = 4;
+/// How often SpaceView measures its own memory while scanning or showing it in About.
+const MEMORY_POLL_SECS: u64 = 2;
+/// The memory warning shows once use passes this share of the limit.
+const MEMORY_WARN_FRACTION: f64 = 0.9;
+const DEFAULT_MEMORY_LIMIT_MB: u64 = 4096;
+/// Tree nodes a scan keeps before it switches to aggregated mode (folder totals only).
+/// About 1.5 GB of tree; past this, layout and every report walk slow to seconds.
+const DEFAULT_NODE_LIMIT: u64 = 10_000_000;
+/// Scan Options the memory warning switches to: shallower tree, small files grouped.
+const LEAN_MAX_DEPTH: usize = 8;
+const LEAN_MIN_FILE_SIZE: u64 = 1 << 20;
+/// Files per Recycle Bin call in a bulk removal (progress and cancel granularity).
+const RECYCLE_BATCH: usize = 200;
+const DRY_RUN_HINT: &str = "List exactly what would be removed, without removing anything, \
+                            to run afterwards or export as CSV for approval";
+/// Minimum time between resume checkpoints written during a scan.
+const CHECKPOINT_INTERVAL_SECS: u64 = 30;
+/// Network profile: live snapshots are throttled to one per this many seconds and
+/// checkpoints are spaced out further, since every clone competes with slow I/O.
+const NETWORK_SNAPSHOT_SECS: u64 = 5;
+const NETWORK_CHECKPOINT_INTERVAL_SECS: u64 = 120;
+/// Quiet time after the last watched change before the touched folders are listed
+/// again, so a build or an unzip is picked up as one update.
+const WATCH_SETTLE: std::time::Duration = std::time::Duration::from_secs(2);
+/// How often auto-pause checks the power source and fullscreen state while scanning.
+const POWER_POLL_SECS: u64 = 2;
+/// How often the drive watcher thread checks for mounted / removed volumes.
+const DRIVE_POLL_SECS: u64 = 2;
 
 // ===================== Color Theme =====================
 
@@ -21,8 +169,22 @@ pub enum ColorTheme {
     Rainbow,
     Neon,
     Ocean,
+    /// Okabe-Ito palette: distinguishable with deuteranopia/protanopia.
+    ColorSafe,
 }
 
+/// Okabe-Ito colors, minus black: orange, sky blue, bluish green, yellow, blue,
+/// vermillion, reddish purple.
+const OKABE_ITO: [(u8, u8, u8); 7] = [
+    (230, 159, 0),
+    (86, 180, 233),
+    (0, 158, 115),
+    (240, 228, 66),
+    (0, 114, 178),
+    (213, 94, 0),
+    (204, 121, 167),
+];
+
 impl ColorTheme {
     fn base_rgb(self, depth: usize) -> (u8, u8, u8) {
         let golden = (depth as f32 * 137.508) % 360.0;
@@ -30,6 +192,13 @@ impl ColorTheme {
             ColorTheme::Rainbow => hsl_to_rgb(golden, 0.75, 0.65),
             ColorTheme::Neon => hsl_to_rgb(golden, 0.95, 0.65),
             ColorTheme::Ocean => hsl_to_rgb((golden + 180.0) % 360.0, 0.60, 0.60),
+            ColorTheme::ColorSafe => {
+                // Later cycles blend toward white so repeats stay distinguishable
+                let (r, g, b) = OKABE_ITO[depth % OKABE_ITO.len()];
+                let lift = ((depth / OKABE_ITO.len()) % 3) as f32 * 0.25;
+                let mix = |c: u8| (c as f32 + (255.0 - c as f32) * lift) as u8;
+                (mix(r), mix(g), mix(b))
+            }
         }
     }
 
@@ -38,11 +207,168 @@ impl ColorTheme {
             ColorTheme::Rainbow => "Rainbow",
             ColorTheme::Neon => "Neon",
             ColorTheme::Ocean => "Ocean",
+            ColorTheme::ColorSafe => "Color-safe",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        THEMES.into_iter().find(|t| t.label() == label)
+    }
+}
+
+const THEMES: [ColorTheme; 4] = [ColorTheme::Rainbow, ColorTheme::Neon, ColorTheme::Ocean, ColorTheme::ColorSafe];
+
+/// Gradient for the Age color mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AgeRamp {
+    /// Red (old) -> yellow -> green (new)
+    RedGreen,
+    /// Orange (old) -> light -> blue (new), readable with red-green color blindness
+    BlueOrange,
+}
+
+impl AgeRamp {
+    fn label(self) -> &'static str {
+        match self {
+            AgeRamp::RedGreen => "Red-Green",
+            AgeRamp::BlueOrange => "Blue-Orange",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        AGE_RAMPS.into_iter().find(|r| r.label() == label)
+    }
+
+    /// Legend swatches for old / mid / new.
+    fn legend(self) -> [egui::Color32; 3] {
+        [self.rgb(0.0), self.rgb(0.5), self.rgb(1.0)]
+    }
+
+    /// Color for t in 0.0 (oldest) ..= 1.0 (newest).
+    fn rgb(self, t: f32) -> egui::Color32 {
+        let (r, g, b) = match self {
+            AgeRamp::RedGreen => {
+                if t < 0.5 {
+                    // Red to Yellow
+                    let s = t * 2.0;
+                    (220.0, 60.0 + 160.0 * s, 50.0)
+                } else {
+                    // Yellow to Green
+                    let s = (t - 0.5) * 2.0;
+                    (220.0 - 160.0 * s, 220.0, 50.0 + 30.0 * s)
+                }
+            }
+            AgeRamp::BlueOrange => {
+                if t < 0.5 {
+                    // Orange to pale
+                    let s = t * 2.0;
+                    (230.0 - 20.0 * s, 120.0 + 90.0 * s, 20.0 + 170.0 * s)
+                } else {
+                    // Pale to Blue
+                    let s = (t - 0.5) * 2.0;
+                    (210.0 - 180.0 * s, 210.0 - 80.0 * s, 190.0 + 40.0 * s)
+                }
+            }
+        };
+        egui::Color32::from_rgb(r as u8, g as u8, b as u8)
+    }
+}
+
+const AGE_RAMPS: [AgeRamp; 2] = [AgeRamp::RedGreen, AgeRamp::BlueOrange];
+
+/// What hovering a treemap block shows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TooltipStyle {
+    /// Name, size, files, attributes and path
+    Detailed,
+    /// Name and size only, so it covers less of the neighbouring blocks
+    Compact,
+    /// No tooltip; the status bar line is the only readout
+    StatusBar,
+}
+
+impl TooltipStyle {
+    fn label(self) -> &'static str {
+        match self {
+            TooltipStyle::Detailed => "Detailed",
+            TooltipStyle::Compact => "Compact",
+            TooltipStyle::StatusBar => "Status bar only",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        TOOLTIP_STYLES.into_iter().find(|t| t.label() == label)
+    }
+}
+
+const TOOLTIP_STYLES: [TooltipStyle; 3] = [TooltipStyle::Detailed, TooltipStyle::Compact, TooltipStyle::StatusBar];
+
+/// When the large-control touch layout is used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TouchMode {
+    /// From the first touch on the screen until the app closes
+    Auto,
+    On,
+    Off,
+}
+
+impl TouchMode {
+    fn label(self) -> &'static str {
+        match self {
+            TouchMode::Auto => "Auto",
+            TouchMode::On => "On",
+            TouchMode::Off => "Off",
         }
     }
+
+    fn from_label(label: &str) -> Option<Self> {
+        TOUCH_MODES.into_iter().find(|t| t.label() == label)
+    }
+}
+
+const TOUCH_MODES: [TouchMode; 3] = [TouchMode::Auto, TouchMode::On, TouchMode::Off];
+
+/// On-screen treemap cell geometry, shared by rendering and hit testing so both
+/// agree on where every block is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellMetrics {
+    /// Blocks narrower or shorter than this are skipped
+    pub min_px: f32,
+    pub header_px: f32,
+    pub pad_px: f32,
+    pub border_px: f32,
+}
+
+impl Default for CellMetrics {
+    fn default() -> Self {
+        Self { min_px: MIN_SCREEN_PX, header_px: HEADER_PX, pad_px: PAD_PX, border_px: BORDER_PX }
+    }
 }
 
-const THEMES: [ColorTheme; 3] = [ColorTheme::Rainbow, ColorTheme::Neon, ColorTheme::Ocean];
+impl CellMetrics {
+    /// Clamp to the ranges the Appearance window allows.
+    fn clamped(self) -> Self {
+        Self {
+            min_px: self.min_px.clamp(1.0, 8.0),
+            header_px: self.header_px.clamp(10.0, 40.0),
+            pad_px: self.pad_px.clamp(0.0, 12.0),
+            border_px: self.border_px.clamp(0.0, 6.0),
+        }
+    }
+
+    /// Area inside a directory block available to its children.
+    pub fn content_rect(&self, inner: egui::Rect) -> egui::Rect {
+        let hh = self.header_px.min(inner.height());
+        egui::Rect::from_min_max(
+            egui::pos2(inner.min.x + self.pad_px, inner.min.y + hh),
+            egui::pos2(inner.max.x - self.pad_px, inner.max.y - self.pad_px),
+        )
+    }
+
+    pub fn too_small(&self, rect: egui::Rect) -> bool {
+        rect.width() < self.min_px || rect.height() < self.min_px
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum ViewMode {
@@ -51,22 +377,191 @@ enum ViewMode {
     LargestFiles,
     Extensions,
     Duplicates,
+    Reports,
+    Diff,
+    Queue,
+}
+
+impl ViewMode {
+    /// Name kept in the per-root view state. Diff and Queue aren't about the
+    /// scanned root, so they are remembered as the Map.
+    fn key(self) -> &'static str {
+        match self {
+            ViewMode::List => "list",
+            ViewMode::LargestFiles => "top_files",
+            ViewMode::Extensions => "types",
+            ViewMode::Duplicates => "dupes",
+            ViewMode::Reports => "reports",
+            ViewMode::Treemap | ViewMode::Diff | ViewMode::Queue => "map",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        [ViewMode::Treemap, ViewMode::List, ViewMode::LargestFiles, ViewMode::Extensions, ViewMode::Duplicates, ViewMode::Reports]
+            .into_iter()
+            .find(|v| v.key() == key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ReportKind {
+    GitRepos,
+    MatchingFolders,
+    ArchiveCandidates,
+    UserProfiles,
+    Names,
+    ClusterSlack,
+    Quotas,
+    AppCaches,
+    System,
+}
+
+impl ReportKind {
+    fn label(self) -> &'static str {
+        match self {
+            ReportKind::GitRepos => "Git Repositories",
+            ReportKind::MatchingFolders => "Matching Folders",
+            ReportKind::ArchiveCandidates => "Archive Candidates",
+            ReportKind::UserProfiles => "User Profiles",
+            ReportKind::Names => "Case & 8.3 Names",
+            ReportKind::ClusterSlack => "Cluster Slack",
+            ReportKind::Quotas => "Quotas",
+            ReportKind::AppCaches => "App Caches",
+            ReportKind::System => "System",
+        }
+    }
 }
 
+const REPORTS: [ReportKind; 9] = [
+    ReportKind::GitRepos,
+    ReportKind::MatchingFolders,
+    ReportKind::ArchiveCandidates,
+    ReportKind::UserProfiles,
+    ReportKind::Names,
+    ReportKind::ClusterSlack,
+    ReportKind::Quotas,
+    ReportKind::AppCaches,
+    ReportKind::System,
+];
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum ColorMode {
     Depth,
     Age,
     Extension,
+    /// Change since a snapshot; only offered while a scan comparison is loaded
+    Growth,
+}
+
+impl ColorMode {
+    fn key(self) -> &'static str {
+        match self {
+            ColorMode::Depth => "depth",
+            ColorMode::Age => "age",
+            ColorMode::Extension => "type",
+            ColorMode::Growth => "growth",
+        }
+    }
+
+    /// Growth isn't restored: it needs the comparison it was showing
+    fn from_key(key: &str) -> Option<Self> {
+        [ColorMode::Depth, ColorMode::Age, ColorMode::Extension].into_iter().find(|c| c.key() == key)
+    }
+}
+
+/// Diff view column the rows are sorted by; None in `diff_sort` keeps the report's order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DiffSort {
+    Path,
+    Left,
+    Right,
+    Change,
+}
+
+/// Sort Diff view rows by `sort`, given each row's (path, left size, right size).
+fn sort_diff_rows<T>(rows: &mut [T], sort: DiffSort, asc: bool, key: impl Fn(&T) -> (&str, u64, u64)) {
+    rows.sort_by(|a, b| {
+        let (path_a, left_a, right_a) = key(a);
+        let (path_b, left_b, right_b) = key(b);
+        let order = match sort {
+            DiffSort::Path => path_a.cmp(path_b),
+            DiffSort::Left => left_a.cmp(&left_b),
+            DiffSort::Right => right_a.cmp(&right_b),
+            DiffSort::Change => (right_a as i64 - left_a as i64).cmp(&(right_b as i64 - left_b as i64)),
+        };
+        if asc { order } else { order.reverse() }
+    });
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum SortColumn {
     Name,
     Size,
+    OnDisk,
     FileCount,
 }
 
+/// List view row: (name, size, file count, is dir, has children, path, folder relative
+/// to the listed folder in flat mode, size on disk).
+type ListEntry = (String, u64, u64, bool, bool, PathBuf, String, u64);
+
+/// Flat List view rows, rebuilt only when this key changes:
+/// (list path, search text, sort, ascending, root size, root file count).
+type FlatListKey = (Vec<String>, String, SortColumn, bool, u64, u64);
+
+fn sort_list_entries(entries: &mut [ListEntry], sort: SortColumn, asc: bool) {
+    match sort {
+        SortColumn::Name => {
+            entries.sort_by(|a, b| {
+                let dir_order = b.3.cmp(&a.3); // dirs first
+                if dir_order != std::cmp::Ordering::Equal { return dir_order; }
+                let cmp = a.0.to_lowercase().cmp(&b.0.to_lowercase());
+                if asc { cmp } else { cmp.reverse() }
+            });
+        }
+        SortColumn::Size => {
+            entries.sort_by(|a, b| {
+                let cmp = b.1.cmp(&a.1);
+                if asc { cmp.reverse() } else { cmp }
+            });
+        }
+        SortColumn::OnDisk => {
+            entries.sort_by(|a, b| {
+                let cmp = b.7.cmp(&a.7);
+                if asc { cmp.reverse() } else { cmp }
+            });
+        }
+        SortColumn::FileCount => {
+            entries.sort_by(|a, b| {
+                let cmp = b.2.cmp(&a.2);
+                if asc { cmp.reverse() } else { cmp }
+            });
+        }
+    }
+}
+
+/// Every file below `dir` (any depth) as list rows, with the folder path relative to `dir`.
+fn flat_list_entries(dir: &FileNode, search: &str) -> Vec<ListEntry> {
+    let q = search.to_lowercase();
+    let mut out = Vec::new();
+    let mut stack: Vec<(&FileNode, String)> = vec![(dir, String::new())];
+    while let Some((node, rel)) = stack.pop() {
+        for child in &node.children {
+            if child.is_dir {
+                let child_rel = if rel.is_empty() {
+                    child.name.clone()
+                } else {
+                    format!("{}{}{}", rel, std::path::MAIN_SEPARATOR, child.name)
+                };
+                stack.push((child, child_rel));
+            } else if child.name != NOT_SCANNED && (q.is_empty() || child.name.to_lowercase().contains(&q)) {
+                out.push((child.name.clone(), child.size, 0, false, false, child.path.clone(), rel.clone(), child.allocated));
+            }
+        }
+    }
+    out
+}
+
 fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
     let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
     let h2 = h / 60.0;
@@ -101,12 +596,152 @@ pub struct Prefs {
     pub window_y: Option<f32>,
     pub window_w: Option<f32>,
     pub window_h: Option<f32>,
+    pub theme: ColorTheme,
+    pub age_ramp: AgeRamp,
+    pub high_contrast: bool,
+    /// Treemap label density (0.5 sparse ..= 2.0 dense)
+    pub label_density: f32,
+    pub cell_metrics: CellMetrics,
+    /// Reopen `last_scan` on startup (from the session snapshot when it matches).
+    pub restore_last_scan: bool,
+    pub last_scan: Option<PathBuf>,
+    /// On window focus, flag scans older than this many minutes (0 = never).
+    pub stale_after_mins: u64,
+    /// Rescan stale data automatically instead of showing the banner.
+    pub auto_rescan_stale: bool,
+    pub scan_options: ScanOptions,
+    pub scan_ops_limit: u64,
+    pub hash_mb_limit: u64,
+    /// Last agent address and folder used in Connect to Agent (the token is never saved)
+    pub remote_addr: String,
+    pub remote_path: String,
+    /// Last FTP/WebDAV URL scanned (credentials are never saved)
+    pub remote_url: String,
+    /// Folder levels included in diagram exports
+    pub export_depth: usize,
+    /// Pause local scans while on battery or while a fullscreen app runs
+    pub auto_pause: bool,
+    /// Keep the tree up to date with changes on disk after a scan
+    pub watch_changes: bool,
+    /// User-defined scan buttons (top bar + welcome screen), one `quick_scan=` line each
+    pub quick_scans: Vec<QuickScan>,
+    /// Offer "Shred (overwrite and delete)" in file context menus
+    pub shred_enabled: bool,
+    /// Keep a snapshot of every completed scan in the history folder
+    pub auto_snapshot: bool,
+    /// Snapshots kept per scanned root; older ones are pruned
+    pub snapshot_keep: usize,
+    /// Warn during scans when SpaceView's memory nears this many MB (0 = off)
+    pub memory_limit_mb: u64,
+    /// Tree nodes a scan keeps before aggregating the rest (0 = unlimited)
+    pub node_limit: u64,
+    /// Milliseconds the pointer rests on a treemap block before its tooltip shows
+    pub hover_delay_ms: u64,
+    pub tooltip_style: TooltipStyle,
+    pub touch_mode: TouchMode,
+    /// Drives whose whole-drive scans hide the free space tile, one `hide_free_space=` line each
+    pub hide_free_drives: Vec<PathBuf>,
+    /// Show the free space tile on folder scans too
+    pub show_free_space_folder: bool,
+    /// Heat overlay on the map: folders glow by how fast they grew
+    pub heat_overlay: bool,
+    /// Treemap blocks sized by size on disk instead of logical size
+    pub size_on_disk: bool,
+    /// Keep the deletion log across sessions
+    pub keep_deletion_log: bool,
+    /// Quota CSV imported last, reloaded at startup
+    pub quota_csv: Option<PathBuf>,
+    /// Archive Candidates report: folders untouched for at least this many months
+    pub archive_months: u32,
+    /// What exports hide (Export > Hide user names / Hide file names)
+    pub export_anonymize: Anonymize,
+    /// Case & 8.3 Names report: also count names that need an 8.3 alias
+    pub report_short_names: bool,
+}
+
+/// A labeled scan target: a folder, share or FTP/WebDAV URL.
+#[derive(Clone, Debug, Default)]
+pub struct QuickScan {
+    pub label: String,
+    pub path: String,
+}
+
+impl QuickScan {
+    /// `label|path` as stored in prefs (`|` can't appear in a Windows path).
+    fn parse(val: &str) -> Option<Self> {
+        let (label, path) = val.split_once('|').unwrap_or(("", val));
+        let path = path.trim();
+        (!path.is_empty()).then(|| QuickScan { label: label.trim().to_string(), path: path.to_string() })
+    }
+
+    /// The label, or the folder's name if none was given.
+    fn display_label(&self) -> String {
+        if !self.label.trim().is_empty() {
+            return self.label.trim().to_string();
+        }
+        let trimmed = self.path.trim_end_matches(['/', '\\']);
+        trimmed.rsplit(['/', '\\']).next().filter(|n| !n.is_empty()).unwrap_or(&self.path).to_string()
+    }
+}
+
+/// Per-user SpaceView folder (%APPDATA%\SpaceView) for prefs and scan checkpoints.
+pub fn data_dir() -> Option<PathBuf> {
+    std::env::var("APPDATA").ok().map(|appdata| PathBuf::from(appdata).join("SpaceView"))
+}
+
+/// SpaceView's data folder, left out of every scan so its own snapshots and logs
+/// don't show up as usage.
+fn own_data_dirs() -> Vec<PathBuf> {
+    data_dir().into_iter().collect()
 }
 
 pub fn prefs_path() -> Option<PathBuf> {
-    std::env::var("APPDATA").ok().map(|appdata| {
-        PathBuf::from(appdata).join("SpaceView").join("prefs.txt")
+    data_dir().map(|d| d.join("prefs.txt"))
+}
+
+/// Partial tree of the last interrupted scan. Removed when a scan completes.
+fn resume_checkpoint_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("resume.svsnap"))
+}
+
+/// Tree of the last completed scan, reopened on startup when session restore is on.
+fn session_snapshot_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("session.svsnap"))
+}
+
+/// One line of the Settings storage table.
+struct StorageItem {
+    label: &'static str,
+    files: u64,
+    bytes: u64,
+}
+
+/// What SpaceView itself keeps on disk, by kind. Everything not listed is settings.
+fn storage_usage() -> Vec<StorageItem> {
+    let Some(dir) = data_dir() else { return Vec::new() };
+    let (total_files, total_bytes) = disk_usage(&dir);
+    let mut items: Vec<StorageItem> = [
+        ("Snapshot history", history_dir()),
+        ("Last session", session_snapshot_path()),
+        ("Resume checkpoint", resume_checkpoint_path()),
+    ]
+    .into_iter()
+    .map(|(label, path)| {
+        let (files, bytes) = path.map_or((0, 0), |p| disk_usage(&p));
+        StorageItem { label, files, bytes }
     })
+    .collect();
+    let files = total_files.saturating_sub(items.iter().map(|i| i.files).sum());
+    let bytes = total_bytes.saturating_sub(items.iter().map(|i| i.bytes).sum());
+    items.push(StorageItem { label: "Settings and logs", files, bytes });
+    items
+}
+
+/// Header of a leftover checkpoint, if the last scan never finished.
+fn pending_resume() -> Option<SnapshotInfo> {
+    resume_checkpoint_path()
+        .and_then(|p| read_snapshot_info(&p).ok())
+        .filter(|info| !info.complete)
 }
 
 pub fn load_prefs() -> Prefs {
@@ -117,6 +752,42 @@ pub fn load_prefs() -> Prefs {
         window_y: None,
         window_w: None,
         window_h: None,
+        theme: ColorTheme::Rainbow,
+        age_ramp: AgeRamp::RedGreen,
+        high_contrast: false,
+        label_density: 1.0,
+        cell_metrics: CellMetrics::default(),
+        restore_last_scan: false,
+        last_scan: None,
+        stale_after_mins: 60,
+        auto_rescan_stale: false,
+        scan_options: ScanOptions { ignore_files: true, ..ScanOptions::default() },
+        scan_ops_limit: 0,
+        hash_mb_limit: 0,
+        remote_addr: String::new(),
+        remote_path: String::new(),
+        remote_url: String::new(),
+        export_depth: 3,
+        auto_pause: false,
+        watch_changes: false,
+        quick_scans: Vec::new(),
+        shred_enabled: false,
+        auto_snapshot: false,
+        snapshot_keep: DEFAULT_SNAPSHOT_KEEP,
+        memory_limit_mb: DEFAULT_MEMORY_LIMIT_MB,
+        node_limit: DEFAULT_NODE_LIMIT,
+        hover_delay_ms: 0,
+        tooltip_style: TooltipStyle::Detailed,
+        touch_mode: TouchMode::Auto,
+        hide_free_drives: Vec::new(),
+        show_free_space_folder: false,
+        heat_overlay: false,
+        size_on_disk: false,
+        keep_deletion_log: false,
+        quota_csv: None,
+        archive_months: DEFAULT_ARCHIVE_MONTHS,
+        export_anonymize: Anonymize::default(),
+        report_short_names: false,
     };
     if let Some(content) = prefs_path().and_then(|p| std::fs::read_to_string(p).ok()) {
         for line in content.lines() {
@@ -129,11 +800,72 @@ pub fn load_prefs() -> Prefs {
                     "window_y" => prefs.window_y = val.trim().parse().ok(),
                     "window_w" => prefs.window_w = val.trim().parse().ok(),
                     "window_h" => prefs.window_h = val.trim().parse().ok(),
+                    "theme" => prefs.theme = ColorTheme::from_label(val.trim()).unwrap_or(prefs.theme),
+                    "age_ramp" => prefs.age_ramp = AgeRamp::from_label(val.trim()).unwrap_or(prefs.age_ramp),
+                    "high_contrast" => prefs.high_contrast = val.trim() == "true",
+                    "cell_min_px" => prefs.cell_metrics.min_px = val.trim().parse().unwrap_or(MIN_SCREEN_PX),
+                    "header_px" => prefs.cell_metrics.header_px = val.trim().parse().unwrap_or(HEADER_PX),
+                    "pad_px" => prefs.cell_metrics.pad_px = val.trim().parse().unwrap_or(PAD_PX),
+                    "border_px" => prefs.cell_metrics.border_px = val.trim().parse().unwrap_or(BORDER_PX),
+                    "label_density" => {
+                        prefs.label_density = val.trim().parse::<f32>().map_or(1.0, |d| d.clamp(0.5, 2.0))
+                    }
+                    "restore_last_scan" => prefs.restore_last_scan = val.trim() == "true",
+                    "stale_after_mins" => prefs.stale_after_mins = val.trim().parse().unwrap_or(prefs.stale_after_mins),
+                    "auto_rescan_stale" => prefs.auto_rescan_stale = val.trim() == "true",
+                    "scan_include_offline" => prefs.scan_options.include_offline = val.trim() == "true",
+                    "scan_links_once" => prefs.scan_options.count_links_once = val.trim() == "true",
+                    "scan_follow_links" => prefs.scan_options.follow_links = val.trim() == "true",
+                    "scan_ignore_files" => prefs.scan_options.ignore_files = val.trim() == "true",
+                    "scan_case_sensitive" => prefs.scan_options.case_sensitive_dirs = val.trim() == "true",
+                    "scan_network" => prefs.scan_options.network = val.trim() == "true",
+                    "scan_include" => prefs.scan_options.include_names = parse_include_names(val),
+                    "scan_exclude" => prefs.scan_options.exclude.extend(parse_exclusions(val)),
+                    "scan_include_min_mb" => {
+                        prefs.scan_options.include_min_size = val.trim().parse::<u64>().map_or(0, |mb| mb * 1024 * 1024)
+                    }
+                    "scan_ops_limit" => prefs.scan_ops_limit = val.trim().parse().unwrap_or(0),
+                    "hash_mb_limit" => prefs.hash_mb_limit = val.trim().parse().unwrap_or(0),
+                    "remote_addr" => prefs.remote_addr = val.trim().to_string(),
+                    "remote_path" => prefs.remote_path = val.trim().to_string(),
+                    "remote_url" => prefs.remote_url = val.trim().to_string(),
+                    "export_anon_users" => prefs.export_anonymize.user_names = val.trim() == "true",
+                    "export_anon_names" => prefs.export_anonymize.file_names = val.trim() == "true",
+                    "export_depth" => prefs.export_depth = val.trim().parse::<usize>().map_or(3, |d| d.clamp(1, 10)),
+                    "auto_pause" => prefs.auto_pause = val.trim() == "true",
+                    "watch_changes" => prefs.watch_changes = val.trim() == "true",
+                    "shred_enabled" => prefs.shred_enabled = val.trim() == "true",
+                    "auto_snapshot" => prefs.auto_snapshot = val.trim() == "true",
+                    "snapshot_keep" => {
+                        prefs.snapshot_keep = val.trim().parse::<usize>().map_or(DEFAULT_SNAPSHOT_KEEP, |n| n.clamp(1, 1000))
+                    }
+                    "memory_limit_mb" => prefs.memory_limit_mb = val.trim().parse().unwrap_or(DEFAULT_MEMORY_LIMIT_MB),
+                    "node_limit" => prefs.node_limit = val.trim().parse().unwrap_or(DEFAULT_NODE_LIMIT),
+                    "hover_delay_ms" => prefs.hover_delay_ms = val.trim().parse::<u64>().map_or(0, |ms| ms.min(MAX_HOVER_DELAY_MS)),
+                    "tooltip_style" => prefs.tooltip_style = TooltipStyle::from_label(val.trim()).unwrap_or(prefs.tooltip_style),
+                    "touch_mode" => prefs.touch_mode = TouchMode::from_label(val.trim()).unwrap_or(prefs.touch_mode),
+                    "quick_scan" => prefs.quick_scans.extend(QuickScan::parse(val)),
+                    "hide_free_space" => prefs.hide_free_drives.push(PathBuf::from(val.trim())),
+                    "show_free_space_folder" => prefs.show_free_space_folder = val.trim() == "true",
+                    "heat_overlay" => prefs.heat_overlay = val.trim() == "true",
+                    "size_on_disk" => prefs.size_on_disk = val.trim() == "true",
+                    "keep_deletion_log" => prefs.keep_deletion_log = val.trim() == "true",
+                    "report_short_names" => prefs.report_short_names = val.trim() == "true",
+                    "archive_months" => {
+                        prefs.archive_months = val.trim().parse::<u32>().map_or(DEFAULT_ARCHIVE_MONTHS, |m| m.clamp(1, MAX_ARCHIVE_MONTHS))
+                    }
+                    "quota_csv" => prefs.quota_csv = Some(PathBuf::from(val.trim())).filter(|p| !p.as_os_str().is_empty()),
+                    "scan_max_depth" => prefs.scan_options.max_depth = val.trim().parse().unwrap_or(0),
+                    "scan_min_file_kb" => {
+                        prefs.scan_options.min_file_size = val.trim().parse::<u64>().map_or(0, |kb| kb * 1024)
+                    }
+                    "last_scan" => prefs.last_scan = Some(PathBuf::from(val.trim())).filter(|p| !p.as_os_str().is_empty()),
                     _ => {}
                 }
             }
         }
     }
+    prefs.cell_metrics = prefs.cell_metrics.clamped();
     prefs
 }
 
@@ -143,9 +875,43 @@ fn save_prefs(prefs: &Prefs) {
             let _ = std::fs::create_dir_all(dir);
         }
         let mut content = format!(
-            "hide_about={}\ndark_mode={}",
-            prefs.hide_about, prefs.dark_mode,
+            "hide_about={}\ndark_mode={}\ntheme={}\nage_ramp={}\nhigh_contrast={}\nlabel_density={}\n\
+             cell_min_px={}\nheader_px={}\npad_px={}\nborder_px={}\n\
+             restore_last_scan={}\nstale_after_mins={}\nauto_rescan_stale={}\n\
+             scan_max_depth={}\nscan_min_file_kb={}\nscan_include_offline={}\nscan_links_once={}\nscan_follow_links={}\n\
+             scan_ignore_files={}\nscan_case_sensitive={}\nscan_network={}\nscan_include={}\nscan_include_min_mb={}\n\
+             scan_ops_limit={}\nhash_mb_limit={}\nremote_addr={}\nremote_path={}\nremote_url={}\nexport_depth={}\n\
+             auto_pause={}\nwatch_changes={}\nshred_enabled={}\nauto_snapshot={}\nsnapshot_keep={}\n\
+             memory_limit_mb={}\nnode_limit={}\nhover_delay_ms={}\ntooltip_style={}\ntouch_mode={}\nshow_free_space_folder={}\nheat_overlay={}\n\
+             size_on_disk={}\nkeep_deletion_log={}\narchive_months={}\nexport_anon_users={}\nexport_anon_names={}\n\
+             report_short_names={}",
+            prefs.hide_about, prefs.dark_mode, prefs.theme.label(), prefs.age_ramp.label(), prefs.high_contrast,
+            prefs.label_density, prefs.cell_metrics.min_px, prefs.cell_metrics.header_px, prefs.cell_metrics.pad_px,
+            prefs.cell_metrics.border_px, prefs.restore_last_scan, prefs.stale_after_mins, prefs.auto_rescan_stale,
+            prefs.scan_options.max_depth, prefs.scan_options.min_file_size / 1024, prefs.scan_options.include_offline,
+            prefs.scan_options.count_links_once, prefs.scan_options.follow_links, prefs.scan_options.ignore_files,
+            prefs.scan_options.case_sensitive_dirs, prefs.scan_options.network, prefs.scan_options.include_names.join(";"),
+            prefs.scan_options.include_min_size / (1024 * 1024), prefs.scan_ops_limit, prefs.hash_mb_limit, prefs.remote_addr, prefs.remote_path,
+            prefs.remote_url, prefs.export_depth, prefs.auto_pause, prefs.watch_changes, prefs.shred_enabled, prefs.auto_snapshot,
+            prefs.snapshot_keep, prefs.memory_limit_mb, prefs.node_limit, prefs.hover_delay_ms, prefs.tooltip_style.label(), prefs.touch_mode.label(),
+            prefs.show_free_space_folder, prefs.heat_overlay, prefs.size_on_disk, prefs.keep_deletion_log, prefs.archive_months,
+            prefs.export_anonymize.user_names, prefs.export_anonymize.file_names, prefs.report_short_names,
         );
+        if let Some(ref last) = prefs.last_scan {
+            content += &format!("\nlast_scan={}", last.to_string_lossy());
+        }
+        if let Some(ref csv) = prefs.quota_csv {
+            content += &format!("\nquota_csv={}", csv.to_string_lossy());
+        }
+        for mount in &prefs.hide_free_drives {
+            content += &format!("\nhide_free_space={}", mount.to_string_lossy());
+        }
+        for pattern in &prefs.scan_options.exclude {
+            content += &format!("\nscan_exclude={}", pattern);
+        }
+        for quick in &prefs.quick_scans {
+            content += &format!("\nquick_scan={}|{}", quick.label.replace('|', "/"), quick.path);
+        }
         if let (Some(x), Some(y), Some(w), Some(h)) =
             (prefs.window_x, prefs.window_y, prefs.window_w, prefs.window_h)
         {
@@ -157,12 +923,15 @@ fn save_prefs(prefs: &Prefs) {
 
 // ===================== Main App =====================
 
+/// Final scan message: (tree, largest files, extension stats, time range).
+type ScanResult = (Option<FileNode>, Option<Vec<(String, u64, String)>>, Option<Vec<(String, u64, u64)>>, (u64, u64));
+
 pub struct SpaceViewApp {
     // Scan state
     scan_root: Option<FileNode>,
     scanning: bool,
     scan_progress: Option<Arc<ScanProgress>>,
-    scan_receiver: Option<std::sync::mpsc::Receiver<(Option<FileNode>, Option<Vec<(String, u64, String)>>, Option<Vec<(String, u64, u64)>>, (u64, u64))>>,
+    scan_receiver: Option<std::sync::mpsc::Receiver<ScanResult>>,
     snapshot_receiver: Option<std::sync::mpsc::Receiver<FileNode>>,
 
     // Camera + layout
@@ -174,6 +943,17 @@ pub struct SpaceViewApp {
     hovered_node_info: Option<HoveredInfo>,
     context_menu_info: Option<HoveredInfo>,
     is_dragging: bool,
+    keymap: Keymap,
+    /// Items picked in any view (Ctrl+click by default), shown by all of them
+    selection: Selection,
+    /// Flattened directories and their original children, for Unflatten
+    flattened: Vec<(PathBuf, Vec<FileNode>)>,
+    /// Items culled from a directory's treemap cell, listed after a badge click
+    hidden_items: Option<HiddenList>,
+    /// Depth legend click: (depth, start time) of the highlight pulse
+    flash_depth: Option<(usize, f64)>,
+    /// Camera targets (center, zoom) saved with Ctrl+1..9, recalled with 1..9
+    bookmarks: [Option<(egui::Pos2, f32)>; 9],
     /// Current depth context from camera center (for breadcrumbs/zoom frame)
     depth_context: Vec<BreadcrumbEntry>,
 
@@ -182,17 +962,134 @@ pub struct SpaceViewApp {
     root_size: u64,
     root_file_count: u64,
     scan_path: Option<PathBuf>,
-    show_free_space: bool,
+    /// Drives (mount points) whose whole-drive scans hide the free space tile
+    hide_free_drives: Vec<PathBuf>,
+    /// Free space tile for folder scans: off by default, since drive-level free space
+    /// isn't comparable with a folder's size
+    show_free_space_folder: bool,
+    heat_overlay: bool,
+    /// Lay the treemap out by size on disk (allocated clusters) instead of logical size
+    size_on_disk: bool,
+    /// Folder growth rates for the Heat overlay, measured against the snapshot history
+    heat: Option<HeatMap>,
+    /// Why the Heat overlay has nothing to show
+    heat_note: Option<&'static str>,
+    /// Free space / unknown tiles laid out after the root's children. View state only:
+    /// they are never part of `scan_root`
+    space_tiles: Vec<FileNode>,
+    /// Per-drive tiles of an all-drives scan, laid out inside each drive's block
+    drive_tiles: Vec<(PathBuf, Vec<FileNode>)>,
+    /// Scan root is a drive/mount root rather than a folder on it
+    scan_is_drive_root: bool,
+    /// Drive holding the scan root, queried once when the scan starts
+    scan_drive: Option<DriveSpace>,
 
     // Last frame time for dt calculation
     last_time: f64,
 
     // Theme
     theme: ColorTheme,
+    age_ramp: AgeRamp,
+    /// Thick black borders, no cushion shading, pure black/white labels
+    high_contrast: bool,
+    label_density: f32,
+    cell_metrics: CellMetrics,
+    show_appearance: bool,
     dark_mode: bool,
 
     // About dialog
     hide_about_on_start: bool,
+    restore_last_scan: bool,
+    /// Root of the last completed scan (persisted for session restore)
+    last_scan: Option<PathBuf>,
+    /// Seconds since epoch when the data on screen was scanned
+    scanned_at: Option<u64>,
+    /// Snapshot file the tree on screen was opened from instead of scanned
+    opened_snapshot: Option<PathBuf>,
+    /// Drive capacity and free space recorded in that snapshot, shown instead of today's
+    snapshot_drive: Option<DriveSpace>,
+    /// The tree on screen is what a cancelled scan reached (banner + `NOT_SCANNED` placeholders)
+    scan_partial: bool,
+    stale_after_mins: u64,
+    auto_rescan_stale: bool,
+    /// Depth limit / small-file folding applied to new scans
+    scan_options: ScanOptions,
+    /// Include-only name patterns as typed; parsed into `scan_options.include_names`
+    scan_include_text: String,
+    /// Exclusions as typed, one per line; parsed into `scan_options.exclude`
+    scan_exclude_text: String,
+    /// The current root is scanned with the network profile (option on, or a UNC path)
+    network_scan: bool,
+    /// Scanner read operations per second (0 = unlimited)
+    scan_ops_limit: u64,
+    /// Duplicate-hashing read rate in MB/s (0 = unlimited)
+    hash_mb_limit: u64,
+    /// Agent the current root was scanned on (None for local scans)
+    remote: Option<RemoteSource>,
+    /// Connect to Agent dialog fields
+    show_remote_dialog: bool,
+    remote_addr: String,
+    remote_token: String,
+    remote_path: String,
+    remote_error_receiver: Option<std::sync::mpsc::Receiver<String>>,
+    /// Folder levels included in diagram exports
+    export_depth: usize,
+    auto_pause: bool,
+    watch_changes: bool,
+    /// Watching the scanned roots (`watch_changes`, local scans only)
+    watcher: Option<Watcher>,
+    /// Paths changed since the last folder refresh, and when the latest came in
+    watch_dirty: Vec<PathBuf>,
+    watch_last: Option<std::time::Instant>,
+    /// Folder refresh running on a background thread
+    watch_job: Option<std::sync::mpsc::Receiver<Vec<FolderUpdate>>>,
+    /// The watch lost changes; the tree may be behind until a rescan
+    watch_overflow: bool,
+    quick_scans: Vec<QuickScan>,
+    show_quick_scans: bool,
+    shred_enabled: bool,
+    auto_snapshot: bool,
+    snapshot_keep: usize,
+    show_settings: bool,
+    /// Settings window's view of SpaceView's own data, measured when it opens
+    storage_usage: Option<Vec<StorageItem>>,
+    memory_limit_mb: u64,
+    node_limit: u64,
+    hover_delay_ms: u64,
+    tooltip_style: TooltipStyle,
+    touch_mode: TouchMode,
+    /// A touch came in this session (TouchMode::Auto)
+    touch_seen: bool,
+    /// Touch layout the egui style was last set up for
+    touch_styled: Option<bool>,
+    /// Block under the pointer and when it got there, for the hover delay
+    hover_started: Option<(egui::Rect, std::time::Instant)>,
+    /// Last measured resident memory of this process
+    memory_rss: Option<u64>,
+    last_memory_check: Option<std::time::Instant>,
+    /// Nodes in the finished tree (0 while scanning)
+    tree_nodes: u64,
+    /// Memory use that raised the warning banner for this scan
+    memory_warning: Option<u64>,
+    /// The warning has been raised for this scan; dismissing it doesn't bring it back
+    memory_warned: bool,
+    /// The scan ran past `node_limit` and aggregated the rest (banner showing)
+    node_limit_banner: bool,
+    node_limit_warned: bool,
+    /// Why the running scan was paused automatically (None if running or paused by hand)
+    auto_paused: Option<&'static str>,
+    /// Last auto-pause condition seen, polled every POWER_POLL_SECS
+    power_busy: Option<&'static str>,
+    last_power_check: Option<std::time::Instant>,
+    /// Scan FTP / WebDAV dialog fields
+    show_url_dialog: bool,
+    remote_url: String,
+    remote_user: String,
+    remote_password: String,
+    /// Window focus last frame, to catch the moment it comes back
+    was_focused: bool,
+    /// Age in seconds of the data when it was found stale on focus; drives the banner
+    stale_banner: Option<u64>,
     show_about: bool,
 
     // About dialog textures
@@ -205,16 +1102,60 @@ pub struct SpaceViewApp {
 
     // Pending delete confirmation
     pending_delete: Option<PathBuf>,
+    /// Context shown above the delete confirmation (e.g. the archive just made)
+    pending_delete_note: Option<String>,
+    delete_in_use: Option<InUseCheck>,
+    /// "Delete all .ext files" from the Types view, waiting for confirmation
+    pending_type_delete: Option<TypeDelete>,
+    /// A bulk removal's dry run, open for review, export or running
+    manifest_review: Option<Manifest>,
+    /// The user checked the folders a loaded manifest empties
+    manifest_confirmed: bool,
+    pending_shred: Option<ShredRequest>,
+    pending_compress: Option<CompressRequest>,
+    pending_migration: Option<MigrationRequest>,
+    file_hash: Option<FileHash>,
+    type_breakdown: Option<TypeBreakdown>,
+    /// Everything deleted through SpaceView, oldest first: earlier sessions' entries
+    /// (persistent log only), then this session's from `session_deletions` on
+    deletions: Vec<Deletion>,
+    session_deletions: usize,
+    /// Where the user left off in recently scanned roots, most recent first
+    view_states: Vec<ViewState>,
+    /// The new root's remembered state, applied when its scan result arrives
+    pending_view_state: Option<ViewState>,
+    /// Remembered camera (center, zoom), applied by the next full layout
+    pending_camera: Option<(egui::Pos2, f32)>,
+    keep_deletion_log: bool,
+    show_deletions: bool,
+    /// Imported quotas, from `quota_csv`
+    quotas: Vec<Quota>,
+    quota_csv: Option<PathBuf>,
+    /// Quota limits by the tree path of the folder they apply to
+    quota_matches: std::collections::HashMap<PathBuf, u64>,
 
     // View mode
     view_mode: ViewMode,
+    /// View drawn last frame; opening another one brings the latest selected item into sight
+    shown_view: ViewMode,
     search_text: String,
     list_sort: SortColumn,
     list_sort_asc: bool,
     list_path: Vec<String>,
+    /// List every file below the current folder instead of its direct children
+    list_flat: bool,
+    list_flat_cache: Option<(FlatListKey, Vec<ListEntry>)>,
     cached_largest: Option<Vec<(String, u64, String)>>,
+    /// Top Files limited to one extension (a filter chip), on top of the search text
+    largest_ext_filter: Option<String>,
     cached_extensions: Option<Vec<(String, u64, u64)>>, // (extension, total_size, file_count)
+    /// Types view weighs blocks by file count instead of bytes
+    ext_by_count: bool,
     cached_duplicates: Option<Vec<DuplicateGroup>>,
+    /// `cached_duplicates` aggregated by (copy folder, original folder)
+    cached_dup_folders: Vec<FolderPairDup>,
+    /// Duplicates view lists folder pairs instead of individual files
+    dup_by_folder: bool,
     dup_receiver: Option<std::sync::mpsc::Receiver<Vec<DuplicateGroup>>>,
 
     // Color mode
@@ -225,36 +1166,344 @@ pub struct SpaceViewApp {
     // Window position tracking (saved on exit)
     last_window_outer_pos: Option<egui::Pos2>,
     last_window_inner_size: Option<egui::Vec2>,
+    /// Restored window position has been checked against the connected monitors
+    window_placement_checked: bool,
+    /// Points-to-pixels scale last frame, to catch DPI changes between monitors
+    last_pixels_per_point: f32,
 
     // Extension breakdown panel
     show_ext_panel: bool,
     selected_extension: Option<String>,
+    /// Age legend band (0 = oldest) whose files stay lit in Age mode; the rest are dimmed
+    age_band: Option<usize>,
+
+    presentation: Option<Presentation>,
 
     // Drive picker
     show_drive_picker: bool,
     cached_drives: Vec<DriveInfo>,
+    /// Fresh drive list from the watcher thread whenever a volume is mounted or removed
+    drive_watch_receiver: Option<std::sync::mpsc::Receiver<Vec<DriveInfo>>>,
+    /// The scanned volume was unplugged; the treemap shows a notice instead of stale data
+    volume_removed: bool,
+    /// User chose to keep browsing the last scan of a removed volume
+    show_removed_data: bool,
+
+    // Reports view (computed lazily from scan_root when first opened)
+    report_kind: ReportKind,
+    cached_git_repos: Option<Vec<GitRepoInfo>>,
+    cached_matching_folders: Option<Vec<MatchingFolders>>,
+    cached_archive_candidates: Option<Vec<ArchiveCandidate>>,
+    cached_user_profiles: Option<Vec<UserProfile>>,
+    cached_name_issues: Option<NameIssues>,
+    /// Cluster Slack report: worst folders and the tree's total slack
+    cached_cluster_slack: Option<(Vec<SlackFolder>, u64)>,
+    /// Cluster size picked in the report (0 = the scanned volume's)
+    slack_cluster: u64,
+    /// The scanned volume's cluster size, read when the report is built
+    detected_cluster: Option<u64>,
+    report_short_names: bool,
+    export_anonymize: Anonymize,
+    archive_months: u32,
+    cached_app_caches: Option<Vec<AppCache>>,
+    app_cache_selected: std::collections::HashSet<PathBuf>,
+    pending_cache_clear: Option<Manifest>,
+    cached_system_items: Option<Vec<SystemItem>>,
+
+    // Diff view (backup comparison, integrity verification)
+    diff_report: Option<DiffReport>,
+    diff_filter: Option<DiffKind>,
+    /// Show the per-folder totals instead of the changed items
+    diff_folders: bool,
+    diff_sort: Option<DiffSort>,
+    diff_sort_asc: bool,
+    /// Change per item of the tree on screen, from Compare Scan with Snapshot
+    diff_growth: Option<GrowthMap>,
+
+    // Background tool job (Tools menu) + result message
+    tool_job: Option<ToolJob>,
+    /// Jobs cancelled by a newer one, drained until they stop so no deletion goes unlogged
+    replaced_jobs: Vec<ToolJob>,
+    info_message: Option<String>,
+
+    // Scan queue (sequential background scans + drives dashboard)
+    scan_queue: Vec<QueuedScan>,
+    queue_tx: Option<std::sync::mpsc::Sender<QueueJob>>,
+    queue_events: Option<std::sync::mpsc::Receiver<QueueEvent>>,
+
+    // Interrupted scan that can be resumed from its checkpoint
+    resume_offer: Option<SnapshotInfo>,
+}
+
+/// Presentation mode: full screen, treemap only, zoom and pan only.
+struct Presentation {
+    /// UI zoom to restore on exit
+    zoom_factor: f32,
+    started: std::time::Instant,
+}
+
+/// A long-running Tools menu operation on a background thread.
+struct ToolJob {
+    kind: ToolKind,
+    progress: Arc<ScanProgress>,
+    receiver: std::sync::mpsc::Receiver<ToolResult>,
+}
+
+/// Which operation a tool job runs, so a dialog can tell its own job from another.
+#[derive(Clone, Copy, PartialEq)]
+enum ToolKind {
+    Compare,
+    Growth,
+    Baseline,
+    Verify,
+    Expand,
+    Delete,
+    Compress,
+    Hash,
+    Shred,
+    Caches,
+    System,
+}
+
+impl ToolKind {
+    /// Status bar text while it runs.
+    fn label(self) -> &'static str {
+        match self {
+            ToolKind::Compare => "Comparing...",
+            ToolKind::Growth => "Measuring growth...",
+            ToolKind::Baseline => "Creating baseline...",
+            ToolKind::Verify => "Verifying...",
+            ToolKind::Expand => "Scanning folder...",
+            ToolKind::Delete => "Deleting...",
+            ToolKind::Compress => "Compressing...",
+            ToolKind::Hash => "Hashing...",
+            ToolKind::Shred => "Shredding...",
+            ToolKind::Caches => "Measuring caches...",
+            ToolKind::System => "Measuring system...",
+        }
+    }
+
+    /// Removes or rewrites files. Stopping one partway would lose the record of
+    /// what it already did, so another tool never replaces it.
+    fn destructive(self) -> bool {
+        matches!(self, ToolKind::Delete | ToolKind::Shred | ToolKind::Compress)
+    }
+}
+
+/// Every file of one extension, listed in the delete-all-of-type dialog.
+struct TypeDelete {
+    ext: String,
+    paths: Vec<PathBuf>,
+    /// Size of each of `paths`, for the deletion log
+    sizes: Vec<u64>,
+    size: u64,
+}
+
+impl TypeDelete {
+    fn manifest(&self) -> Manifest {
+        let items = self.paths.iter().zip(&self.sizes)
+            .map(|(path, &size)| PlannedRemoval {
+                path: path.clone(),
+                size,
+                method: DeleteMethod::RecycleBin,
+                reason: format!("{} file", self.ext),
+            })
+            .collect();
+        Manifest { title: format!("Delete All {} Files", self.ext), items, from_file: false }
+    }
+}
+
+/// A file waiting in the Shred confirmation dialog.
+struct ShredRequest {
+    path: PathBuf,
+    size: u64,
+    /// On a spinning disk, where overwriting in place is effective
+    hdd: bool,
+    passes: usize,
+    /// The "cannot be undone" box; Shred stays disabled until it is ticked
+    understood: bool,
+}
+
+/// A folder waiting in the Compress to .zip dialog.
+struct CompressRequest {
+    dir: PathBuf,
+    dest: PathBuf,
+    /// Offer the folder for deletion once the archive is complete
+    delete_after: bool,
+}
+
+/// Selected folders waiting in the Migration Script dialog.
+struct MigrationRequest {
+    sources: Vec<MigrationSource>,
+    dest: String,
+    /// Delete the sources once copied (robocopy /MOVE, rsync --remove-source-files)
+    moving: bool,
+}
+
+/// A file in the File Hash dialog.
+struct FileHash {
+    path: PathBuf,
+    size: u64,
+    kind: Checksum,
+    /// Finished digests, so switching algorithms back doesn't read the file again
+    results: Vec<(Checksum, String)>,
+    /// Pasted published checksum to compare against
+    expected: String,
+}
+
+/// One folder's own file type stats for the Type Breakdown window.
+struct TypeBreakdown {
+    path: PathBuf,
+    name: String,
+    size: u64,
+    /// Worker walking a copy of the folder's subtree; None once `stats` is in
+    receiver: Option<std::sync::mpsc::Receiver<Vec<(String, u64, u64)>>>,
+    /// (extension, bytes, files), largest first
+    stats: Vec<(String, u64, u64)>,
+}
+
+/// Background open-handle check for the path in the delete dialog.
+struct InUseCheck {
+    path: PathBuf,
+    receiver: std::sync::mpsc::Receiver<InUse>,
+    /// Processes holding files open; None while the check runs.
+    result: Option<InUse>,
+}
+
+/// Job for the queue worker: (queue index, root, progress, filters).
+type QueueJob = (usize, PathBuf, Arc<ScanProgress>, ScanOptions);
+
+/// A scan waiting in, running on, or finished by the queue worker thread.
+struct QueuedScan {
+    path: PathBuf,
+    progress: Arc<ScanProgress>,
+    state: QueueState,
+}
+
+enum QueueState {
+    Pending,
+    Running,
+    /// `result` is taken when the scan is opened; the summary stays on the dashboard.
+    Done {
+        size: u64,
+        file_count: u64,
+        free: Option<u64>,
+        secs: f64,
+        result: Option<Box<ScanResult>>,
+    },
+    Cancelled,
+}
+
+enum QueueEvent {
+    Started(usize),
+    Finished(usize, Option<Box<ScanResult>>, f64),
+}
+
+enum ToolResult {
+    Diff(DiffReport),
+    /// Scan compared with a snapshot: the Diff view plus growth colors for the map
+    Growth(DiffReport, GrowthMap),
+    Heat(HeatMap),
+    AppCaches(Vec<AppCache>),
+    System(Vec<SystemItem>),
+    Message(String),
+    /// Files were deleted: show the message, log them and rescan
+    Deleted { msg: String, removed: Vec<Deletion> },
+    /// Cache folders were emptied: show the message and log them
+    CachesCleared { msg: String, removed: Vec<Deletion> },
+    /// Archive written; `delete` is the source folder when it should go to the Recycle Bin next
+    Compressed { msg: String, delete: Option<PathBuf> },
+    /// Checksum for the File Hash dialog
+    Hashed { path: PathBuf, kind: Checksum, hex: String },
+    /// Fresh scan of a folder the depth limit had summarized
+    Expanded(FileNode),
+    Cancelled,
+}
+
+/// A watched folder to list again: its subfolders in `keep` are left as they are.
+struct FolderRefresh {
+    dir: PathBuf,
+    /// Levels below the scan root, so the depth limit still counts from there
+    depth: usize,
+    keep: Vec<PathBuf>,
+}
+
+/// A watched folder listed again, with the `keep` subfolders left out of `fresh`.
+struct FolderUpdate {
+    fresh: FileNode,
+    /// Which of them are still there
+    kept: std::collections::HashSet<PathBuf>,
+}
+
+/// Where a remote root came from, kept so Rescan goes back to the same place.
+#[derive(Clone)]
+enum RemoteSource {
+    /// A `spaceview --agent` on another machine
+    Agent { addr: String, token: String },
+    /// An FTP or WebDAV server walked through its listings; the root path is the URL
+    Url { user: String, password: String },
+    /// The built-in sample tree; nothing is read from disk
+    Demo,
+}
+
+impl RemoteSource {
+    fn label(&self) -> String {
+        match self {
+            RemoteSource::Agent { addr, .. } => format!("Agent: {}", addr),
+            RemoteSource::Url { .. } => "Remote listing (read-only)".to_string(),
+            RemoteSource::Demo => "Demo data (read-only)".to_string(),
+        }
+    }
 }
 
 #[derive(Clone)]
 struct HoveredInfo {
     name: String,
+    /// Exact path of the hovered item (empty for the free space tile)
+    path: PathBuf,
     size: u64,
+    /// Size on disk
+    allocated: u64,
     file_count: u64,
     is_dir: bool,
+    /// Scanner ATTR_* bits (storage state, links, special folders)
+    attrs: u16,
     world_rect: egui::Rect,
     has_children: bool,
     screen_rect: egui::Rect,
 }
 
+impl HoveredInfo {
+    /// Path for Open/Copy/Select/Delete, or None for the free space tile.
+    fn target_path(&self) -> Option<&PathBuf> {
+        Some(&self.path).filter(|p| !p.as_os_str().is_empty())
+    }
+
+    /// A folder a depth-limited scan kept only as a total (Scan Contents fills it in).
+    fn is_summarized(&self) -> bool {
+        self.is_dir && !self.has_children && self.file_count > 0
+    }
+}
+
 #[derive(Clone)]
 struct DuplicateGroup {
     size: u64,
     paths: Vec<String>, // full paths of duplicate files
 }
 
+/// Duplicate bytes one folder holds of another, summed over file-level groups.
+struct FolderPairDup {
+    copy_dir: String,
+    original_dir: String,
+    bytes: u64,
+    files: u64,
+}
+
 #[derive(Clone)]
 struct BreadcrumbEntry {
     name: String,
+    path: PathBuf,
+    size: u64,
     color_index: usize,
     world_rect: egui::Rect,
 }
@@ -269,6 +1518,30 @@ struct DriveInfo {
     is_removable: bool,
 }
 
+/// Poll the drive list on a background thread and send it (and repaint) whenever
+/// the set of mount points changes.
+fn spawn_drive_watcher(ctx: egui::Context) -> std::sync::mpsc::Receiver<Vec<DriveInfo>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mount_points = |drives: &[DriveInfo]| drives.iter().map(|d| d.mount_point.clone()).collect::<Vec<_>>();
+        let mut known = mount_points(&enumerate_drives());
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(DRIVE_POLL_SECS));
+            let drives = enumerate_drives();
+            let current = mount_points(&drives);
+            if current == known {
+                continue;
+            }
+            known = current;
+            if tx.send(drives).is_err() {
+                break;
+            }
+            ctx.request_repaint();
+        }
+    });
+    rx
+}
+
 fn enumerate_drives() -> Vec<DriveInfo> {
     use sysinfo::Disks;
     let disks = Disks::new_with_refreshed_list();
@@ -283,6 +1556,12 @@ fn enumerate_drives() -> Vec<DriveInfo> {
     }).collect()
 }
 
+/// Default export file name for a root (`C:\` has no usable name of its own).
+fn export_file_stem(name: &str) -> String {
+    let stem: String = name.chars().filter(|c| !r#"\/:*?"<>|"#.contains(*c)).collect();
+    if stem.trim().is_empty() { "tree".to_string() } else { stem }
+}
+
 /// Compare two version strings (e.g. "0.5.3" vs "0.5.4").
 /// Returns true if `remote` is strictly newer than `local`.
 fn is_newer_version(local: &str, remote: &str) -> bool {
@@ -306,7 +1585,7 @@ fn is_newer_version(local: &str, remote: &str) -> bool {
 }
 
 impl SpaceViewApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let prefs = load_prefs();
 
         // Spawn background version check
@@ -339,7 +1618,8 @@ impl SpaceViewApp {
             let _ = update_tx.send(result);
         });
 
-        Self {
+        let deletions = if prefs.keep_deletion_log { load_ledger() } else { Vec::new() };
+        let mut app = Self {
             scan_root: None,
             scanning: false,
             scan_progress: None,
@@ -351,473 +1631,3427 @@ impl SpaceViewApp {
             hovered_node_info: None,
             context_menu_info: None,
             is_dragging: false,
+            keymap: Keymap::load(data_dir().map(|d| d.join("keymap.txt")).as_deref()),
+            selection: Selection::default(),
+            bookmarks: [None; 9],
+            flash_depth: None,
+            hidden_items: None,
+            flattened: Vec::new(),
             depth_context: Vec::new(),
             root_name: String::new(),
             root_size: 0,
             root_file_count: 0,
             scan_path: None,
-            show_free_space: true,
+            hide_free_drives: prefs.hide_free_drives.clone(),
+            show_free_space_folder: prefs.show_free_space_folder,
+            heat_overlay: prefs.heat_overlay,
+            size_on_disk: prefs.size_on_disk,
+            heat: None,
+            heat_note: None,
+            space_tiles: Vec::new(),
+            drive_tiles: Vec::new(),
+            scan_is_drive_root: false,
+            scan_drive: None,
             last_time: 0.0,
-            theme: ColorTheme::Rainbow,
+            theme: prefs.theme,
+            age_ramp: prefs.age_ramp,
+            high_contrast: prefs.high_contrast,
+            label_density: prefs.label_density,
+            cell_metrics: prefs.cell_metrics,
+            show_appearance: false,
             dark_mode: prefs.dark_mode,
             hide_about_on_start: prefs.hide_about,
+            restore_last_scan: prefs.restore_last_scan,
+            last_scan: prefs.last_scan.clone(),
+            scanned_at: None,
+            opened_snapshot: None,
+            snapshot_drive: None,
+            stale_after_mins: prefs.stale_after_mins,
+            auto_rescan_stale: prefs.auto_rescan_stale,
+            scan_include_text: prefs.scan_options.include_names.join("; "),
+            scan_exclude_text: prefs.scan_options.exclude.join("\n"),
+            scan_options: prefs.scan_options,
+            network_scan: false,
+            scan_ops_limit: prefs.scan_ops_limit,
+            hash_mb_limit: prefs.hash_mb_limit,
+            remote: None,
+            show_remote_dialog: false,
+            remote_addr: prefs.remote_addr.clone(),
+            remote_token: String::new(),
+            remote_path: prefs.remote_path.clone(),
+            remote_error_receiver: None,
+            export_depth: prefs.export_depth,
+            auto_pause: prefs.auto_pause,
+            watch_changes: prefs.watch_changes,
+            watcher: None,
+            watch_dirty: Vec::new(),
+            watch_last: None,
+            watch_job: None,
+            watch_overflow: false,
+            shred_enabled: prefs.shred_enabled,
+            auto_snapshot: prefs.auto_snapshot,
+            snapshot_keep: prefs.snapshot_keep,
+            show_settings: false,
+            storage_usage: None,
+            memory_limit_mb: prefs.memory_limit_mb,
+            node_limit: prefs.node_limit,
+            hover_delay_ms: prefs.hover_delay_ms,
+            tooltip_style: prefs.tooltip_style,
+            touch_mode: prefs.touch_mode,
+            touch_seen: false,
+            touch_styled: None,
+            hover_started: None,
+            memory_rss: None,
+            last_memory_check: None,
+            tree_nodes: 0,
+            memory_warning: None,
+            memory_warned: false,
+            node_limit_banner: false,
+            node_limit_warned: false,
+            quick_scans: prefs.quick_scans.clone(),
+            show_quick_scans: false,
+            auto_paused: None,
+            power_busy: None,
+            last_power_check: None,
+            show_url_dialog: false,
+            remote_url: prefs.remote_url.clone(),
+            remote_user: String::new(),
+            remote_password: String::new(),
+            was_focused: true,
+            stale_banner: None,
+            scan_partial: false,
             show_about: !prefs.hide_about,
             icon_texture: None,
             face_texture: None,
             update_check_receiver: Some(update_rx),
             latest_version: None,
             pending_delete: None,
+            pending_delete_note: None,
+            delete_in_use: None,
+            pending_type_delete: None,
+            manifest_review: None,
+            manifest_confirmed: false,
+            pending_shred: None,
+            pending_compress: None,
+            pending_migration: None,
+            file_hash: None,
+            type_breakdown: None,
+            session_deletions: deletions.len(),
+            deletions,
+            view_states: load_view_states(),
+            pending_view_state: None,
+            pending_camera: None,
+            keep_deletion_log: prefs.keep_deletion_log,
+            show_deletions: false,
+            quotas: Vec::new(),
+            quota_csv: None,
+            quota_matches: std::collections::HashMap::new(),
             view_mode: ViewMode::Treemap,
+            shown_view: ViewMode::Treemap,
             search_text: String::new(),
             list_sort: SortColumn::Size,
             list_sort_asc: false,
             list_path: Vec::new(),
+            list_flat: false,
+            list_flat_cache: None,
             cached_largest: None,
+            largest_ext_filter: None,
             cached_extensions: None,
+            ext_by_count: false,
             cached_duplicates: None,
+            cached_dup_folders: Vec::new(),
+            dup_by_folder: false,
             dup_receiver: None,
             color_mode: ColorMode::Depth,
             time_range: (0, 0),
             ext_color_map: std::collections::HashMap::new(),
             last_window_outer_pos: None,
             last_window_inner_size: None,
+            window_placement_checked: false,
+            last_pixels_per_point: 0.0,
             show_ext_panel: false,
             selected_extension: None,
+            age_band: None,
+            presentation: None,
             show_drive_picker: false,
             cached_drives: Vec::new(),
+            drive_watch_receiver: Some(spawn_drive_watcher(cc.egui_ctx.clone())),
+            volume_removed: false,
+            show_removed_data: false,
+            report_kind: ReportKind::GitRepos,
+            cached_git_repos: None,
+            cached_matching_folders: None,
+            cached_archive_candidates: None,
+            cached_user_profiles: None,
+            cached_name_issues: None,
+            cached_cluster_slack: None,
+            slack_cluster: 0,
+            detected_cluster: None,
+            report_short_names: prefs.report_short_names,
+            export_anonymize: prefs.export_anonymize,
+            archive_months: prefs.archive_months,
+            cached_app_caches: None,
+            app_cache_selected: std::collections::HashSet::new(),
+            pending_cache_clear: None,
+            cached_system_items: None,
+            diff_report: None,
+            diff_filter: None,
+            diff_folders: false,
+            diff_sort: None,
+            diff_sort_asc: false,
+            diff_growth: None,
+            tool_job: None,
+            replaced_jobs: Vec::new(),
+            info_message: None,
+            scan_queue: Vec::new(),
+            queue_tx: None,
+            queue_events: None,
+            resume_offer: pending_resume(),
+        };
+        if let Some(csv) = prefs.quota_csv.clone() {
+            app.import_quotas(csv, false);
+        }
+        // An interrupted scan takes priority: the welcome screen offers to resume it
+        if prefs.restore_last_scan && app.resume_offer.is_none() {
+            if let Some(path) = prefs.last_scan.filter(|p| p.is_dir()) {
+                app.restore_session(path);
+            }
         }
+        app
     }
 
     fn start_scan(&mut self, path: PathBuf) {
-        if let Some(ref prog) = self.scan_progress {
-            prog.cancel.store(true, Ordering::Relaxed);
-        }
-
-        // Deferred drops: move old data to background thread for deallocation
-        let old_root = self.scan_root.take();
-        let old_layout = self.world_layout.take();
-        let old_largest = self.cached_largest.take();
-        let old_extensions = self.cached_extensions.take();
-        if old_root.is_some() || old_layout.is_some() {
-            std::thread::spawn(move || {
-                drop(old_root);
-                drop(old_layout);
-                drop(old_largest);
-                drop(old_extensions);
-            });
+        // Rescans of a remote root go back to its agent or server
+        if let Some(remote) = self.remote.clone().filter(|_| self.scan_path.as_ref() == Some(&path)) {
+            self.start_remote_scan(remote, path);
+            return;
         }
+        self.start_scan_from(path, None);
+    }
 
-        self.camera = Camera::new(egui::pos2(0.5, 0.5), 1.0);
+    /// Have a remote agent scan `path` on its machine and stream the tree back, or
+    /// walk the FTP/WebDAV URL in `path`.
+    fn start_remote_scan(&mut self, remote: RemoteSource, path: PathBuf) {
+        self.reset_for_new_root(path.clone());
+        // Remote paths could match a local root's remembered state
+        self.pending_view_state = None;
+        // Drives and free space belong to the remote machine, not this one
+        self.network_scan = true;
+        self.scan_drive = None;
+        self.scan_is_drive_root = false;
+        self.remote = Some(remote.clone());
         self.scanning = true;
-        self.view_mode = ViewMode::Treemap;
-        self.depth_context.clear();
-        self.hovered_node_info = None;
-        self.scan_path = Some(path.clone());
-        self.list_path.clear();
-        self.cached_duplicates = None;
-        self.dup_receiver = None;
-        self.selected_extension = None;
-        self.cached_drives.clear();
-        self.show_drive_picker = false;
+        self.resume_offer = None;
 
         let progress = Arc::new(ScanProgress::new());
         self.scan_progress = Some(progress.clone());
-
         let (tx, rx) = std::sync::mpsc::channel();
         self.scan_receiver = Some(rx);
-
         let (snapshot_tx, snapshot_rx) = std::sync::mpsc::channel();
         self.snapshot_receiver = Some(snapshot_rx);
-
+        let (error_tx, error_rx) = std::sync::mpsc::channel();
+        self.remote_error_receiver = Some(error_rx);
         std::thread::spawn(move || {
-            let result = scan_directory_live(&path, progress, snapshot_tx);
-            let (largest, extensions, time_range) = if let Some(ref root) = result {
-                // Compute time range on scan thread (not UI thread)
-                let time_range = compute_time_range(root);
-
-                // Collect all files once, derive both largest and extension stats
-                let mut all_files: Vec<(String, u64, String)> = Vec::new();
-                collect_all_files(root, &mut all_files);
-
-                // Extension stats from all files
-                let mut ext_map: std::collections::HashMap<String, (u64, u64)> = std::collections::HashMap::new();
-                for (name, size, _) in &all_files {
-                    let ext = name.rsplit('.').next()
-                        .filter(|e| e.len() < 10 && *e != name.as_str())
-                        .map(|e| format!(".{}", e.to_lowercase()))
-                        .unwrap_or_else(|| "(no ext)".to_string());
-                    let entry = ext_map.entry(ext).or_insert((0, 0));
-                    entry.0 += size;
-                    entry.1 += 1;
-                }
-                let mut ext_list: Vec<(String, u64, u64)> = ext_map.into_iter()
-                    .map(|(ext, (size, count))| (ext, size, count))
-                    .collect();
-                ext_list.sort_by(|a, b| b.1.cmp(&a.1));
+            let result = match remote {
+                RemoteSource::Agent { addr, token } => request_scan(&addr, &token, &path, &progress, |partial| {
+                    let _ = snapshot_tx.send(partial);
+                }),
+                RemoteSource::Url { user, password } => scan_url(&path.to_string_lossy(), &user, &password, &progress),
+                RemoteSource::Demo => Ok(Some(demo_tree())),
+            };
+            let root = result.unwrap_or_else(|e| {
+                let _ = error_tx.send(e);
+                None
+            });
+            let _ = tx.send(finish_scan(root));
+        });
+    }
 
-                // Largest 1000 files
-                all_files.sort_by(|a, b| b.1.cmp(&a.1));
-                all_files.truncate(1000);
+    /// Reopen a previous scan root: load the session snapshot if it is a complete
+    /// scan of `path`, otherwise rescan.
+    fn restore_session(&mut self, path: PathBuf) {
+        let Some((snapshot, info)) = session_snapshot_path()
+            .and_then(|p| read_snapshot_info(&p).ok().map(|info| (p, info)))
+            .filter(|(_, info)| info.complete && info.root == path)
+        else {
+            self.start_scan(path);
+            return;
+        };
+        self.reset_for_new_root(path.clone());
+        self.scanning = true;
+        self.scanned_at = Some(info.created);
 
-                (Some(all_files), Some(ext_list), time_range)
-            } else {
-                (None, None, (0, 0))
+        let progress = Arc::new(ScanProgress::with_ops_limit(self.scan_ops_limit));
+        self.scan_progress = Some(progress.clone());
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.scan_receiver = Some(rx);
+        let options = ScanOptions {
+            network: self.network_scan,
+            skip_dirs: own_data_dirs(),
+            max_nodes: self.node_limit,
+            ..self.scan_options.clone()
+        };
+        std::thread::spawn(move || {
+            let result = match load_snapshot(&snapshot) {
+                Ok((_, root)) => Some(root),
+                Err(_) if is_all_drives(&path) => scan_all_drives(&local_drive_roots(), progress, None, &options, |_| {}),
+                Err(_) => scan_directory(&path, progress, &options),
             };
-            let _ = tx.send((result, largest, extensions, time_range));
+            let _ = tx.send(finish_scan(result));
         });
     }
 
-    fn build_layout(&mut self, viewport: egui::Rect) {
-        if let Some(ref mut root) = self.scan_root {
-            // Skip free space injection during live scanning (changes every frame)
-            if !self.scanning && self.show_free_space {
-                if let Some(ref path) = self.scan_path {
-                    if let Some(free) = get_free_space(path) {
-                        if free > 0 {
-                            // Remove any previous free space node and its size
-                            if let Some(old) = root.children.iter().find(|c| c.name == "<Free Space>") {
-                                root.size -= old.size;
-                            }
-                            root.children.retain(|c| c.name != "<Free Space>");
-                            root.children.push(FileNode {
-                                name: "<Free Space>".to_string(),
-                                path: PathBuf::new(),
-                                size: free,
-                                is_dir: false,
-                                file_count: 0,
-                                modified: 0,
-                                children: Vec::new(),
-                            });
-                            root.size += free;
-                            // Sort by size descending, but force free space to the end
-                            // so the treemap places it in the bottom-right corner
-                            root.children.sort_by(|a, b| {
-                                let a_free = a.name == "<Free Space>";
-                                let b_free = b.name == "<Free Space>";
-                                if a_free && !b_free { return std::cmp::Ordering::Greater; }
-                                if !a_free && b_free { return std::cmp::Ordering::Less; }
-                                b.size.cmp(&a.size)
-                            });
-                        }
-                    }
-                }
+    /// Show a saved snapshot as if it had just been scanned, without reading the
+    /// scanned folder. Scan time and drive space come from the file.
+    fn open_snapshot(&mut self, file: PathBuf) {
+        let info = match read_snapshot_info(&file) {
+            Ok(info) => info,
+            Err(e) => {
+                self.info_message = Some(format!("Could not open snapshot: {}", e));
+                return;
             }
+        };
+        self.reset_for_new_root(info.root.clone());
+        self.scanning = true;
+        self.resume_offer = None;
+        self.scanned_at = Some(info.scanned_at());
+        self.scan_is_drive_root = info.drive.as_ref().is_some_and(|d| d.mount_point == info.root);
+        self.snapshot_drive = info.drive;
+        self.opened_snapshot = Some(file.clone());
 
-            let aspect = viewport.height() / viewport.width();
-            let layout = WorldLayout::new(root, aspect);
-            self.camera.reset(layout.world_rect);
-            self.camera.set_world_rect(layout.world_rect);
-            self.world_layout = Some(layout);
-            self.root_name = root.name.clone();
-            self.root_size = root.size;
-            self.root_file_count = root.file_count;
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.scan_receiver = Some(rx);
+        std::thread::spawn(move || {
+            let root = load_snapshot(&file).ok().map(|(_, root)| root);
+            let _ = tx.send(finish_scan(root));
+        });
+    }
+
+    /// Rescan (or offer to) if the data on screen is older than `stale_after_mins`.
+    /// Opened snapshots are old on purpose.
+    fn check_stale_on_focus(&mut self) {
+        if self.scanning || self.stale_after_mins == 0 || self.opened_snapshot.is_some() {
+            return;
+        }
+        let Some(at) = self.scanned_at.filter(|_| self.scan_root.is_some()) else { return };
+        let age = now_secs().saturating_sub(at);
+        if age < self.stale_after_mins * 60 {
+            return;
+        }
+        match self.scan_path.clone() {
+            Some(path) if self.auto_rescan_stale => self.start_scan(path),
+            _ => self.stale_banner = Some(age),
         }
     }
 
-    fn rebuild_layout_preserving_camera(&mut self, viewport: egui::Rect) {
-        if let Some(ref root) = self.scan_root {
-            let old_aspect = self.world_layout.as_ref()
-                .map(|l| l.world_rect.height() / l.world_rect.width())
-                .unwrap_or(1.0);
-            let new_aspect = viewport.height() / viewport.width();
+    /// Tighten Scan Options (depth limit, small-file grouping) and restart the scan,
+    /// for volumes whose full tree wouldn't fit in memory.
+    fn rescan_lean(&mut self) {
+        if self.scan_options.max_depth == 0 || self.scan_options.max_depth > LEAN_MAX_DEPTH {
+            self.scan_options.max_depth = LEAN_MAX_DEPTH;
+        }
+        self.scan_options.min_file_size = self.scan_options.min_file_size.max(LEAN_MIN_FILE_SIZE);
+        save_prefs(&self.current_prefs());
+        if let Some(path) = self.scan_path.clone() {
+            self.start_scan(path);
+        }
+    }
 
-            // Remap camera center.y proportionally
-            let y_ratio = if old_aspect > 0.0 {
-                new_aspect / old_aspect
+    /// Continue the interrupted scan from its checkpoint, skipping finished folders.
+    fn resume_scan(&mut self) {
+        if let (Some(info), Some(checkpoint)) = (self.resume_offer.take(), resume_checkpoint_path()) {
+            self.start_scan_from(info.root, Some(checkpoint));
+        }
+    }
+
+    /// Start a live scan, optionally seeded from a resume checkpoint. While scanning,
+    /// the partial tree is checkpointed periodically and on cancel so it survives crashes.
+    fn start_scan_from(&mut self, path: PathBuf, resume: Option<PathBuf>) {
+        self.reset_for_new_root(path.clone());
+        self.scanning = true;
+        self.resume_offer = None;
+
+        let progress = Arc::new(ScanProgress::with_ops_limit(self.scan_ops_limit));
+        self.scan_progress = Some(progress.clone());
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.scan_receiver = Some(rx);
+
+        let (snapshot_tx, snapshot_rx) = std::sync::mpsc::channel();
+        self.snapshot_receiver = Some(snapshot_rx);
+
+        let checkpoint = resume_checkpoint_path();
+        let session = if self.restore_last_scan { session_snapshot_path() } else { None };
+        let history_keep = self.auto_snapshot.then_some(self.snapshot_keep);
+        let options = ScanOptions {
+            network: self.network_scan,
+            skip_dirs: own_data_dirs(),
+            max_nodes: self.node_limit,
+            ..self.scan_options.clone()
+        };
+        let (snapshot_secs, checkpoint_secs) = if options.network {
+            (NETWORK_SNAPSHOT_SECS, NETWORK_CHECKPOINT_INTERVAL_SECS)
+        } else {
+            (0, CHECKPOINT_INTERVAL_SECS)
+        };
+        std::thread::spawn(move || {
+            // Read the old checkpoint before this scan starts overwriting it
+            let partial = resume.and_then(|p| load_snapshot(&p).ok()).map(|(_, root)| root);
+            let cancel_flag = progress.clone();
+            let mut last_checkpoint = std::time::Instant::now();
+            let mut last_snapshot: Option<std::time::Instant> = None;
+            // `sent` goes to the UI; `whole` is the tree so far, for the checkpoint. They
+            // differ in an all-drives scan, where only the drive that changed is sent.
+            let mut on_snapshot = |sent: &FileNode, whole: &FileNode| {
+                let cancelled = cancel_flag.cancel.load(Ordering::Relaxed);
+                if cancelled || last_snapshot.is_none_or(|t| t.elapsed().as_secs() >= snapshot_secs) {
+                    let _ = snapshot_tx.send(sent.clone());
+                    last_snapshot = Some(std::time::Instant::now());
+                }
+                let due = last_checkpoint.elapsed().as_secs() >= checkpoint_secs;
+                if (cancelled || due) && whole.children.iter().any(|c| c.is_dir) {
+                    if let Some(ref cp) = checkpoint {
+                        if let Some(dir) = cp.parent() {
+                            let _ = std::fs::create_dir_all(dir);
+                        }
+                        let _ = save_snapshot(whole, false, cp);
+                    }
+                    last_checkpoint = std::time::Instant::now();
+                }
+            };
+            let result = if is_all_drives(&path) {
+                let mut drives = None;
+                scan_all_drives(&local_drive_roots(), progress, partial, &options, |drive| {
+                    let path = drive.path.clone();
+                    let all = merge_drive(&mut drives, drive);
+                    if let Some(drive) = all.children.iter().find(|d| d.path == path) {
+                        on_snapshot(drive, all);
+                    }
+                })
             } else {
-                1.0
+                scan_directory_live(&path, progress, partial, &options, |node| on_snapshot(node, node))
             };
+            // A cancelled scan still returns its partial tree; keep the checkpoint for it
+            let complete = !cancel_flag.cancel.load(Ordering::Relaxed);
+            if let Some(root) = result.as_ref().filter(|_| complete) {
+                if let Some(ref cp) = checkpoint {
+                    let _ = std::fs::remove_file(cp);
+                }
+                if let Some(ref session) = session {
+                    if let Some(dir) = session.parent() {
+                        let _ = std::fs::create_dir_all(dir);
+                    }
+                    let _ = save_snapshot(root, true, session);
+                }
+                if let Some(keep) = history_keep {
+                    let _ = save_to_history(root, keep);
+                }
+            }
+            let _ = tx.send(finish_scan(result));
+        });
+    }
 
-            let layout = WorldLayout::new(root, new_aspect);
-            self.camera.set_world_rect(layout.world_rect);
-            self.world_layout = Some(layout);
+    /// Cancel any scan in flight and clear everything derived from the previous root.
+    fn reset_for_new_root(&mut self, path: PathBuf) {
+        self.save_view_state();
+        self.pending_view_state = self.view_states.iter().find(|s| same_root(&s.root, &path)).cloned();
+        self.pending_camera = None;
+        if let Some(ref prog) = self.scan_progress {
+            prog.cancel.store(true, Ordering::Relaxed);
+        }
+        self.scanning = false;
+        self.scan_receiver = None;
+        self.snapshot_receiver = None;
 
-            // Scale the camera center Y proportionally
-            self.camera.center.y *= y_ratio;
-            self.camera.target_center.y *= y_ratio;
+        // Deferred drops: move old data to background thread for deallocation
+        let old_root = self.scan_root.take();
+        let old_layout = self.world_layout.take();
+        let old_largest = self.cached_largest.take();
+        let old_extensions = self.cached_extensions.take();
+        if old_root.is_some() || old_layout.is_some() {
+            std::thread::spawn(move || {
+                drop(old_root);
+                drop(old_layout);
+                drop(old_largest);
+                drop(old_extensions);
+            });
         }
+
+        self.camera = Camera::new(egui::pos2(0.5, 0.5), 1.0);
+        self.view_mode = ViewMode::Treemap;
+        self.depth_context.clear();
+        self.hovered_node_info = None;
+        self.selection.clear();
+        self.flattened.clear();
+        self.bookmarks = [None; 9];
+        self.scanned_at = None;
+        self.opened_snapshot = None;
+        self.snapshot_drive = None;
+        self.stale_banner = None;
+        self.scan_partial = false;
+        self.tree_nodes = 0;
+        self.memory_warning = None;
+        self.memory_warned = false;
+        self.node_limit_banner = false;
+        self.node_limit_warned = false;
+        self.network_scan = self.scan_options.network || is_network_path(&path);
+        // Free-space queries can block for seconds on a slow share
+        self.scan_drive = if self.network_scan { None } else { drive_space(&path) };
+        self.scan_is_drive_root = self.scan_drive.as_ref().is_some_and(|d| d.mount_point == path);
+        self.space_tiles.clear();
+        self.drive_tiles.clear();
+        self.scan_path = Some(path);
+        self.list_path.clear();
+        self.list_flat_cache = None;
+        self.largest_ext_filter = None;
+        self.cached_duplicates = None;
+        self.dup_receiver = None;
+        self.cached_git_repos = None;
+        self.cached_matching_folders = None;
+        self.cached_archive_candidates = None;
+        self.cached_user_profiles = None;
+        self.cached_name_issues = None;
+        self.cached_cluster_slack = None;
+        self.clear_growth();
+        self.heat = None;
+        self.heat_note = None;
+        self.selected_extension = None;
+        self.age_band = None;
+        self.cached_drives.clear();
+        self.show_drive_picker = false;
+        self.volume_removed = false;
+        self.show_removed_data = false;
+        self.remote = None;
+        self.remote_error_receiver = None;
+        self.auto_paused = None;
+        self.power_busy = None;
+        self.watcher = None;
+        self.watch_dirty.clear();
+        self.watch_job = None;
+        self.watch_overflow = false;
     }
 
-    fn current_prefs(&self) -> Prefs {
-        Prefs {
-            hide_about: self.hide_about_on_start,
-            dark_mode: self.dark_mode,
-            window_x: self.last_window_outer_pos.map(|p| p.x),
-            window_y: self.last_window_outer_pos.map(|p| p.y),
-            window_w: self.last_window_inner_size.map(|s| s.x),
-            window_h: self.last_window_inner_size.map(|s| s.y),
+    /// Scan a quick-scan target. URLs go through the FTP / WebDAV dialog for credentials.
+    fn start_quick_scan(&mut self, target: &str) {
+        if is_scan_url(target) {
+            self.remote_url = target.to_string();
+            self.show_url_dialog = true;
+        } else {
+            self.start_scan(PathBuf::from(target));
         }
     }
 
-    fn update_breadcrumbs(&mut self) {
-        self.depth_context.clear();
-        if let Some(ref layout) = self.world_layout {
-            let chain = layout.ancestor_chain(self.camera.center);
-            for (name, ci, wr) in chain {
-                self.depth_context.push(BreadcrumbEntry {
-                    name: name.to_string(),
-                    color_index: ci,
-                    world_rect: wr,
-                });
-            }
+    /// Pause or resume the running scan. Pausing also checkpoints the latest partial
+    /// tree, so a scan left paused survives the app closing or the machine sleeping.
+    fn set_scan_paused(&mut self, paused: bool) {
+        let Some(ref prog) = self.scan_progress else { return };
+        prog.set_paused(paused);
+        self.auto_paused = None;
+        if !paused || self.remote.is_some() {
+            return;
+        }
+        let partial = self.scan_root.as_ref().filter(|r| r.children.iter().any(|c| c.is_dir));
+        if let (Some(root), Some(cp)) = (partial, resume_checkpoint_path()) {
+            let root = root.clone();
+            std::thread::spawn(move || {
+                if let Some(dir) = cp.parent() {
+                    let _ = std::fs::create_dir_all(dir);
+                }
+                let _ = save_snapshot(&root, false, &cp);
+            });
         }
     }
-}
 
-fn load_image_from_png(ctx: &egui::Context, name: &str, png_data: &[u8]) -> egui::TextureHandle {
-    let img = image::load_from_memory(png_data).expect("Failed to decode PNG");
-    let rgba = img.to_rgba8();
-    let (w, h) = rgba.dimensions();
-    let color_image = egui::ColorImage::from_rgba_unmultiplied(
-        [w as usize, h as usize],
-        rgba.as_raw(),
-    );
-    ctx.load_texture(name, color_image, egui::TextureOptions::LINEAR)
-}
+    /// Swap in a finished scan and kick off background duplicate detection.
+    fn apply_scan_result(&mut self, (result, largest, extensions, time_range): ScanResult) {
+        self.time_range = time_range;
+        self.tree_nodes = result.as_ref().map_or(0, node_count);
+        self.scan_root = result;
+        self.cached_largest = largest;
+        // Build extension color map (sorted by size, largest first)
+        self.ext_color_map.clear();
+        if let Some(ref exts) = extensions {
+            for (i, (ext, _, _)) in exts.iter().enumerate() {
+                self.ext_color_map.insert(ext.clone(), i);
+            }
+        }
+        self.cached_extensions = extensions;
+        self.scanning = false;
+        self.scan_receiver = None;
+        self.snapshot_receiver = None;
+        self.world_layout = None; // Force final layout rebuild
+        self.rematch_quotas();
+        self.restore_view_state();
+        if self.scan_root.is_some() {
+            self.scanned_at.get_or_insert_with(now_secs);
+            if self.remote.is_none() && self.opened_snapshot.is_none() && self.last_scan != self.scan_path {
+                self.last_scan = self.scan_path.clone();
+                save_prefs(&self.current_prefs());
+            }
+        }
 
-impl eframe::App for SpaceViewApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Apply dark/light mode
-        if self.dark_mode {
-            ctx.set_visuals(egui::Visuals::dark());
-        } else {
-            ctx.set_visuals(egui::Visuals::light());
+        if self.heat_overlay {
+            self.start_heat();
         }
 
-        let now = ctx.input(|i| i.time);
-        let dt = if self.last_time > 0.0 {
-            (now - self.last_time) as f32
-        } else {
-            1.0 / 60.0
+        // Start background duplicate detection
+        self.cached_duplicates = None;
+        if let Some(ref root) = self.scan_root {
+            let root_clone = root.clone();
+            let limit = RateLimiter::new(self.hash_mb_limit * 1024 * 1024);
+            let demo = matches!(self.remote, Some(RemoteSource::Demo));
+            let (dup_tx, dup_rx) = std::sync::mpsc::channel();
+            self.dup_receiver = Some(dup_rx);
+            std::thread::spawn(move || {
+                let dups = if demo { demo_duplicates(&root_clone) } else { find_duplicates(&root_clone, &limit) };
+                let _ = dup_tx.send(dups);
+            });
+        }
+        self.update_watcher();
+    }
+
+    /// Remember the view, color mode, camera and List folder of the loaded local root,
+    /// so opening it again returns there.
+    fn save_view_state(&mut self) {
+        let Some(ref root) = self.scan_root else { return };
+        if self.remote.is_some() {
+            return;
+        }
+        // Without a layout the Map was never opened: remember the overview
+        let (center, zoom) = match self.world_layout {
+            Some(ref layout) => {
+                let center = self.camera.target_center;
+                ((center.x, center.y / layout.world_rect.height().max(f32::EPSILON)), self.camera.target_zoom)
+            }
+            None => ((0.5, 0.5), 1.0),
         };
-        self.last_time = now;
+        let state = ViewState {
+            root: root.path.clone(),
+            view: self.view_mode.key().to_string(),
+            color: self.color_mode.key().to_string(),
+            center,
+            zoom,
+            list_path: self.list_path.clone(),
+        };
+        let _ = remember_view_state(&mut self.view_states, state);
+    }
 
-        // Track window position for save-on-exit
-        let vp_info = ctx.input(|i| i.viewport().clone());
-        if let Some(outer) = vp_info.outer_rect {
-            self.last_window_outer_pos = Some(outer.min);
+    /// Apply the remembered state of the root that just finished scanning, unless the
+    /// user already moved on to another view or folder while it ran.
+    fn restore_view_state(&mut self) {
+        let Some(state) = self.pending_view_state.take() else { return };
+        let Some(ref root) = self.scan_root else { return };
+        if self.view_mode != ViewMode::Treemap || !self.list_path.is_empty() {
+            return;
         }
-        if let Some(inner) = vp_info.inner_rect {
-            self.last_window_inner_size = Some(inner.size());
+        if let Some(view) = ViewMode::from_key(&state.view) {
+            self.view_mode = view;
         }
-
-        // Handle drag-and-drop folders
-        let dropped: Vec<_> = ctx.input(|i| {
-            i.raw.dropped_files.iter()
-                .filter_map(|f| f.path.clone())
-                .collect()
-        });
-        if let Some(path) = dropped.into_iter().find(|p| p.is_dir()) {
-            self.start_scan(path);
+        if let Some(color) = ColorMode::from_key(&state.color) {
+            self.color_mode = color;
         }
+        if find_dir_by_path(root, &state.list_path).is_some() {
+            self.list_path = state.list_path;
+        }
+        self.pending_camera = Some((egui::pos2(state.center.0, state.center.1), state.zoom));
+    }
 
-        // Check for scan completion and live snapshots
-        if self.scanning {
-            // Drain live tree snapshots (keep only the newest)
-            if let Some(ref rx) = self.snapshot_receiver {
-                let mut latest = None;
-                while let Ok(snapshot) = rx.try_recv() {
-                    latest = Some(snapshot);
-                }
-                if let Some(tree) = latest {
-                    self.scan_root = Some(tree);
-                    self.world_layout = None; // Force layout rebuild
-                }
+    /// Run a Tools menu job on a background thread, replacing any job in flight.
+    /// While a destructive job runs the new one is refused with a busy message.
+    fn start_tool_job<F>(&mut self, kind: ToolKind, job: F)
+    where
+        F: FnOnce(Arc<ScanProgress>) -> ToolResult + Send + 'static,
+    {
+        if let Some(ref old) = self.tool_job {
+            if old.kind.destructive() {
+                self.info_message = Some(format!(
+                    "SpaceView is still {}. Try again once it has finished.",
+                    old.kind.label().trim_end_matches('.').to_lowercase(),
+                ));
+                return;
             }
+            old.progress.cancel.store(true, Ordering::Relaxed);
+        }
+        self.replaced_jobs.extend(self.tool_job.take());
+        let progress = Arc::new(ScanProgress::new());
+        let (tx, rx) = std::sync::mpsc::channel();
+        let job_progress = progress.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(job(job_progress));
+        });
+        self.tool_job = Some(ToolJob { kind, progress, receiver: rx });
+    }
 
-            // Check for final scan completion
-            if let Some(ref rx) = self.scan_receiver {
-                if let Ok((result, largest, extensions, time_range)) = rx.try_recv() {
-                    self.time_range = time_range;
-                    self.scan_root = result;
-                    self.cached_largest = largest;
-                    // Build extension color map (sorted by size, largest first)
-                    self.ext_color_map.clear();
-                    if let Some(ref exts) = extensions {
-                        for (i, (ext, _, _)) in exts.iter().enumerate() {
-                            self.ext_color_map.insert(ext.clone(), i);
-                        }
-                    }
-                    self.cached_extensions = extensions;
-                    self.scanning = false;
-                    self.scan_receiver = None;
-                    self.snapshot_receiver = None;
-                    self.world_layout = None; // Force final layout rebuild
-
-                    // Start background duplicate detection
-                    self.cached_duplicates = None;
-                    if let Some(ref root) = self.scan_root {
-                        let root_clone = root.clone();
-                        let (dup_tx, dup_rx) = std::sync::mpsc::channel();
-                        self.dup_receiver = Some(dup_rx);
-                        std::thread::spawn(move || {
-                            let dups = find_duplicates(&root_clone);
-                            let _ = dup_tx.send(dups);
-                        });
-                    }
-                }
+    /// Walk a source folder and its backup and diff them.
+    fn start_backup_compare(&mut self, source: PathBuf, backup: PathBuf) {
+        self.start_tool_job(ToolKind::Compare, move |progress| {
+            let left = scan_directory(&source, progress.clone(), &ScanOptions::default());
+            let right = scan_directory(&backup, progress, &ScanOptions::default());
+            match (left, right) {
+                (Some(l), Some(r)) => ToolResult::Diff(DiffReport {
+                    title: "Backup Check".to_string(),
+                    left_label: "source".to_string(),
+                    right_label: "backup".to_string(),
+                    only_left_label: "Missing in backup".to_string(),
+                    only_right_label: "Extra in backup".to_string(),
+                    entries: compare_trees(&l, &r),
+                    folders: Vec::new(),
+                    left_root: source,
+                    right_root: backup,
+                }),
+                _ => ToolResult::Cancelled,
             }
-            ctx.request_repaint();
+        });
+    }
+
+    /// The tree exports are made from: the scan, or a copy with the names
+    /// `export_anonymize` asks to hide replaced.
+    fn export_tree(&self) -> Option<std::borrow::Cow<'_, FileNode>> {
+        let root = self.scan_root.as_ref()?;
+        Some(if self.export_anonymize.any() {
+            std::borrow::Cow::Owned(anonymize(root, self.export_anonymize))
+        } else {
+            std::borrow::Cow::Borrowed(root)
+        })
+    }
+
+    /// Write the top `export_depth` levels of the scan as a Graphviz or Mermaid diagram.
+    fn export_diagram(&mut self, kind: &str, ext: &str) {
+        let Some(root) = self.export_tree() else { return };
+        let out = rfd::FileDialog::new()
+            .set_title(format!("Export {} diagram", kind))
+            .add_filter(kind, &[ext])
+            .set_file_name(format!("{}.{}", export_file_stem(&root.name), ext))
+            .save_file();
+        let Some(out) = out else { return };
+        let text = match ext {
+            "dot" => tree_to_dot(&root, self.export_depth),
+            _ => tree_to_mermaid(&root, self.export_depth),
+        };
+        if let Err(e) = std::fs::write(&out, text) {
+            self.info_message = Some(format!("Could not export diagram: {}", e));
         }
+    }
 
-        // Check for duplicate detection result
-        if let Some(ref rx) = self.dup_receiver {
-            if let Ok(dups) = rx.try_recv() {
-                self.cached_duplicates = Some(dups);
-                self.dup_receiver = None;
-            }
+    /// Write the treemap, `export_depth` levels deep, as a vector image in the current colors.
+    fn export_svg(&mut self) {
+        let Some(root) = self.export_tree() else { return };
+        let out = rfd::FileDialog::new()
+            .set_title("Export treemap")
+            .add_filter("SVG image", &["svg"])
+            .set_file_name(format!("{}.svg", export_file_stem(&root.name)))
+            .save_file();
+        let Some(out) = out else { return };
+        if let Err(e) = std::fs::write(&out, self.treemap_svg(&root)) {
+            self.info_message = Some(format!("Could not export treemap: {}", e));
         }
+    }
 
-        // Check for version update result
-        if let Some(ref rx) = self.update_check_receiver {
-            if let Ok(result) = rx.try_recv() {
-                self.latest_version = result;
-                self.update_check_receiver = None;
-            }
+    /// Save the whole scan as a snapshot for sharing, with names hidden as
+    /// `export_anonymize` asks. Sizes, dates and structure stay.
+    fn export_snapshot(&mut self) {
+        let Some(root) = self.export_tree() else { return };
+        let out = rfd::FileDialog::new()
+            .set_title("Export snapshot")
+            .add_filter("SpaceView snapshot", &["svsnap"])
+            .set_file_name(format!("{}.svsnap", export_file_stem(&root.name)))
+            .save_file();
+        let Some(out) = out else { return };
+        if let Err(e) = save_snapshot(&root, true, &out) {
+            self.info_message = Some(format!("Could not export snapshot: {}", e));
         }
+    }
 
-        // ---- About popup ----
-        let mut escape_consumed = false;
-        if self.show_about && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            self.show_about = false;
-            escape_consumed = true;
+    /// Print the treemap and the largest folders: the page goes to the default browser,
+    /// which brings up the system print dialog as soon as it loads.
+    fn print_report(&mut self) {
+        let Some(ref root) = self.scan_root else { return };
+        let html = print_html(root, &self.treemap_svg(root), PRINT_TOP_FOLDERS);
+        let out = std::env::temp_dir().join("SpaceView-print.html");
+        match std::fs::write(&out, html) {
+            Ok(()) => open_in_explorer(&out),
+            Err(e) => self.info_message = Some(format!("Could not prepare the print page: {}", e)),
         }
-        if self.show_about {
-            // Lazy-load textures on first open
-            if self.icon_texture.is_none() {
-                self.icon_texture = Some(load_image_from_png(
-                    ctx, "app_icon", include_bytes!("../assets/icon.png"),
-                ));
+    }
+
+    /// `root` as SVG, `export_depth` levels deep, in the on-screen aspect and colors.
+    fn treemap_svg(&self, root: &FileNode) -> String {
+        let aspect = self.world_layout.as_ref().map_or(0.625, |w| w.world_rect.height() / w.world_rect.width());
+        let size = egui::vec2(SVG_EXPORT_WIDTH, (SVG_EXPORT_WIDTH * aspect).round());
+        let (theme, color_mode, age_ramp, time_range) = (self.theme, self.color_mode, self.age_ramp, self.time_range);
+        let (ext_colors, high_contrast) = (&self.ext_color_map, self.high_contrast);
+        let growth = self.diff_growth.as_ref();
+        let (growth_of, largest_growth) = (|path: &Path| growth.and_then(|g| g.delta(path)), growth.map_or(0, |g| g.largest));
+        let colors = |node: &FileNode, depth: usize, part: SvgPart| {
+            let fill = match (part, color_mode) {
+                (SvgPart::Header, ColorMode::Growth) => growth_header_color(growth_of(&node.path), largest_growth),
+                (SvgPart::Body, ColorMode::Growth) => growth_body_color(growth_of(&node.path), largest_growth),
+                (SvgPart::Leaf, ColorMode::Growth) => growth_color(growth_of(&node.path), largest_growth),
+                (SvgPart::Header, ColorMode::Age) => age_header_color(node.modified, time_range, age_ramp),
+                (SvgPart::Header, _) => header_color(depth, theme),
+                (SvgPart::Body, ColorMode::Age) => age_body_color(node.modified, time_range, age_ramp),
+                (SvgPart::Body, _) => body_color(depth, theme),
+                (SvgPart::Leaf, ColorMode::Age) => age_color(node.modified, time_range, age_ramp),
+                (SvgPart::Leaf, _) if node.is_dir => dir_color(depth, theme),
+                (SvgPart::Leaf, ColorMode::Extension) => ext_file_color(&node.name, ext_colors, theme),
+                (SvgPart::Leaf, _) => file_color(depth, theme),
+            };
+            (fill, text_color_for(fill, high_contrast))
+        };
+        treemap_to_svg(root, size, self.export_depth, &self.cell_metrics, &colors)
+    }
+
+    /// Load two saved snapshots and diff them (same engine as `--diff` on the command line).
+    fn start_snapshot_compare(&mut self, old: PathBuf, new: PathBuf) {
+        self.start_tool_job(ToolKind::Compare, move |_| {
+            match compare_snapshots(&old, &new) {
+                Ok(report) => ToolResult::Diff(report),
+                Err(e) => ToolResult::Message(format!("Could not compare snapshots: {}", e)),
             }
-            if self.face_texture.is_none() {
-                self.face_texture = Some(load_image_from_png(
-                    ctx, "tront_face", include_bytes!("../assets/tront.png"),
+        });
+    }
+
+    /// Diff a saved snapshot of the current root against the scan on screen: what
+    /// grew, what's new and what's gone, and growth colors for the map.
+    fn start_growth_compare(&mut self, snapshot: PathBuf) {
+        let Some(current) = self.scan_root.clone() else { return };
+        match read_snapshot_info(&snapshot) {
+            Ok(info) if !same_root(&info.root, &current.path) => {
+                self.info_message = Some(format!(
+                    "That snapshot is of {}, not {}.",
+                    info.root.to_string_lossy(),
+                    current.path.to_string_lossy(),
                 ));
+                return;
             }
-
-            let mut open = true;
-            let icon_tex = self.icon_texture.clone();
-            let face_tex = self.face_texture.clone();
-            egui::Window::new("About SpaceView")
+            Ok(_) => {}
+            Err(e) => {
+                self.info_message = Some(format!("Could not open snapshot: {}", e));
+                return;
+            }
+        }
+        self.start_tool_job(ToolKind::Compare, move |_| {
+            match compare_with_snapshot(&snapshot, &current) {
+                Ok((report, growth)) => ToolResult::Growth(report, growth),
+                Err(e) => ToolResult::Message(format!("Could not compare with the snapshot: {}", e)),
+            }
+        });
+    }
+
+    /// Measure how fast each folder grew since the newest history snapshot at least
+    /// `HEAT_MIN_AGE_SECS` older than the scan on screen, for the Heat overlay.
+    fn start_heat(&mut self) {
+        self.heat = None;
+        self.heat_note = None;
+        let Some(current) = self.scan_root.clone().filter(|_| !self.scanning) else { return };
+        if self.remote.is_some() {
+            self.heat_note = Some("Heat needs the snapshot history, which is kept for local scans only");
+            return;
+        }
+        let scanned = self.scanned_at.unwrap_or_else(now_secs);
+        let Some(snapshot) = snapshot_before(&current.path, scanned.saturating_sub(HEAT_MIN_AGE_SECS)) else {
+            self.heat_note = Some(
+                "No snapshot of this folder from an hour or more before this scan. Snapshots are saved \
+                 after every scan (Settings > Snapshot history)",
+            );
+            return;
+        };
+        self.start_tool_job(ToolKind::Growth, move |_| match heat_map(&snapshot, &current, scanned) {
+            Ok(heat) => ToolResult::Heat(heat),
+            Err(e) => ToolResult::Message(format!("Could not measure growth from the snapshot history: {}", e)),
+        });
+    }
+
+    /// Drop the growth colors, which only fit the tree they were computed for.
+    fn clear_growth(&mut self) {
+        self.diff_growth = None;
+        if self.color_mode == ColorMode::Growth {
+            self.color_mode = ColorMode::Depth;
+        }
+    }
+
+    /// Hash every file under `root` and write the baseline to `out`.
+    fn start_baseline_create(&mut self, root: PathBuf, out: PathBuf) {
+        self.start_tool_job(ToolKind::Baseline, move |progress| {
+            match create_baseline(&root, progress) {
+                Some(baseline) => match save_baseline(&baseline, &out) {
+                    Ok(()) => {
+                        let unhashed = baseline.unhashed();
+                        let skipped = if unhashed > 0 {
+                            format!(
+                                " {} could not be read or are offline (cloud) files, and will only be checked by size.",
+                                format_count(unhashed as u64),
+                            )
+                        } else {
+                            String::new()
+                        };
+                        ToolResult::Message(format!(
+                            "Baseline saved: {} files hashed.{}\n{}",
+                            format_count((baseline.entries.len() - unhashed) as u64),
+                            skipped,
+                            out.to_string_lossy(),
+                        ))
+                    }
+                    Err(e) => ToolResult::Message(format!("Could not save baseline: {}", e)),
+                },
+                None => ToolResult::Cancelled,
+            }
+        });
+    }
+
+    /// Re-hash the baseline's folder and diff against the stored hashes.
+    fn start_baseline_verify(&mut self, baseline_path: PathBuf) {
+        let baseline = match load_baseline(&baseline_path) {
+            Ok(b) => b,
+            Err(e) => {
+                self.info_message = Some(format!("Could not load baseline: {}", e));
+                return;
+            }
+        };
+        // The archive may have moved (e.g. different drive letter): ask for the folder
+        let root_override = if baseline.root.is_dir() {
+            None
+        } else {
+            match rfd::FileDialog::new().set_title("Baseline folder not found. Select folder to verify").pick_folder() {
+                Some(p) => Some(p),
+                None => return,
+            }
+        };
+        self.start_tool_job(ToolKind::Verify, move |progress| {
+            match verify_baseline(&baseline, root_override.as_deref(), progress) {
+                Some(report) => ToolResult::Diff(report),
+                None => ToolResult::Cancelled,
+            }
+        });
+    }
+
+    /// Combined (size, files) of the multi-selection. Items inside another selected
+    /// folder are already in its totals and are not counted again.
+    fn selection_totals(&self) -> (u64, u64) {
+        let Some(ref root) = self.scan_root else { return (0, 0) };
+        self.selection.iter()
+            .filter(|p| !self.selection.iter().any(|other| other != *p && p.starts_with(other)))
+            .filter_map(|p| find_node(root, p))
+            .fold((0, 0), |(size, files), node| {
+                (size + node.size, files + if node.is_dir { node.file_count } else { 1 })
+            })
+    }
+
+    /// Bring the latest selected item into sight in the treemap: zoom to the deepest
+    /// laid-out folder holding it, unless the item is already big enough to see.
+    fn reveal_in_map(&mut self, viewport: egui::Rect) {
+        let (Some(path), Some(layout)) = (self.selection.latest(), self.world_layout.as_ref()) else { return };
+        let mut nodes = &layout.root_nodes[..];
+        let mut target = None;
+        while let Some(node) = nodes.iter().find(|n| !n.path.as_os_str().is_empty() && path.starts_with(&n.path)) {
+            if node.path == path {
+                let rect = self.camera.world_to_screen(node.world_rect, viewport);
+                if viewport.intersects(rect) && rect.width().min(rect.height()) >= REVEAL_MIN_PX {
+                    return;
+                }
+                if node.is_dir && node.has_children {
+                    target = Some(node.world_rect);
+                }
+                break;
+            }
+            target = Some(node.world_rect);
+            nodes = &node.children;
+        }
+        if let Some(rect) = target {
+            self.camera.snap_to(rect, viewport);
+        }
+    }
+
+    /// Open the List view at the folder holding the latest selected item.
+    fn reveal_in_list(&mut self) {
+        let (Some(root), Some(path)) = (self.scan_root.as_ref(), self.selection.latest()) else { return };
+        let Some(rel) = path.parent().and_then(|p| p.strip_prefix(&root.path).ok()) else { return };
+        let segments: Vec<String> = rel.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if find_dir_by_path(root, &segments).is_some() {
+            self.list_path = segments;
+        }
+    }
+
+    /// Flatten a directory in place (its children become every file below it) or
+    /// restore it. Sizes are unchanged, so the camera stays where it is.
+    fn toggle_flatten(&mut self, path: &std::path::Path, viewport: egui::Rect) {
+        let Some(dir) = self.scan_root.as_mut().and_then(|root| find_node_mut(root, path)) else { return };
+        if let Some(pos) = self.flattened.iter().position(|(p, _)| p == path) {
+            dir.children = self.flattened.remove(pos).1;
+        } else {
+            let mut files = Vec::new();
+            collect_files_owned(&dir.children, &mut files);
+            if files.is_empty() {
+                return; // Nothing to show, and no way back to Unflatten
+            }
+            files.sort_by_key(|f| std::cmp::Reverse(f.size));
+            let original = std::mem::replace(&mut dir.children, files);
+            self.flattened.push((path.to_path_buf(), original));
+        }
+        self.rebuild_layout_preserving_camera(viewport);
+    }
+
+    /// Scan one folder on its own, with the same Scan Options counted from that folder:
+    /// one the depth limit summarized, so memory stays bounded as the user drills in,
+    /// or one to rescan after changes made outside SpaceView.
+    fn start_expand(&mut self, path: PathBuf) {
+        let options = ScanOptions {
+            network: self.network_scan,
+            skip_dirs: own_data_dirs(),
+            max_nodes: self.node_limit,
+            ..self.scan_options.clone()
+        };
+        self.start_tool_job(ToolKind::Expand, move |progress| {
+            match scan_directory(&path, progress, &options) {
+                Some(node) => ToolResult::Expanded(node),
+                None => ToolResult::Cancelled,
+            }
+        });
+    }
+
+    /// Graft an on-demand folder scan into the tree in place of what it had, and swap
+    /// its files in the Top Files and Types data. Only the folder and its ancestors
+    /// are laid out again.
+    fn apply_expanded(&mut self, subtree: FileNode) {
+        let mut files = Vec::new();
+        collect_all_files(&subtree, &mut files);
+        let added_nodes = node_count(&subtree);
+        let mut old_files = Vec::new();
+        let mut old_nodes = 0;
+        if let Some(old) = self.scan_root.as_ref().and_then(|root| find_node(root, &subtree.path)) {
+            collect_all_files(old, &mut old_files);
+            old_nodes = node_count(old);
+        }
+        if !self.graft_folder(subtree, old_files, files, added_nodes, old_nodes) {
+            self.info_message = Some("That folder is no longer part of the scan.".to_string());
+        }
+    }
+
+    /// Put `subtree` in the tree in place of the folder at its path and bring the
+    /// caches along: `old_files` / `old_nodes` went with the old folder, `files` /
+    /// `nodes` came with the new one. False if the folder isn't in the tree.
+    fn graft_folder(
+        &mut self,
+        subtree: FileNode,
+        old_files: Vec<(String, u64, String)>,
+        files: Vec<(String, u64, String)>,
+        nodes: u64,
+        old_nodes: u64,
+    ) -> bool {
+        let path = subtree.path.clone();
+        let grafted = self.scan_root.as_mut().is_some_and(|root| graft_subtree(root, subtree));
+        let (Some(root), true) = (self.scan_root.as_ref(), grafted) else { return false };
+        self.tree_nodes = (self.tree_nodes + nodes).saturating_sub(old_nodes);
+        self.root_size = root.size;
+        self.root_file_count = root.file_count;
+        if let Some(ref mut exts) = self.cached_extensions {
+            let mut index: std::collections::HashMap<String, usize> = exts.iter().enumerate().map(|(i, e)| (e.0.clone(), i)).collect();
+            for (name, size, _) in &old_files {
+                if let Some(&i) = index.get(&extension_key(name)) {
+                    exts[i].1 = exts[i].1.saturating_sub(*size);
+                    exts[i].2 = exts[i].2.saturating_sub(1);
+                }
+            }
+            exts.retain(|e| e.2 > 0);
+            index = exts.iter().enumerate().map(|(i, e)| (e.0.clone(), i)).collect();
+            for (name, size, _) in &files {
+                let key = extension_key(name);
+                let i = *index.entry(key.clone()).or_insert_with(|| {
+                    exts.push((key, 0, 0));
+                    exts.len() - 1
+                });
+                exts[i].1 += size;
+                exts[i].2 += 1;
+            }
+            exts.sort_by_key(|e| std::cmp::Reverse(e.1));
+            self.ext_color_map = exts.iter().enumerate().map(|(i, e)| (e.0.clone(), i)).collect();
+        }
+        if let Some(ref mut largest) = self.cached_largest {
+            let old: std::collections::HashSet<&str> = old_files.iter().map(|f| f.2.as_str()).collect();
+            let before = largest.len();
+            largest.retain(|f| !old.contains(f.2.as_str()));
+            if largest.len() < before {
+                // Files that had missed the cut may belong in the list now
+                largest.clear();
+                collect_all_files(root, largest);
+            } else {
+                largest.extend(files);
+            }
+            largest.sort_by_key(|f| std::cmp::Reverse(f.1));
+            largest.truncate(LARGEST_FILES);
+        }
+        self.cached_matching_folders = None;
+        self.cached_archive_candidates = None;
+        self.cached_user_profiles = None;
+        self.cached_name_issues = None;
+        self.cached_cluster_slack = None;
+        self.rematch_quotas();
+        self.list_flat_cache = None;
+        match (self.world_layout.as_mut(), self.scan_root.as_ref()) {
+            (Some(layout), Some(root)) => layout.refresh_path(root, &self.space_tiles, &path),
+            _ => self.rebuild_layout_preserving_camera(self.last_viewport),
+        }
+        true
+    }
+
+    /// Start or stop watching the scanned roots to match the Watch for changes
+    /// option. Only finished local scans are watched; snapshots and remote trees
+    /// have nothing on this machine to follow.
+    fn update_watcher(&mut self) {
+        let watchable = self.watch_changes && !self.scanning && self.remote.is_none() && self.opened_snapshot.is_none();
+        let roots: Vec<PathBuf> = match self.scan_root.as_ref().filter(|_| watchable) {
+            Some(root) if is_all_drives(&root.path) => {
+                root.children.iter().filter(|c| c.is_dir).map(|c| c.path.clone()).collect()
+            }
+            Some(root) => vec![root.path.clone()],
+            None => Vec::new(),
+        };
+        self.watcher = if roots.is_empty() { None } else { watch(&roots) };
+        self.watch_dirty.clear();
+        self.watch_last = None;
+        self.watch_job = None;
+        self.watch_overflow = false;
+    }
+
+    /// Collect watched changes and, once they settle, list the folders they touched
+    /// again; graft the listings in as they come back.
+    fn poll_watcher(&mut self, ctx: &egui::Context) {
+        let Some(ref watcher) = self.watcher else { return };
+        loop {
+            match watcher.events.try_recv() {
+                Ok(WatchEvent::Changed(paths)) => {
+                    self.watch_dirty.extend(paths);
+                    self.watch_last = Some(std::time::Instant::now());
+                }
+                Ok(WatchEvent::Overflow) => self.watch_overflow = true,
+                Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                // Every watched root is gone
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.watcher = None;
+                    break;
+                }
+            }
+        }
+        if let Some(ref rx) = self.watch_job {
+            match rx.try_recv() {
+                Ok(updates) => {
+                    self.watch_job = None;
+                    for update in updates {
+                        self.apply_folder_update(update);
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => self.watch_job = None,
+            }
+        }
+        let settled = self.watch_last.is_some_and(|t| t.elapsed() >= WATCH_SETTLE);
+        if settled && self.watch_job.is_none() && !self.watch_dirty.is_empty() {
+            self.refresh_folders();
+        }
+        ctx.request_repaint_after(WATCH_SETTLE);
+    }
+
+    /// List again, on a background thread, the deepest scanned folder holding each
+    /// changed path. Its subfolders are kept as they are unless they went away; ones
+    /// the depth limit summarized are measured again, since the change may be inside.
+    fn refresh_folders(&mut self) {
+        let changed = std::mem::take(&mut self.watch_dirty);
+        let Some(ref root) = self.scan_root else { return };
+        let own = own_data_dirs();
+        let max_depth = self.scan_options.max_depth;
+        // The all-drives root is not a folder: its drives are each at depth 0
+        let offset = usize::from(is_all_drives(&root.path));
+        let mut targets: Vec<FolderRefresh> = Vec::new();
+        for path in &changed {
+            let Some(parent) = path.parent() else { continue };
+            if own.iter().any(|d| parent.starts_with(d)) {
+                continue;
+            }
+            let Some((node, depth)) = listed_folder(root, parent, max_depth, offset) else { continue };
+            if depth < offset || targets.iter().any(|t| t.dir == node.path) {
+                continue;
+            }
+            let depth = depth - offset;
+            let keep = node.children.iter()
+                .filter(|c| c.is_dir && !summarized(c, depth + 1, max_depth))
+                .map(|c| c.path.clone())
+                .collect();
+            targets.push(FolderRefresh { dir: node.path.clone(), depth, keep });
+        }
+        if targets.is_empty() {
+            return;
+        }
+        let options = ScanOptions {
+            skip_dirs: own,
+            max_nodes: self.node_limit,
+            ..self.scan_options.clone()
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.watch_job = Some(rx);
+        std::thread::spawn(move || {
+            let updates = targets.into_iter()
+                .filter(|t| t.dir.is_dir()) // Went away: its parent's listing covers that
+                .filter_map(|t| {
+                    let mut options = ScanOptions {
+                        max_depth: if options.max_depth > 0 { options.max_depth - t.depth } else { 0 },
+                        ..options.clone()
+                    };
+                    options.skip_dirs.extend(t.keep.iter().cloned());
+                    let fresh = scan_directory(&t.dir, Arc::new(ScanProgress::new()), &options)?;
+                    let kept = t.keep.into_iter().filter(|d| d.is_dir()).collect();
+                    Some(FolderUpdate { fresh, kept })
+                })
+                .collect();
+            let _ = tx.send(updates);
+        });
+    }
+
+    /// Graft a watched folder's new listing into the tree, carrying over the
+    /// subfolders it left alone.
+    fn apply_folder_update(&mut self, FolderUpdate { mut fresh, kept }: FolderUpdate) {
+        let mut files = Vec::new();
+        collect_all_files(&fresh, &mut files);
+        let nodes = node_count(&fresh);
+        let mut old_files = Vec::new();
+        let mut old_nodes = 1;
+        let Some(old) = self.scan_root.as_mut().and_then(|root| find_node_mut(root, &fresh.path)) else { return };
+        for child in std::mem::take(&mut old.children) {
+            if child.is_dir && kept.contains(&child.path) {
+                fresh.size += child.size;
+                fresh.allocated += child.allocated;
+                fresh.file_count += child.file_count;
+                fresh.modified = fresh.modified.max(child.modified);
+                fresh.children.push(child);
+            } else {
+                collect_file_or_files(&child, &mut old_files);
+                old_nodes += node_count(&child);
+            }
+        }
+        fresh.name = old.name.clone();
+        fresh.attrs |= old.attrs;
+        fresh.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+        self.graft_folder(fresh, old_files, files, nodes, old_nodes);
+    }
+
+    /// Add a folder to the scan queue, starting the worker thread on first use.
+    /// Queued scans run one at a time so drives aren't thrashed in parallel.
+    fn enqueue_scan(&mut self, path: PathBuf) {
+        let already_queued = self.scan_queue.iter()
+            .any(|q| q.path == path && !matches!(q.state, QueueState::Cancelled));
+        if already_queued {
+            return;
+        }
+        if self.queue_tx.is_none() {
+            let (job_tx, job_rx) = std::sync::mpsc::channel::<QueueJob>();
+            let (event_tx, event_rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                for (idx, path, progress, options) in job_rx {
+                    if progress.cancel.load(Ordering::Relaxed) {
+                        let _ = event_tx.send(QueueEvent::Finished(idx, None, 0.0));
+                        continue;
+                    }
+                    let _ = event_tx.send(QueueEvent::Started(idx));
+                    let start = std::time::Instant::now();
+                    let result = scan_directory(&path, progress, &options).map(|root| Box::new(finish_scan(Some(root))));
+                    let secs = start.elapsed().as_secs_f64();
+                    if event_tx.send(QueueEvent::Finished(idx, result, secs)).is_err() {
+                        break;
+                    }
+                }
+            });
+            self.queue_tx = Some(job_tx);
+            self.queue_events = Some(event_rx);
+        }
+        let progress = Arc::new(ScanProgress::with_ops_limit(self.scan_ops_limit));
+        if let Some(ref tx) = self.queue_tx {
+            let options = ScanOptions {
+                network: self.scan_options.network || is_network_path(&path),
+                skip_dirs: own_data_dirs(),
+                max_nodes: self.node_limit,
+                ..self.scan_options.clone()
+            };
+            let _ = tx.send((self.scan_queue.len(), path.clone(), progress.clone(), options));
+        }
+        self.scan_queue.push(QueuedScan { path, progress, state: QueueState::Pending });
+        self.view_mode = ViewMode::Queue;
+    }
+
+    fn queue_busy(&self) -> bool {
+        self.scan_queue.iter().any(|q| matches!(q.state, QueueState::Pending | QueueState::Running))
+    }
+
+    /// Load a queued scan into the main view. Falls back to rescanning if its tree
+    /// was already handed off.
+    fn open_queued_scan(&mut self, idx: usize) {
+        let path = self.scan_queue[idx].path.clone();
+        let taken = match self.scan_queue[idx].state {
+            QueueState::Done { ref mut result, .. } => result.take(),
+            _ => None,
+        };
+        match taken {
+            Some(result) => {
+                self.reset_for_new_root(path);
+                self.apply_scan_result(*result);
+            }
+            None => self.start_scan(path),
+        }
+    }
+
+    /// Carry out a bulk removal: empty its cache folders (contents only) and send its
+    /// files to the Recycle Bin in batches. Items gone or changed
+    /// since the list was made are left alone. Rescans afterwards if files went.
+    fn start_removal(&mut self, manifest: Manifest) {
+        if manifest.items.iter().any(|i| i.method == DeleteMethod::CacheCleared) {
+            self.cached_app_caches = None;
+        }
+        self.start_tool_job(ToolKind::Delete, move |progress| {
+            let mut removed = Vec::new();
+            let mut files = Vec::new();
+            let (mut folders, mut freed, mut in_use, mut changed) = (0, 0, 0, 0);
+            for item in manifest.items {
+                if progress.cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                if !unchanged(&item) {
+                    changed += 1;
+                } else if item.method == DeleteMethod::CacheCleared {
+                    let (f, s) = clear_dir_contents(&item.path);
+                    folders += 1;
+                    freed += f;
+                    in_use += s;
+                    if f > 0 {
+                        removed.push(Deletion::now(item.path, f, DeleteMethod::CacheCleared));
+                    }
+                    progress.files_scanned.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    files.push(item);
+                }
+            }
+            let mut deleted = 0;
+            for batch in files.chunks(RECYCLE_BATCH) {
+                if progress.cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let paths: Vec<PathBuf> = batch.iter().map(|i| i.path.clone()).collect();
+                match recycle_files(&paths) {
+                    Ok(n) => deleted += n,
+                    Err(e) => return ToolResult::Message(e),
+                }
+                removed.extend(batch.iter()
+                    .filter(|i| !i.path.exists())
+                    .map(|i| Deletion::now(i.path.clone(), i.size, DeleteMethod::RecycleBin)));
+                progress.files_scanned.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            }
+
+            let mut lines = Vec::new();
+            if folders > 0 {
+                lines.push(format!("Freed {} from {} cache folders.", format_size(freed), folders));
+            }
+            if in_use > 0 {
+                lines.push(format!("{} files were in use and skipped.", format_count(in_use)));
+            }
+            if !files.is_empty() {
+                lines.push(format!(
+                    "Sent {} of {} files to the Recycle Bin.",
+                    format_count(deleted as u64), format_count(files.len() as u64),
+                ));
+                if deleted < files.len() {
+                    lines.push(format!("{} were in use, not deleted, or skipped.", format_count((files.len() - deleted) as u64)));
+                }
+            }
+            if changed > 0 {
+                lines.push(format!("{} items were gone or had changed since the list was made and were left alone.", format_count(changed)));
+            }
+            let msg = lines.join("\n");
+            if files.is_empty() {
+                ToolResult::CachesCleared { msg, removed }
+            } else {
+                ToolResult::Deleted { msg, removed }
+            }
+        });
+    }
+
+    /// Zip a folder next to itself, then optionally hand it to the delete confirmation.
+    fn start_compress(&mut self, request: CompressRequest) {
+        self.start_tool_job(ToolKind::Compress, move |progress| {
+            let CompressRequest { dir, dest, delete_after } = request;
+            let summary = match zip_folder(&dir, &dest, &progress) {
+                Ok(Some(summary)) => summary,
+                Ok(None) => return ToolResult::Cancelled,
+                Err(e) => return ToolResult::Message(format!("Could not create {}: {}", dest.to_string_lossy(), e)),
+            };
+            let ratio = if summary.bytes_in > 0 { summary.bytes_out as f64 * 100.0 / summary.bytes_in as f64 } else { 100.0 };
+            let mut msg = format!(
+                "Created {}\n{} files, {} compressed to {} ({:.0}%).",
+                dest.to_string_lossy(), format_count(summary.files),
+                format_size(summary.bytes_in), format_size(summary.bytes_out), ratio,
+            );
+            // Never offer to delete a folder the archive doesn't fully cover
+            let delete = if summary.skipped > 0 {
+                msg += &format!("\n{} items could not be read or are links, and are not in the archive.", format_count(summary.skipped));
+                if delete_after {
+                    msg += " The folder was kept.";
+                }
+                None
+            } else {
+                delete_after.then_some(dir)
+            };
+            ToolResult::Compressed { msg, delete }
+        });
+    }
+
+    fn request_compress(&mut self, dir: PathBuf) {
+        let dest = zip_destination(&dir);
+        self.pending_compress = Some(CompressRequest { dir, dest, delete_after: false });
+    }
+
+    /// Open the Migration Script dialog for the selected folders. Items inside another
+    /// selected folder are left out, since that folder's copy includes them.
+    fn request_migration(&mut self) {
+        let Some(ref root) = self.scan_root else { return };
+        let sources: Vec<MigrationSource> = self.selection.iter()
+            .filter(|p| !self.selection.iter().any(|other| other != *p && p.starts_with(other)))
+            .filter_map(|p| find_node(root, p))
+            .filter(|n| n.is_dir)
+            .map(|n| MigrationSource { path: n.path.clone(), size: n.size, files: n.file_count })
+            .collect();
+        if sources.is_empty() {
+            self.info_message = Some("Select one or more folders to migrate first (Ctrl+click on the map or in a list).".to_string());
+            return;
+        }
+        self.pending_migration = Some(MigrationRequest { sources, dest: String::new(), moving: false });
+    }
+
+    fn action_env(&self) -> ActionEnv {
+        ActionEnv {
+            remote: self.remote.is_some(),
+            scanning: self.scanning,
+            shred_enabled: self.shred_enabled,
+            busy: self.tool_job.is_some(),
+        }
+    }
+
+    /// Facts for the shared item menu on a listed path. Paths outside the loaded tree
+    /// (a backup, another scan's folder) are described as a plain item of `size`.
+    fn path_facts(&self, path: &Path, is_dir: bool, size: u64) -> NodeFacts {
+        let facts = match self.scan_root.as_ref().and_then(|r| find_node(r, path)) {
+            Some(node) if !path.as_os_str().is_empty() => NodeFacts::from_node(node),
+            _ => {
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                NodeFacts::new(&name, path, is_dir, size, 0, false)
+            }
+        };
+        facts.with_selected(self.selection.contains(path))
+            .with_flattened(self.flattened.iter().any(|(f, _)| f == path))
+    }
+
+    /// Carry out a context menu action picked in any view.
+    fn run_node_action(&mut self, ctx: &egui::Context, action: NodeAction, facts: NodeFacts) {
+        let NodeFacts { path, size, .. } = facts;
+        match action {
+            NodeAction::Open => reveal_in_explorer(&path),
+            NodeAction::CopyPath => ctx.copy_text(path.to_string_lossy().to_string()),
+            NodeAction::CopyFile => {
+                if let Err(e) = copy_files(std::slice::from_ref(&path)) {
+                    self.info_message = Some(e);
+                }
+            }
+            NodeAction::ComputeHash => self.request_hash(path, size),
+            NodeAction::TypeBreakdown => self.request_type_breakdown(&path),
+            NodeAction::Flatten => self.toggle_flatten(&path, self.last_viewport),
+            NodeAction::ScanContents | NodeAction::Rescan => self.start_expand(path),
+            NodeAction::Compress => self.request_compress(path),
+            NodeAction::ToggleSelection => self.selection.toggle(path),
+            NodeAction::Delete => self.pending_delete = Some(path),
+            NodeAction::Shred => self.request_shred(path, size),
+        }
+    }
+
+    /// Open the Migration Script dialog for every Archive Candidates folder, as a move.
+    fn request_archive_migration(&mut self) {
+        let Some(ref candidates) = self.cached_archive_candidates else { return };
+        let sources = candidates.iter()
+            .map(|c| MigrationSource { path: c.path.clone(), size: c.size, files: c.file_count })
+            .collect();
+        self.pending_migration = Some(MigrationRequest { sources, dest: String::new(), moving: true });
+    }
+
+    /// Cluster size the Cluster Slack report uses: the one picked, else the volume's.
+    fn slack_cluster_size(&self) -> u64 {
+        match self.slack_cluster {
+            0 => self.detected_cluster.unwrap_or(DEFAULT_CLUSTER_SIZE),
+            picked => picked,
+        }
+    }
+
+    /// Save the Archive Candidates as CSV, for planning the move outside SpaceView.
+    fn export_archive_list(&mut self) {
+        let Some(ref candidates) = self.cached_archive_candidates else { return };
+        let out = rfd::FileDialog::new()
+            .set_title("Export archive candidates")
+            .add_filter("CSV", &["csv"])
+            .set_file_name("archive-candidates.csv")
+            .save_file();
+        let Some(out) = out else { return };
+        let mut csv = String::from("path,bytes,files,last_modified_utc\n");
+        for c in candidates {
+            csv += &format!(
+                "\"{}\",{},{},{}\n",
+                c.path.to_string_lossy().replace('"', "\"\""), c.size, c.file_count, iso_date_time(c.modified),
+            );
+        }
+        if let Err(e) = std::fs::write(&out, csv) {
+            self.info_message = Some(format!("Could not export list: {}", e));
+        }
+    }
+
+    /// Save a bulk removal's dry run as CSV, for approval before it runs.
+    fn export_manifest(&mut self, manifest: &Manifest) {
+        let out = rfd::FileDialog::new()
+            .set_title("Export deletion manifest")
+            .add_filter("CSV", &["csv"])
+            .set_file_name("deletion-manifest.csv")
+            .save_file();
+        let Some(out) = out else { return };
+        if let Err(e) = std::fs::write(&out, manifest.to_csv()) {
+            self.info_message = Some(format!("Could not export manifest: {}", e));
+        }
+    }
+
+    /// Load an exported (approved) manifest into the dry run window, to run it.
+    fn open_manifest(&mut self, csv: PathBuf) {
+        let title = csv.file_name().map_or_else(String::new, |n| n.to_string_lossy().to_string());
+        let parsed = std::fs::read_to_string(&csv)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Manifest::parse_csv(title, &text));
+        match parsed {
+            Ok(manifest) => {
+                self.manifest_review = Some(manifest);
+                self.manifest_confirmed = false;
+            }
+            Err(e) => self.info_message = Some(format!("Could not read manifest {}: {}", csv.to_string_lossy(), e)),
+        }
+    }
+
+    /// Write the migration script where the user picks. Nothing is copied here.
+    fn save_migration_script(&mut self, request: &MigrationRequest) {
+        let ext = script_extension();
+        let out = rfd::FileDialog::new()
+            .set_title("Save migration script")
+            .add_filter(if ext == "cmd" { "Batch file" } else { "Shell script" }, &[ext])
+            .set_file_name(format!("migrate.{}", ext))
+            .save_file();
+        let Some(out) = out else { return };
+        let dest = PathBuf::from(request.dest.trim());
+        let total: u64 = request.sources.iter().map(|s| s.size).sum();
+        let header = [
+            format!("Generated by SpaceView {} on {} UTC.", env!("CARGO_PKG_VERSION"), iso_date_time(now_secs())),
+            format!(
+                "{} {} folders ({}) to {}.",
+                if request.moving { "Moves" } else { "Copies" },
+                request.sources.len(),
+                format_size(total),
+                dest.to_string_lossy(),
+            ),
+            "Review before running: nothing has been copied yet.".to_string(),
+        ];
+        let script = migration_script(&request.sources, &dest, request.moving, &header);
+        if let Err(e) = std::fs::write(&out, script) {
+            self.info_message = Some(format!("Could not save {}: {}", out.to_string_lossy(), e));
+            return;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&out, std::fs::Permissions::from_mode(0o755));
+        }
+        self.info_message = Some(format!("Saved {}.\nReview it, then run it yourself.", out.to_string_lossy()));
+        reveal_in_explorer(&out);
+    }
+
+    /// Open the File Hash dialog and start with SHA-256, the usual published checksum.
+    fn request_hash(&mut self, path: PathBuf, size: u64) {
+        self.file_hash = Some(FileHash { path: path.clone(), size, kind: Checksum::Sha256, results: Vec::new(), expected: String::new() });
+        self.start_hash(path, Checksum::Sha256);
+    }
+
+    fn start_hash(&mut self, path: PathBuf, kind: Checksum) {
+        self.start_tool_job(ToolKind::Hash, move |progress| {
+            match checksum_file(&path, kind, &progress) {
+                Ok(Some(hex)) => ToolResult::Hashed { path, kind, hex },
+                Ok(None) => ToolResult::Cancelled,
+                Err(e) => ToolResult::Message(format!("Could not read {}: {}", path.to_string_lossy(), e)),
+            }
+        });
+    }
+
+    /// Open the Type Breakdown window for a folder. The stats come from a copy of its
+    /// subtree walked on a worker thread, so large folders don't stall the UI.
+    fn request_type_breakdown(&mut self, path: &Path) {
+        let Some(node) = self.scan_root.as_ref().and_then(|root| find_node(root, path)) else { return };
+        let subtree = node.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut files = Vec::new();
+            collect_all_files(&subtree, &mut files);
+            let _ = tx.send(extension_stats(&files));
+        });
+        self.type_breakdown = Some(TypeBreakdown {
+            path: path.to_path_buf(),
+            name: node.name.clone(),
+            size: node.size,
+            receiver: Some(rx),
+            stats: Vec::new(),
+        });
+    }
+
+    /// Load a quota CSV and overlay it on the tree. `announce` reports the result;
+    /// the silent reload at startup only complains if the file is gone.
+    fn import_quotas(&mut self, csv: PathBuf, announce: bool) {
+        let text = match std::fs::read_to_string(&csv) {
+            Ok(text) => text,
+            Err(e) => {
+                self.info_message = Some(format!("Could not read quota file {}: {}", csv.to_string_lossy(), e));
+                return;
+            }
+        };
+        let (quotas, skipped) = parse_quota_csv(&text);
+        self.quotas = quotas;
+        if self.quota_csv.as_ref() != Some(&csv) {
+            self.quota_csv = Some(csv);
+            save_prefs(&self.current_prefs());
+        }
+        self.rematch_quotas();
+        if announce {
+            let mut msg = format!(
+                "Imported {} quotas, {} on folders in this scan.",
+                format_count(self.quotas.len() as u64),
+                format_count(self.quota_matches.len() as u64),
+            );
+            if skipped > 0 {
+                msg += &format!("\n{} lines were not `path,quota` and were skipped.", format_count(skipped as u64));
+            }
+            self.info_message = Some(msg);
+        }
+    }
+
+    fn clear_quotas(&mut self) {
+        self.quotas.clear();
+        self.quota_matches.clear();
+        self.quota_csv = None;
+        save_prefs(&self.current_prefs());
+    }
+
+    /// Find the imported quotas' folders in the current tree.
+    fn rematch_quotas(&mut self) {
+        self.quota_matches = match self.scan_root {
+            Some(ref root) if !self.quotas.is_empty() => match_quotas(root, &self.quotas),
+            _ => std::collections::HashMap::new(),
+        };
+    }
+
+    /// Add finished deletions to the ledger, and to the log file when it is kept.
+    fn record_deletions(&mut self, removed: Vec<Deletion>) {
+        if self.keep_deletion_log {
+            if let Err(e) = append_ledger(&removed) {
+                self.info_message = Some(format!("Could not write the deletion log: {}", e));
+            }
+        }
+        self.deletions.extend(removed);
+    }
+
+    /// Bytes deleted through SpaceView in the last `secs` seconds.
+    fn reclaimed_within(&self, secs: u64) -> u64 {
+        let since = now_secs().saturating_sub(secs);
+        self.deletions.iter().filter(|d| d.time >= since).map(|d| d.size).sum()
+    }
+
+    /// Turn the persistent log on or off. Turning it on writes this session's
+    /// deletions so far and brings back earlier sessions'; off keeps the file but
+    /// shows this session only.
+    fn set_keep_deletion_log(&mut self, keep: bool) {
+        self.keep_deletion_log = keep;
+        save_prefs(&self.current_prefs());
+        let session = self.deletions.split_off(self.session_deletions);
+        if keep {
+            let written = append_ledger(&session);
+            self.deletions = load_ledger();
+            match written {
+                // The file now ends with this session's entries; keep the in-memory ones
+                Ok(()) => self.deletions.truncate(self.deletions.len().saturating_sub(session.len())),
+                Err(e) => self.info_message = Some(format!("Could not write the deletion log: {}", e)),
+            }
+        } else {
+            self.deletions.clear();
+        }
+        self.session_deletions = self.deletions.len();
+        self.deletions.extend(session);
+    }
+
+    /// Open the Shred confirmation, defaulting to multiple passes only on spinning disks.
+    fn request_shred(&mut self, path: PathBuf, size: u64) {
+        // The drive with the longest mount point containing the file
+        let hdd = self.cached_drives.iter()
+            .filter(|d| path.starts_with(&d.mount_point))
+            .max_by_key(|d| d.mount_point.len())
+            .is_some_and(|d| d.kind == "HDD");
+        let passes = if hdd { SHRED_PASSES_HDD } else { 1 };
+        self.pending_shred = Some(ShredRequest { path, size, hdd, passes, understood: false });
+    }
+
+    /// Send one file or folder to the Recycle Bin through PowerShell and wait for it.
+    /// It is logged only if the delete succeeded and the item is gone, so one that
+    /// was locked or cancelled in the error dialog doesn't count as reclaimed.
+    #[cfg(target_os = "windows")]
+    fn start_recycle(&mut self, path: PathBuf) {
+        let size = self.scan_root.as_ref().and_then(|r| find_node(r, &path)).map_or(0, |n| n.size);
+        self.start_tool_job(ToolKind::Delete, move |_| {
+            // The shell doesn't take `\\?\` paths, so over-long ones go by their 8.3 alias
+            let path_str = crate::paths::shell_path(&path).to_string_lossy().to_string();
+            let script = format!(
+                "Add-Type -AssemblyName Microsoft.VisualBasic; [Microsoft.VisualBasic.FileIO.FileSystem]::DeleteFile('{}', 'OnlyErrorDialogs', 'SendToRecycleBin')",
+                path_str.replace('\'', "''")
+            );
+            let status = std::process::Command::new("powershell")
+                .args(["-NoProfile", "-Command", &script])
+                .status();
+            let name = path.to_string_lossy().to_string();
+            if status.is_ok_and(|s| s.success()) && !path.exists() {
+                ToolResult::Deleted {
+                    msg: format!("Sent {} to the Recycle Bin.", name),
+                    removed: vec![Deletion::now(path, size, DeleteMethod::RecycleBin)],
+                }
+            } else {
+                ToolResult::Message(format!("{} could not be sent to the Recycle Bin.", name))
+            }
+        });
+    }
+
+    fn start_shred(&mut self, path: PathBuf, size: u64, passes: usize) {
+        self.start_tool_job(ToolKind::Shred, move |progress| {
+            let name = path.to_string_lossy().to_string();
+            match shred_file(&path, passes, &progress) {
+                Ok(true) => ToolResult::Deleted {
+                    msg: format!("Shredded {} ({} pass{}).", name, passes, if passes == 1 { "" } else { "es" }),
+                    removed: vec![Deletion::now(path, size, DeleteMethod::Shredded)],
+                },
+                Ok(false) => ToolResult::Message(format!(
+                    "Shredding cancelled. {} was partly overwritten and not deleted.", name,
+                )),
+                Err(e) => ToolResult::Message(format!("Could not shred {}: {}", name, e)),
+            }
+        });
+    }
+
+    /// Full screen treemap with larger labels for a wall display. Esc leaves.
+    fn enter_presentation(&mut self, ctx: &egui::Context) {
+        self.view_mode = ViewMode::Treemap;
+        self.context_menu_info = None;
+        self.presentation = Some(Presentation { zoom_factor: ctx.zoom_factor(), started: std::time::Instant::now() });
+        ctx.set_zoom_factor(ctx.zoom_factor() * PRESENT_ZOOM);
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+    }
+
+    fn exit_presentation(&mut self, ctx: &egui::Context) {
+        if let Some(presentation) = self.presentation.take() {
+            ctx.set_zoom_factor(presentation.zoom_factor);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
+        }
+    }
+
+    /// Whether the free space tile is on for the current root.
+    fn free_space_shown(&self) -> bool {
+        if self.all_drives_scan() {
+            !self.hide_free_drives.iter().any(|m| is_all_drives(m))
+        } else if self.scan_is_drive_root {
+            self.scan_drive.as_ref().is_none_or(|d| !self.hide_free_drives.contains(&d.mount_point))
+        } else {
+            self.show_free_space_folder
+        }
+    }
+
+    /// Whether the loaded root is an all-drives scan (one child per drive).
+    fn all_drives_scan(&self) -> bool {
+        self.scan_path.as_deref().is_some_and(is_all_drives)
+    }
+
+    /// Size the map lays out: the tree plus the free space / unknown tiles.
+    fn map_total(&self) -> u64 {
+        let tiles = self.space_tiles.iter().chain(self.drive_tiles.iter().flat_map(|(_, tiles)| tiles));
+        self.root_size + tiles.map(|t| t.size).sum::<u64>()
+    }
+
+    fn build_layout(&mut self, viewport: egui::Rect) {
+        // Skip free space during live scanning (changes every frame)
+        let show_free = self.free_space_shown() && !self.scanning && (!self.network_scan || self.snapshot_drive.is_some());
+        if let Some(ref mut root) = self.scan_root {
+            // Trees saved while the tiles were still injected into the root may hold them
+            if root.children.iter().any(|c| is_space_tile(&c.name)) {
+                root.size -= root.children.iter().filter(|c| is_space_tile(&c.name)).map(|c| c.size).sum::<u64>();
+                root.children.retain(|c| !is_space_tile(&c.name));
+            }
+            self.space_tiles.clear();
+            // An opened snapshot shows the drive as it was when scanned
+            let drive = self.snapshot_drive.clone().or_else(|| self.scan_path.as_deref().and_then(drive_space));
+            if let Some(drive) = drive.filter(|d| show_free && d.available > 0) {
+                self.space_tiles = space_tiles(&drive, root.size, self.scan_is_drive_root);
+            }
+            // All drives: each drive gets its own tiles, inside its block
+            self.drive_tiles.clear();
+            if show_free && is_all_drives(&root.path) && self.opened_snapshot.is_none() {
+                for child in root.children.iter().filter(|c| c.is_dir) {
+                    if let Some(drive) = drive_space(&child.path).filter(|d| d.mount_point == child.path && d.available > 0) {
+                        self.drive_tiles.push((child.path.clone(), space_tiles(&drive, child.size, true)));
+                    }
+                }
+            }
+
+            let aspect = viewport.height() / viewport.width();
+            let layout = WorldLayout::new(root, &self.space_tiles, self.drive_tiles.clone(), aspect, self.size_on_disk);
+            self.camera.reset(layout.world_rect);
+            self.camera.set_world_rect(layout.world_rect);
+            // Live layouts are rebuilt all through a scan; wait for the finished tree
+            if let Some((center, zoom)) = self.pending_camera.take_if(|_| !self.scanning) {
+                let center = egui::pos2(center.x, center.y * layout.world_rect.height());
+                self.camera.snap_to_view(center, zoom, viewport);
+            }
+            self.world_layout = Some(layout);
+            self.root_name = root.name.clone();
+            self.root_size = root.size;
+            self.root_file_count = root.file_count;
+        }
+    }
+
+    /// Pull a restored window back onto a connected monitor if the saved position is
+    /// off-screen (monitor unplugged, resolution changed). Works in physical pixels.
+    fn fit_window_to_monitors(&self, ctx: &egui::Context, outer: egui::Rect, inner: egui::Rect, ppp: f32) {
+        let Some(areas) = monitor_work_areas() else { return };
+        let physical = egui::Rect::from_min_max((outer.min.to_vec2() * ppp).to_pos2(), (outer.max.to_vec2() * ppp).to_pos2());
+        let fitted = fit_to_monitors(physical, &areas);
+        if fitted == physical {
+            return;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition((fitted.min.to_vec2() / ppp).to_pos2()));
+        if fitted.size() != physical.size() {
+            let decorations = outer.size() - inner.size();
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(fitted.size() / ppp - decorations));
+        }
+    }
+
+    fn rebuild_layout_preserving_camera(&mut self, viewport: egui::Rect) {
+        if let Some(ref root) = self.scan_root {
+            let old_aspect = self.world_layout.as_ref()
+                .map(|l| l.world_rect.height() / l.world_rect.width())
+                .unwrap_or(1.0);
+            let new_aspect = viewport.height() / viewport.width();
+
+            // Remap camera center.y proportionally
+            let y_ratio = if old_aspect > 0.0 {
+                new_aspect / old_aspect
+            } else {
+                1.0
+            };
+
+            let layout = WorldLayout::new(root, &self.space_tiles, self.drive_tiles.clone(), new_aspect, self.size_on_disk);
+            self.camera.set_world_rect(layout.world_rect);
+            self.world_layout = Some(layout);
+
+            // Scale the camera center Y proportionally
+            self.camera.center.y *= y_ratio;
+            self.camera.target_center.y *= y_ratio;
+            for (center, _) in self.bookmarks.iter_mut().flatten() {
+                center.y *= y_ratio;
+            }
+        }
+    }
+
+    fn current_prefs(&self) -> Prefs {
+        Prefs {
+            hide_about: self.hide_about_on_start,
+            dark_mode: self.dark_mode,
+            window_x: self.last_window_outer_pos.map(|p| p.x),
+            window_y: self.last_window_outer_pos.map(|p| p.y),
+            window_w: self.last_window_inner_size.map(|s| s.x),
+            window_h: self.last_window_inner_size.map(|s| s.y),
+            theme: self.theme,
+            age_ramp: self.age_ramp,
+            high_contrast: self.high_contrast,
+            label_density: self.label_density,
+            cell_metrics: self.cell_metrics,
+            restore_last_scan: self.restore_last_scan,
+            last_scan: self.last_scan.clone(),
+            stale_after_mins: self.stale_after_mins,
+            auto_rescan_stale: self.auto_rescan_stale,
+            scan_options: self.scan_options.clone(),
+            scan_ops_limit: self.scan_ops_limit,
+            hash_mb_limit: self.hash_mb_limit,
+            remote_addr: self.remote_addr.clone(),
+            remote_path: self.remote_path.clone(),
+            remote_url: self.remote_url.clone(),
+            export_depth: self.export_depth,
+            auto_pause: self.auto_pause,
+            watch_changes: self.watch_changes,
+            quick_scans: self.quick_scans.clone(),
+            shred_enabled: self.shred_enabled,
+            auto_snapshot: self.auto_snapshot,
+            snapshot_keep: self.snapshot_keep,
+            memory_limit_mb: self.memory_limit_mb,
+            node_limit: self.node_limit,
+            hover_delay_ms: self.hover_delay_ms,
+            tooltip_style: self.tooltip_style,
+            touch_mode: self.touch_mode,
+            hide_free_drives: self.hide_free_drives.clone(),
+            show_free_space_folder: self.show_free_space_folder,
+            heat_overlay: self.heat_overlay,
+            size_on_disk: self.size_on_disk,
+            keep_deletion_log: self.keep_deletion_log,
+            quota_csv: self.quota_csv.clone(),
+            archive_months: self.archive_months,
+            export_anonymize: self.export_anonymize,
+            report_short_names: self.report_short_names,
+        }
+    }
+
+    fn touch_active(&self) -> bool {
+        match self.touch_mode {
+            TouchMode::Auto => self.touch_seen,
+            TouchMode::On => true,
+            TouchMode::Off => false,
+        }
+    }
+
+    /// Cell sizes as drawn: touch mode raises the header height so folder headers
+    /// are big enough to tap.
+    fn shown_metrics(&self) -> CellMetrics {
+        let mut metrics = self.cell_metrics;
+        if self.touch_active() {
+            metrics.header_px = metrics.header_px.max(TOUCH_HEADER_PX);
+        }
+        metrics
+    }
+
+    fn update_breadcrumbs(&mut self) {
+        self.depth_context.clear();
+        if let Some(ref layout) = self.world_layout {
+            let chain = layout.ancestor_chain(self.camera.center);
+            for node in chain {
+                self.depth_context.push(BreadcrumbEntry {
+                    name: node.name.clone(),
+                    path: node.path.clone(),
+                    size: node.size,
+                    color_index: node.color_index,
+                    world_rect: node.world_rect,
+                });
+            }
+        }
+    }
+}
+
+fn load_image_from_png(ctx: &egui::Context, name: &str, png_data: &[u8]) -> egui::TextureHandle {
+    let img = image::load_from_memory(png_data).expect("Failed to decode PNG");
+    let rgba = img.to_rgba8();
+    let (w, h) = rgba.dimensions();
+    let color_image = egui::ColorImage::from_rgba_unmultiplied(
+        [w as usize, h as usize],
+        rgba.as_raw(),
+    );
+    ctx.load_texture(name, color_image, egui::TextureOptions::LINEAR)
+}
+
+impl eframe::App for SpaceViewApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Apply dark/light mode
+        let mut visuals = if self.dark_mode { egui::Visuals::dark() } else { egui::Visuals::light() };
+        if self.high_contrast {
+            visuals.override_text_color = Some(if self.dark_mode { egui::Color32::WHITE } else { egui::Color32::BLACK });
+        }
+        ctx.set_visuals(visuals);
+
+        // Touch layout: bigger controls and text, set up again only when it changes
+        if !self.touch_seen && ctx.input(|i| i.raw.events.iter().any(|e| matches!(e, egui::Event::Touch { .. }))) {
+            self.touch_seen = true;
+        }
+        let touch_layout = self.touch_active();
+        if self.touch_styled != Some(touch_layout) {
+            self.touch_styled = Some(touch_layout);
+            ctx.all_styles_mut(|style| touch_style(style, touch_layout));
+        }
+
+        let now = ctx.input(|i| i.time);
+        let dt = if self.last_time > 0.0 {
+            (now - self.last_time) as f32
+        } else {
+            1.0 / 60.0
+        };
+        self.last_time = now;
+
+        // Presentation mode ends on Esc, or when there's no map left to show
+        if self.presentation.is_some()
+            && (self.scan_root.is_none() || ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)))
+        {
+            self.exit_presentation(ctx);
+        }
+        let presenting = self.presentation.is_some();
+
+        // View shortcuts (not while typing, and the presentation stays on the map)
+        if !presenting && self.scan_root.is_some() && !ctx.wants_keyboard_input() {
+            if let Some(view) = ctx.input_mut(|i| view_shortcut(i, self.view_mode)) {
+                self.view_mode = view;
+            }
+        }
+
+        // Track window position for save-on-exit (not the full screen presentation window)
+        let vp_info = ctx.input(|i| i.viewport().clone());
+        if let (Some(outer), false) = (vp_info.outer_rect, presenting) {
+            self.last_window_outer_pos = Some(outer.min);
+        }
+        if let (Some(inner), false) = (vp_info.inner_rect, presenting) {
+            self.last_window_inner_size = Some(inner.size());
+        }
+        let ppp = ctx.pixels_per_point();
+        if !self.window_placement_checked {
+            if let (Some(outer), Some(inner)) = (vp_info.outer_rect, vp_info.inner_rect) {
+                self.window_placement_checked = true;
+                self.fit_window_to_monitors(ctx, outer, inner, ppp);
+            }
+        }
+        // Moved to a monitor with a different scale. Treemap sizes are in points, so egui
+        // rescales them; only state cached in screen coordinates needs dropping.
+        if ppp != self.last_pixels_per_point {
+            if self.last_pixels_per_point > 0.0 {
+                self.hovered_node_info = None;
+                if self.last_viewport.is_positive() {
+                    self.rebuild_layout_preserving_camera(self.last_viewport);
+                }
+                ctx.request_repaint();
+            }
+            self.last_pixels_per_point = ppp;
+        }
+
+        // Handle drag-and-drop folders
+        let dropped: Vec<_> = ctx.input(|i| {
+            i.raw.dropped_files.iter()
+                .filter_map(|f| f.path.clone())
+                .collect()
+        });
+        if let Some(path) = dropped.into_iter().find(|p| p.is_dir()) {
+            self.start_scan(path);
+        }
+
+        // Stale data check when the window regains focus
+        let focused = ctx.input(|i| i.focused);
+        if focused && !self.was_focused {
+            self.check_stale_on_focus();
+        }
+        self.was_focused = focused;
+
+        // Auto-pause: stop on battery or under a fullscreen app (unless that's us, focused),
+        // resume once it clears. Acts on changes only, so a manual Resume sticks.
+        let power_due = self.last_power_check.is_none_or(|t| t.elapsed().as_secs() >= POWER_POLL_SECS);
+        if self.scanning && self.auto_pause && self.remote.is_none() && power_due {
+            self.last_power_check = Some(std::time::Instant::now());
+            let busy = if on_battery() {
+                Some("on battery")
+            } else if !focused && !presenting && fullscreen_app() {
+                Some("fullscreen app running")
+            } else {
+                None
+            };
+            if busy != self.power_busy {
+                let paused = self.scan_progress.as_ref().is_some_and(|p| p.paused.load(Ordering::Relaxed));
+                match busy {
+                    Some(reason) if !paused => {
+                        self.set_scan_paused(true);
+                        self.auto_paused = Some(reason);
+                    }
+                    None if self.auto_paused.is_some() => self.set_scan_paused(false),
+                    _ => {}
+                }
+                self.power_busy = busy;
+            }
+        }
+
+        // Own memory: polled while scanning (for the warning) and while About shows it
+        let memory_due = self.last_memory_check.is_none_or(|t| t.elapsed().as_secs() >= MEMORY_POLL_SECS);
+        if (self.scanning || self.show_about) && memory_due {
+            self.last_memory_check = Some(std::time::Instant::now());
+            self.memory_rss = process_rss();
+            let limit = self.memory_limit_mb as f64 * 1024.0 * 1024.0;
+            let near_limit = self.memory_rss.filter(|rss| *rss as f64 >= limit * MEMORY_WARN_FRACTION);
+            if let (Some(rss), true, false) = (near_limit, self.scanning && limit > 0.0, self.memory_warned) {
+                self.memory_warning = Some(rss);
+                self.memory_warned = true;
+            }
+        }
+        let aggregated = self.scan_progress.as_ref().is_some_and(|p| p.aggregated.load(Ordering::Relaxed));
+        if self.scanning && aggregated && !self.node_limit_warned {
+            self.node_limit_banner = true;
+            self.node_limit_warned = true;
+        }
+
+        // Check for scan completion and live snapshots
+        if self.scanning {
+            // Drain live tree snapshots (keep only the newest). An all-drives scan
+            // sends one drive at a time, so each is merged into the tree instead.
+            if let Some(ref rx) = self.snapshot_receiver {
+                let all_drives = self.all_drives_scan();
+                let mut changed = false;
+                while let Ok(snapshot) = rx.try_recv() {
+                    if all_drives {
+                        merge_drive(&mut self.scan_root, snapshot);
+                    } else {
+                        self.scan_root = Some(snapshot);
+                    }
+                    changed = true;
+                }
+                if changed {
+                    self.world_layout = None; // Force layout rebuild
+                }
+            }
+
+            // Check for final scan completion
+            let finished = self.scan_receiver.as_ref().and_then(|rx| rx.try_recv().ok());
+            if let Some(result) = finished {
+                let cancelled = self.scan_progress.as_ref().is_some_and(|p| p.cancel.load(Ordering::Relaxed));
+                self.apply_scan_result(result);
+                self.scan_partial = cancelled && self.scan_root.as_ref().is_some_and(is_partial);
+                // Cancelled: the scan thread just checkpointed what it had
+                self.resume_offer = if self.scan_root.is_none() || self.scan_partial { pending_resume() } else { None };
+            }
+            ctx.request_repaint();
+        }
+
+        // Remote scan failures (the scan itself just ends empty)
+        if let Some(error) = self.remote_error_receiver.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.info_message = Some(format!("Remote scan failed: {}", error));
+            self.remote_error_receiver = None;
+        }
+
+        // Check for duplicate detection result
+        if let Some(ref rx) = self.dup_receiver {
+            if let Ok(dups) = rx.try_recv() {
+                self.cached_dup_folders = duplicate_folder_pairs(&dups);
+                self.cached_duplicates = Some(dups);
+                self.dup_receiver = None;
+            }
+        }
+
+        self.poll_watcher(ctx);
+
+        // A replaced job may have removed files before it saw the cancel: log them anyway
+        let mut removed = Vec::new();
+        self.replaced_jobs.retain(|job| match job.receiver.try_recv() {
+            Ok(ToolResult::Deleted { removed: r, .. } | ToolResult::CachesCleared { removed: r, .. }) => {
+                removed.extend(r);
+                false
+            }
+            Ok(_) | Err(std::sync::mpsc::TryRecvError::Disconnected) => false,
+            Err(std::sync::mpsc::TryRecvError::Empty) => true,
+        });
+        if !removed.is_empty() {
+            self.record_deletions(removed);
+        }
+        if !self.replaced_jobs.is_empty() {
+            ctx.request_repaint();
+        }
+
+        // Check for Tools menu job result
+        if let Some(ref job) = self.tool_job {
+            match job.receiver.try_recv() {
+                Ok(result) => {
+                    match result {
+                        ToolResult::Diff(report) => {
+                            self.diff_report = Some(report);
+                            self.diff_filter = None;
+                            self.diff_folders = false;
+                            self.diff_sort = None;
+                            self.clear_growth();
+                            self.view_mode = ViewMode::Diff;
+                        }
+                        ToolResult::Growth(report, growth) => {
+                            self.diff_report = Some(report);
+                            self.diff_filter = None;
+                            self.diff_folders = true;
+                            self.diff_sort = None;
+                            self.diff_growth = Some(growth);
+                            self.view_mode = ViewMode::Diff;
+                        }
+                        ToolResult::Heat(heat) => self.heat = Some(heat),
+                        ToolResult::AppCaches(caches) => {
+                            // Preselect caches whose app is closed
+                            self.app_cache_selected = caches.iter()
+                                .filter(|c| !c.running)
+                                .map(|c| c.path.clone())
+                                .collect();
+                            self.cached_app_caches = Some(caches);
+                        }
+                        ToolResult::System(items) => self.cached_system_items = Some(items),
+                        ToolResult::Message(msg) => self.info_message = Some(msg),
+                        ToolResult::CachesCleared { msg, removed } => {
+                            self.info_message = Some(msg);
+                            self.record_deletions(removed);
+                        }
+                        ToolResult::Compressed { msg, delete: Some(dir) } => {
+                            self.pending_delete = Some(dir);
+                            self.pending_delete_note = Some(msg);
+                        }
+                        ToolResult::Compressed { msg, delete: None } => self.info_message = Some(msg),
+                        ToolResult::Hashed { path, kind, hex } => {
+                            if let Some(hash) = self.file_hash.as_mut().filter(|h| h.path == path) {
+                                hash.results.push((kind, hex));
+                            }
+                        }
+                        ToolResult::Deleted { msg, removed } => {
+                            self.info_message = Some(msg);
+                            self.record_deletions(removed);
+                            if let Some(ref scan_path) = self.scan_path {
+                                self.start_scan(scan_path.clone());
+                            }
+                        }
+                        ToolResult::Expanded(node) => self.apply_expanded(node),
+                        ToolResult::Cancelled => {}
+                    }
+                    self.tool_job = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => ctx.request_repaint(),
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => self.tool_job = None,
+            }
+        }
+
+        // Check for scan queue progress
+        if let Some(ref rx) = self.queue_events {
+            while let Ok(event) = rx.try_recv() {
+                match event {
+                    QueueEvent::Started(idx) => self.scan_queue[idx].state = QueueState::Running,
+                    QueueEvent::Finished(idx, result, secs) => {
+                        let job = &mut self.scan_queue[idx];
+                        job.state = match result {
+                            Some(result) => {
+                                let (size, file_count) = result.0.as_ref()
+                                    .map(|r| (r.size, r.file_count))
+                                    .unwrap_or((0, 0));
+                                QueueState::Done {
+                                    size,
+                                    file_count,
+                                    free: get_free_space(&job.path),
+                                    secs,
+                                    result: Some(result),
+                                }
+                            }
+                            None => QueueState::Cancelled,
+                        };
+                    }
+                }
+            }
+            if self.queue_busy() {
+                ctx.request_repaint();
+            }
+        }
+
+        // Volumes mounted or removed
+        if let Some(drives) = self.drive_watch_receiver.as_ref().and_then(|rx| rx.try_iter().last()) {
+            let removed = self.scan_drive.as_ref()
+                .is_some_and(|d| !drives.iter().any(|info| Path::new(&info.mount_point) == d.mount_point));
+            if removed && !self.volume_removed {
+                self.volume_removed = true;
+                self.show_removed_data = false;
+                if let Some(ref prog) = self.scan_progress {
+                    prog.cancel.store(true, Ordering::Relaxed);
+                }
+            }
+            self.cached_drives = drives;
+        }
+
+        // Check for version update result
+        if let Some(ref rx) = self.update_check_receiver {
+            if let Ok(result) = rx.try_recv() {
+                self.latest_version = result;
+                self.update_check_receiver = None;
+            }
+        }
+
+        // ---- About popup ----
+        // Consume Escape so it doesn't also trigger the treemap's zoom-out binding
+        if self.show_about && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            self.show_about = false;
+        }
+        if self.show_about {
+            // Lazy-load textures on first open
+            if self.icon_texture.is_none() {
+                self.icon_texture = Some(load_image_from_png(
+                    ctx, "app_icon", include_bytes!("../assets/icon.png"),
+                ));
+            }
+            if self.face_texture.is_none() {
+                self.face_texture = Some(load_image_from_png(
+                    ctx, "tront_face", include_bytes!("../assets/tront.png"),
+                ));
+            }
+
+            let mut open = true;
+            let icon_tex = self.icon_texture.clone();
+            let face_tex = self.face_texture.clone();
+            egui::Window::new("About SpaceView")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        // Icon at top
+                        if let Some(ref tex) = icon_tex {
+                            ui.image(egui::load::SizedTexture::new(tex.id(), egui::vec2(64.0, 64.0)));
+                            ui.add_space(8.0);
+                        }
+                        ui.heading(format!("SpaceView v{}", VERSION));
+                        ui.add_space(4.0);
+                        ui.label("Disk space visualizer");
+                        ui.add_space(4.0);
+
+                        // Face next to author name
+                        ui.horizontal(|ui| {
+                            ui.add_space(ui.available_width() / 2.0 - 50.0);
+                            if let Some(ref tex) = face_tex {
+                                ui.image(egui::load::SizedTexture::new(tex.id(), egui::vec2(24.0, 24.0)));
+                            }
+                            ui.label("By tront");
+                        });
+
+                        ui.add_space(4.0);
+                        ui.label("Built with Rust + egui");
+                        if let Some(rss) = self.memory_rss {
+                            let nodes = if self.tree_nodes > 0 {
+                                format!("{} tree nodes", format_count(self.tree_nodes))
+                            } else {
+                                let files = self.scan_progress.as_ref().map_or(0, |p| p.files_scanned.load(Ordering::Relaxed));
+                                format!("{} files scanned", format_count(files))
+                            };
+                            ui.weak(format!("Using {} of memory ({})", format_size(rss), nodes));
+                        }
+                        ui.add_space(12.0);
+                    });
+
+                    // Update notification
+                    if let Some(ref ver) = self.latest_version {
+                        ui.add_space(4.0);
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Update available: v{}", ver));
+                            ui.hyperlink_to(
+                                "Download",
+                                "https://github.com/TrentSterling/SpaceView/releases/latest",
+                            );
+                        });
+                        ui.add_space(4.0);
+                    }
+
+                    ui.separator();
+                    ui.add_space(4.0);
+                    ui.strong("Keyboard Shortcuts");
+                    ui.add_space(4.0);
+                    egui::Grid::new("about_shortcuts")
+                        .num_columns(2)
+                        .spacing([20.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("Scroll");
+                            ui.label("Zoom in/out");
+                            ui.end_row();
+                            ui.label("Right-click");
+                            ui.label("Menu (zoom out on empty space)");
+                            ui.end_row();
+                            ui.label("Drag");
+                            ui.label("Pan view");
+                            ui.end_row();
+                            ui.label("Pinch / two-finger");
+                            ui.label("Zoom / pan (touchpad, touch)");
+                            ui.end_row();
+                            ui.label("Ctrl+1..9 / 1..9");
+                            ui.label("Save / jump to zoom bookmark");
+                            ui.end_row();
+                            ui.label("Alt+1..5 / Ctrl+Tab");
+                            ui.label("Switch view (1..5 outside the Map)");
+                            ui.end_row();
+                            keymap_rows(ui, &self.keymap);
+                        });
+
+                    ui.add_space(8.0);
+                    ui.separator();
+                    ui.add_space(4.0);
+                    let mut hide = self.hide_about_on_start;
+                    if ui.checkbox(&mut hide, "Don't show on startup").changed() {
+                        self.hide_about_on_start = hide;
+                        save_prefs(&self.current_prefs());
+                    }
+                    let mut stale_changed = false;
+                    ui.horizontal(|ui| {
+                        ui.label("On focus, flag scans older than");
+                        stale_changed |= ui.add(
+                            egui::DragValue::new(&mut self.stale_after_mins).range(0..=10_080).suffix(" min"),
+                        ).on_hover_text("0 turns the check off").changed();
+                    });
+                    stale_changed |= ui.checkbox(&mut self.auto_rescan_stale, "Rescan stale data automatically").changed();
+                    if stale_changed {
+                        save_prefs(&self.current_prefs());
+                    }
+                    if ui.checkbox(&mut self.shred_enabled, "Offer Shred (overwrite and delete) for files")
+                        .on_hover_text("Adds a permanent, unrecoverable delete to file context menus")
+                        .changed()
+                    {
+                        save_prefs(&self.current_prefs());
+                    }
+                    if ui.checkbox(&mut self.restore_last_scan, "Reopen last scan on startup")
+                        .on_hover_text("Loads the saved result of the last scan, or rescans it if none was saved")
+                        .changed()
+                    {
+                        save_prefs(&self.current_prefs());
+                        // Save what's on screen now so the next launch doesn't need a rescan
+                        if let (true, false, Some(root), Some(session)) =
+                            (self.restore_last_scan, self.scanning, self.scan_root.clone(), session_snapshot_path())
+                        {
+                            std::thread::spawn(move || {
+                                if let Some(dir) = session.parent() {
+                                    let _ = std::fs::create_dir_all(dir);
+                                }
+                                let _ = save_snapshot(&root, true, &session);
+                            });
+                        }
+                    }
+                    ui.add_space(4.0);
+                    ui.vertical_centered(|ui| {
+                        if ui.button("Close").clicked() {
+                            self.show_about = false;
+                        }
+                    });
+                });
+            if !open {
+                self.show_about = false;
+            }
+        }
+
+        // ---- Settings ----
+        if self.show_settings {
+            let usage = self.storage_usage.get_or_insert_with(storage_usage);
+            let mut open = true;
+            let mut changed = false;
+            let mut prune = false;
+            let mut clear = false;
+            egui::Window::new("Settings")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    ui.strong("Snapshot history");
+                    changed |= ui.checkbox(&mut self.auto_snapshot, "Save a snapshot after every completed scan")
+                        .on_hover_text("Kept in SpaceView's data folder for comparing scans over time")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label("Keep the newest");
+                        let keep = ui.add(egui::DragValue::new(&mut self.snapshot_keep).range(1..=1000));
+                        ui.label("per scanned folder");
+                        // Prune once per drag, not on every intermediate value
+                        prune = keep.drag_stopped() || (keep.changed() && !keep.dragged());
+                    });
+                    ui.add_space(8.0);
+                    ui.separator();
+                    ui.strong("Memory");
+                    ui.horizontal(|ui| {
+                        ui.label("Warn during scans above");
+                        let limit = ui.add(egui::DragValue::new(&mut self.memory_limit_mb).range(0..=1_048_576).speed(64).suffix(" MB"))
+                            .on_hover_text("Offers a depth-limited rescan before a huge volume exhausts memory. 0 turns the warning off");
+                        changed |= limit.drag_stopped() || (limit.changed() && !limit.dragged());
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Keep folder totals only past");
+                        let limit = ui.add(egui::DragValue::new(&mut self.node_limit).range(0..=1_000_000_000).speed(100_000))
+                            .on_hover_text("Once a scan holds this many files and folders, folders scanned after that \
+                                            keep their size but not their contents, so huge volumes stay responsive. \
+                                            Scan Contents opens one up later. 0 keeps everything");
+                        ui.label("tree nodes");
+                        changed |= limit.drag_stopped() || (limit.changed() && !limit.dragged());
+                    });
+                    ui.add_space(8.0);
+                    ui.separator();
+                    ui.strong("Treemap tooltips");
+                    ui.horizontal(|ui| {
+                        ui.label("Show");
+                        egui::ComboBox::from_id_salt("tooltip_style")
+                            .selected_text(self.tooltip_style.label())
+                            .show_ui(ui, |ui| {
+                                for style in TOOLTIP_STYLES {
+                                    changed |= ui.selectable_value(&mut self.tooltip_style, style, style.label()).changed();
+                                }
+                            });
+                    });
+                    ui.add_enabled_ui(self.tooltip_style != TooltipStyle::StatusBar, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Hover delay");
+                            let delay = ui.add(egui::DragValue::new(&mut self.hover_delay_ms).range(0..=MAX_HOVER_DELAY_MS).speed(10).suffix(" ms"))
+                                .on_hover_text("How long the pointer rests on a block before its tooltip shows");
+                            changed |= delay.drag_stopped() || (delay.changed() && !delay.dragged());
+                        });
+                    });
+                    ui.add_space(8.0);
+                    ui.separator();
+                    ui.strong("Touch");
+                    ui.horizontal(|ui| {
+                        ui.label("Large controls");
+                        egui::ComboBox::from_id_salt("touch_mode")
+                            .selected_text(self.touch_mode.label())
+                            .show_ui(ui, |ui| {
+                                for mode in TOUCH_MODES {
+                                    changed |= ui.selectable_value(&mut self.touch_mode, mode, mode.label()).changed();
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Bigger buttons and text, taller treemap headers, zoom buttons on the map and \
+                                    press-and-hold for a block's menu. Auto turns them on at the first touch");
+                    ui.add_space(8.0);
+                    ui.separator();
+                    ui.strong("Storage used by SpaceView");
+                    if let Some(dir) = data_dir() {
+                        ui.horizontal(|ui| {
+                            ui.label("App data:");
+                            ui.label(egui::RichText::new(dir.to_string_lossy()).weak());
+                        })
+                        .response
+                        .on_hover_text("Left out of scans, so SpaceView's own snapshots and logs never show up as usage");
+                    }
+                    egui::Grid::new("storage_usage")
+                        .num_columns(3)
+                        .spacing([20.0, 4.0])
+                        .show(ui, |ui| {
+                            for item in usage.iter() {
+                                ui.label(item.label);
+                                ui.label(format!("{} files", format_count(item.files)));
+                                ui.label(format_size(item.bytes));
+                                ui.end_row();
+                            }
+                            ui.strong("Total");
+                            ui.label(format!("{} files", format_count(usage.iter().map(|i| i.files).sum())));
+                            ui.strong(format_size(usage.iter().map(|i| i.bytes).sum()));
+                            ui.end_row();
+                        });
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        if let Some(dir) = data_dir() {
+                            if ui.button("Open Folder").clicked() {
+                                let _ = std::fs::create_dir_all(&dir);
+                                reveal_in_explorer(&dir);
+                            }
+                        }
+                        let history = usage.iter().find(|i| i.label == "Snapshot history").map_or(0, |i| i.files);
+                        if ui.add_enabled(history > 0, egui::Button::new("Delete Snapshot History")).clicked() {
+                            clear = true;
+                        }
+                    });
+                });
+            if prune {
+                let (removed, freed) = prune_all(self.snapshot_keep);
+                if removed > 0 {
+                    self.info_message = Some(format!(
+                        "Removed {} older snapshots ({}).", format_count(removed as u64), format_size(freed),
+                    ));
+                }
+            }
+            if clear {
+                if let Err(e) = clear_history() {
+                    self.info_message = Some(format!("Could not delete the snapshot history: {}", e));
+                }
+            }
+            if changed || prune {
+                save_prefs(&self.current_prefs());
+            }
+            if prune || clear {
+                self.storage_usage = None;
+            }
+            if !open {
+                self.show_settings = false;
+            }
+        }
+
+        // ---- Delete confirmation dialog ----
+        if self.pending_delete.is_some() && self.remote.is_some() {
+            self.pending_delete = None;
+            self.pending_delete_note = None;
+            self.info_message = Some("Remote scans are read-only. Delete on the agent's machine.".to_string());
+        }
+        if self.pending_delete.is_some() {
+            let path = self.pending_delete.clone().unwrap();
+            let mut keep_open = true;
+
+            // Look for open handles up front so a locked folder doesn't fail halfway
+            if self.delete_in_use.as_ref().is_none_or(|c| c.path != path) {
+                let (tx, rx) = std::sync::mpsc::channel();
+                let check_path = path.clone();
+                std::thread::spawn(move || {
+                    let _ = tx.send(processes_using(&check_path));
+                });
+                self.delete_in_use = Some(InUseCheck { path: path.clone(), receiver: rx, result: None });
+            }
+            if let Some(ref mut check) = self.delete_in_use {
+                if check.result.is_none() {
+                    match check.receiver.try_recv() {
+                        Ok(in_use) => check.result = Some(in_use),
+                        Err(_) => ctx.request_repaint(),
+                    }
+                }
+            }
+            let check_result = self.delete_in_use.as_ref().and_then(|c| c.result.as_ref());
+            let in_use = check_result.map(|r| r.names.clone());
+            let check_partial = check_result.is_some_and(|r| r.partial);
+            // What the scan saw inside, to catch the wrong folder before it goes
+            let target = self.scan_root.as_ref().and_then(|r| find_node(r, &path));
+            let summary = target.map(|node| {
+                let newest = if node.modified > 0 { iso_date(node.modified) } else { "unknown".to_string() };
+                if node.is_dir {
+                    format!("{} in {} files. Newest change: {}.", format_size(node.size), format_count(node.file_count), newest)
+                } else {
+                    format!("{}. Modified: {}.", format_size(node.size), newest)
+                }
+            });
+            let largest: Vec<(String, u64)> = target.filter(|n| n.is_dir).map_or_else(Vec::new, |node| {
+                node.children.iter()
+                    .filter(|c| !is_space_tile(&c.name))
+                    .take(DELETE_PREVIEW_ITEMS)
+                    .map(|c| {
+                        let name = if c.is_dir { format!("{}{}", c.name, std::path::MAIN_SEPARATOR) } else { c.name.clone() };
+                        (name, c.size)
+                    })
+                    .collect()
+            });
+
+            egui::Window::new("Confirm Delete")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    if let Some(ref note) = self.pending_delete_note {
+                        ui.label(note);
+                        ui.add_space(4.0);
+                    }
+                    ui.label("Send to Recycle Bin?");
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(path.to_string_lossy().to_string()).monospace());
+                    ui.add_space(4.0);
+                    if let Some(ref summary) = summary {
+                        ui.label(summary);
+                        if !largest.is_empty() {
+                            ui.weak("Largest inside:");
+                            egui::Grid::new("delete_preview").num_columns(2).spacing([16.0, 2.0]).show(ui, |ui| {
+                                for (name, size) in &largest {
+                                    ui.label(name);
+                                    ui.label(format_size(*size));
+                                    ui.end_row();
+                                }
+                            });
+                        }
+                        ui.add_space(4.0);
+                    }
+                    match in_use {
+                        None => {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.weak("Checking for open files...");
+                            });
+                        }
+                        Some(ref names) if !names.is_empty() => {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(220, 180, 50),
+                                format!("Contents are in use by {}", names.join(", ")),
+                            );
+                            ui.weak("Close these programs first or the delete may stop partway.");
+                        }
+                        Some(_) => {}
+                    }
+                    if check_partial {
+                        ui.weak(format!(
+                            "Only the first {} files were checked for open handles.",
+                            format_count(MAX_FILES_CHECKED as u64),
+                        ));
+                    }
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let in_use_found = in_use.as_ref().is_some_and(|n| !n.is_empty());
+                        let delete_label = if in_use_found { "Delete Anyway" } else { "Delete" };
+                        let delete = ui.add_enabled(self.tool_job.is_none(), egui::Button::new(delete_label))
+                            .on_disabled_hover_text(BUSY_HINT);
+                        if delete.clicked() {
+                            // Logged and rescanned once the delete has finished
+                            #[cfg(target_os = "windows")]
+                            self.start_recycle(path.clone());
+                            #[cfg(not(target_os = "windows"))]
+                            if let Some(ref scan_path) = self.scan_path {
+                                self.start_scan(scan_path.clone());
+                            }
+                            keep_open = false;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if !keep_open {
+                self.pending_delete = None;
+                self.pending_delete_note = None;
+                self.delete_in_use = None;
+            }
+        }
+
+        // ---- Compress to .zip ----
+        if let Some(mut request) = self.pending_compress.take() {
+            let mut keep_open = true;
+            let mut confirmed = false;
+            egui::Window::new("Compress to .zip")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("Compress");
+                    ui.label(egui::RichText::new(request.dir.to_string_lossy().to_string()).monospace());
+                    ui.label("into");
+                    ui.label(egui::RichText::new(request.dest.to_string_lossy().to_string()).monospace());
+                    ui.add_space(4.0);
+                    ui.checkbox(&mut request.delete_after, "Then send the folder to the Recycle Bin")
+                        .on_hover_text("Asks for confirmation once the archive is complete. Skipped if any file couldn't be read.");
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let compress = ui.add_enabled(self.tool_job.is_none(), egui::Button::new("Compress"))
+                            .on_disabled_hover_text(BUSY_HINT);
+                        if compress.clicked() {
+                            confirmed = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if confirmed {
+                self.start_compress(request);
+            } else if keep_open {
+                self.pending_compress = Some(request);
+            }
+        }
+
+        // ---- Migration Script ----
+        if let Some(mut request) = self.pending_migration.take() {
+            let mut keep_open = true;
+            let mut save = false;
+            egui::Window::new("Migration Script")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    let total: u64 = request.sources.iter().map(|s| s.size).sum();
+                    ui.label(format!(
+                        "Writes a {} script for {} folders ({}) to review and run yourself.",
+                        if cfg!(windows) { "robocopy" } else { "rsync" },
+                        request.sources.len(),
+                        format_size(total),
+                    ));
+                    egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                        for source in &request.sources {
+                            ui.label(egui::RichText::new(source.path.to_string_lossy().to_string()).monospace());
+                        }
+                    });
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label("Destination");
+                        ui.add(egui::TextEdit::singleline(&mut request.dest).desired_width(280.0));
+                        if ui.button("Browse...").clicked() {
+                            if let Some(dir) = rfd::FileDialog::new().set_title("Select destination").pick_folder() {
+                                request.dest = dir.to_string_lossy().to_string();
+                            }
+                        }
+                    });
+                    ui.weak("Each folder goes to a subfolder of the same name.");
+                    ui.checkbox(&mut request.moving, "Move: delete each source once it is copied");
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let dest = request.dest.trim();
+                        let ready = Path::new(dest).is_absolute();
+                        if ui.add_enabled(ready, egui::Button::new("Save Script...")).clicked() {
+                            save = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if save {
+                self.save_migration_script(&request);
+            } else if keep_open {
+                self.pending_migration = Some(request);
+            }
+        }
+
+        // ---- File Hash ----
+        if let Some(mut hash) = self.file_hash.take() {
+            let mut keep_open = true;
+            let mut compute: Option<Checksum> = None;
+            let hashing = self.tool_job.as_ref().is_some_and(|j| j.kind == ToolKind::Hash);
+            egui::Window::new("File Hash")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new(hash.path.to_string_lossy().to_string()).monospace());
+                    ui.label(format_size(hash.size));
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        for kind in CHECKSUMS {
+                            if ui.selectable_label(hash.kind == kind, kind.label()).clicked() && hash.kind != kind {
+                                hash.kind = kind;
+                                if !hash.results.iter().any(|(k, _)| *k == kind) {
+                                    compute = Some(kind);
+                                }
+                            }
+                        }
+                    });
+                    ui.add_space(4.0);
+                    let digest = hash.results.iter().find(|(k, _)| *k == hash.kind).map(|(_, hex)| hex.clone());
+                    match digest {
+                        Some(hex) => {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(&hex).monospace());
+                                if ui.button("Copy").clicked() {
+                                    ctx.copy_text(hex.clone());
+                                }
+                            });
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                ui.label("Compare with:");
+                                ui.add(egui::TextEdit::singleline(&mut hash.expected).desired_width(300.0).font(egui::TextStyle::Monospace));
+                            });
+                            let expected = hash.expected.trim();
+                            if !expected.is_empty() {
+                                if expected.eq_ignore_ascii_case(&hex) {
+                                    ui.colored_label(egui::Color32::from_rgb(80, 200, 80), "Match");
+                                } else {
+                                    ui.colored_label(egui::Color32::from_rgb(220, 60, 50), "Does not match");
+                                }
+                            }
+                        }
+                        None if hashing => {
+                            let done = self.tool_job.as_ref().map_or(0, |j| j.progress.bytes_scanned.load(Ordering::Relaxed));
+                            let fraction = done as f32 / hash.size.max(1) as f32;
+                            ui.add(egui::ProgressBar::new(fraction.min(1.0))
+                                .text(format!("{} of {}", format_size(done), format_size(hash.size))));
+                        }
+                        None => {
+                            if ui.button(format!("Compute {}", hash.kind.label())).clicked() {
+                                compute = Some(hash.kind);
+                            }
+                        }
+                    }
+                    ui.add_space(8.0);
+                    if ui.button("Close").clicked() {
+                        keep_open = false;
+                    }
+                });
+            if let Some(kind) = compute {
+                self.start_hash(hash.path.clone(), kind);
+            }
+            if keep_open {
+                self.file_hash = Some(hash);
+            } else if hashing {
+                if let Some(ref job) = self.tool_job {
+                    job.progress.cancel.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+
+        // ---- Deletion Log ----
+        if self.show_deletions {
+            let mut open = true;
+            let mut keep_log = self.keep_deletion_log;
+            let mut clear_clicked = false;
+            egui::Window::new("Deletion Log")
+                .open(&mut open)
+                .collapsible(false)
+                .default_width(640.0)
+                .show(ctx, |ui| {
+                    let session: u64 = self.deletions[self.session_deletions..].iter().map(|d| d.size).sum();
+                    let all: u64 = self.deletions.iter().map(|d| d.size).sum();
+                    ui.label(format!(
+                        "Reclaimed today: {}.  This session: {}.  Listed: {} in {} items.",
+                        format_size(self.reclaimed_within(86_400)),
+                        format_size(session),
+                        format_size(all),
+                        format_count(self.deletions.len() as u64),
+                    ));
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut keep_log, "Keep across sessions")
+                            .on_hover_text("Save the log to deletions.log in the SpaceView settings folder");
+                        if ui.add_enabled(!self.deletions.is_empty(), egui::Button::new("Copy as CSV")).clicked() {
+                            let mut csv = String::from("time_utc,bytes,method,path\n");
+                            for d in &self.deletions {
+                                csv += &format!(
+                                    "{},{},{},\"{}\"\n",
+                                    iso_date_time(d.time), d.size, d.method.label(),
+                                    d.path.to_string_lossy().replace('"', "\"\""),
+                                );
+                            }
+                            ctx.copy_text(csv);
+                        }
+                        clear_clicked = ui.add_enabled(!self.deletions.is_empty(), egui::Button::new("Clear")).clicked();
+                    });
+                    ui.separator();
+                    if self.deletions.is_empty() {
+                        ui.label("Nothing deleted yet.");
+                        return;
+                    }
+                    let row_h = 18.0;
+                    egui::ScrollArea::vertical().max_height(360.0).auto_shrink([false, true]).show_rows(
+                        ui, row_h, self.deletions.len(), |ui, range| {
+                        egui::Grid::new("deletion_log_grid").num_columns(4).spacing([12.0, 2.0]).show(ui, |ui| {
+                            // Newest first
+                            for i in range {
+                                let d = &self.deletions[self.deletions.len() - 1 - i];
+                                ui.label(iso_date_time(d.time));
+                                ui.label(format_size(d.size));
+                                ui.label(d.method.label());
+                                ui.label(egui::RichText::new(d.path.to_string_lossy()).weak());
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+            if keep_log != self.keep_deletion_log {
+                self.set_keep_deletion_log(keep_log);
+            }
+            if clear_clicked {
+                if let Err(e) = clear_ledger() {
+                    self.info_message = Some(format!("Could not clear the deletion log: {}", e));
+                }
+                self.deletions.clear();
+                self.session_deletions = 0;
+            }
+            self.show_deletions = open;
+        }
+
+        // ---- Type Breakdown ----
+        if let Some(mut breakdown) = self.type_breakdown.take() {
+            let mut keep_open = true;
+            if let Some(ref rx) = breakdown.receiver {
+                match rx.try_recv() {
+                    Ok(stats) => {
+                        breakdown.stats = stats;
+                        breakdown.receiver = None;
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => ctx.request_repaint(),
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => breakdown.receiver = None,
+                }
+            }
+            egui::Window::new(format!("Type Breakdown - {}", breakdown.name))
+                .id(egui::Id::new("type_breakdown"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new(breakdown.path.to_string_lossy().to_string()).monospace());
+                    ui.label(format_size(breakdown.size));
+                    ui.add_space(4.0);
+                    if breakdown.receiver.is_some() {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Counting file types...");
+                        });
+                    } else if breakdown.stats.is_empty() {
+                        ui.label("No files.");
+                    } else {
+                        let total = breakdown.size.max(1);
+                        egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                            egui::Grid::new("type_breakdown_grid")
+                                .num_columns(4)
+                                .spacing([16.0, 4.0])
+                                .show(ui, |ui| {
+                                    for (i, (ext, size, count)) in breakdown.stats.iter().take(TYPE_BREAKDOWN_ROWS).enumerate() {
+                                        // Same colors as the File Types panel
+                                        let ci = self.ext_color_map.get(ext).copied().unwrap_or(i);
+                                        let (r, g, b) = self.theme.base_rgb(ci);
+                                        ui.horizontal(|ui| {
+                                            let (swatch, _) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+                                            ui.painter().rect_filled(swatch, 2.0, egui::Color32::from_rgb(r, g, b));
+                                            let active = self.selected_extension.as_deref() == Some(ext.as_str());
+                                            if ui.selectable_label(active, ext.as_str())
+                                                .on_hover_text("Highlight these files on the map")
+                                                .clicked()
+                                            {
+                                                self.selected_extension = if active { None } else { Some(ext.clone()) };
+                                                self.color_mode = ColorMode::Extension;
+                                            }
+                                        });
+                                        ui.label(format_size(*size));
+                                        ui.label(format!("{:.1}%", *size as f64 / total as f64 * 100.0));
+                                        ui.label(format!("{} files", format_count(*count)));
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                        let rest = breakdown.stats.len().saturating_sub(TYPE_BREAKDOWN_ROWS);
+                        if rest > 0 {
+                            let rest_size: u64 = breakdown.stats[TYPE_BREAKDOWN_ROWS..].iter().map(|e| e.1).sum();
+                            ui.weak(format!("{} more types ({})", format_count(rest as u64), format_size(rest_size)));
+                        }
+                    }
+                    ui.add_space(8.0);
+                    if ui.button("Close").clicked() {
+                        keep_open = false;
+                    }
+                });
+            if keep_open {
+                self.type_breakdown = Some(breakdown);
+            }
+        }
+
+        // ---- Shred confirmation ----
+        if let Some(mut shred) = self.pending_shred.take() {
+            let mut keep_open = true;
+            let mut confirmed = false;
+            let danger = egui::Color32::from_rgb(220, 60, 50);
+            egui::Window::new("Shred File")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.colored_label(danger, egui::RichText::new("Permanently destroy this file?").strong().size(16.0));
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(shred.path.to_string_lossy().to_string()).monospace());
+                    ui.label(format_size(shred.size));
+                    ui.add_space(4.0);
+                    ui.label("The contents are overwritten, then the file is deleted. It does not go to \
+                              the Recycle Bin and cannot be recovered by SpaceView, Windows or undelete tools.");
+                    ui.add_space(4.0);
+                    let caveat = if !shred.hdd {
+                        "This drive is an SSD (or its type is unknown). Wear leveling and TRIM can keep old \
+                         copies of the data where overwriting can't reach. For sensitive data on SSDs, rely on \
+                         BitLocker or the drive's secure erase instead."
+                    } else {
+                        "Copies elsewhere (backups, shadow copies, cloud sync, the page file) are not touched."
+                    };
+                    ui.colored_label(egui::Color32::from_rgb(220, 180, 50), caveat);
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label("Overwrite:");
+                        ui.radio_value(&mut shred.passes, 1, "1 pass");
+                        ui.radio_value(&mut shred.passes, SHRED_PASSES_HDD, format!("{} passes (HDD)", SHRED_PASSES_HDD));
+                    });
+                    ui.checkbox(&mut shred.understood, "I understand this cannot be undone");
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let button = egui::Button::new(egui::RichText::new("Shred").color(egui::Color32::WHITE)).fill(danger);
+                        if ui.add_enabled(shred.understood && self.tool_job.is_none(), button)
+                            .on_disabled_hover_text(if shred.understood { BUSY_HINT } else { "Tick the box above first" })
+                            .clicked()
+                        {
+                            confirmed = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if confirmed {
+                self.start_shred(shred.path, shred.size, shred.passes);
+            } else if keep_open {
+                self.pending_shred = Some(shred);
+            }
+        }
+
+        // ---- Delete all files of a type ----
+        if let Some(delete) = self.pending_type_delete.take() {
+            let mut keep_open = true;
+            let mut confirmed = false;
+            let mut dry_run = false;
+            egui::Window::new(format!("Delete All {} Files", delete.ext))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "Send {} {} files ({}) to the Recycle Bin?",
+                        format_count(delete.paths.len() as u64), delete.ext, format_size(delete.size),
+                    ));
+                    ui.add_space(4.0);
+                    egui::ScrollArea::vertical().max_height(300.0).auto_shrink([false, true]).show_rows(
+                        ui, 16.0, delete.paths.len(), |ui, range| {
+                            for path in &delete.paths[range] {
+                                ui.label(egui::RichText::new(path.to_string_lossy().to_string()).monospace());
+                            }
+                        },
+                    );
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let delete = ui.add_enabled(self.tool_job.is_none(), egui::Button::new("Delete"))
+                            .on_disabled_hover_text(BUSY_HINT);
+                        if delete.clicked() {
+                            confirmed = true;
+                        }
+                        dry_run = ui.button("Dry Run...").on_hover_text(DRY_RUN_HINT).clicked();
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if confirmed {
+                self.start_removal(delete.manifest());
+            } else if dry_run {
+                self.manifest_review = Some(delete.manifest());
+            } else if keep_open {
+                self.pending_type_delete = Some(delete);
+            }
+        }
+
+        // ---- Bulk removal dry run ----
+        if let Some(manifest) = self.manifest_review.take() {
+            let mut keep_open = true;
+            let mut run = false;
+            let mut export = false;
+            egui::Window::new(format!("Dry Run: {}", manifest.title))
+                .collapsible(false)
+                .default_width(720.0)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "{} items, {}. Nothing has been removed.",
+                        format_count(manifest.items.len() as u64), format_size(manifest.total()),
+                    ));
+                    ui.label(egui::RichText::new(
+                        "Run removes exactly this list. Files that are gone or changed size by then are left alone.",
+                    ).weak());
+                    ui.add_space(4.0);
+                    egui::ScrollArea::vertical().max_height(360.0).auto_shrink([false, true]).show_rows(
+                        ui, 18.0, manifest.items.len(), |ui, range| {
+                            for item in &manifest.items[range] {
+                                ui.horizontal(|ui| {
+                                    let action = if item.method == DeleteMethod::CacheCleared { "Empty" } else { "Recycle" };
+                                    ui.add_sized([60.0, 18.0], egui::Label::new(action));
+                                    ui.add_sized([80.0, 18.0], egui::Label::new(format_size(item.size)));
+                                    ui.label(egui::RichText::new(item.path.to_string_lossy().to_string()).monospace());
+                                    ui.label(egui::RichText::new(&item.reason).weak());
+                                });
+                            }
+                        },
+                    );
+                    // A loaded list can name any folder to empty, not just the caches found here
+                    let folders = manifest.folders_to_empty();
+                    let confirm = manifest.from_file && folders > 0;
+                    if confirm {
+                        ui.add_space(4.0);
+                        ui.colored_label(
+                            egui::Color32::from_rgb(220, 180, 50),
+                            format!(
+                                "This file empties {} folder{}: everything inside goes, not to the Recycle Bin.",
+                                format_count(folders as u64), if folders == 1 { "" } else { "s" },
+                            ),
+                        );
+                        ui.checkbox(&mut self.manifest_confirmed, "I have checked every folder marked Empty above");
+                    }
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let local = self.remote.is_none();
+                        let ready = !confirm || self.manifest_confirmed;
+                        let idle = self.tool_job.is_none();
+                        let hint = if !local {
+                            "Remote trees are read-only"
+                        } else if !ready {
+                            "Confirm the folders to empty first"
+                        } else {
+                            BUSY_HINT
+                        };
+                        run = ui.add_enabled(local && ready && idle, egui::Button::new("Run"))
+                            .on_disabled_hover_text(hint)
+                            .clicked();
+                        export = ui.button("Export CSV...")
+                            .on_hover_text("Save the list for change-control approval. Tools > Run Deletion Manifest... runs it later")
+                            .clicked();
+                        if ui.button("Close").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if export {
+                self.export_manifest(&manifest);
+            }
+            if run {
+                self.start_removal(manifest);
+            } else if keep_open {
+                self.manifest_review = Some(manifest);
+            }
+        }
+
+        // ---- Culled children list ----
+        if let Some(HiddenList { dir_name, items }) = self.hidden_items.clone() {
+            let mut open = true;
+            let mut hidden_action = None;
+            let action_env = self.action_env();
+            egui::Window::new(format!("Too small to show in {}", dir_name))
+                .open(&mut open)
+                .collapsible(false)
+                .default_height(320.0)
+                .show(ctx, |ui| {
+                    ui.label(format!("{} items, {} total", items.len(),
+                        format_size(items.iter().map(|(_, size, _)| size).sum())));
+                    ui.separator();
+                    egui::ScrollArea::vertical().show_rows(ui, 18.0, items.len(), |ui, range| {
+                        for (name, size, path) in &items[range] {
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.label(format_size(*size));
+                                let label = ui.add(egui::Label::new(name).truncate().sense(egui::Sense::click()))
+                                    .on_hover_text(path.to_string_lossy());
+                                // <Small Files> has no path of its own
+                                if path.as_os_str().is_empty() {
+                                    return;
+                                }
+                                label.context_menu(|ui| {
+                                    let facts = self.path_facts(path, false, *size);
+                                    if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                        hidden_action = Some((action, facts));
+                                    }
+                                });
+                            });
+                        }
+                    });
+                });
+            if let Some((action, facts)) = hidden_action {
+                self.run_node_action(ctx, action, facts);
+            }
+            if !open {
+                self.hidden_items = None;
+            }
+        }
+
+        // ---- Appearance (cell geometry) ----
+        if self.show_appearance {
+            let mut open = true;
+            let mut save = false;
+            egui::Window::new("Cell Sizes")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let m = &mut self.cell_metrics;
+                    let mut released = false;
+                    egui::Grid::new("cell_metrics").num_columns(2).show(ui, |ui| {
+                        for (label, value, range) in [
+                            ("Header height", &mut m.header_px, 10.0..=40.0),
+                            ("Padding", &mut m.pad_px, 0.0..=12.0),
+                            ("Border", &mut m.border_px, 0.0..=6.0),
+                            ("Smallest block", &mut m.min_px, 1.0..=8.0),
+                        ] {
+                            ui.label(label);
+                            let r = ui.add(egui::Slider::new(value, range).suffix(" px"));
+                            released |= r.drag_stopped() || (r.changed() && !r.dragged());
+                            ui.end_row();
+                        }
+                    });
+                    ui.add_space(4.0);
+                    if ui.button("Reset to defaults").clicked() {
+                        *m = CellMetrics::default();
+                        released = true;
+                    }
+                    save = released;
+                });
+            if save {
+                save_prefs(&self.current_prefs());
+            }
+            if !open {
+                self.show_appearance = false;
+            }
+        }
+
+        // ---- Connect to a remote agent ----
+        if self.show_remote_dialog {
+            let mut open = true;
+            let mut connect = false;
+            egui::Window::new("Connect to Agent")
+                .open(&mut open)
                 .collapsible(false)
                 .resizable(false)
-                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                .open(&mut open)
                 .show(ctx, |ui| {
-                    ui.vertical_centered(|ui| {
-                        // Icon at top
-                        if let Some(ref tex) = icon_tex {
-                            ui.image(egui::load::SizedTexture::new(tex.id(), egui::vec2(64.0, 64.0)));
-                            ui.add_space(8.0);
-                        }
-                        ui.heading(format!("SpaceView v{}", VERSION));
-                        ui.add_space(4.0);
-                        ui.label("Disk space visualizer");
-                        ui.add_space(4.0);
+                    egui::Grid::new("remote_agent").num_columns(2).show(ui, |ui| {
+                        ui.label("Agent");
+                        ui.add(egui::TextEdit::singleline(&mut self.remote_addr).hint_text("server:7878"));
+                        ui.end_row();
+                        ui.label("Token");
+                        ui.add(egui::TextEdit::singleline(&mut self.remote_token).password(true));
+                        ui.end_row();
+                        ui.label("Folder");
+                        ui.add(egui::TextEdit::singleline(&mut self.remote_path).hint_text(r"D:\Shares"));
+                        ui.end_row();
+                    });
+                    ui.add_space(4.0);
+                    ui.small("Start the agent on the server with: spaceview --agent --token <secret>");
+                    ui.add_space(4.0);
+                    let ready = !self.remote_addr.trim().is_empty()
+                        && !self.remote_token.is_empty()
+                        && !self.remote_path.trim().is_empty();
+                    connect = ui.add_enabled(ready, egui::Button::new("Scan")).clicked();
+                });
+            if connect {
+                self.show_remote_dialog = false;
+                save_prefs(&self.current_prefs());
+                let remote = RemoteSource::Agent { addr: self.remote_addr.trim().to_string(), token: self.remote_token.clone() };
+                self.start_remote_scan(remote, PathBuf::from(self.remote_path.trim()));
+            }
+            if !open {
+                self.show_remote_dialog = false;
+            }
+        }
 
-                        // Face next to author name
-                        ui.horizontal(|ui| {
-                            ui.add_space(ui.available_width() / 2.0 - 50.0);
-                            if let Some(ref tex) = face_tex {
-                                ui.image(egui::load::SizedTexture::new(tex.id(), egui::vec2(24.0, 24.0)));
+        // ---- Quick scan buttons ----
+        if self.show_quick_scans {
+            let mut open = true;
+            let mut changed = false;
+            egui::Window::new("Quick Scan Buttons")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label("Shown in the top bar and on the welcome screen.");
+                    ui.add_space(4.0);
+                    let mut remove = None;
+                    egui::Grid::new("quick_scans").num_columns(4).show(ui, |ui| {
+                        ui.strong("Label");
+                        ui.strong("Folder, share or URL");
+                        ui.end_row();
+                        for (i, quick) in self.quick_scans.iter_mut().enumerate() {
+                            let hint = quick.display_label();
+                            changed |= ui.add(egui::TextEdit::singleline(&mut quick.label)
+                                .hint_text(hint)
+                                .desired_width(120.0)).lost_focus();
+                            changed |= ui.add(egui::TextEdit::singleline(&mut quick.path)
+                                .hint_text(r"\\nas\media")
+                                .desired_width(260.0)).lost_focus();
+                            if ui.button("Browse...").clicked() {
+                                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                                    quick.path = path.to_string_lossy().to_string();
+                                    changed = true;
+                                }
                             }
-                            ui.label("By tront");
-                        });
-
-                        ui.add_space(4.0);
-                        ui.label("Built with Rust + egui");
-                        ui.add_space(12.0);
+                            if ui.small_button("Remove").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
                     });
-
-                    // Update notification
-                    if let Some(ref ver) = self.latest_version {
-                        ui.add_space(4.0);
-                        ui.horizontal(|ui| {
-                            ui.label(format!("Update available: v{}", ver));
-                            ui.hyperlink_to(
-                                "Download",
-                                "https://github.com/TrentSterling/SpaceView/releases/latest",
-                            );
-                        });
-                        ui.add_space(4.0);
+                    if let Some(i) = remove {
+                        self.quick_scans.remove(i);
+                        changed = true;
                     }
+                    ui.horizontal(|ui| {
+                        if ui.button("Add").clicked() {
+                            self.quick_scans.push(QuickScan::default());
+                        }
+                        let current = self.scan_path.clone().filter(|_| self.remote.is_none());
+                        if let Some(path) = current {
+                            if ui.button("Add Current Folder").clicked() {
+                                self.quick_scans.push(QuickScan { label: String::new(), path: path.to_string_lossy().to_string() });
+                                changed = true;
+                            }
+                        }
+                    });
+                });
+            if !open {
+                self.show_quick_scans = false;
+                self.quick_scans.retain(|q| !q.path.trim().is_empty());
+                changed = true;
+            }
+            if changed {
+                save_prefs(&self.current_prefs());
+            }
+        }
 
-                    ui.separator();
+        // ---- Scan an FTP / WebDAV server ----
+        if self.show_url_dialog {
+            let mut open = true;
+            let mut scan = false;
+            egui::Window::new("Scan FTP / WebDAV")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    egui::Grid::new("remote_url").num_columns(2).show(ui, |ui| {
+                        ui.label("URL");
+                        ui.add(egui::TextEdit::singleline(&mut self.remote_url)
+                            .hint_text("ftp://nas/share or https://host/dav/")
+                            .desired_width(280.0));
+                        ui.end_row();
+                        ui.label("User");
+                        ui.add(egui::TextEdit::singleline(&mut self.remote_user).hint_text("anonymous"));
+                        ui.end_row();
+                        ui.label("Password");
+                        ui.add(egui::TextEdit::singleline(&mut self.remote_password).password(true));
+                        ui.end_row();
+                    });
                     ui.add_space(4.0);
-                    ui.strong("Keyboard Shortcuts");
+                    ui.small("Sizes come from directory listings; nothing is downloaded.");
                     ui.add_space(4.0);
-                    egui::Grid::new("about_shortcuts")
-                        .num_columns(2)
-                        .spacing([20.0, 4.0])
-                        .show(ui, |ui| {
-                            ui.label("Scroll");
-                            ui.label("Zoom in/out");
-                            ui.end_row();
-                            ui.label("Double-click");
-                            ui.label("Zoom into folder");
-                            ui.end_row();
-                            ui.label("Right-click");
-                            ui.label("Zoom out");
-                            ui.end_row();
-                            ui.label("Drag");
-                            ui.label("Pan view");
-                            ui.end_row();
-                            ui.label("Backspace / Esc");
-                            ui.label("Zoom out");
-                            ui.end_row();
-                        });
+                    scan = ui.add_enabled(is_scan_url(&self.remote_url), egui::Button::new("Scan")).clicked();
+                });
+            if scan {
+                self.show_url_dialog = false;
+                // Keep passwords out of the root path, breadcrumbs and prefs
+                let (url, credentials) = split_credentials(&self.remote_url);
+                self.remote_url = url;
+                if let Some((user, password)) = credentials {
+                    self.remote_user = user;
+                    self.remote_password = password;
+                }
+                save_prefs(&self.current_prefs());
+                let remote = RemoteSource::Url { user: self.remote_user.clone(), password: self.remote_password.clone() };
+                self.start_remote_scan(remote, PathBuf::from(self.remote_url.trim()));
+            }
+            if !open {
+                self.show_url_dialog = false;
+            }
+        }
 
+        // ---- Tool result message ----
+        if let Some(msg) = self.info_message.clone() {
+            egui::Window::new("SpaceView")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(msg);
                     ui.add_space(8.0);
-                    ui.separator();
-                    ui.add_space(4.0);
-                    let mut hide = self.hide_about_on_start;
-                    if ui.checkbox(&mut hide, "Don't show on startup").changed() {
-                        self.hide_about_on_start = hide;
-                        save_prefs(&self.current_prefs());
-                    }
-                    ui.add_space(4.0);
                     ui.vertical_centered(|ui| {
-                        if ui.button("Close").clicked() {
-                            self.show_about = false;
+                        if ui.button("OK").clicked() {
+                            self.info_message = None;
                         }
                     });
                 });
-            if !open {
-                self.show_about = false;
-            }
         }
 
-        // ---- Delete confirmation dialog ----
-        if self.pending_delete.is_some() {
-            let path = self.pending_delete.clone().unwrap();
+        // ---- Cache clear confirmation ----
+        if let Some(manifest) = self.pending_cache_clear.clone() {
             let mut keep_open = true;
-            egui::Window::new("Confirm Delete")
+            egui::Window::new("Clear App Caches")
                 .collapsible(false)
                 .resizable(false)
                 .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
                 .show(ctx, |ui| {
-                    ui.label("Send to Recycle Bin?");
+                    ui.label(format!("Permanently delete the contents of {} cache folders?", manifest.items.len()));
                     ui.add_space(4.0);
-                    ui.label(egui::RichText::new(path.to_string_lossy().to_string()).monospace());
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        for item in &manifest.items {
+                            ui.label(egui::RichText::new(item.path.to_string_lossy().to_string()).monospace());
+                        }
+                    });
                     ui.add_space(8.0);
                     ui.horizontal(|ui| {
-                        if ui.button("Delete").clicked() {
-                            #[cfg(target_os = "windows")]
-                            {
-                                // Use PowerShell to send to recycle bin
-                                let path_str = path.to_string_lossy().to_string();
-                                let script = format!(
-                                    "Add-Type -AssemblyName Microsoft.VisualBasic; [Microsoft.VisualBasic.FileIO.FileSystem]::DeleteFile('{}', 'OnlyErrorDialogs', 'SendToRecycleBin')",
-                                    path_str.replace('\'', "''")
-                                );
-                                let _ = std::process::Command::new("powershell")
-                                    .args(["-NoProfile", "-Command", &script])
-                                    .spawn();
-                            }
-                            // Rescan after delete
-                            if let Some(ref scan_path) = self.scan_path {
-                                self.start_scan(scan_path.clone());
-                            }
+                        let clear = ui.add_enabled(self.tool_job.is_none(), egui::Button::new("Clear"))
+                            .on_disabled_hover_text(BUSY_HINT);
+                        if clear.clicked() {
+                            self.start_removal(manifest.clone());
+                            keep_open = false;
+                        }
+                        if ui.button("Dry Run...").on_hover_text(DRY_RUN_HINT).clicked() {
+                            self.manifest_review = Some(manifest.clone());
                             keep_open = false;
                         }
                         if ui.button("Cancel").clicked() {
@@ -826,7 +5060,7 @@ impl eframe::App for SpaceViewApp {
                     });
                 });
             if !keep_open {
-                self.pending_delete = None;
+                self.pending_cache_clear = None;
             }
         }
 
@@ -834,6 +5068,7 @@ impl eframe::App for SpaceViewApp {
         if self.show_drive_picker {
             let mut close_picker = false;
             let mut scan_target: Option<PathBuf> = None;
+            let mut queue_targets: Vec<PathBuf> = Vec::new();
             egui::Window::new("Select Drive")
                 .collapsible(false)
                 .resizable(false)
@@ -890,23 +5125,47 @@ impl eframe::App for SpaceViewApp {
                                 format_size(drive.total_space),
                             ));
                         });
-                        if resp.response.interact(egui::Sense::click()).clicked() {
+                        let resp = resp.response.interact(egui::Sense::click());
+                        if resp.clicked() {
                             scan_target = Some(PathBuf::from(&drive.mount_point));
                             close_picker = true;
                         }
+                        resp.context_menu(|ui| {
+                            if ui.button("Add to Scan Queue").clicked() {
+                                queue_targets.push(PathBuf::from(&drive.mount_point));
+                                ui.close_menu();
+                            }
+                        });
                         ui.add_space(2.0);
                     }
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Scan All Drives")
+                            .on_hover_text("Scan every local drive at once into one map, each with its free space")
+                            .clicked()
+                        {
+                            scan_target = Some(PathBuf::from(ALL_DRIVES));
+                            close_picker = true;
+                        }
+                        if ui.button("Queue All Drives").on_hover_text("Scan every drive one after another").clicked() {
+                            queue_targets.extend(self.cached_drives.iter().map(|d| PathBuf::from(&d.mount_point)));
+                            close_picker = true;
+                        }
+                    });
                 });
             if let Some(path) = scan_target {
                 self.start_scan(path);
             }
+            for path in queue_targets {
+                self.enqueue_scan(path);
+            }
             if close_picker {
                 self.show_drive_picker = false;
             }
         }
 
         // ---- Top panel ----
-        egui::TopBottomPanel::top("top_bar").show(ctx, |ui| {
+        egui::TopBottomPanel::top("top_bar").show_animated(ctx, !presenting, |ui| {
             ui.horizontal(|ui| {
                 ui.heading("SpaceView");
                 ui.separator();
@@ -922,10 +5181,406 @@ impl eframe::App for SpaceViewApp {
                     self.cached_drives = enumerate_drives();
                     self.show_drive_picker = !self.show_drive_picker;
                 }
+                let mut quick_target = None;
+                for quick in self.quick_scans.iter().filter(|q| !q.path.trim().is_empty()) {
+                    if ui.button(quick.display_label()).on_hover_text(&quick.path).clicked() {
+                        quick_target = Some(quick.path.clone());
+                    }
+                }
+                if let Some(target) = quick_target {
+                    self.start_quick_scan(&target);
+                }
 
-                if self.scanning {
+                let options_label = if self.scan_options.is_filtered() { "Scan Options*" } else { "Scan Options" };
+                ui.menu_button(options_label, |ui| {
+                    ui.label("Applies to the next scan. Quick triage of huge volumes:");
+                    let mut changed = false;
+                    ui.horizontal(|ui| {
+                        ui.label("Max depth");
+                        changed |= ui.add(
+                            egui::DragValue::new(&mut self.scan_options.max_depth).range(0..=64),
+                        ).on_hover_text("Folders deeper than this are measured but not expanded, which bounds memory on \
+                                         huge volumes. Right-click one to scan it on demand. 0 = unlimited").changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Group files under");
+                        let mut kb = self.scan_options.min_file_size / 1024;
+                        if ui.add(egui::DragValue::new(&mut kb).range(0..=1_048_576).suffix(" KB"))
+                            .on_hover_text("Files below this size become one <Small Files> block per folder. 0 = off")
+                            .changed()
+                        {
+                            self.scan_options.min_file_size = kb * 1024;
+                            changed = true;
+                        }
+                    });
+                    ui.separator();
+                    ui.label("Include only (targeted audits):");
+                    ui.horizontal(|ui| {
+                        ui.label("Names");
+                        if ui.add(egui::TextEdit::singleline(&mut self.scan_include_text)
+                                .hint_text("*.log; *.dmp")
+                                .desired_width(140.0))
+                            .on_hover_text("Keep only files matching one of these patterns (* and ?, separated by ; \
+                                            or ,). Everything else is left out of the tree and the totals. Empty = all files")
+                            .changed()
+                        {
+                            self.scan_options.include_names = parse_include_names(&self.scan_include_text);
+                            changed = true;
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("At least");
+                        let mut mb = self.scan_options.include_min_size / (1024 * 1024);
+                        if ui.add(egui::DragValue::new(&mut mb).range(0..=1_048_576).suffix(" MB"))
+                            .on_hover_text("Keep only files at least this large. 0 = all sizes")
+                            .changed()
+                        {
+                            self.scan_options.include_min_size = mb * 1024 * 1024;
+                            changed = true;
+                        }
+                    });
+                    ui.separator();
+                    let excluded = self.scan_options.exclude.len();
+                    ui.label(if excluded > 0 { format!("Exclude ({}):", excluded) } else { "Exclude:".to_string() });
+                    if ui.add(egui::TextEdit::multiline(&mut self.scan_exclude_text)
+                            .hint_text("**\\node_modules\n*.iso\nC:\\Windows\\WinSxS")
+                            .desired_rows(3)
+                            .desired_width(220.0))
+                        .on_hover_text("Files and folders matching these are never read, so they cost no scan \
+                                        time or memory. One pattern per line, * and ? wildcards, any case. A pattern \
+                                        with \\ or / matches the full path, otherwise the name. \
+                                        Kept across scans and by Reset")
+                        .changed()
+                    {
+                        self.scan_options.exclude = parse_exclusions(&self.scan_exclude_text);
+                        changed = true;
+                    }
+                    ui.separator();
+                    changed |= ui.checkbox(&mut self.scan_options.include_offline, "Count offline / cloud-only files")
+                        .on_hover_text("Archived (HSM) and not-downloaded cloud files use no local disk space, \
+                                        so by default they count as 0 bytes")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.scan_options.count_links_once, "Count hard-linked files once")
+                        .on_hover_text("Files with several hard links (WinSxS, package caches) count at their \
+                                        first link only, so totals match the space really used. Slower: every \
+                                        file is opened to read its link count")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.scan_options.follow_links, "Follow folder links and junctions")
+                        .on_hover_text("Scan the folders that symbolic links and junctions point to, shown with a \
+                                        dashed outline. A link into something the scan already covers (a loop, or \
+                                        a folder reached another way) is still not followed. Off: links show as \
+                                        tiny files")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.scan_options.ignore_files, "Honor .spaceviewignore files")
+                        .on_hover_text("Leave out what a .spaceviewignore file lists (gitignore syntax: \
+                                        build/, *.obj, !keep.obj) in its folder and everything below, so \
+                                        projects can keep build outputs out of everyone's scans")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.scan_options.case_sensitive_dirs, "Detect case-sensitive folders")
+                        .on_hover_text("Mark folders with NTFS case sensitivity turned on (WSL makes them) with \
+                                        the Aa badge and list them in Reports > Case & 8.3 Names. Slower: every \
+                                        folder is opened to read the flag")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.scan_options.network, "Network volume profile")
+                        .on_hover_text("For mapped drives and shares: parallel folder listing, no free-space \
+                                        queries, fewer live updates, listing latency shown while scanning. \
+                                        Always used for \\\\server\\share paths.")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.auto_pause, "Pause on battery or fullscreen")
+                        .on_hover_text("Pause local scans while running on battery or while a game, video or \
+                                        presentation is fullscreen, and resume when that ends")
+                        .changed();
+                    let watch_toggle = ui.add_enabled(
+                        watch_supported(),
+                        egui::Checkbox::new(&mut self.watch_changes, "Watch for changes after a scan"),
+                    )
+                        .on_hover_text("Keep the map and lists up to date as files are created, deleted or \
+                                        written under the scanned folder, without rescanning")
+                        .on_disabled_hover_text("Only available on Windows");
+                    if watch_toggle.changed() {
+                        self.update_watcher();
+                        changed = true;
+                    }
+                    ui.separator();
+                    ui.label("Throttle (for busy file servers):");
+                    ui.horizontal(|ui| {
+                        ui.label("Max reads");
+                        changed |= ui.add(
+                            egui::DragValue::new(&mut self.scan_ops_limit).range(0..=100_000).suffix(" /sec"),
+                        ).on_hover_text("Folder listings + file lookups per second. 0 = unlimited").changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Duplicate hashing");
+                        changed |= ui.add(
+                            egui::DragValue::new(&mut self.hash_mb_limit).range(0..=10_000).suffix(" MB/s"),
+                        ).on_hover_text("Read rate of the background duplicate finder. 0 = unlimited").changed();
+                    });
+                    if ui.add_enabled(self.scan_options.is_filtered(), egui::Button::new("Reset")).clicked() {
+                        // Exclusions are a standing list, not a quick-triage filter
+                        self.scan_options = ScanOptions {
+                            exclude: std::mem::take(&mut self.scan_options.exclude),
+                            ignore_files: self.scan_options.ignore_files,
+                            ..ScanOptions::default()
+                        };
+                        self.scan_include_text.clear();
+                        changed = true;
+                    }
+                    if changed {
+                        save_prefs(&self.current_prefs());
+                    }
+                    ui.separator();
+                    if ui.button("Quick Scan Buttons...").clicked() {
+                        ui.close_menu();
+                        self.show_quick_scans = true;
+                    }
+                });
+
+                ui.menu_button("Tools", |ui| {
+                    if ui.button("Queue Folder Scans...").clicked() {
+                        ui.close_menu();
+                        if let Some(paths) = rfd::FileDialog::new().set_title("Select folders to scan").pick_folders() {
+                            for path in paths {
+                                self.enqueue_scan(path);
+                            }
+                        }
+                    }
+                    if ui.button("Connect to Agent...").clicked() {
+                        ui.close_menu();
+                        self.show_remote_dialog = true;
+                    }
+                    if ui.button("Scan FTP / WebDAV...").clicked() {
+                        ui.close_menu();
+                        self.show_url_dialog = true;
+                    }
+                    ui.separator();
+                    let has_tree = self.scan_root.is_some() && !self.scanning;
+                    ui.add_enabled_ui(has_tree, |ui| {
+                        ui.menu_button("Export", |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Levels");
+                                if ui.add(egui::DragValue::new(&mut self.export_depth).range(1..=10)).changed() {
+                                    save_prefs(&self.current_prefs());
+                                }
+                            });
+                            let diagram = if ui.button("Graphviz Diagram (.dot)...").clicked() {
+                                Some(("Graphviz", "dot"))
+                            } else if ui.button("Mermaid Diagram (.mmd)...").clicked() {
+                                Some(("Mermaid", "mmd"))
+                            } else {
+                                None
+                            };
+                            if let Some((kind, ext)) = diagram {
+                                ui.close_menu();
+                                self.export_diagram(kind, ext);
+                            }
+                            ui.separator();
+                            if ui.button("Treemap Image (.svg)...").clicked() {
+                                ui.close_menu();
+                                self.export_svg();
+                            }
+                            if ui.button("Snapshot (.svsnap)...")
+                                .on_hover_text("The whole scan, to open in another SpaceView")
+                                .clicked()
+                            {
+                                ui.close_menu();
+                                self.export_snapshot();
+                            }
+                            ui.separator();
+                            let anon = &mut self.export_anonymize;
+                            let changed = ui.checkbox(&mut anon.user_names, "Hide user names")
+                                .on_hover_text("Profile folders become user-1, user-2..., also where the names appear elsewhere")
+                                .changed()
+                                | ui.checkbox(&mut anon.file_names, "Hide file names")
+                                    .on_hover_text("Files and folders become file-N.ext / folder-N. Sizes, extensions and structure stay")
+                                    .changed();
+                            if changed {
+                                save_prefs(&self.current_prefs());
+                            }
+                        });
+                        if ui.button("Print...").on_hover_text("Treemap and largest folders, via the browser's print dialog").clicked() {
+                            ui.close_menu();
+                            self.print_report();
+                        }
+                    });
+                    ui.separator();
+                    if ui.button("Compare with Backup...").clicked() {
+                        ui.close_menu();
+                        let source = rfd::FileDialog::new()
+                            .set_title("Select source folder")
+                            .pick_folder();
+                        if let Some(source) = source {
+                            let backup = rfd::FileDialog::new()
+                                .set_title("Select backup folder")
+                                .pick_folder();
+                            if let Some(backup) = backup {
+                                self.start_backup_compare(source, backup);
+                            }
+                        }
+                    }
+                    let can_migrate = !self.selection.is_empty() && self.remote.is_none();
+                    if ui.add_enabled(can_migrate, egui::Button::new("Migration Script..."))
+                        .on_hover_text("robocopy / rsync script that copies or moves the selected folders")
+                        .on_disabled_hover_text("Select folders first (Ctrl+click)")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.request_migration();
+                    }
+                    ui.separator();
+                    let can_save = self.scan_root.is_some() && !self.scanning;
+                    if ui.add_enabled(can_save, egui::Button::new("Save Snapshot...")).clicked() {
+                        ui.close_menu();
+                        let out = rfd::FileDialog::new()
+                            .set_title("Save snapshot")
+                            .add_filter("SpaceView snapshot", &["svsnap"])
+                            .set_file_name("scan.svsnap")
+                            .save_file();
+                        if let (Some(out), Some(root)) = (out, self.scan_root.as_ref()) {
+                            let scanned = self.scanned_at.unwrap_or_else(now_secs);
+                            let drive = self.snapshot_drive.as_ref().or(self.scan_drive.as_ref());
+                            if let Err(e) = save_scan_snapshot(root, scanned, drive, &out) {
+                                self.info_message = Some(format!("Could not save snapshot: {}", e));
+                            }
+                        }
+                    }
+                    if ui.add_enabled(!self.scanning, egui::Button::new("Open Snapshot..."))
+                        .on_hover_text("Show a saved scan again without rescanning")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        let file = rfd::FileDialog::new()
+                            .set_title("Open snapshot")
+                            .add_filter("SpaceView snapshot", &["svsnap"])
+                            .pick_file();
+                        if let Some(file) = file {
+                            self.open_snapshot(file);
+                        }
+                    }
+                    if ui.add_enabled(can_save, egui::Button::new("Compare Scan with Snapshot..."))
+                        .on_hover_text("What grew, what's new and what's gone since a saved snapshot of this folder")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        let mut dialog = rfd::FileDialog::new()
+                            .set_title("Select earlier snapshot")
+                            .add_filter("SpaceView snapshot", &["svsnap"]);
+                        // Automatic snapshots of this root, when there are any
+                        if let Some(dir) = self.scan_root.as_ref().and_then(|r| root_history(&r.path)).filter(|d| d.is_dir()) {
+                            dialog = dialog.set_directory(dir);
+                        }
+                        if let Some(snapshot) = dialog.pick_file() {
+                            self.start_growth_compare(snapshot);
+                        }
+                    }
+                    if ui.button("Compare with Snapshot...").clicked() {
+                        ui.close_menu();
+                        let old = rfd::FileDialog::new()
+                            .set_title("Select older snapshot")
+                            .add_filter("SpaceView snapshot", &["svsnap"])
+                            .pick_file();
+                        if let Some(old) = old {
+                            let new = rfd::FileDialog::new()
+                                .set_title("Select newer snapshot")
+                                .add_filter("SpaceView snapshot", &["svsnap"])
+                                .pick_file();
+                            if let Some(new) = new {
+                                self.start_snapshot_compare(old, new);
+                            }
+                        }
+                    }
+                    if ui.button("Import Quota CSV...")
+                        .on_hover_text("path,quota rows from a file server; shows each folder's quota use on the map and in Reports")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        let csv = rfd::FileDialog::new()
+                            .set_title("Import quotas")
+                            .add_filter("CSV", &["csv", "txt"])
+                            .pick_file();
+                        if let Some(csv) = csv {
+                            self.import_quotas(csv, true);
+                            self.view_mode = ViewMode::Reports;
+                            self.report_kind = ReportKind::Quotas;
+                        }
+                    }
+                    if !self.quotas.is_empty() && ui.button("Clear Quotas").clicked() {
+                        ui.close_menu();
+                        self.clear_quotas();
+                    }
+                    if ui.button("Run Deletion Manifest...")
+                        .on_hover_text("Review and run a bulk removal dry run exported as CSV, e.g. once it was approved")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        let csv = rfd::FileDialog::new()
+                            .set_title("Open deletion manifest")
+                            .add_filter("CSV", &["csv"])
+                            .pick_file();
+                        if let Some(csv) = csv {
+                            self.open_manifest(csv);
+                        }
+                    }
+                    if ui.button("Deletion Log...").on_hover_text("Everything deleted through SpaceView and the space it freed").clicked() {
+                        ui.close_menu();
+                        self.show_deletions = true;
+                    }
+                    if ui.button("Notification Settings...")
+                        .on_hover_text("Webhook, email and thresholds for `spaceview --monitor`")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        if let Some(path) = crate::notify::ensure_settings_file() {
+                            open_in_explorer(&path);
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("Create Integrity Baseline...").clicked() {
+                        ui.close_menu();
+                        let root = rfd::FileDialog::new()
+                            .set_title("Select folder to baseline")
+                            .pick_folder();
+                        if let Some(root) = root {
+                            let out = rfd::FileDialog::new()
+                                .set_title("Save baseline")
+                                .add_filter("SpaceView baseline", &["svbase"])
+                                .set_file_name("baseline.svbase")
+                                .save_file();
+                            if let Some(out) = out {
+                                self.start_baseline_create(root, out);
+                            }
+                        }
+                    }
+                    if ui.button("Verify Against Baseline...").clicked() {
+                        ui.close_menu();
+                        let file = rfd::FileDialog::new()
+                            .set_title("Open baseline")
+                            .add_filter("SpaceView baseline", &["svbase"])
+                            .pick_file();
+                        if let Some(file) = file {
+                            self.start_baseline_verify(file);
+                        }
+                    }
+                });
+
+                if let Some(ref job) = self.tool_job {
                     ui.separator();
                     ui.spinner();
+                    ui.label(format!(
+                        "{} {} files",
+                        job.kind.label(),
+                        format_count(job.progress.files_scanned.load(Ordering::Relaxed)),
+                    ));
+                    if ui.button("Cancel").clicked() {
+                        job.progress.cancel.store(true, Ordering::Relaxed);
+                    }
+                }
+
+                if self.scanning {
+                    ui.separator();
+                    let paused = self.scan_progress.as_ref().is_some_and(|p| p.paused.load(Ordering::Relaxed));
+                    if !paused {
+                        ui.spinner();
+                    }
                     if let Some(ref prog) = self.scan_progress {
                         let files = prog.files_scanned.load(Ordering::Relaxed);
                         let bytes = prog.bytes_scanned.load(Ordering::Relaxed);
@@ -935,12 +5590,22 @@ impl eframe::App for SpaceViewApp {
                         } else {
                             0.0
                         };
+                        // "Pausing..." until the scan threads have actually stopped
+                        let state = match (paused, prog.suspended.load(Ordering::Relaxed) > 0) {
+                            (false, _) => "Scanning...",
+                            (true, false) => "Pausing...",
+                            (true, true) => "Paused -",
+                        };
                         let mut text = format!(
-                            "Scanning... {} files, {}",
+                            "{} {} files, {}",
+                            state,
                             format_count(files),
                             format_size(bytes),
                         );
-                        if elapsed >= 1.0 {
+                        if let Some(reason) = self.auto_paused {
+                            text += &format!(" ({})", reason);
+                        }
+                        if elapsed >= 1.0 && !paused {
                             text += &format!(
                                 " - {} ({}/sec)",
                                 format_duration(elapsed),
@@ -948,12 +5613,20 @@ impl eframe::App for SpaceViewApp {
                             );
                         }
                         ui.label(text);
+                        if let Some((text, lines)) = drive_progress(prog) {
+                            ui.separator();
+                            ui.label(text).on_hover_text(lines.join("\n"));
+                        }
+                        if let Some((text, tip)) = listing_latency(prog) {
+                            ui.separator();
+                            ui.label(text).on_hover_text(tip);
+                        }
                     }
-                    if let Some(ref prog) = self.scan_progress {
-                        let is_paused = prog.paused.load(Ordering::Relaxed);
-                        let pause_label = if is_paused { "Resume" } else { "Pause" };
+                    // Remote scans run elsewhere and can't be paused
+                    if self.scan_progress.is_some() && self.remote.is_none() {
+                        let pause_label = if paused { "Resume" } else { "Pause" };
                         if ui.button(pause_label).clicked() {
-                            prog.paused.store(!is_paused, Ordering::Relaxed);
+                            self.set_scan_paused(!paused);
                         }
                     }
                     if ui.button("Cancel").clicked() {
@@ -970,8 +5643,27 @@ impl eframe::App for SpaceViewApp {
                     egui::ComboBox::from_id_salt("theme_selector")
                         .selected_text(current_label)
                         .show_ui(ui, |ui| {
+                            let mut changed = false;
                             for &t in &THEMES {
-                                ui.selectable_value(&mut self.theme, t, t.label());
+                                changed |= ui.selectable_value(&mut self.theme, t, t.label()).changed();
+                            }
+                            ui.separator();
+                            ui.label("Age Map gradient");
+                            for &r in &AGE_RAMPS {
+                                changed |= ui.selectable_value(&mut self.age_ramp, r, r.label()).changed();
+                            }
+                            ui.separator();
+                            changed |= ui.checkbox(&mut self.high_contrast, "High contrast").changed();
+                            ui.label("Label density");
+                            let density = ui.add(egui::Slider::new(&mut self.label_density, 0.5..=2.0))
+                                .on_hover_text("Higher labels smaller blocks; lower keeps dense maps readable");
+                            // Save once per drag, not every frame of it
+                            changed |= density.drag_stopped() || (density.changed() && !density.dragged());
+                            if ui.button("Cell sizes...").clicked() {
+                                self.show_appearance = true;
+                            }
+                            if changed {
+                                save_prefs(&self.current_prefs());
                             }
                         });
                     let mode_label = if self.dark_mode { "Light" } else { "Dark" };
@@ -979,30 +5671,55 @@ impl eframe::App for SpaceViewApp {
                         self.dark_mode = !self.dark_mode;
                         save_prefs(&self.current_prefs());
                     }
-                    // Color mode toggle (cycles Depth -> Age -> Extension -> Depth)
+                    // Color mode toggle (cycles Depth -> Age -> Extension -> Depth, with
+                    // Growth after Extension while a scan comparison is loaded)
                     if self.scan_root.is_some() {
+                        let growth = self.diff_growth.is_some();
                         let color_label = match self.color_mode {
                             ColorMode::Depth => "Age Map",
                             ColorMode::Age => "By Type",
-                            ColorMode::Extension => "Depth",
+                            ColorMode::Extension if growth => "Growth",
+                            ColorMode::Extension | ColorMode::Growth => "Depth",
                         };
                         if ui.button(color_label).clicked() {
                             self.color_mode = match self.color_mode {
                                 ColorMode::Depth => ColorMode::Age,
                                 ColorMode::Age => ColorMode::Extension,
-                                ColorMode::Extension => ColorMode::Depth,
+                                ColorMode::Extension if growth => ColorMode::Growth,
+                                ColorMode::Extension | ColorMode::Growth => ColorMode::Depth,
                             };
                         }
+                        if ui.selectable_label(self.heat_overlay, "Heat")
+                            .on_hover_text("Make folders glow by how fast they grew since an earlier snapshot, \
+                                            relative to their size, so a small folder that is filling up fast \
+                                            stands out as much as a big one")
+                            .clicked()
+                        {
+                            self.heat_overlay = !self.heat_overlay;
+                            if self.heat_overlay {
+                                self.start_heat();
+                            }
+                            save_prefs(&self.current_prefs());
+                        }
+                        if ui.selectable_label(self.size_on_disk, "On Disk")
+                            .on_hover_text("Size blocks by the space they take on disk (whole clusters, \
+                                            after compression) instead of their logical size")
+                            .clicked()
+                        {
+                            self.size_on_disk = !self.size_on_disk;
+                            self.rebuild_layout_preserving_camera(self.last_viewport);
+                            save_prefs(&self.current_prefs());
+                        }
                     }
                 }
 
                 // View mode tabs (only when scan is complete, since List/TopFiles need final data)
                 if self.scan_root.is_some() && !self.scanning {
                     ui.separator();
-                    ui.selectable_value(&mut self.view_mode, ViewMode::Treemap, "Map");
-                    ui.selectable_value(&mut self.view_mode, ViewMode::List, "List");
-                    ui.selectable_value(&mut self.view_mode, ViewMode::LargestFiles, "Top Files");
-                    ui.selectable_value(&mut self.view_mode, ViewMode::Extensions, "Types");
+                    ui.selectable_value(&mut self.view_mode, ViewMode::Treemap, "Map").on_hover_text("Alt+1");
+                    ui.selectable_value(&mut self.view_mode, ViewMode::List, "List").on_hover_text("Alt+2");
+                    ui.selectable_value(&mut self.view_mode, ViewMode::LargestFiles, "Top Files").on_hover_text("Alt+3");
+                    ui.selectable_value(&mut self.view_mode, ViewMode::Extensions, "Types").on_hover_text("Alt+4");
                     let dup_label = if self.cached_duplicates.is_some() {
                         "Dupes"
                     } else if self.dup_receiver.is_some() {
@@ -1010,7 +5727,26 @@ impl eframe::App for SpaceViewApp {
                     } else {
                         "Dupes"
                     };
-                    ui.selectable_value(&mut self.view_mode, ViewMode::Duplicates, dup_label);
+                    ui.selectable_value(&mut self.view_mode, ViewMode::Duplicates, dup_label).on_hover_text("Alt+5");
+                    ui.selectable_value(&mut self.view_mode, ViewMode::Reports, "Reports");
+                }
+                if self.diff_report.is_some() {
+                    if self.scan_root.is_none() || self.scanning {
+                        ui.separator();
+                    }
+                    ui.selectable_value(&mut self.view_mode, ViewMode::Diff, "Diff");
+                }
+                if !self.scan_queue.is_empty() {
+                    if (self.scan_root.is_none() || self.scanning) && self.diff_report.is_none() {
+                        ui.separator();
+                    }
+                    let done = self.scan_queue.iter().filter(|q| !matches!(q.state, QueueState::Pending | QueueState::Running)).count();
+                    let label = if self.queue_busy() {
+                        format!("Queue {}/{}", done, self.scan_queue.len())
+                    } else {
+                        "Queue".to_string()
+                    };
+                    ui.selectable_value(&mut self.view_mode, ViewMode::Queue, label);
                 }
 
                 // Right-aligned About button + Free Space toggle
@@ -1018,12 +5754,23 @@ impl eframe::App for SpaceViewApp {
                     if ui.button("About").clicked() {
                         self.show_about = !self.show_about;
                     }
+                    if ui.button("Settings").clicked() {
+                        self.show_settings = !self.show_settings;
+                        self.storage_usage = None;
+                    }
                     if self.scan_root.is_some() && !self.scanning {
                         ui.add(egui::TextEdit::singleline(&mut self.search_text)
                             .hint_text("Search...")
                             .desired_width(120.0));
                     }
                     if self.scan_root.is_some() && !self.scanning {
+                        if ui.button("Present")
+                            .on_hover_text("Full screen map with larger labels for a wall display. \
+                                            Only zoom and pan work; Esc to exit.")
+                            .clicked()
+                        {
+                            self.enter_presentation(ctx);
+                        }
                         if self.cached_extensions.is_some() {
                             let ext_label = if self.show_ext_panel { "Hide Types" } else { "Types" };
                             if ui.button(ext_label).clicked() {
@@ -1033,35 +5780,57 @@ impl eframe::App for SpaceViewApp {
                                 }
                             }
                         }
-                        let fs_label = if self.show_free_space { "Hide Free" } else { "Show Free" };
-                        if ui.button(fs_label).clicked() {
-                            self.show_free_space = !self.show_free_space;
-                            // Remove free space node if hiding
-                            if !self.show_free_space {
-                                if let Some(ref mut root) = self.scan_root {
-                                    if let Some(pos) = root.children.iter().position(|c| c.name == "<Free Space>") {
-                                        let free_size = root.children[pos].size;
-                                        root.children.remove(pos);
-                                        root.size -= free_size;
-                                    }
+                        let fs_label = if self.free_space_shown() { "Hide Free" } else { "Show Free" };
+                        let mut fs_button = ui.button(fs_label);
+                        let all_drives = self.all_drives_scan();
+                        if !self.scan_is_drive_root && !all_drives {
+                            fs_button = fs_button.on_hover_text(
+                                "This scan is a folder, not a whole drive. Free space is for the entire \
+                                 drive, so it isn't comparable with the folder's size and is hidden by default.",
+                            );
+                        }
+                        if fs_button.clicked() {
+                            if all_drives {
+                                // Remembered like a drive, under the virtual root's name
+                                if let Some(pos) = self.hide_free_drives.iter().position(|m| is_all_drives(m)) {
+                                    self.hide_free_drives.remove(pos);
+                                } else {
+                                    self.hide_free_drives.push(PathBuf::from(ALL_DRIVES));
+                                }
+                            } else if !self.scan_is_drive_root {
+                                self.show_free_space_folder = !self.show_free_space_folder;
+                            } else if let Some(ref drive) = self.scan_drive {
+                                // Remembered per drive
+                                if let Some(pos) = self.hide_free_drives.iter().position(|m| *m == drive.mount_point) {
+                                    self.hide_free_drives.remove(pos);
+                                } else {
+                                    self.hide_free_drives.push(drive.mount_point.clone());
                                 }
                             }
+                            save_prefs(&self.current_prefs());
                             self.world_layout = None;
                         }
                     }
                 });
             });
 
-            // Breadcrumb bar
+            // Breadcrumb bar: each folder shows its share of the root, and hovering
+            // one shows how its children split it
             if self.scan_root.is_some() {
+                let theme = self.theme;
+                let root_size = self.root_size;
                 ui.horizontal(|ui| {
+                    let share_card = |resp: egui::Response, node: Option<&FileNode>| match node {
+                        Some(node) => resp.on_hover_ui(|ui| crumb_share_card(ui, node, theme)),
+                        None => resp,
+                    };
                     match self.view_mode {
                         ViewMode::Treemap => {
                             if self.depth_context.is_empty() {
-                                ui.strong(&self.root_name);
+                                share_card(ui.strong(&self.root_name), self.scan_root.as_ref());
                             } else {
                                 let root_name = self.root_name.clone();
-                                if ui.link(&root_name).clicked() {
+                                if share_card(ui.link(&root_name), self.scan_root.as_ref()).clicked() {
                                     if let Some(ref layout) = self.world_layout {
                                         let viewport = self.last_viewport;
                                         if !viewport.is_negative() {
@@ -1074,15 +5843,17 @@ impl eframe::App for SpaceViewApp {
                             let last_idx = crumbs.len().saturating_sub(1);
                             for (i, crumb) in crumbs.iter().enumerate() {
                                 ui.label(">");
+                                let text = format!("{} ({})", crumb.name, share_label(crumb.size, root_size));
+                                let node = self.scan_root.as_ref().and_then(|root| find_node(root, &crumb.path));
                                 if i < last_idx {
-                                    if ui.link(&crumb.name).clicked() {
+                                    if share_card(ui.link(text), node).clicked() {
                                         let viewport = self.last_viewport;
                                         if !viewport.is_negative() {
                                             self.camera.snap_to(crumb.world_rect, viewport);
                                         }
                                     }
                                 } else {
-                                    ui.strong(&crumb.name);
+                                    share_card(ui.strong(text), node);
                                 }
                             }
                             if self.camera.zoom > 1.5 {
@@ -1093,9 +5864,9 @@ impl eframe::App for SpaceViewApp {
                         ViewMode::List => {
                             let root_name = self.root_name.clone();
                             if self.list_path.is_empty() {
-                                ui.strong(&root_name);
+                                share_card(ui.strong(&root_name), self.scan_root.as_ref());
                             } else {
-                                if ui.link(&root_name).clicked() {
+                                if share_card(ui.link(&root_name), self.scan_root.as_ref()).clicked() {
                                     self.list_path.clear();
                                 }
                             }
@@ -1103,12 +5874,17 @@ impl eframe::App for SpaceViewApp {
                             let last_idx = path.len().saturating_sub(1);
                             for (i, segment) in path.iter().enumerate() {
                                 ui.label(">");
+                                let node = self.scan_root.as_ref().and_then(|root| find_dir_by_path(root, &path[..=i]));
+                                let text = match node {
+                                    Some(node) => format!("{} ({})", segment, share_label(node.size, root_size)),
+                                    None => segment.clone(),
+                                };
                                 if i < last_idx {
-                                    if ui.link(segment).clicked() {
+                                    if share_card(ui.link(text), node).clicked() {
                                         self.list_path.truncate(i + 1);
                                     }
                                 } else {
-                                    ui.strong(segment);
+                                    share_card(ui.strong(text), node);
                                 }
                             }
                         }
@@ -1124,13 +5900,156 @@ impl eframe::App for SpaceViewApp {
                             ui.strong(&self.root_name);
                             ui.label("> Duplicate Files");
                         }
+                        ViewMode::Reports => {
+                            ui.strong(&self.root_name);
+                            ui.label(format!("> {}", self.report_kind.label()));
+                        }
+                        ViewMode::Diff => {
+                            if let Some(ref report) = self.diff_report {
+                                ui.strong(&report.title);
+                            }
+                        }
+                        ViewMode::Queue => {
+                            ui.strong("Scan Queue");
+                        }
                     }
                 });
             }
         });
 
+        // ---- Presentation title: where the map is zoomed to ----
+        if presenting {
+            egui::TopBottomPanel::top("present_bar").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(&self.root_name);
+                    for crumb in &self.depth_context {
+                        ui.label(">");
+                        ui.strong(&crumb.name);
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(format_size(self.root_size));
+                    });
+                });
+            });
+            if let Some(ref presentation) = self.presentation {
+                if presentation.started.elapsed().as_secs() < PRESENT_HINT_SECS {
+                    egui::Area::new(egui::Id::new("present_hint"))
+                        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -16.0])
+                        .interactable(false)
+                        .show(ctx, |ui| {
+                            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                                ui.label("Presentation mode - press Esc to exit");
+                            });
+                        });
+                    ctx.request_repaint_after(std::time::Duration::from_millis(500));
+                }
+            }
+        }
+
+        // ---- Stale data banner ----
+        if let (Some(age), false) = (self.stale_banner, presenting) {
+            let mut rescan = false;
+            egui::TopBottomPanel::top("stale_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("Data is {} old", format_age(age))).weak());
+                    if ui.small_button("Rescan").clicked() {
+                        rescan = true;
+                    }
+                    if ui.small_button("Dismiss").clicked() {
+                        self.stale_banner = None;
+                    }
+                });
+            });
+            if rescan {
+                if let Some(path) = self.scan_path.clone() {
+                    self.start_scan(path);
+                }
+            }
+        }
+
+        // ---- Partial results banner ----
+        if self.scan_partial && !self.scanning && !presenting {
+            let (mut resume, mut rescan) = (false, false);
+            egui::TopBottomPanel::top("partial_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 180, 50),
+                        format!("Scan cancelled - partial results. Folders with {} were cut short.", NOT_SCANNED),
+                    );
+                    if self.resume_offer.is_some() && ui.small_button("Resume Scan").clicked() {
+                        resume = true;
+                    }
+                    if ui.small_button("Rescan").clicked() {
+                        rescan = true;
+                    }
+                    if ui.small_button("Dismiss").clicked() {
+                        self.scan_partial = false;
+                    }
+                });
+            });
+            if resume {
+                self.resume_scan();
+            } else if rescan {
+                if let Some(path) = self.scan_path.clone() {
+                    self.start_scan(path);
+                }
+            }
+        }
+
+        // ---- Memory warning banner ----
+        if let (Some(rss), false) = (self.memory_warning, presenting) {
+            let mut lean = false;
+            egui::TopBottomPanel::top("memory_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 180, 50),
+                        format!(
+                            "SpaceView is using {} of memory (warning limit {}).",
+                            format_size(rss), format_size(self.memory_limit_mb * 1024 * 1024),
+                        ),
+                    );
+                    if self.scanning && self.remote.is_none() && ui.small_button("Rescan with Limits")
+                        .on_hover_text(format!(
+                            "Restart the scan keeping folders up to {} levels deep and grouping files under {} \
+                             into {} blocks. Change this later in Scan Options.",
+                            LEAN_MAX_DEPTH, format_size(LEAN_MIN_FILE_SIZE), SMALL_FILES,
+                        ))
+                        .clicked()
+                    {
+                        lean = true;
+                    }
+                    if ui.small_button("Dismiss").clicked() {
+                        self.memory_warning = None;
+                    }
+                });
+            });
+            if lean {
+                self.rescan_lean();
+            }
+        }
+
+        // ---- Node limit banner ----
+        if self.node_limit_banner && !presenting {
+            egui::TopBottomPanel::top("node_limit_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 180, 50),
+                        format!(
+                            "This scan passed {} files and folders. To stay responsive, folders scanned after \
+                             that keep only their totals.",
+                            format_count(self.node_limit),
+                        ),
+                    ).on_hover_text("Right-click one of those folders and pick Scan Contents to look inside it. \
+                                     The limit is in Settings");
+                    if ui.small_button("Dismiss").clicked() {
+                        self.node_limit_banner = false;
+                    }
+                });
+            });
+        }
+
         // ---- Status bar ----
-        if self.scan_root.is_some() {
+        if self.scan_root.is_some() && !presenting {
             egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     ui.label(format!(
@@ -1140,10 +6059,74 @@ impl eframe::App for SpaceViewApp {
                         format_count(self.root_file_count),
                     ));
 
+                    // Folder scans: share of the whole drive
+                    if let Some(drive) = self.scan_drive.as_ref().filter(|_| !self.scan_is_drive_root) {
+                        let folder = self.root_size;
+                        let used = drive.total.saturating_sub(drive.available);
+                        if used > 0 {
+                            ui.separator();
+                            ui.label(format!("{:.1}% of drive", folder as f64 / used as f64 * 100.0))
+                                .on_hover_text(format!(
+                                    "{} of {} used on {} ({} total, {:.1}% of capacity)",
+                                    format_size(folder),
+                                    format_size(used),
+                                    drive.mount_point.to_string_lossy(),
+                                    format_size(drive.total),
+                                    folder as f64 / drive.total.max(1) as f64 * 100.0,
+                                ));
+                        }
+                    }
+
+                    let link_bytes = self.scan_progress.as_ref().map_or(0, |p| p.link_bytes.load(Ordering::Relaxed));
+                    if link_bytes > 0 {
+                        ui.separator();
+                        ui.label(format!("Hard links: {} counted once", format_size(link_bytes)))
+                            .on_hover_text("Further links to files already counted elsewhere in the scan add 0 bytes \
+                                            to the totals above. Turn this off in Scan Options");
+                    }
+                    let links_skipped = self.scan_progress.as_ref().map_or(0, |p| p.links_skipped.load(Ordering::Relaxed));
+                    if links_skipped > 0 {
+                        ui.separator();
+                        ui.label(format!("Links: {} not followed", format_count(links_skipped)))
+                            .on_hover_text("Folder links that loop back, or lead into folders the scan already \
+                                            covers, were left as links so nothing is counted twice");
+                    }
+                    let ignored = self.scan_progress.as_ref().map_or(0, |p| p.ignored.load(Ordering::Relaxed));
+                    if ignored > 0 {
+                        ui.separator();
+                        ui.label(format!("Ignore files: {} items left out", format_count(ignored)))
+                            .on_hover_text("Files and folders listed in .spaceviewignore files were not scanned and \
+                                            are not in the totals. Turn this off in Scan Options");
+                    }
+                    if self.watcher.is_some() {
+                        ui.separator();
+                        if self.watch_overflow {
+                            ui.label("Watching: some changes missed")
+                                .on_hover_text("More changed at once than the watch could report. Rescan to catch up");
+                        } else {
+                            ui.label("Watching for changes")
+                                .on_hover_text("Changes under the scanned folder update the map as they happen. \
+                                                Turn this off in Scan Options");
+                        }
+                    }
+
+                    let reclaimed = self.reclaimed_within(86_400);
+                    if reclaimed > 0 {
+                        ui.separator();
+                        if ui.link(format!("Reclaimed today: {}", format_size(reclaimed)))
+                            .on_hover_text("Deleted through SpaceView in the last 24 hours. Click for the deletion log")
+                            .clicked()
+                        {
+                            self.show_deletions = true;
+                        }
+                    }
+
                     if let Some(ref info) = self.hovered_node_info {
                         ui.separator();
-                        let pct = if self.root_size > 0 {
-                            (info.size as f64 / self.root_size as f64) * 100.0
+                        // Shares of the whole map, free space included
+                        let map_total = self.map_total();
+                        let pct = if map_total > 0 {
+                            (info.size as f64 / map_total as f64) * 100.0
                         } else {
                             0.0
                         };
@@ -1165,16 +6148,162 @@ impl eframe::App for SpaceViewApp {
                                 format_size(info.size),
                                 pct
                             ));
+                            // The whole file type, from the scan's extension stats
+                            // (ext_color_map holds each extension's index in them)
+                            let ext_total = self.cached_extensions.as_ref().and_then(|exts| {
+                                let ext = extension_key(&info.name);
+                                self.ext_color_map.get(&ext).and_then(|&i| exts.get(i)).filter(|e| e.0 == ext)
+                            });
+                            if let Some((ext, ext_size, ext_count)) = ext_total {
+                                let ext_pct = *ext_size as f64 / self.root_size.max(1) as f64 * 100.0;
+                                ui.label(format!(
+                                    "- all {}: {} ({:.1}%, {} files)",
+                                    ext,
+                                    format_size(*ext_size),
+                                    ext_pct,
+                                    format_count(*ext_count),
+                                ));
+                            }
                         }
+                        // With tooltips off, the attributes they would show go here
+                        if self.tooltip_style == TooltipStyle::StatusBar && info.attrs != 0 {
+                            ui.weak(attr_labels(info.attrs).join(", "));
+                        }
+                    }
+
+                    if !self.selection.is_empty() {
+                        ui.separator();
+                        let (size, files) = self.selection_totals();
+                        let pct = if self.root_size > 0 { size as f64 / self.root_size as f64 * 100.0 } else { 0.0 };
+                        ui.label(format!(
+                            "{} selected - {} ({:.1}%, {} files)",
+                            self.selection.len(),
+                            format_size(size),
+                            pct,
+                            format_count(files),
+                        ));
+                        if self.remote.is_none() && ui.small_button("Script...")
+                            .on_hover_text("Generate a robocopy / rsync script that copies or moves the selected folders")
+                            .clicked()
+                        {
+                            self.request_migration();
+                        }
+                        if ui.small_button("Clear").clicked() {
+                            self.selection.clear();
+                        }
+                    }
+
+                    if let Some(at) = self.scanned_at.filter(|_| !self.scanning) {
+                        let age = now_secs().saturating_sub(at);
+                        if age >= 60 {
+                            ui.separator();
+                            ui.label(format!("Scanned {} ago", format_age(age)));
+                        }
+                    }
+
+                    if self.volume_removed {
+                        ui.separator();
+                        ui.colored_label(egui::Color32::from_rgb(220, 180, 50), "Volume removed");
+                    }
+
+                    if let Some(ref remote) = self.remote {
+                        ui.separator();
+                        ui.label(remote.label());
+                    }
+
+                    if let Some(ref file) = self.opened_snapshot {
+                        ui.separator();
+                        let name = file.file_name().map_or_else(|| file.to_string_lossy(), |n| n.to_string_lossy());
+                        ui.label(format!("Snapshot: {}", name))
+                            .on_hover_text(format!("{}\nRescan to read the folder again", file.display()));
+                    }
+
+                    if self.bookmarks.iter().any(Option::is_some) {
+                        ui.separator();
+                        let slots: Vec<String> = self.bookmarks.iter().enumerate()
+                            .filter(|(_, b)| b.is_some())
+                            .map(|(i, _)| (i + 1).to_string())
+                            .collect();
+                        ui.label(format!("Bookmarks: {}", slots.join(" ")))
+                            .on_hover_text("Press a number to jump back, Ctrl+number to overwrite");
                     }
 
                     if self.color_mode == ColorMode::Age {
                         ui.separator();
-                        ui.colored_label(egui::Color32::from_rgb(220, 60, 50), "Old");
+                        let (oldest, newest) = self.time_range;
+                        if oldest < newest {
+                            // Real dates at the band edges, with a clickable swatch per band
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            let edges: Vec<u64> = (0..=AGE_BANDS)
+                                .map(|k| age_at(k as f64 / AGE_BANDS as f64, self.time_range))
+                                .collect();
+                            for band in 0..AGE_BANDS {
+                                ui.label(legend_date(edges[band]));
+                                let col = self.age_ramp.rgb((band as f32 + 0.5) / AGE_BANDS as f32);
+                                let active = self.age_band == Some(band);
+                                let swatch = ui.add(egui::Button::new("").fill(col).min_size(egui::vec2(22.0, 0.0)).selected(active))
+                                    .on_hover_text(format!(
+                                        "Modified {} to {}. Click to show only these files",
+                                        iso_date(edges[band]), iso_date(edges[band + 1]),
+                                    ));
+                                if swatch.clicked() {
+                                    self.age_band = if active { None } else { Some(band) };
+                                }
+                            }
+                            let last = if now_secs().saturating_sub(newest) < 86_400 {
+                                "today".to_string()
+                            } else {
+                                legend_date(newest)
+                            };
+                            ui.label(last);
+                        } else {
+                            let [old, mid, new] = self.age_ramp.legend();
+                            ui.colored_label(old, "Old");
+                            ui.label("-");
+                            ui.colored_label(mid, "Mid");
+                            ui.label("-");
+                            ui.colored_label(new, "New");
+                        }
+                    }
+                    if let Some(growth) = self.diff_growth.as_ref().filter(|_| self.color_mode == ColorMode::Growth) {
+                        ui.separator();
+                        let largest = growth.largest as i64;
+                        ui.colored_label(growth_color(Some(-largest), growth.largest), "Shrank");
                         ui.label("-");
-                        ui.colored_label(egui::Color32::from_rgb(220, 220, 50), "Mid");
+                        ui.colored_label(growth_color(None, growth.largest), "Unchanged");
                         ui.label("-");
-                        ui.colored_label(egui::Color32::from_rgb(60, 220, 80), "New");
+                        ui.colored_label(growth_color(Some(largest), growth.largest), "Grew")
+                            .on_hover_text(format!("Brightest: {} change", format_size(growth.largest)));
+                    }
+                    if self.heat_overlay {
+                        if let Some(ref heat) = self.heat {
+                            ui.separator();
+                            ui.colored_label(HEAT_COLOR, format!("Heat since {}", legend_date(heat.since)))
+                                .on_hover_text(format!(
+                                    "Folders glow by how fast they grew since that snapshot, relative to their \
+                                     size. Fastest: {} a day",
+                                    format_size(heat.fastest),
+                                ));
+                        } else if let Some(note) = self.heat_note {
+                            ui.separator();
+                            ui.weak("Heat: no history").on_hover_text(note);
+                        }
+                    }
+                    if self.color_mode == ColorMode::Depth {
+                        ui.separator();
+                        ui.label("Depth:");
+                        ui.spacing_mut().item_spacing.x = 2.0;
+                        for depth in 0..LEGEND_DEPTHS {
+                            let col = dir_color(depth, self.theme);
+                            let label = egui::RichText::new((depth + 1).to_string())
+                                .small()
+                                .color(text_color_for(col, self.high_contrast));
+                            let swatch = ui.add(egui::Button::new(label).fill(col).min_size(egui::vec2(18.0, 0.0)))
+                                .on_hover_text(format!("Level {} below the root. Click to highlight", depth + 1));
+                            if swatch.clicked() {
+                                self.flash_depth = Some((depth, ctx.input(|i| i.time)));
+                            }
+                        }
                     }
                     if self.color_mode == ColorMode::Extension {
                         ui.separator();
@@ -1185,17 +6314,15 @@ impl eframe::App for SpaceViewApp {
         }
 
         // ---- Extension breakdown side panel ----
-        if self.show_ext_panel && self.cached_extensions.is_some() {
+        if self.show_ext_panel && self.cached_extensions.is_some() && !presenting {
             egui::SidePanel::right("ext_panel")
                 .default_width(220.0)
                 .width_range(180.0..=350.0)
                 .resizable(true)
                 .show(ctx, |ui| {
                     ui.heading("File Types");
-                    if self.selected_extension.is_some() {
-                        if ui.button("Clear filter").clicked() {
-                            self.selected_extension = None;
-                        }
+                    if self.selected_extension.is_some() && ui.button("Clear filter").clicked() {
+                        self.selected_extension = None;
                     }
                     ui.separator();
 
@@ -1266,7 +6393,33 @@ impl eframe::App for SpaceViewApp {
 
         // ---- Central panel: treemap ----
         egui::CentralPanel::default().show(ctx, |ui| {
-            if self.scan_root.is_none() && !self.scanning {
+            if self.volume_removed && !self.show_removed_data && self.scan_root.is_some() {
+                let mount = self.scan_drive.as_ref().map(|d| d.mount_point.clone()).unwrap_or_default();
+                let reconnected = self.cached_drives.iter().any(|d| Path::new(&d.mount_point) == mount);
+                ui.vertical_centered(|ui| {
+                    ui.add_space(ui.available_height() / 3.0);
+                    ui.heading("Volume removed");
+                    ui.add_space(6.0);
+                    ui.label(format!(
+                        "{} is no longer connected, so the last scan of it may be out of date.",
+                        mount.to_string_lossy(),
+                    ));
+                    ui.add_space(12.0);
+                    if ui.add_enabled(reconnected, egui::Button::new("Rescan"))
+                        .on_disabled_hover_text("Reconnect the volume to rescan it")
+                        .clicked()
+                    {
+                        if let Some(path) = self.scan_path.clone() {
+                            self.start_scan(path);
+                        }
+                    }
+                    if ui.button("Show Last Scan").clicked() {
+                        self.show_removed_data = true;
+                    }
+                });
+                return;
+            }
+            if self.scan_root.is_none() && !self.scanning && !matches!(self.view_mode, ViewMode::Diff | ViewMode::Queue) {
                 // Populate drives on first render
                 if self.cached_drives.is_empty() {
                     self.cached_drives = enumerate_drives();
@@ -1274,6 +6427,10 @@ impl eframe::App for SpaceViewApp {
 
                 // Welcome screen with drive cards
                 let mut scan_target: Option<PathBuf> = None;
+                let mut quick_target: Option<String> = None;
+                let mut resume_clicked = false;
+                let mut discard_clicked = false;
+                let mut demo_clicked = false;
                 ui.vertical_centered(|ui| {
                     ui.add_space(ui.available_height() / 8.0);
                     ui.heading(format!("SpaceView v{}", VERSION));
@@ -1282,6 +6439,29 @@ impl eframe::App for SpaceViewApp {
                     ui.label("Select a drive or folder to see where your space goes.");
                     ui.add_space(16.0);
 
+                    // Interrupted scan
+                    if let Some(ref info) = self.resume_offer {
+                        ui.group(|ui| {
+                            ui.set_min_width(320.0);
+                            ui.set_max_width(400.0);
+                            ui.strong(format!("Interrupted scan of {}", info.root.to_string_lossy()));
+                            ui.label(format!(
+                                "{} in {} files scanned before it stopped.",
+                                format_size(info.size),
+                                format_count(info.file_count),
+                            ));
+                            ui.horizontal(|ui| {
+                                if ui.button("Resume Scan").clicked() {
+                                    resume_clicked = true;
+                                }
+                                if ui.button("Discard").clicked() {
+                                    discard_clicked = true;
+                                }
+                            });
+                        });
+                        ui.add_space(8.0);
+                    }
+
                     // Drive cards
                     for drive in &self.cached_drives {
                         let used = drive.total_space.saturating_sub(drive.available_space);
@@ -1339,6 +6519,27 @@ impl eframe::App for SpaceViewApp {
                         }
                         ui.add_space(2.0);
                     }
+                    if self.cached_drives.len() > 1 {
+                        ui.add_space(4.0);
+                        if ui.button("Scan All Drives")
+                            .on_hover_text("Scan every local drive at once into one map, each with its free space")
+                            .clicked()
+                        {
+                            scan_target = Some(PathBuf::from(ALL_DRIVES));
+                        }
+                    }
+
+                    if !self.quick_scans.is_empty() {
+                        ui.add_space(6.0);
+                        ui.horizontal_wrapped(|ui| {
+                            ui.set_max_width(400.0);
+                            for quick in self.quick_scans.iter().filter(|q| !q.path.trim().is_empty()) {
+                                if ui.button(quick.display_label()).on_hover_text(&quick.path).clicked() {
+                                    quick_target = Some(quick.path.clone());
+                                }
+                            }
+                        });
+                    }
 
                     ui.add_space(8.0);
                     if ui.button("Open Folder...").clicked() {
@@ -1346,6 +6547,13 @@ impl eframe::App for SpaceViewApp {
                             scan_target = Some(path);
                         }
                     }
+                    ui.add_space(4.0);
+                    if ui.button("Demo Data")
+                        .on_hover_text("Explore a made-up disk in every view. Nothing is scanned or changed.")
+                        .clicked()
+                    {
+                        demo_clicked = true;
+                    }
 
                     ui.add_space(20.0);
                     ui.strong("Keyboard Shortcuts");
@@ -1358,22 +6566,37 @@ impl eframe::App for SpaceViewApp {
                             ui.label("Scroll");
                             ui.label("Zoom in/out");
                             ui.end_row();
-                            ui.label("Double-click");
-                            ui.label("Zoom into folder");
-                            ui.end_row();
                             ui.label("Right-click");
-                            ui.label("Zoom out");
+                            ui.label("Menu (zoom out on empty space)");
                             ui.end_row();
                             ui.label("Drag");
                             ui.label("Pan view");
                             ui.end_row();
-                            ui.label("Backspace / Esc");
-                            ui.label("Zoom out");
+                            ui.label("Pinch / two-finger");
+                            ui.label("Zoom / pan (touchpad, touch)");
+                            ui.end_row();
+                            ui.label("Ctrl+1..9 / 1..9");
+                            ui.label("Save / jump to zoom bookmark");
                             ui.end_row();
+                            ui.label("Alt+1..5 / Ctrl+Tab");
+                            ui.label("Switch view (1..5 outside the Map)");
+                            ui.end_row();
+                            keymap_rows(ui, &self.keymap);
                         });
                 });
                 if let Some(path) = scan_target {
                     self.start_scan(path);
+                } else if let Some(target) = quick_target {
+                    self.start_quick_scan(&target);
+                } else if demo_clicked {
+                    self.start_remote_scan(RemoteSource::Demo, PathBuf::from(DEMO_ROOT));
+                } else if resume_clicked {
+                    self.resume_scan();
+                } else if discard_clicked {
+                    if let Some(cp) = resume_checkpoint_path() {
+                        let _ = std::fs::remove_file(cp);
+                    }
+                    self.resume_offer = None;
                 }
                 return;
             }
@@ -1397,6 +6620,15 @@ impl eframe::App for SpaceViewApp {
                                 format_count(rate as u64),
                             ));
                         }
+                        if let Some((text, tip)) = listing_latency(prog) {
+                            ui.label(text).on_hover_text(tip);
+                        }
+                        if let Some((_, lines)) = drive_progress(prog) {
+                            ui.add_space(8.0);
+                            for line in lines {
+                                ui.label(line);
+                            }
+                        }
                     }
                     ui.spinner();
                 });
@@ -1421,6 +6653,10 @@ impl eframe::App for SpaceViewApp {
                 }
             }
 
+            // Switching views brings the latest selected item into sight in the new one
+            let reveal = self.shown_view != self.view_mode;
+            self.shown_view = self.view_mode;
+
             match self.view_mode {
             ViewMode::Treemap => {
 
@@ -1428,6 +6664,9 @@ impl eframe::App for SpaceViewApp {
             if !has_layout {
                 return;
             }
+            if reveal {
+                self.reveal_in_map(viewport);
+            }
 
             // 1. Advance camera animation
             let camera_moving = self.camera.tick(dt, viewport);
@@ -1439,12 +6678,38 @@ impl eframe::App for SpaceViewApp {
             let mouse_pos = ctx.input(|i| i.pointer.hover_pos());
             let mouse_in_viewport = mouse_pos.map(|p| viewport.contains(p)).unwrap_or(false);
 
-            // Scroll zoom
-            let scroll_y = ctx.input(|i| i.raw_scroll_delta.y);
-            if mouse_in_viewport && scroll_y.abs() > 0.1 {
-                if let Some(pos) = mouse_pos {
-                    let world_focus = self.camera.screen_to_world(pos, viewport);
-                    self.camera.scroll_zoom(scroll_y / 120.0, world_focus, viewport);
+            // Scroll: a mouse wheel zooms, touchpad two-finger scrolling pans.
+            // Ctrl+scroll arrives through zoom_delta() below instead.
+            let (scroll, touchpad_scroll, zoom_held) = ctx.input(|i| {
+                (i.raw_scroll_delta, is_touchpad_scroll(&i.raw.events), i.modifiers.command)
+            });
+            let scale = self.camera.zoom * viewport.width();
+            if mouse_in_viewport && !zoom_held && scroll != egui::Vec2::ZERO {
+                if touchpad_scroll {
+                    self.camera.drag_pan(scroll / scale, viewport);
+                } else if let Some(pos) = mouse_pos {
+                    // Tilt wheel / Shift+wheel pan sideways
+                    if scroll.x.abs() > 0.1 {
+                        self.camera.drag_pan(egui::vec2(scroll.x / scale, 0.0), viewport);
+                    }
+                    if scroll.y.abs() > 0.1 {
+                        let world_focus = self.camera.screen_to_world(pos, viewport);
+                        self.camera.scroll_zoom(scroll.y / 120.0, world_focus, viewport);
+                    }
+                }
+            }
+
+            // Pinch zoom (touchpad, touch screen, Ctrl+scroll) and two-finger touch pan
+            let (zoom_delta, touch) = ctx.input(|i| (i.zoom_delta(), i.multi_touch()));
+            let gesture_focus = touch.map(|t| t.center_pos).or(mouse_pos).filter(|p| viewport.contains(*p));
+            if let Some(focus) = gesture_focus {
+                if (zoom_delta - 1.0).abs() > 0.0001 {
+                    let world_focus = self.camera.screen_to_world(focus, viewport);
+                    self.camera.gesture_zoom(zoom_delta, world_focus, viewport);
+                }
+                if let Some(touch) = touch {
+                    let scale = self.camera.zoom * viewport.width();
+                    self.camera.drag_pan(touch.translation_delta / scale, viewport);
                 }
             }
 
@@ -1462,22 +6727,114 @@ impl eframe::App for SpaceViewApp {
                 self.is_dragging = false;
             }
 
-            // Double-click: snap zoom into hovered directory
-            if response.double_clicked() && !self.is_dragging {
-                if let Some(ref info) = self.hovered_node_info {
-                    if info.is_dir && info.has_children {
-                        self.camera.snap_to(info.world_rect, viewport);
+            // Keymap bindings (keyboard + mouse). Actions on a node use the hovered one.
+            let actions = ctx.input(|i| self.keymap.triggered(i, &response));
+            let mut key_zoom_out = false;
+            let mut zoom_to_root = false;
+            for action in actions {
+                if presenting && !matches!(action, Action::ZoomIn | Action::ZoomOut | Action::ZoomToRoot) {
+                    continue;
+                }
+                let hovered_path = self.hovered_node_info.as_ref().and_then(|info| info.target_path().cloned());
+                match action {
+                    Action::ZoomIn => {
+                        if let Some(ref info) = self.hovered_node_info {
+                            if info.is_dir && info.has_children && !self.is_dragging {
+                                self.camera.snap_to(info.world_rect, viewport);
+                            }
+                        }
+                    }
+                    Action::ZoomOut => key_zoom_out = true,
+                    Action::ZoomToRoot => zoom_to_root = true,
+                    Action::OpenInExplorer => {
+                        if let Some(p) = hovered_path {
+                            reveal_in_explorer(&p);
+                        }
+                    }
+                    Action::CopyPath => {
+                        if let Some(p) = hovered_path {
+                            ctx.copy_text(p.to_string_lossy().to_string());
+                        }
+                    }
+                    Action::ToggleSelect => {
+                        if let Some(p) = hovered_path {
+                            self.selection.toggle(p);
+                        }
+                    }
+                    Action::ClearSelection => self.selection.clear(),
+                    Action::Delete => {
+                        if hovered_path.is_some() {
+                            self.pending_delete = hovered_path;
+                        }
+                    }
+                }
+            }
+
+            // Zoom bookmarks: Ctrl+1..9 saves the camera target, 1..9 returns to it
+            if !ctx.wants_keyboard_input() {
+                let pressed = ctx.input(|i| {
+                    BOOKMARK_KEYS.iter().position(|k| i.key_pressed(*k))
+                        .filter(|_| !i.modifiers.alt && !i.modifiers.shift)
+                        .map(|slot| (slot, i.modifiers.command))
+                });
+                match pressed {
+                    Some((slot, true)) if !presenting => {
+                        self.bookmarks[slot] = Some((self.camera.target_center, self.camera.target_zoom));
+                    }
+                    Some((slot, false)) => {
+                        if let Some((center, zoom)) = self.bookmarks[slot] {
+                            self.camera.snap_to_view(center, zoom, viewport);
+                        }
                     }
+                    _ => {}
+                }
+            }
+
+            // Touch: on-screen zoom buttons, and holding a finger still on a block
+            // opens its menu as a right-click does
+            let long_touched = touch_layout && response.long_touched() && self.hovered_node_info.is_some();
+            if touch_layout {
+                if response.is_pointer_button_down_on() {
+                    ctx.request_repaint(); // Notice the hold time running out
+                }
+                let mut zoom_steps = 0.0;
+                egui::Area::new(egui::Id::new("touch_zoom"))
+                    .fixed_pos(viewport.right_bottom() - egui::vec2(12.0, 12.0))
+                    .pivot(egui::Align2::RIGHT_BOTTOM)
+                    .show(ctx, |ui| {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.vertical(|ui| {
+                                let size = egui::vec2(TOUCH_TARGET_PX * 1.5, TOUCH_TARGET_PX * 1.5);
+                                if ui.add(egui::Button::new("+").min_size(size)).on_hover_text("Zoom in").clicked() {
+                                    zoom_steps = TOUCH_ZOOM_STEPS;
+                                }
+                                if ui.add(egui::Button::new("-").min_size(size)).on_hover_text("Zoom out").clicked() {
+                                    zoom_steps = -TOUCH_ZOOM_STEPS;
+                                }
+                                if ui.add(egui::Button::new("Fit").min_size(size)).on_hover_text("Whole map").clicked() {
+                                    zoom_to_root = true;
+                                }
+                            });
+                        });
+                    });
+                if zoom_steps != 0.0 {
+                    let world_focus = self.camera.screen_to_world(viewport.center(), viewport);
+                    self.camera.scroll_zoom(zoom_steps, world_focus, viewport);
                 }
             }
 
-            // Right-click context menu or zoom out
-            let right_clicked = ctx.input(|i| i.pointer.secondary_clicked());
-            let key_zoom_out = ctx.input(|i| i.key_pressed(egui::Key::Backspace))
-                || (!escape_consumed && ctx.input(|i| i.key_pressed(egui::Key::Escape)));
+            // Right-click context menu or zoom out. A zoom-to-root bound to a
+            // double right-click swallows the second click instead of reopening the menu.
+            let right_clicked = (ctx.input(|i| i.pointer.secondary_clicked()) || long_touched) && !zoom_to_root;
+            if zoom_to_root {
+                ui.memory_mut(|mem| mem.close_popup());
+                self.context_menu_info = None;
+            }
 
             // Show context menu on right-click over a hovered node
             let mut context_zoom_out = false;
+            let mut node_action = None;
+            let action_env = self.action_env();
             if right_clicked && self.hovered_node_info.is_some() {
                 self.context_menu_info = self.hovered_node_info.clone();
             }
@@ -1498,49 +6855,31 @@ impl eframe::App for SpaceViewApp {
                         ui.set_min_width(160.0);
                         ui.label(egui::RichText::new(&info.name).strong());
                         ui.label(format!("{} ({:.1}%)", format_size(info.size),
-                            if self.root_size > 0 { info.size as f64 / self.root_size as f64 * 100.0 } else { 0.0 }));
+                            info.size as f64 / self.map_total().max(1) as f64 * 100.0));
                         ui.separator();
-                        if info.is_dir && info.has_children {
-                            if ui.button("Zoom In").clicked() {
-                                self.camera.snap_to(info.world_rect, viewport);
-                            }
+                        if info.is_dir && info.has_children && ui.button("Zoom In").clicked() {
+                            self.camera.snap_to(info.world_rect, viewport);
                         }
                         if ui.button("Zoom Out").clicked() {
                             context_zoom_out = true;
                         }
-                        ui.separator();
-                        if ui.button("Open in Explorer").clicked() {
-                            if let Some(ref root) = self.scan_root {
-                                let path = find_path_for_node(root, &info.name, info.size);
-                                if let Some(p) = path {
-                                    let _ = std::process::Command::new("explorer")
-                                        .arg("/select,")
-                                        .arg(&p)
-                                        .spawn();
-                                }
-                            }
-                        }
-                        if ui.button("Copy Path").clicked() {
-                            if let Some(ref root) = self.scan_root {
-                                let path = find_path_for_node(root, &info.name, info.size);
-                                if let Some(p) = path {
-                                    ctx.copy_text(p.to_string_lossy().to_string());
-                                }
-                            }
+                        // Presenting: the map is read-only, nothing past zooming
+                        if presenting {
+                            return;
                         }
-                        if info.name != "<Free Space>" {
+                        let facts = NodeFacts::new(&info.name, &info.path, info.is_dir, info.size, info.file_count, info.has_children)
+                            .with_attrs(info.attrs)
+                            .with_selected(self.selection.contains(&info.path))
+                            .with_flattened(self.flattened.iter().any(|(f, _)| *f == info.path));
+                        if info.target_path().is_some() {
                             ui.separator();
-                            if ui.button("Delete to Recycle Bin").clicked() {
-                                if let Some(ref root) = self.scan_root {
-                                    let path = find_path_for_node(root, &info.name, info.size);
-                                    if let Some(p) = path {
-                                        self.pending_delete = Some(p);
-                                    }
-                                }
-                            }
                         }
+                        node_action = action_buttons(ui, &facts, &action_env).map(|action| (action, facts));
                     },
                 );
+                if let Some((action, facts)) = node_action {
+                    self.run_node_action(ctx, action, facts);
+                }
                 if !ui.memory(|mem| mem.is_popup_open(menu_id)) {
                     self.context_menu_info = None;
                 }
@@ -1549,7 +6888,11 @@ impl eframe::App for SpaceViewApp {
             let zoom_out = (right_clicked && self.hovered_node_info.is_none())
                 || key_zoom_out || context_zoom_out;
 
-            if zoom_out {
+            if zoom_to_root {
+                if let Some(ref layout) = self.world_layout {
+                    self.camera.snap_to(layout.world_rect, viewport);
+                }
+            } else if zoom_out {
                 // Zoom out: snap to parent of current center, or to root
                 if !self.depth_context.is_empty() {
                     // If we have 2+ breadcrumbs, go to second-to-last; otherwise root
@@ -1575,11 +6918,57 @@ impl eframe::App for SpaceViewApp {
 
             // 4. Render
             let painter = ui.painter_at(viewport);
+            // Legend highlight: pulse twice, fading out
+            let flash = self.flash_depth.and_then(|(depth, start)| {
+                let t = (ctx.input(|i| i.time) - start) / FLASH_SECS;
+                if t >= 1.0 {
+                    return None;
+                }
+                ctx.request_repaint();
+                let pulse = (t * 2.0 * std::f64::consts::TAU).cos() * 0.5 + 0.5;
+                Some((depth, (pulse * (1.0 - t)) as f32))
+            });
+            if flash.is_none() {
+                self.flash_depth = None;
+            }
             let theme = self.theme;
 
             // Walk the layout tree and draw visible nodes
+            let badges = std::cell::RefCell::new(Vec::new());
             if let Some(ref layout) = self.world_layout {
-                render_nodes(&painter, &layout.root_nodes, &self.camera, viewport, theme, self.color_mode, self.time_range, &self.ext_color_map, self.selected_extension.as_deref());
+                let rc = RenderCtx {
+                    painter: &painter,
+                    viewport,
+                    theme,
+                    color_mode: self.color_mode,
+                    age_ramp: self.age_ramp,
+                    high_contrast: self.high_contrast,
+                    label_density: self.label_density,
+                    metrics: self.shown_metrics(),
+                    time_range: self.time_range,
+                    ext_colors: &self.ext_color_map,
+                    selected_ext: self.selected_extension.as_deref(),
+                    age_band: self.age_band.filter(|_| self.color_mode == ColorMode::Age),
+                    selection: &self.selection,
+                    quotas: &self.quota_matches,
+                    growth: self.diff_growth.as_ref(),
+                    heat: self.heat.as_ref().filter(|_| self.heat_overlay),
+                    flash,
+                    badges: &badges,
+                };
+                render_nodes(&rc, &layout.root_nodes, &self.camera);
+            }
+
+            // Click on a "+N more" badge lists the culled children
+            let clicked_badge = mouse_pos
+                .filter(|_| response.clicked_by(egui::PointerButton::Primary) && !presenting)
+                .and_then(|pos| badges.borrow_mut().drain(..).rev().find(|b| b.rect.contains(pos)));
+            if let (Some(badge), Some(layout)) = (clicked_badge, self.world_layout.as_ref()) {
+                if let Some(dir) = find_layout_node(&layout.root_nodes, &badge.dir) {
+                    let mut items = hidden_children(dir, badge.content, &self.shown_metrics());
+                    items.sort_by_key(|(_, size, _)| std::cmp::Reverse(*size));
+                    self.hidden_items = Some(HiddenList { dir_name: dir.name.clone(), items });
+                }
             }
 
             // 5. Hit test for hover (screen-space, skip while dragging)
@@ -1587,7 +6976,7 @@ impl eframe::App for SpaceViewApp {
                 if let Some(pos) = mouse_pos {
                     if mouse_in_viewport {
                         if let Some(ref layout) = self.world_layout {
-                            if let Some(hit) = screen_hit_test(&layout.root_nodes, &self.camera, viewport, pos) {
+                            if let Some(hit) = screen_hit_test(&layout.root_nodes, &self.camera, viewport, pos, &self.shown_metrics()) {
                                 // Draw hover highlight using the screen_rect from hit test
                                 if hit.screen_rect.intersects(viewport) {
                                     painter.rect_stroke(
@@ -1602,529 +6991,1850 @@ impl eframe::App for SpaceViewApp {
                                 self.hovered_node_info = None;
                             }
                         }
-                    } else {
-                        self.hovered_node_info = None;
+                    } else {
+                        self.hovered_node_info = None;
+                    }
+                } else {
+                    self.hovered_node_info = None;
+                }
+            }
+
+            // Rich tooltip on hover, once the pointer has rested on one block for the hover delay
+            match self.hovered_node_info {
+                Some(ref info) if self.hover_started.is_none_or(|(rect, _)| rect != info.world_rect) => {
+                    self.hover_started = Some((info.world_rect, std::time::Instant::now()));
+                }
+                None => self.hover_started = None,
+                _ => {}
+            }
+            let delay = std::time::Duration::from_millis(self.hover_delay_ms);
+            let waiting = self.hover_started
+                .map(|(_, since)| delay.saturating_sub(since.elapsed()))
+                .filter(|left| !left.is_zero());
+            if let Some(left) = waiting.filter(|_| response.hovered()) {
+                ctx.request_repaint_after(left);
+            }
+            let show_tip = waiting.is_none() && self.tooltip_style != TooltipStyle::StatusBar;
+            if let Some(info) = self.hovered_node_info.as_ref().filter(|_| show_tip) {
+                if response.hovered() {
+                    let pct = info.size as f64 / self.map_total().max(1) as f64 * 100.0;
+                    let mut tip = format!("{}\n{} ({:.2}%)", info.name, format_size(info.size), pct);
+                    // Compact stops at name and size
+                    if self.tooltip_style == TooltipStyle::Detailed {
+                        // Directories, and the <Small Files> node that stands in for several
+                        if info.is_dir || info.file_count > 0 {
+                            tip += &format!("\n{} files", format_count(info.file_count));
+                        }
+                        if info.allocated != info.size && !info.path.as_os_str().is_empty() {
+                            tip += &format!("\n{} on disk", format_size(info.allocated));
+                        }
+                        if info.is_summarized() {
+                            tip += "\nContents summarized by the depth limit (right-click > Scan Contents)";
+                        }
+                        if info.attrs != 0 {
+                            tip += &format!("\n{}", attr_labels(info.attrs).join(", "));
+                            if is_offline(info.attrs) && info.size == 0 {
+                                tip += " - not stored locally, not counted";
+                            }
+                        }
+                        if let Some(p) = info.target_path() {
+                            tip += &format!("\n{}", p.to_string_lossy());
+                        } else if info.name == UNKNOWN_SPACE {
+                            tip += "\nUsed on the drive but not reachable by the scan: folders without \
+                                    access, file system metadata, shadow copies (see Reports > System)";
+                        }
+                    }
+                    response.clone().on_hover_text(tip);
+                }
+            }
+
+            // 6. Update breadcrumbs from camera center
+            self.update_breadcrumbs();
+
+            // 7. Draw zoom frame borders (when zoomed in)
+            if !self.depth_context.is_empty() && self.camera.zoom > 1.2 {
+                // Use the color of the deepest breadcrumb
+                let last = &self.depth_context[self.depth_context.len() - 1];
+                let ci = last.color_index;
+                let (r, g, b) = theme.base_rgb(ci);
+                let frame_col = egui::Color32::from_rgb(
+                    (r as f32 * 0.7) as u8,
+                    (g as f32 * 0.7) as u8,
+                    (b as f32 * 0.7) as u8,
+                );
+                let w = ZOOM_FRAME_WIDTH;
+                let fr = viewport;
+                // Top
+                painter.rect_filled(
+                    egui::Rect::from_min_max(fr.min, egui::pos2(fr.max.x, fr.min.y + w)),
+                    0.0, frame_col,
+                );
+                // Bottom
+                painter.rect_filled(
+                    egui::Rect::from_min_max(egui::pos2(fr.min.x, fr.max.y - w), fr.max),
+                    0.0, frame_col,
+                );
+                // Left
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(fr.min.x, fr.min.y + w),
+                        egui::pos2(fr.min.x + w, fr.max.y - w),
+                    ),
+                    0.0, frame_col,
+                );
+                // Right
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(fr.max.x - w, fr.min.y + w),
+                        egui::pos2(fr.max.x, fr.max.y - w),
+                    ),
+                    0.0, frame_col,
+                );
+            }
+
+            // 8. Zoom minimap (bottom-right corner when zoomed in)
+            if self.camera.zoom > 1.5 {
+                if let Some(ref layout) = self.world_layout {
+                    let mini_w = 180.0f32;
+                    let world_aspect = layout.world_rect.height() / layout.world_rect.width();
+                    let mini_h = mini_w * world_aspect;
+                    let margin = 8.0;
+                    let mini_rect = egui::Rect::from_min_size(
+                        egui::pos2(viewport.max.x - mini_w - margin, viewport.max.y - mini_h - margin),
+                        egui::vec2(mini_w, mini_h),
+                    );
+
+                    // Background
+                    painter.rect_filled(mini_rect, 4.0, egui::Color32::from_rgba_premultiplied(20, 20, 20, 200));
+
+                    // Render simplified treemap into minimap
+                    let mini_camera = Camera::new(
+                        egui::pos2(
+                            layout.world_rect.center().x,
+                            layout.world_rect.center().y,
+                        ),
+                        1.0,
+                    );
+                    render_minimap_nodes(&painter, &layout.root_nodes, &mini_camera, mini_rect, theme);
+
+                    // Draw viewport indicator
+                    let vp_world_min = self.camera.screen_to_world(viewport.min, viewport);
+                    let vp_world_max = self.camera.screen_to_world(viewport.max, viewport);
+                    let to_mini = |world_pos: egui::Pos2| -> egui::Pos2 {
+                        let nx = (world_pos.x - layout.world_rect.min.x) / layout.world_rect.width();
+                        let ny = (world_pos.y - layout.world_rect.min.y) / layout.world_rect.height();
+                        egui::pos2(
+                            mini_rect.min.x + nx * mini_rect.width(),
+                            mini_rect.min.y + ny * mini_rect.height(),
+                        )
+                    };
+                    let vp_mini = egui::Rect::from_min_max(
+                        to_mini(vp_world_min),
+                        to_mini(vp_world_max),
+                    ).intersect(mini_rect);
+                    painter.rect_stroke(
+                        vp_mini, 0.0,
+                        egui::Stroke::new(1.5, egui::Color32::WHITE),
+                        egui::StrokeKind::Outside,
+                    );
+
+                    // Border
+                    painter.rect_stroke(
+                        mini_rect, 4.0,
+                        egui::Stroke::new(1.0, egui::Color32::from_gray(80)),
+                        egui::StrokeKind::Outside,
+                    );
+                }
+            }
+
+            // 9. Request repaint if camera is moving
+            if camera_moving {
+                ctx.request_repaint();
+            }
+
+            } // ViewMode::Treemap
+
+            ViewMode::List => {
+                if reveal {
+                    self.reveal_in_list();
+                }
+                if let Some(ref root) = self.scan_root {
+                    let current_dir = if self.list_path.is_empty() {
+                        root
+                    } else {
+                        find_dir_by_path(root, &self.list_path).unwrap_or(root)
+                    };
+                    let parent_size = current_dir.size.max(1);
+                    let depth = self.list_path.len() + 1;
+                    let theme = self.theme;
+
+                    // Flat rows can run to millions, so they are kept sorted in a cache
+                    // (taken out here, put back at the end) instead of rebuilt each frame
+                    let flat = self.list_flat;
+                    let mut flat_cache = None;
+                    let direct_entries: Vec<ListEntry>;
+                    let entries: &Vec<ListEntry> = if flat {
+                        let key: FlatListKey = (
+                            self.list_path.clone(), self.search_text.clone(), self.list_sort, self.list_sort_asc,
+                            root.size, root.file_count,
+                        );
+                        let cache = match self.list_flat_cache.take() {
+                            Some(cache) if cache.0 == key => cache,
+                            _ => {
+                                let mut rows = flat_list_entries(current_dir, &self.search_text);
+                                sort_list_entries(&mut rows, self.list_sort, self.list_sort_asc);
+                                (key, rows)
+                            }
+                        };
+                        &flat_cache.insert(cache).1
+                    } else {
+                        let mut rows: Vec<ListEntry> = current_dir.children.iter()
+                            .map(|c| (c.name.clone(), c.size, c.file_count, c.is_dir, !c.children.is_empty(), c.path.clone(), String::new(), c.allocated))
+                            .collect();
+                        if !self.search_text.is_empty() {
+                            let q = self.search_text.to_lowercase();
+                            rows.retain(|e| e.0.to_lowercase().contains(&q));
+                        }
+                        sort_list_entries(&mut rows, self.list_sort, self.list_sort_asc);
+                        direct_entries = rows;
+                        &direct_entries
+                    };
+
+                    // Flat mode trades the file count column for a wider folder path
+                    let (name_w, last_w) = if flat { (0.30, 0.25) } else { (0.40, 0.15) };
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.list_flat, "Flat")
+                            .on_hover_text("List every file below this folder, with its folder path");
+                        if flat {
+                            ui.weak(format!("{} files", format_count(entries.len() as u64)));
+                        }
+                    });
+
+                    // Column headers (pre-compute arrows to avoid borrow conflict)
+                    let arrow = |col: SortColumn| -> &str {
+                        if self.list_sort == col {
+                            if self.list_sort_asc { " ^" } else { " v" }
+                        } else { "" }
+                    };
+                    let name_arrow = arrow(SortColumn::Name).to_string();
+                    let size_arrow = arrow(SortColumn::Size).to_string();
+                    let disk_arrow = arrow(SortColumn::OnDisk).to_string();
+                    let fc_arrow = arrow(SortColumn::FileCount).to_string();
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 4.0;
+                        let w = ui.available_width();
+                        if ui.add_sized([w * name_w, 18.0], egui::SelectableLabel::new(false,
+                            format!("Name{}", name_arrow))).clicked() {
+                            if self.list_sort == SortColumn::Name { self.list_sort_asc = !self.list_sort_asc; }
+                            else { self.list_sort = SortColumn::Name; self.list_sort_asc = true; }
+                        }
+                        if ui.add_sized([w * 0.15, 18.0], egui::SelectableLabel::new(false,
+                            format!("Size{}", size_arrow))).clicked() {
+                            if self.list_sort == SortColumn::Size { self.list_sort_asc = !self.list_sort_asc; }
+                            else { self.list_sort = SortColumn::Size; self.list_sort_asc = false; }
+                        }
+                        if ui.add_sized([w * 0.15, 18.0], egui::SelectableLabel::new(false,
+                            format!("On Disk{}", disk_arrow)))
+                            .on_hover_text("Space taken on disk: whole clusters, after compression")
+                            .clicked()
+                        {
+                            if self.list_sort == SortColumn::OnDisk { self.list_sort_asc = !self.list_sort_asc; }
+                            else { self.list_sort = SortColumn::OnDisk; self.list_sort_asc = false; }
+                        }
+                        ui.add_sized([w * 0.10, 18.0], egui::Label::new("%"));
+                        if flat {
+                            ui.add_sized([w * last_w, 18.0], egui::Label::new("Folder"));
+                        } else if ui.add_sized([w * last_w, 18.0], egui::SelectableLabel::new(false,
+                            format!("Files{}", fc_arrow))).clicked() {
+                            if self.list_sort == SortColumn::FileCount { self.list_sort_asc = !self.list_sort_asc; }
+                            else { self.list_sort = SortColumn::FileCount; self.list_sort_asc = false; }
+                        }
+                    });
+                    ui.separator();
+
+                    let mut nav_target: Option<String> = None;
+                    // Row clicked and whether Ctrl was held, applied to the selection afterwards
+                    let mut row_click: Option<(usize, bool)> = None;
+                    let selection = &self.selection;
+                    let list_action: std::cell::Cell<Option<(NodeAction, NodeFacts)>> = std::cell::Cell::new(None);
+                    let action_env = self.action_env();
+
+                    // ".." entry (outside virtual scroll)
+                    if !self.list_path.is_empty() && ui.selectable_label(false, "  ..").double_clicked() {
+                        nav_target = Some("..".to_string());
+                    }
+
+                    if entries.is_empty() && !self.search_text.is_empty() {
+                        ui.label("No matching items.");
+                    } else {
+                        let row_h = 22.0;
+                        let mut scroll = egui::ScrollArea::vertical().auto_shrink(false);
+                        if let Some(i) = selection.latest().filter(|_| reveal).and_then(|p| entries.iter().position(|e| e.5 == p)) {
+                            scroll = scroll.vertical_scroll_offset(row_offset(ui, i, row_h));
+                        }
+                        scroll.show_rows(
+                            ui, row_h, entries.len(), |ui, row_range| {
+                            for i in row_range {
+                                let (name, size, file_count, is_dir, has_children, path, folder, allocated) = &entries[i];
+                                let pct = (*size as f64 / parent_size as f64) * 100.0;
+                                let (r, g, b) = if *name == "<Free Space>" {
+                                    (60u8, 140u8, 60u8)
+                                } else if *name == UNKNOWN_SPACE {
+                                    (UNKNOWN_SPACE_COLOR.r(), UNKNOWN_SPACE_COLOR.g(), UNKNOWN_SPACE_COLOR.b())
+                                } else {
+                                    theme.base_rgb(depth)
+                                };
+                                let icon_col = egui::Color32::from_rgb(r, g, b);
+                                let icon = if *is_dir { "D" } else { "F" };
+
+                                ui.horizontal(|ui| {
+                                    ui.spacing_mut().item_spacing.x = 4.0;
+                                    let w = ui.available_width();
+
+                                    let name_text = format!("[{}] {}", icon, name);
+                                    let label = if *is_dir {
+                                        egui::RichText::new(&name_text).strong().color(icon_col)
+                                    } else {
+                                        egui::RichText::new(&name_text)
+                                    };
+                                    let resp = ui.add_sized([w * name_w, 18.0],
+                                        egui::SelectableLabel::new(selection.contains(path), label));
+                                    if resp.clicked() {
+                                        row_click = Some((i, ui.input(|inp| inp.modifiers.command)));
+                                    }
+                                    if resp.double_clicked() && *is_dir && *has_children {
+                                        nav_target = Some(name.clone());
+                                    }
+                                    resp.context_menu(|ui| {
+                                        ui.label(egui::RichText::new(name).strong());
+                                        ui.label(format!("{} ({:.1}%)", format_size(*size), pct));
+                                        let facts = match find_node(root, path) {
+                                            Some(node) if !path.as_os_str().is_empty() => NodeFacts::from_node(node),
+                                            _ => NodeFacts::new(name, path, *is_dir, *size, *file_count, *has_children),
+                                        };
+                                        if facts.kind != NodeKind::SpaceTile {
+                                            ui.separator();
+                                        }
+                                        let facts = facts.with_selected(selection.contains(path))
+                                            .with_flattened(self.flattened.iter().any(|(f, _)| f == path));
+                                        if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                            list_action.set(Some((action, facts)));
+                                        }
+                                    });
+
+                                    ui.add_sized([w * 0.15, 18.0], egui::Label::new(format_size(*size)));
+                                    ui.add_sized([w * 0.15, 18.0], egui::Label::new(format_size(*allocated)));
+                                    ui.add_sized([w * 0.10, 18.0], egui::Label::new(format!("{:.1}%", pct)));
+                                    if flat {
+                                        let folder = if folder.is_empty() { "." } else { folder.as_str() };
+                                        ui.add_sized([w * last_w, 18.0], egui::Label::new(folder).truncate())
+                                            .on_hover_text(folder);
+                                    } else {
+                                        let fc = if *is_dir { format_count(*file_count) } else { String::new() };
+                                        ui.add_sized([w * 0.15, 18.0], egui::Label::new(fc));
+                                    }
+                                });
+                            }
+                        });
+                    }
+
+                    if let Some((i, toggle)) = row_click {
+                        self.selection.click(entries[i].5.clone(), toggle);
+                    }
+                    // Handle navigation
+                    if let Some(ref target) = nav_target {
+                        if target == ".." {
+                            self.list_path.pop();
+                        } else {
+                            self.list_path.push(target.clone());
+                        }
+                    }
+                    if let Some((action, facts)) = list_action.take() {
+                        self.run_node_action(ctx, action, facts);
+                    }
+                    if let Some(cache) = flat_cache {
+                        self.list_flat_cache = Some(cache);
                     }
-                } else {
-                    self.hovered_node_info = None;
                 }
             }
 
-            // Rich tooltip on hover
-            if let Some(ref info) = self.hovered_node_info {
-                if response.hovered() {
-                    let pct = if self.root_size > 0 {
-                        (info.size as f64 / self.root_size as f64) * 100.0
-                    } else { 0.0 };
-                    let mut tip = format!("{}\n{} ({:.2}%)", info.name, format_size(info.size), pct);
-                    if info.is_dir {
-                        tip += &format!("\n{} files", format_count(info.file_count));
+            ViewMode::LargestFiles => {
+                // Context menu picks, applied once `cached_largest` is no longer borrowed
+                let mut top_action: Option<(NodeAction, NodeFacts)> = None;
+                let mut top_click: Option<(PathBuf, bool)> = None;
+                let action_env = self.action_env();
+                // Data is pre-collected during scan (no freeze on tab click)
+                if let Some(ref files) = self.cached_largest {
+                    let total_size = self.root_size.max(1);
+                    let theme = self.theme;
+                    {
+                    // Filter chips: the biggest types overall, toggled by clicking
+                    if let Some(ref exts) = self.cached_extensions {
+                        ui.horizontal_wrapped(|ui| {
+                            if ui.selectable_label(self.largest_ext_filter.is_none(), "All").clicked() {
+                                self.largest_ext_filter = None;
+                            }
+                            for (ext, size, _) in exts.iter().take(LARGEST_EXT_CHIPS) {
+                                let active = self.largest_ext_filter.as_ref() == Some(ext);
+                                if ui.selectable_label(active, ext.as_str()).on_hover_text(format_size(*size)).clicked() {
+                                    self.largest_ext_filter = if active { None } else { Some(ext.clone()) };
+                                }
+                            }
+                        });
+                        ui.add_space(2.0);
                     }
-                    if let Some(ref root) = self.scan_root {
-                        if let Some(p) = find_path_for_node(root, &info.name, info.size) {
-                            tip += &format!("\n{}", p.to_string_lossy());
+
+                    let mut filtered: Vec<(usize, &(String, u64, String))> = files.iter().enumerate().collect();
+                    if let Some(ref ext) = self.largest_ext_filter {
+                        filtered.retain(|(_, f)| extension_key(&f.0) == *ext);
+                    }
+                    if !self.search_text.is_empty() {
+                        let q = self.search_text.to_lowercase();
+                        filtered.retain(|(_, f)| f.0.to_lowercase().contains(&q) || f.2.to_lowercase().contains(&q));
+                    }
+
+                    // Column headers
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 4.0;
+                        let w = ui.available_width();
+                        ui.add_sized([w * 0.04, 18.0], egui::Label::new("#"));
+                        ui.add_sized([w * 0.28, 18.0], egui::Label::new("Name"));
+                        ui.add_sized([w * 0.38, 18.0], egui::Label::new("Path"));
+                        ui.add_sized([w * 0.15, 18.0], egui::Label::new("Size"));
+                        ui.add_sized([w * 0.10, 18.0], egui::Label::new("%"));
+                    });
+                    ui.separator();
+
+                    if filtered.is_empty() && (!self.search_text.is_empty() || self.largest_ext_filter.is_some()) {
+                        ui.label("No matching files.");
+                    } else {
+                        let row_h = 22.0;
+                        let mut scroll = egui::ScrollArea::vertical().auto_shrink(false);
+                        if let Some(i) = self.selection.latest().filter(|_| reveal)
+                            .and_then(|p| filtered.iter().position(|(_, f)| Path::new(&f.2) == p))
+                        {
+                            scroll = scroll.vertical_scroll_offset(row_offset(ui, i, row_h));
                         }
+                        let selection = &self.selection;
+                        let scan_root = self.scan_root.as_ref();
+                        scroll.show_rows(
+                            ui, row_h, filtered.len(), |ui, row_range| {
+                            for rank in row_range {
+                                let (_, entry) = &filtered[rank];
+                                let pct = (entry.1 as f64 / total_size as f64) * 100.0;
+                                let ci = rank % 20;
+                                let (r, g, b) = theme.base_rgb(ci);
+
+                                ui.horizontal(|ui| {
+                                    ui.spacing_mut().item_spacing.x = 4.0;
+                                    let w = ui.available_width();
+                                    ui.add_sized([w * 0.04, 18.0], egui::Label::new(
+                                        egui::RichText::new(format!("{}", rank + 1)).weak()));
+                                    let selected = selection.contains(Path::new(&entry.2));
+                                    let resp = ui.add_sized([w * 0.28, 18.0], egui::SelectableLabel::new(selected,
+                                        egui::RichText::new(&entry.0).color(egui::Color32::from_rgb(r, g, b))));
+                                    if resp.clicked() {
+                                        top_click = Some((PathBuf::from(&entry.2), ui.input(|i| i.modifiers.command)));
+                                    }
+                                    resp.context_menu(|ui| {
+                                        let path = PathBuf::from(&entry.2);
+                                        let facts = scan_root.and_then(|r| find_node(r, &path))
+                                            .map_or_else(|| NodeFacts::new(&entry.0, &path, false, entry.1, 0, false), NodeFacts::from_node)
+                                            .with_selected(selected);
+                                        if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                            top_action = Some((action, facts));
+                                        }
+                                    });
+                                    ui.add_sized([w * 0.38, 18.0], egui::Label::new(
+                                        egui::RichText::new(&entry.2).weak()));
+                                    ui.add_sized([w * 0.15, 18.0], egui::Label::new(format_size(entry.1)));
+                                    ui.add_sized([w * 0.10, 18.0], egui::Label::new(format!("{:.1}%", pct)));
+                                });
+                            }
+                        });
                     }
-                    response.clone().on_hover_text(tip);
+                }
+                } // else if cached_largest
+                if let Some((path, toggle)) = top_click {
+                    self.selection.click(path, toggle);
+                }
+                if let Some((action, facts)) = top_action {
+                    self.run_node_action(ctx, action, facts);
                 }
             }
 
-            // 6. Update breadcrumbs from camera center
-            self.update_breadcrumbs();
+            ViewMode::Extensions => {
+                let mut delete_type: Option<String> = None;
+                let can_delete = self.remote.is_none() && !self.scanning;
+                // Types of the selected files get the selection outline
+                let selected_exts: std::collections::HashSet<String> = match self.scan_root {
+                    Some(ref root) => self.selection.iter()
+                        .filter_map(|p| find_node(root, p))
+                        .filter(|n| !n.is_dir)
+                        .map(|n| extension_key(&n.name))
+                        .collect(),
+                    None => Default::default(),
+                };
+                if let Some(ref ext_data) = self.cached_extensions {
+                    let by_count = self.ext_by_count;
+                    let total = if by_count { self.root_file_count } else { self.root_size }.max(1);
+                    let theme = self.theme;
 
-            // 7. Draw zoom frame borders (when zoomed in)
-            if !self.depth_context.is_empty() && self.camera.zoom > 1.2 {
-                // Use the color of the deepest breadcrumb
-                let last = &self.depth_context[self.depth_context.len() - 1];
-                let ci = last.color_index;
-                let (r, g, b) = theme.base_rgb(ci);
-                let frame_col = egui::Color32::from_rgb(
-                    (r as f32 * 0.7) as u8,
-                    (g as f32 * 0.7) as u8,
-                    (b as f32 * 0.7) as u8,
-                );
-                let w = ZOOM_FRAME_WIDTH;
-                let fr = viewport;
-                // Top
-                painter.rect_filled(
-                    egui::Rect::from_min_max(fr.min, egui::pos2(fr.max.x, fr.min.y + w)),
-                    0.0, frame_col,
-                );
-                // Bottom
-                painter.rect_filled(
-                    egui::Rect::from_min_max(egui::pos2(fr.min.x, fr.max.y - w), fr.max),
-                    0.0, frame_col,
-                );
-                // Left
-                painter.rect_filled(
-                    egui::Rect::from_min_max(
-                        egui::pos2(fr.min.x, fr.min.y + w),
-                        egui::pos2(fr.min.x + w, fr.max.y - w),
-                    ),
-                    0.0, frame_col,
-                );
-                // Right
-                painter.rect_filled(
-                    egui::Rect::from_min_max(
-                        egui::pos2(fr.max.x - w, fr.min.y + w),
-                        egui::pos2(fr.max.x, fr.max.y - w),
-                    ),
-                    0.0, frame_col,
-                );
-            }
+                    ui.horizontal(|ui| {
+                        ui.label("Size by:");
+                        ui.selectable_value(&mut self.ext_by_count, false, "Bytes");
+                        ui.selectable_value(&mut self.ext_by_count, true, "File count")
+                            .on_hover_text("Show which types clutter the disk with many files");
+                    });
+                    ui.separator();
 
-            // 8. Zoom minimap (bottom-right corner when zoomed in)
-            if self.camera.zoom > 1.5 {
-                if let Some(ref layout) = self.world_layout {
-                    let mini_w = 180.0f32;
-                    let world_aspect = layout.world_rect.height() / layout.world_rect.width();
-                    let mini_h = mini_w * world_aspect;
-                    let margin = 8.0;
-                    let mini_rect = egui::Rect::from_min_size(
-                        egui::pos2(viewport.max.x - mini_w - margin, viewport.max.y - mini_h - margin),
-                        egui::vec2(mini_w, mini_h),
-                    );
+                    let mut filtered: Vec<&(String, u64, u64)> = ext_data.iter().collect();
+                    if !self.search_text.is_empty() {
+                        let q = self.search_text.to_lowercase();
+                        filtered.retain(|e| e.0.to_lowercase().contains(&q));
+                    }
+                    // cached_extensions is largest first; squarify wants the weights descending
+                    if by_count {
+                        filtered.sort_by_key(|e| std::cmp::Reverse(e.2));
+                    }
 
-                    // Background
-                    painter.rect_filled(mini_rect, 4.0, egui::Color32::from_rgba_premultiplied(20, 20, 20, 200));
+                    if filtered.is_empty() {
+                        ui.label("No matching file types.");
+                    } else {
+                        // Render as a treemap of extensions
+                        let ext_rect = ui.available_rect_before_wrap();
+                        let painter = ui.painter_at(ext_rect);
+                        let _response = ui.allocate_rect(ext_rect, egui::Sense::hover());
 
-                    // Render simplified treemap into minimap
-                    let mini_camera = Camera::new(
-                        egui::pos2(
-                            layout.world_rect.center().x,
-                            layout.world_rect.center().y,
-                        ),
-                        1.0,
-                    );
-                    render_minimap_nodes(&painter, &layout.root_nodes, &mini_camera, mini_rect, theme);
+                        let sizes: Vec<f64> = filtered.iter()
+                            .map(|e| if by_count { e.2 } else { e.1 } as f64)
+                            .collect();
+                        let rects = treemap::layout(
+                            ext_rect.min.x, ext_rect.min.y,
+                            ext_rect.width(), ext_rect.height(),
+                            &sizes,
+                        );
 
-                    // Draw viewport indicator
-                    let vp_world_min = self.camera.screen_to_world(viewport.min, viewport);
-                    let vp_world_max = self.camera.screen_to_world(viewport.max, viewport);
-                    let to_mini = |world_pos: egui::Pos2| -> egui::Pos2 {
-                        let nx = (world_pos.x - layout.world_rect.min.x) / layout.world_rect.width();
-                        let ny = (world_pos.y - layout.world_rect.min.y) / layout.world_rect.height();
-                        egui::pos2(
-                            mini_rect.min.x + nx * mini_rect.width(),
-                            mini_rect.min.y + ny * mini_rect.height(),
-                        )
-                    };
-                    let vp_mini = egui::Rect::from_min_max(
-                        to_mini(vp_world_min),
-                        to_mini(vp_world_max),
-                    ).intersect(mini_rect);
-                    painter.rect_stroke(
-                        vp_mini, 0.0,
-                        egui::Stroke::new(1.5, egui::Color32::WHITE),
-                        egui::StrokeKind::Outside,
-                    );
+                        for tr in &rects {
+                            let ext = &filtered[tr.index];
+                            let rect = egui::Rect::from_min_size(
+                                egui::pos2(tr.x, tr.y),
+                                egui::vec2(tr.w, tr.h),
+                            );
+                            let inner = rect.shrink(1.0);
+                            let ci = tr.index;
+                            let (r, g, b) = theme.base_rgb(ci);
+                            let col = egui::Color32::from_rgb(r, g, b);
+                            painter.rect_filled(inner, 2.0, col);
+                            if selected_exts.contains(&ext.0) {
+                                painter.rect_stroke(inner, 2.0, egui::Stroke::new(2.0, SELECTION_COLOR), egui::StrokeKind::Inside);
+                            }
 
-                    // Border
-                    painter.rect_stroke(
-                        mini_rect, 4.0,
-                        egui::Stroke::new(1.0, egui::Color32::from_gray(80)),
-                        egui::StrokeKind::Outside,
-                    );
+                            let block = ui.interact(inner.intersect(ext_rect), ui.id().with(("ext_block", &ext.0)), egui::Sense::click());
+                            block.context_menu(|ui| {
+                                let label = format!(
+                                    "Delete all {} files ({} files, {})...",
+                                    ext.0, format_count(ext.2), format_size(ext.1),
+                                );
+                                // Extensionless files include executables and system files; too broad to bulk delete
+                                let allowed = can_delete && ext.0.starts_with('.');
+                                if ui.add_enabled(allowed, egui::Button::new(label)).clicked() {
+                                    delete_type = Some(ext.0.clone());
+                                    ui.close_menu();
+                                }
+                            });
+
+                            // Draw text if block is big enough
+                            if inner.width() > 40.0 && inner.height() > 18.0 {
+                                let text_clip = inner.intersect(ext_rect);
+                                let text_painter = painter.with_clip_rect(text_clip);
+                                let text_col = text_color_for(col, self.high_contrast);
+                                let weight = if by_count { ext.2 } else { ext.1 };
+                                let pct = (weight as f64 / total as f64) * 100.0;
+
+                                // Extension name
+                                let font_size = (inner.height() * 0.3).clamp(11.0, 24.0);
+                                let font = egui::FontId::proportional(font_size);
+                                let label = truncate_middle(&text_painter, &ext.0, &font, inner.width() - 6.0);
+                                text_painter.text(
+                                    inner.min + egui::vec2(4.0, 4.0),
+                                    egui::Align2::LEFT_TOP,
+                                    label,
+                                    font,
+                                    text_col,
+                                );
+
+                                // Size and count
+                                if inner.height() > 36.0 {
+                                    let info = if by_count {
+                                        format!("{} files ({:.1}%, {})", format_count(ext.2), pct, format_size(ext.1))
+                                    } else {
+                                        format!("{} ({:.1}%, {} files)", format_size(ext.1), pct, format_count(ext.2))
+                                    };
+                                    let info_size = (font_size * 0.7).clamp(9.0, 14.0);
+                                    text_painter.text(
+                                        inner.min + egui::vec2(4.0, font_size + 6.0),
+                                        egui::Align2::LEFT_TOP,
+                                        info,
+                                        egui::FontId::proportional(info_size),
+                                        text_col.gamma_multiply(0.7),
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+                if let (Some(ext), Some(root)) = (delete_type, self.scan_root.as_ref()) {
+                    let mut files = Vec::new();
+                    collect_files_of_type(root, &ext, &mut files);
+                    let size = files.iter().map(|f| f.size).sum();
+                    let paths = files.iter().map(|f| f.path.clone()).collect();
+                    let sizes = files.iter().map(|f| f.size).collect();
+                    self.pending_type_delete = Some(TypeDelete { ext, paths, sizes, size });
                 }
             }
 
-            // 9. Request repaint if camera is moving
-            if camera_moving {
-                ctx.request_repaint();
-            }
+            ViewMode::Duplicates => {
+                let mut dup_action: Option<(NodeAction, NodeFacts)> = None;
+                let mut dup_click: Option<(PathBuf, bool)> = None;
+                let mut extra_copies: Option<Manifest> = None;
+                let action_env = self.action_env();
+                if self.dup_receiver.is_some() && self.cached_duplicates.is_none() {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(ui.available_height() / 3.0);
+                        ui.heading("Analyzing duplicates...");
+                        ui.spinner();
+                    });
+                } else if let Some(ref dups) = self.cached_duplicates {
+                    let total_waste: u64 = dups.iter()
+                        .map(|g| g.size * (g.paths.len() as u64 - 1))
+                        .sum();
+                    let total_groups = dups.len();
 
-            } // ViewMode::Treemap
+                    // Summary header
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "{} duplicate groups. {} wasted.",
+                            format_count(total_groups as u64),
+                            format_size(total_waste),
+                        ));
+                        ui.separator();
+                        ui.label("Group by:");
+                        ui.selectable_value(&mut self.dup_by_folder, false, "Files");
+                        ui.selectable_value(&mut self.dup_by_folder, true, "Folder pairs");
+                        ui.separator();
+                        let local = self.remote.is_none() && !dups.is_empty();
+                        if ui.add_enabled(local, egui::Button::new("Remove Extra Copies..."))
+                            .on_hover_text("Dry run: lists every copy but one per group (the one in the shortest \
+                                            folder path is kept), to review, run or export. Honors the search box")
+                            .clicked()
+                        {
+                            let q = self.search_text.to_lowercase();
+                            let groups: Vec<&DuplicateGroup> = dups.iter()
+                                .filter(|g| q.is_empty() || g.paths.iter().any(|p| p.to_lowercase().contains(&q)))
+                                .collect();
+                            extra_copies = Some(extra_copies_manifest(&groups));
+                        }
+                    });
+                    ui.separator();
 
-            ViewMode::List => {
-                if let Some(ref root) = self.scan_root {
-                    let current_dir = if self.list_path.is_empty() {
-                        root
+                    if self.dup_by_folder {
+                        let q = self.search_text.to_lowercase();
+                        let pairs: Vec<&FolderPairDup> = self.cached_dup_folders.iter()
+                            .filter(|p| q.is_empty()
+                                || p.copy_dir.to_lowercase().contains(&q)
+                                || p.original_dir.to_lowercase().contains(&q))
+                            .collect();
+                        egui::ScrollArea::vertical().auto_shrink(false).show_rows(ui, 22.0, pairs.len(), |ui, range| {
+                            for (i, pair) in pairs[range.clone()].iter().enumerate() {
+                                let (r, g, b) = self.theme.base_rgb((range.start + i) % 20);
+                                ui.horizontal(|ui| {
+                                    let mut folder_label = |ui: &mut egui::Ui, dir: &str| {
+                                        ui.add(egui::Label::new(egui::RichText::new(dir).strong()).sense(egui::Sense::click()))
+                                            .context_menu(|ui| {
+                                                let facts = self.path_facts(Path::new(dir), true, pair.bytes);
+                                                if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                    dup_action = Some((action, facts));
+                                                }
+                                            });
+                                    };
+                                    let amount = format!("{} in {} files", format_size(pair.bytes), format_count(pair.files));
+                                    folder_label(ui, &pair.copy_dir);
+                                    if pair.copy_dir == pair.original_dir {
+                                        ui.colored_label(egui::Color32::from_rgb(r, g, b), format!("holds {} duplicated within itself", amount));
+                                    } else {
+                                        ui.colored_label(egui::Color32::from_rgb(r, g, b), format!("duplicates {} of", amount));
+                                        folder_label(ui, &pair.original_dir);
+                                    }
+                                });
+                            }
+                        });
                     } else {
-                        find_dir_by_path(root, &self.list_path).unwrap_or(root)
-                    };
-                    let parent_size = current_dir.size.max(1);
-                    let depth = self.list_path.len() + 1;
-                    let theme = self.theme;
+                        let mut filtered: Vec<&DuplicateGroup> = dups.iter().collect();
+                        if !self.search_text.is_empty() {
+                            let q = self.search_text.to_lowercase();
+                            filtered.retain(|g| g.paths.iter().any(|p| p.to_lowercase().contains(&q)));
+                        }
 
-                    // Collect entries as owned data (avoids borrow issues)
-                    let mut entries: Vec<(String, u64, u64, bool, bool, PathBuf)> = current_dir.children.iter()
-                        .map(|c| (c.name.clone(), c.size, c.file_count, c.is_dir, !c.children.is_empty(), c.path.clone()))
-                        .collect();
+                        if filtered.is_empty() && !self.search_text.is_empty() {
+                            ui.label("No matching duplicates.");
+                        } else {
+                            egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                                for (gi, group) in filtered.iter().enumerate() {
+                                    let waste = group.size * (group.paths.len() as u64 - 1);
+                                    let ci = gi % 20;
+                                    let (r, g, b) = self.theme.base_rgb(ci);
+                                    let col = egui::Color32::from_rgb(r, g, b);
 
-                    // Search filter
-                    if !self.search_text.is_empty() {
-                        let q = self.search_text.to_lowercase();
-                        entries.retain(|e| e.0.to_lowercase().contains(&q));
-                    }
+                                    ui.horizontal(|ui| {
+                                        ui.colored_label(col, format!(
+                                            "{} x {} (wastes {})",
+                                            group.paths.len(),
+                                            format_size(group.size),
+                                            format_size(waste),
+                                        ));
+                                    });
 
-                    // Sort
-                    match self.list_sort {
-                        SortColumn::Name => {
-                            entries.sort_by(|a, b| {
-                                let dir_order = b.3.cmp(&a.3); // dirs first
-                                if dir_order != std::cmp::Ordering::Equal { return dir_order; }
-                                let cmp = a.0.to_lowercase().cmp(&b.0.to_lowercase());
-                                if self.list_sort_asc { cmp } else { cmp.reverse() }
+                                    for path in &group.paths {
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(16.0);
+                                            let resp = ui.add(egui::SelectableLabel::new(
+                                                self.selection.contains(Path::new(path)),
+                                                egui::RichText::new(path).weak(),
+                                            ));
+                                            if resp.clicked() {
+                                                dup_click = Some((PathBuf::from(path), ui.input(|i| i.modifiers.command)));
+                                            }
+                                            if reveal && self.selection.latest() == Some(Path::new(path)) {
+                                                resp.scroll_to_me(Some(egui::Align::Center));
+                                            }
+                                            resp.context_menu(|ui| {
+                                                let facts = self.path_facts(Path::new(path), false, group.size);
+                                                if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                    dup_action = Some((action, facts));
+                                                }
+                                            });
+                                        });
+                                    }
+                                    ui.add_space(4.0);
+                                    ui.separator();
+                                }
                             });
                         }
-                        SortColumn::Size => {
-                            entries.sort_by(|a, b| {
-                                let cmp = b.1.cmp(&a.1);
-                                if self.list_sort_asc { cmp.reverse() } else { cmp }
-                            });
+                    }
+                } else {
+                    ui.label("No duplicate data available. Scan a drive first.");
+                }
+                if let Some((path, toggle)) = dup_click {
+                    self.selection.click(path, toggle);
+                }
+                if let Some((action, facts)) = dup_action {
+                    self.run_node_action(ctx, action, facts);
+                }
+                if extra_copies.is_some() {
+                    self.manifest_review = extra_copies;
+                }
+            }
+
+            ViewMode::Reports => {
+                let mut report_action: Option<(NodeAction, NodeFacts)> = None;
+                let mut report_click: Option<(PathBuf, bool)> = None;
+                let action_env = self.action_env();
+                ui.horizontal(|ui| {
+                    ui.label("Report:");
+                    egui::ComboBox::from_id_salt("report_selector")
+                        .selected_text(self.report_kind.label())
+                        .show_ui(ui, |ui| {
+                            for &r in &REPORTS {
+                                ui.selectable_value(&mut self.report_kind, r, r.label());
+                            }
+                        });
+                });
+                ui.separator();
+
+                match self.report_kind {
+                    ReportKind::GitRepos => {
+                        if self.cached_git_repos.is_none() {
+                            if let Some(ref root) = self.scan_root {
+                                self.cached_git_repos = Some(find_git_repos(root));
+                            }
                         }
-                        SortColumn::FileCount => {
-                            entries.sort_by(|a, b| {
-                                let cmp = b.2.cmp(&a.2);
-                                if self.list_sort_asc { cmp.reverse() } else { cmp }
+                        if let Some(ref repos) = self.cached_git_repos {
+                            let total_git: u64 = repos.iter().map(|r| r.git_size).sum();
+                            ui.label(format!(
+                                "{} repositories. {} in .git folders.",
+                                format_count(repos.len() as u64),
+                                format_size(total_git),
+                            ));
+                            ui.separator();
+
+                            let mut filtered: Vec<&GitRepoInfo> = repos.iter().collect();
+                            if !self.search_text.is_empty() {
+                                let q = self.search_text.to_lowercase();
+                                filtered.retain(|r| r.path.to_string_lossy().to_lowercase().contains(&q));
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 4.0;
+                                let w = ui.available_width();
+                                ui.add_sized([w * 0.40, 18.0], egui::Label::new("Repository"));
+                                ui.add_sized([w * 0.13, 18.0], egui::Label::new("Working tree"));
+                                ui.add_sized([w * 0.13, 18.0], egui::Label::new(".git"));
+                                ui.add_sized([w * 0.30, 18.0], egui::Label::new("Suggestion"));
                             });
+                            ui.separator();
+
+                            if filtered.is_empty() {
+                                ui.label("No Git repositories found.");
+                            } else {
+                                let row_h = 22.0;
+                                egui::ScrollArea::vertical().auto_shrink(false).show_rows(
+                                    ui, row_h, filtered.len(), |ui, row_range| {
+                                    for i in row_range {
+                                        let repo = filtered[i];
+                                        ui.horizontal(|ui| {
+                                            ui.spacing_mut().item_spacing.x = 4.0;
+                                            let w = ui.available_width();
+                                            let path_str = repo.path.to_string_lossy().to_string();
+                                            let mut resp = ui.add_sized([w * 0.40, 18.0],
+                                                egui::SelectableLabel::new(false, &path_str));
+                                            if !repo.large_files.is_empty() {
+                                                let mut tip = String::from("Large files:");
+                                                for (name, size) in repo.large_files.iter().take(10) {
+                                                    tip += &format!("\n{} ({})", name, format_size(*size));
+                                                }
+                                                resp = resp.on_hover_text(tip);
+                                            }
+                                            resp.context_menu(|ui| {
+                                                let facts = self.path_facts(&repo.path, true, repo.working_size());
+                                                if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                    report_action = Some((action, facts));
+                                                }
+                                            });
+                                            ui.add_sized([w * 0.13, 18.0], egui::Label::new(format_size(repo.working_size())));
+                                            ui.add_sized([w * 0.13, 18.0], egui::Label::new(format_size(repo.git_size)));
+                                            let hint = repo.suggestions().join(", ");
+                                            ui.add_sized([w * 0.30, 18.0], egui::Label::new(
+                                                egui::RichText::new(hint).color(egui::Color32::from_rgb(220, 180, 50))));
+                                        });
+                                    }
+                                });
+                            }
                         }
                     }
-
-                    // Column headers (pre-compute arrows to avoid borrow conflict)
-                    let arrow = |col: SortColumn| -> &str {
-                        if self.list_sort == col {
-                            if self.list_sort_asc { " ^" } else { " v" }
-                        } else { "" }
-                    };
-                    let name_arrow = arrow(SortColumn::Name).to_string();
-                    let size_arrow = arrow(SortColumn::Size).to_string();
-                    let fc_arrow = arrow(SortColumn::FileCount).to_string();
-                    ui.horizontal(|ui| {
-                        ui.spacing_mut().item_spacing.x = 4.0;
-                        let w = ui.available_width();
-                        if ui.add_sized([w * 0.50, 18.0], egui::SelectableLabel::new(false,
-                            format!("Name{}", name_arrow))).clicked() {
-                            if self.list_sort == SortColumn::Name { self.list_sort_asc = !self.list_sort_asc; }
-                            else { self.list_sort = SortColumn::Name; self.list_sort_asc = true; }
+                    ReportKind::MatchingFolders => {
+                        if self.cached_matching_folders.is_none() {
+                            if let Some(ref root) = self.scan_root {
+                                self.cached_matching_folders = Some(find_matching_folders(root));
+                            }
                         }
-                        if ui.add_sized([w * 0.20, 18.0], egui::SelectableLabel::new(false,
-                            format!("Size{}", size_arrow))).clicked() {
-                            if self.list_sort == SortColumn::Size { self.list_sort_asc = !self.list_sort_asc; }
-                            else { self.list_sort = SortColumn::Size; self.list_sort_asc = false; }
+                        if let Some(ref groups) = self.cached_matching_folders {
+                            let total_extra: u64 = groups.iter().map(|g| g.extra()).sum();
+                            ui.label(format!(
+                                "{} sets of folders with the same names and sizes inside. {} in extra copies.",
+                                format_count(groups.len() as u64),
+                                format_size(total_extra),
+                            )).on_hover_text("Matched by structure only, not content. Compare or hash before deleting a copy.");
+                            ui.separator();
+
+                            let mut filtered: Vec<&MatchingFolders> = groups.iter().collect();
+                            if !self.search_text.is_empty() {
+                                let q = self.search_text.to_lowercase();
+                                filtered.retain(|g| g.paths.iter().any(|p| p.to_string_lossy().to_lowercase().contains(&q)));
+                            }
+
+                            if filtered.is_empty() {
+                                ui.label("No matching folders found.");
+                            } else {
+                                egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                                    for (gi, group) in filtered.iter().enumerate() {
+                                        let (r, g, b) = self.theme.base_rgb(gi % 20);
+                                        ui.colored_label(egui::Color32::from_rgb(r, g, b), format!(
+                                            "{} x {}, {} files each (extra copies hold {})",
+                                            group.paths.len(),
+                                            format_size(group.size),
+                                            format_count(group.file_count),
+                                            format_size(group.extra()),
+                                        ));
+                                        for path in &group.paths {
+                                            ui.horizontal(|ui| {
+                                                ui.add_space(16.0);
+                                                let path_str = path.to_string_lossy().to_string();
+                                                let resp = ui.add(egui::SelectableLabel::new(
+                                                    self.selection.contains(path),
+                                                    egui::RichText::new(&path_str).weak(),
+                                                ));
+                                                if resp.clicked() {
+                                                    report_click = Some((path.clone(), ui.input(|i| i.modifiers.command)));
+                                                }
+                                                resp.context_menu(|ui| {
+                                                    let facts = self.path_facts(path, true, group.size);
+                                                    if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                        report_action = Some((action, facts));
+                                                    }
+                                                });
+                                            });
+                                        }
+                                        ui.add_space(4.0);
+                                        ui.separator();
+                                    }
+                                });
+                            }
                         }
-                        ui.add_sized([w * 0.10, 18.0], egui::Label::new("%"));
-                        if ui.add_sized([w * 0.15, 18.0], egui::SelectableLabel::new(false,
-                            format!("Files{}", fc_arrow))).clicked() {
-                            if self.list_sort == SortColumn::FileCount { self.list_sort_asc = !self.list_sort_asc; }
-                            else { self.list_sort = SortColumn::FileCount; self.list_sort_asc = false; }
+                    }
+                    ReportKind::ArchiveCandidates => {
+                        ui.horizontal(|ui| {
+                            ui.label("Untouched for at least");
+                            let changed = ui.add(egui::DragValue::new(&mut self.archive_months)
+                                .range(1..=MAX_ARCHIVE_MONTHS)
+                                .suffix(" months"))
+                                .changed();
+                            if changed {
+                                self.cached_archive_candidates = None;
+                            }
+                        });
+                        if self.cached_archive_candidates.is_none() {
+                            if let Some(ref root) = self.scan_root {
+                                let cutoff = now_secs().saturating_sub(self.archive_months as u64 * ARCHIVE_MONTH_SECS);
+                                self.cached_archive_candidates = Some(find_archive_candidates(root, cutoff));
+                            }
                         }
-                    });
-                    ui.separator();
+                        let mut export_list = false;
+                        let mut make_script = false;
+                        if let Some(ref candidates) = self.cached_archive_candidates {
+                            let total: u64 = candidates.iter().map(|c| c.size).sum();
+                            let scanned = self.scan_root.as_ref().map_or(0, |r| r.size);
+                            ui.horizontal(|ui| {
+                                ui.label(format!(
+                                    "{} cold folders. Moving them to secondary storage would free {} ({:.1}% of the scan).",
+                                    format_count(candidates.len() as u64),
+                                    format_size(total),
+                                    total as f64 / scanned.max(1) as f64 * 100.0,
+                                )).on_hover_text("Cold: nothing inside was modified in that time. Folders under 100 MB are left out.");
+                                let any = !candidates.is_empty();
+                                export_list = ui.add_enabled(any, egui::Button::new("Export List...")).clicked();
+                                make_script = ui.add_enabled(any && self.remote.is_none(), egui::Button::new("Migration Script..."))
+                                    .on_hover_text("robocopy / rsync script that moves these folders")
+                                    .clicked();
+                            });
+                            ui.separator();
 
-                    let mut nav_target: Option<String> = None;
-                    let list_action: std::cell::Cell<Option<(usize, u8)>> = std::cell::Cell::new(None);
+                            let mut filtered: Vec<&ArchiveCandidate> = candidates.iter().collect();
+                            if !self.search_text.is_empty() {
+                                let q = self.search_text.to_lowercase();
+                                filtered.retain(|c| c.path.to_string_lossy().to_lowercase().contains(&q));
+                            }
 
-                    // ".." entry (outside virtual scroll)
-                    if !self.list_path.is_empty() {
-                        if ui.selectable_label(false, "  ..").double_clicked() {
-                            nav_target = Some("..".to_string());
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 4.0;
+                                let w = ui.available_width();
+                                ui.add_sized([w * 0.52, 18.0], egui::Label::new("Folder"));
+                                ui.add_sized([w * 0.14, 18.0], egui::Label::new("Size"));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Files"));
+                                ui.add_sized([w * 0.18, 18.0], egui::Label::new("Last change"));
+                            });
+                            ui.separator();
+
+                            if filtered.is_empty() {
+                                ui.label("No cold folders found.");
+                            } else {
+                                let row_h = 22.0;
+                                egui::ScrollArea::vertical().auto_shrink(false).show_rows(
+                                    ui, row_h, filtered.len(), |ui, row_range| {
+                                    for i in row_range {
+                                        let candidate = filtered[i];
+                                        ui.horizontal(|ui| {
+                                            ui.spacing_mut().item_spacing.x = 4.0;
+                                            let w = ui.available_width();
+                                            let path_str = candidate.path.to_string_lossy().to_string();
+                                            let resp = ui.add_sized([w * 0.52, 18.0], egui::SelectableLabel::new(
+                                                self.selection.contains(&candidate.path), &path_str));
+                                            if resp.clicked() {
+                                                report_click = Some((candidate.path.clone(), ui.input(|i| i.modifiers.command)));
+                                            }
+                                            resp.context_menu(|ui| {
+                                                let facts = self.path_facts(&candidate.path, true, candidate.size);
+                                                if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                    report_action = Some((action, facts));
+                                                }
+                                            });
+                                            ui.add_sized([w * 0.14, 18.0], egui::Label::new(format_size(candidate.size)));
+                                            ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_count(candidate.file_count)));
+                                            ui.add_sized([w * 0.18, 18.0], egui::Label::new(iso_date(candidate.modified)));
+                                        });
+                                    }
+                                });
+                            }
+                        }
+                        if export_list {
+                            self.export_archive_list();
+                        }
+                        if make_script {
+                            self.request_archive_migration();
                         }
                     }
+                    ReportKind::UserProfiles => {
+                        if self.cached_user_profiles.is_none() {
+                            if let Some(ref root) = self.scan_root {
+                                self.cached_user_profiles = Some(find_user_profiles(root));
+                            }
+                        }
+                        if let Some(ref profiles) = self.cached_user_profiles {
+                            if profiles.is_empty() {
+                                ui.label("No user profiles in this scan. Scan the system drive or its Users folder.");
+                            } else {
+                                let total: u64 = profiles.iter().map(|p| p.size).sum();
+                                let app_data: u64 = profiles.iter().map(|p| p.app_data_size()).sum();
+                                ui.label(format!(
+                                    "{} profiles, {} in total. {} of it is app data.",
+                                    format_count(profiles.len() as u64),
+                                    format_size(total),
+                                    format_size(app_data),
+                                ));
+                                ui.separator();
 
-                    if entries.is_empty() && !self.search_text.is_empty() {
-                        ui.label("No matching items.");
-                    } else {
-                        let row_h = 22.0;
-                        egui::ScrollArea::vertical().auto_shrink(false).show_rows(
-                            ui, row_h, entries.len(), |ui, row_range| {
-                            for i in row_range {
-                                let (name, size, file_count, is_dir, has_children, _path) = &entries[i];
-                                let pct = (*size as f64 / parent_size as f64) * 100.0;
-                                let (r, g, b) = if *name == "<Free Space>" {
-                                    (60u8, 140u8, 60u8)
-                                } else {
-                                    theme.base_rgb(depth)
-                                };
-                                let icon_col = egui::Color32::from_rgb(r, g, b);
-                                let icon = if *is_dir { "D" } else { "F" };
-
+                                const COLUMNS: [&str; 7] = ["User", "Total", "Desktop", "Documents", "Downloads", "AppData", "Other"];
                                 ui.horizontal(|ui| {
                                     ui.spacing_mut().item_spacing.x = 4.0;
                                     let w = ui.available_width();
-
-                                    let name_text = format!("[{}] {}", icon, name);
-                                    let label = if *is_dir {
-                                        egui::RichText::new(&name_text).strong().color(icon_col)
-                                    } else {
-                                        egui::RichText::new(&name_text)
-                                    };
-                                    let resp = ui.add_sized([w * 0.50, 18.0],
-                                        egui::SelectableLabel::new(false, label));
-                                    if resp.double_clicked() && *is_dir && *has_children {
-                                        nav_target = Some(name.clone());
+                                    for (i, title) in COLUMNS.into_iter().enumerate() {
+                                        let share = if i == 0 { 0.22 } else { 0.12 };
+                                        ui.add_sized([w * share, 18.0], egui::Label::new(title));
                                     }
-                                    resp.context_menu(|ui| {
-                                        ui.label(egui::RichText::new(name).strong());
-                                        ui.label(format!("{} ({:.1}%)", format_size(*size), pct));
-                                        ui.separator();
-                                        if ui.button("Open in Explorer").clicked() {
-                                            list_action.set(Some((i, 0)));
-                                            ui.close_menu();
-                                        }
-                                        if ui.button("Copy Path").clicked() {
-                                            list_action.set(Some((i, 1)));
-                                            ui.close_menu();
-                                        }
-                                        if *name != "<Free Space>" {
-                                            ui.separator();
-                                            if ui.button("Delete to Recycle Bin").clicked() {
-                                                list_action.set(Some((i, 2)));
-                                                ui.close_menu();
-                                            }
-                                        }
-                                    });
+                                });
+                                ui.separator();
 
-                                    ui.add_sized([w * 0.20, 18.0], egui::Label::new(format_size(*size)));
-                                    ui.add_sized([w * 0.10, 18.0], egui::Label::new(format!("{:.1}%", pct)));
-                                    let fc = if *is_dir { format_count(*file_count) } else { String::new() };
-                                    ui.add_sized([w * 0.15, 18.0], egui::Label::new(fc));
+                                egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                                    for profile in profiles {
+                                        ui.horizontal(|ui| {
+                                            ui.spacing_mut().item_spacing.x = 4.0;
+                                            let w = ui.available_width();
+                                            let path_str = profile.path.to_string_lossy().to_string();
+                                            let resp = ui.add_sized([w * 0.22, 18.0], egui::SelectableLabel::new(
+                                                self.selection.contains(&profile.path), &profile.name))
+                                                .on_hover_text(&path_str);
+                                            if resp.clicked() {
+                                                report_click = Some((profile.path.clone(), ui.input(|i| i.modifiers.command)));
+                                            }
+                                            resp.context_menu(|ui| {
+                                                let facts = self.path_facts(&profile.path, true, profile.size);
+                                                if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                    report_action = Some((action, facts));
+                                                }
+                                            });
+                                            for size in [profile.size, profile.desktop, profile.documents, profile.downloads] {
+                                                ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_size(size)));
+                                            }
+                                            let split: Vec<String> = profile.app_data.iter()
+                                                .map(|(name, size)| format!("{}: {}", name, format_size(*size)))
+                                                .collect();
+                                            let resp = ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_size(profile.app_data_size())));
+                                            if !split.is_empty() {
+                                                resp.on_hover_text(split.join("\n"));
+                                            }
+                                            ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_size(profile.other())));
+                                        });
+                                    }
                                 });
                             }
-                        });
-                    }
-
-                    // Handle navigation
-                    if let Some(ref target) = nav_target {
-                        if target == ".." {
-                            self.list_path.pop();
-                        } else {
-                            self.list_path.push(target.clone());
                         }
                     }
-                    // Handle context menu actions
-                    if let Some((idx, action)) = list_action.get() {
-                        let path = &entries[idx].5;
-                        match action {
-                            0 => { // Open in Explorer
-                                let _ = std::process::Command::new("explorer")
-                                    .arg("/select,")
-                                    .arg(path)
-                                    .spawn();
-                            }
-                            1 => { // Copy Path
-                                ctx.copy_text(path.to_string_lossy().to_string());
-                            }
-                            2 => { // Delete to Recycle Bin
-                                self.pending_delete = Some(path.clone());
+                    ReportKind::Names => {
+                        let toggled = ui.checkbox(&mut self.report_short_names, "Estimate 8.3 short-name overhead")
+                            .on_hover_text("Count the names NTFS also stores as a DOS 8.3 alias (PROGRA~1)")
+                            .changed();
+                        if toggled {
+                            self.cached_name_issues = None;
+                            save_prefs(&self.current_prefs());
+                        }
+                        if ui.checkbox(&mut self.scan_options.case_sensitive_dirs, "Detect case-sensitive folders")
+                            .on_hover_text("Read each folder's case-sensitivity flag on the next scan. Slower: every folder is opened")
+                            .changed()
+                        {
+                            save_prefs(&self.current_prefs());
+                        }
+                        if self.cached_name_issues.is_none() {
+                            if let Some(ref root) = self.scan_root {
+                                self.cached_name_issues = Some(find_name_issues(root, self.report_short_names, self.remote.is_none()));
                             }
-                            _ => {}
                         }
-                    }
-                }
-            }
-
-            ViewMode::LargestFiles => {
-                // Data is pre-collected during scan (no freeze on tab click)
-                if let Some(ref files) = self.cached_largest {
-                    let total_size = self.root_size.max(1);
-                    let theme = self.theme;
-                    {
-                    let mut filtered: Vec<(usize, &(String, u64, String))> = files.iter().enumerate().collect();
-                    if !self.search_text.is_empty() {
-                        let q = self.search_text.to_lowercase();
-                        filtered.retain(|(_, f)| f.0.to_lowercase().contains(&q) || f.2.to_lowercase().contains(&q));
-                    }
-
-                    // Column headers
-                    ui.horizontal(|ui| {
-                        ui.spacing_mut().item_spacing.x = 4.0;
-                        let w = ui.available_width();
-                        ui.add_sized([w * 0.04, 18.0], egui::Label::new("#"));
-                        ui.add_sized([w * 0.28, 18.0], egui::Label::new("Name"));
-                        ui.add_sized([w * 0.38, 18.0], egui::Label::new("Path"));
-                        ui.add_sized([w * 0.15, 18.0], egui::Label::new("Size"));
-                        ui.add_sized([w * 0.10, 18.0], egui::Label::new("%"));
-                    });
-                    ui.separator();
+                        if let Some(ref issues) = self.cached_name_issues {
+                            ui.label(format!(
+                                "{} case-sensitive folders. {} folders hold names that differ only by case.",
+                                format_count(issues.case_sensitive.len() as u64),
+                                format_count(issues.case_clashes.len() as u64),
+                            )).on_hover_text("WSL makes case-sensitive folders. Windows tools that ignore case may open the wrong one of two such names, or fail on them.");
+                            if self.report_short_names {
+                                let count = issues.long_name_count();
+                                let status = match issues.short_names_on {
+                                    Some(true) => " This volume generates them: `fsutil 8dot3name set <drive> 1` stops that, \
+                                        `fsutil 8dot3name strip` removes existing ones (old installers may rely on them).",
+                                    Some(false) => " The sampled name has no alias, so this volume doesn't generate them.",
+                                    None => "",
+                                };
+                                ui.label(format!(
+                                    "{} names need an 8.3 alias, about {} of file system metadata.{}",
+                                    format_count(count),
+                                    format_size(count * SHORT_NAME_BYTES),
+                                    status,
+                                )).on_hover_text("Estimated. Files grouped by Scan Options and folders past the depth limit aren't counted.");
+                            }
+                            ui.separator();
 
-                    if filtered.is_empty() && !self.search_text.is_empty() {
-                        ui.label("No matching files.");
-                    } else {
-                        let row_h = 22.0;
-                        egui::ScrollArea::vertical().auto_shrink(false).show_rows(
-                            ui, row_h, filtered.len(), |ui, row_range| {
-                            for rank in row_range {
-                                let (_, entry) = &filtered[rank];
-                                let pct = (entry.1 as f64 / total_size as f64) * 100.0;
-                                let ci = rank % 20;
-                                let (r, g, b) = theme.base_rgb(ci);
+                            let q = self.search_text.to_lowercase();
+                            let shown = |path: &Path| q.is_empty() || path.to_string_lossy().to_lowercase().contains(&q);
+                            let mut path_label = |ui: &mut egui::Ui, path: &Path, width: f32| {
+                                let path_str = path.to_string_lossy().to_string();
+                                let resp = ui.add_sized([width, 18.0], egui::SelectableLabel::new(self.selection.contains(path), &path_str));
+                                if resp.clicked() {
+                                    report_click = Some((path.to_path_buf(), ui.input(|i| i.modifiers.command)));
+                                }
+                                resp.context_menu(|ui| {
+                                    let facts = self.path_facts(path, true, 0);
+                                    if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                        report_action = Some((action, facts));
+                                    }
+                                });
+                            };
+                            egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                                ui.strong("Case-sensitive folders");
+                                if issues.case_sensitive.is_empty() {
+                                    ui.weak(if self.scan_options.case_sensitive_dirs || self.remote.is_some() {
+                                        "None found."
+                                    } else {
+                                        "Not checked: turn on Detect case-sensitive folders and rescan."
+                                    });
+                                }
+                                for (path, size, files) in issues.case_sensitive.iter().filter(|(p, _, _)| shown(p)) {
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 4.0;
+                                        let w = ui.available_width();
+                                        path_label(ui, path, w * 0.62);
+                                        ui.add_sized([w * 0.16, 18.0], egui::Label::new(format_size(*size)));
+                                        ui.add_sized([w * 0.16, 18.0], egui::Label::new(format!("{} files", format_count(*files))));
+                                    });
+                                }
+                                ui.add_space(6.0);
+                                ui.strong("Names that differ only by case");
+                                if issues.case_clashes.is_empty() {
+                                    ui.weak("None found.");
+                                }
+                                for (path, names) in issues.case_clashes.iter().filter(|(p, _)| shown(p)) {
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 4.0;
+                                        let w = ui.available_width();
+                                        path_label(ui, path, w * 0.50);
+                                        ui.add_sized([w * 0.46, 18.0], egui::Label::new(
+                                            egui::RichText::new(names.join(", ")).color(egui::Color32::from_rgb(220, 180, 50))).truncate());
+                                    });
+                                }
+                                if self.report_short_names {
+                                    ui.add_space(6.0);
+                                    ui.strong("Names needing an 8.3 alias");
+                                    for (path, count) in issues.long_names.iter().filter(|(p, _)| shown(p)) {
+                                        ui.horizontal(|ui| {
+                                            ui.spacing_mut().item_spacing.x = 4.0;
+                                            let w = ui.available_width();
+                                            path_label(ui, path, w * 0.62);
+                                            ui.add_sized([w * 0.16, 18.0], egui::Label::new(format!("{} names", format_count(*count))));
+                                            ui.add_sized([w * 0.16, 18.0], egui::Label::new(format_size(count * SHORT_NAME_BYTES)));
+                                        });
+                                    }
+                                }
+                            });
+                        }
+                    }
+                    ReportKind::ClusterSlack => {
+                        let mut picked = self.slack_cluster;
+                        ui.horizontal(|ui| {
+                            ui.label("Cluster size");
+                            let auto = match self.detected_cluster {
+                                Some(size) => format!("This volume ({})", format_size(size)),
+                                None => format!("Unknown ({} assumed)", format_size(DEFAULT_CLUSTER_SIZE)),
+                            };
+                            let text = if picked == 0 { auto.clone() } else { format_size(picked) };
+                            egui::ComboBox::from_id_salt("slack_cluster").selected_text(text).show_ui(ui, |ui| {
+                                ui.selectable_value(&mut picked, 0, auto);
+                                for size in CLUSTER_SIZES {
+                                    ui.selectable_value(&mut picked, size, format_size(size));
+                                }
+                            }).response.on_hover_text("Pick another size to see what the same files would waste on a volume formatted with it");
+                        });
+                        if picked != self.slack_cluster {
+                            self.slack_cluster = picked;
+                            self.cached_cluster_slack = None;
+                        }
+                        if self.cached_cluster_slack.is_none() {
+                            if let Some(ref root) = self.scan_root {
+                                self.detected_cluster = if self.remote.is_none() { cluster_size(&root.path) } else { None };
+                                self.cached_cluster_slack = Some(find_cluster_slack(root, self.slack_cluster_size()));
+                            }
+                        }
+                        let cluster = self.slack_cluster_size();
+                        if let Some((ref folders, total)) = self.cached_cluster_slack {
+                            let scanned = self.scan_root.as_ref().map_or(0, |r| r.size);
+                            ui.label(format!(
+                                "{} lost to rounding files up to whole {} clusters ({:.1}% on top of the {} scanned).",
+                                format_size(total),
+                                format_size(cluster),
+                                total as f64 / scanned.max(1) as f64 * 100.0,
+                                format_size(scanned),
+                            )).on_hover_text(
+                                "Allocated minus logical size of each folder's own files. Tiny NTFS files that fit in their MFT record \
+                                 take no cluster, so this is an upper bound there. Compressed, sparse and cloud-only files are left out. \
+                                 Rows marked ~ are partly estimated from Small Files or depth-limited totals.",
+                            );
+                            ui.separator();
 
-                                ui.horizontal(|ui| {
-                                    ui.spacing_mut().item_spacing.x = 4.0;
-                                    let w = ui.available_width();
-                                    ui.add_sized([w * 0.04, 18.0], egui::Label::new(
-                                        egui::RichText::new(format!("{}", rank + 1)).weak()));
-                                    ui.add_sized([w * 0.28, 18.0], egui::Label::new(
-                                        egui::RichText::new(&entry.0).color(egui::Color32::from_rgb(r, g, b))));
-                                    ui.add_sized([w * 0.38, 18.0], egui::Label::new(
-                                        egui::RichText::new(&entry.2).weak()));
-                                    ui.add_sized([w * 0.15, 18.0], egui::Label::new(format_size(entry.1)));
-                                    ui.add_sized([w * 0.10, 18.0], egui::Label::new(format!("{:.1}%", pct)));
-                                });
+                            let mut filtered: Vec<&SlackFolder> = folders.iter().collect();
+                            if !self.search_text.is_empty() {
+                                let q = self.search_text.to_lowercase();
+                                filtered.retain(|f| f.path.to_string_lossy().to_lowercase().contains(&q));
                             }
-                        });
-                    }
-                }
-                } // else if cached_largest
-            }
 
-            ViewMode::Extensions => {
-                if let Some(ref ext_data) = self.cached_extensions {
-                    let total_size = self.root_size.max(1);
-                    let theme = self.theme;
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 4.0;
+                                let w = ui.available_width();
+                                ui.add_sized([w * 0.48, 18.0], egui::Label::new(format!("Folder (top {})", SLACK_REPORT_ROWS)));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Files"));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Size"));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Slack"));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Of allocated"));
+                            });
+                            ui.separator();
 
-                    let mut filtered: Vec<&(String, u64, u64)> = ext_data.iter().collect();
-                    if !self.search_text.is_empty() {
-                        let q = self.search_text.to_lowercase();
-                        filtered.retain(|e| e.0.to_lowercase().contains(&q));
+                            if filtered.is_empty() {
+                                ui.label("No slack found.");
+                            } else {
+                                let row_h = 22.0;
+                                egui::ScrollArea::vertical().auto_shrink(false).show_rows(
+                                    ui, row_h, filtered.len(), |ui, row_range| {
+                                    for i in row_range {
+                                        let folder = filtered[i];
+                                        ui.horizontal(|ui| {
+                                            ui.spacing_mut().item_spacing.x = 4.0;
+                                            let w = ui.available_width();
+                                            let path_str = folder.path.to_string_lossy().to_string();
+                                            let resp = ui.add_sized([w * 0.48, 18.0], egui::SelectableLabel::new(
+                                                self.selection.contains(&folder.path), &path_str));
+                                            if resp.clicked() {
+                                                report_click = Some((folder.path.clone(), ui.input(|i| i.modifiers.command)));
+                                            }
+                                            resp.context_menu(|ui| {
+                                                let facts = self.path_facts(&folder.path, true, folder.size);
+                                                if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                    report_action = Some((action, facts));
+                                                }
+                                            });
+                                            ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_count(folder.file_count)));
+                                            ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_size(folder.size)));
+                                            let mark = if folder.estimated { "~" } else { "" };
+                                            ui.add_sized([w * 0.12, 18.0], egui::Label::new(format!("{}{}", mark, format_size(folder.slack))));
+                                            let share = folder.slack as f64 / (folder.size + folder.slack).max(1) as f64 * 100.0;
+                                            ui.add_sized([w * 0.12, 18.0], egui::Label::new(format!("{:.0}%", share)));
+                                        });
+                                    }
+                                });
+                            }
+                        }
                     }
+                    ReportKind::Quotas => {
+                        if self.quotas.is_empty() {
+                            ui.vertical_centered(|ui| {
+                                ui.add_space(ui.available_height() / 3.0);
+                                ui.label("Import a CSV of path,quota rows, as exported by most file servers, \
+                                          to see how full each folder's quota is.");
+                                ui.add_space(8.0);
+                                if ui.button("Import Quota CSV...").clicked() {
+                                    let csv = rfd::FileDialog::new()
+                                        .set_title("Import quotas")
+                                        .add_filter("CSV", &["csv", "txt"])
+                                        .pick_file();
+                                    if let Some(csv) = csv {
+                                        self.import_quotas(csv, true);
+                                    }
+                                }
+                            });
+                        } else {
+                            // (folder, used, quota), fullest first
+                            let mut rows: Vec<(&PathBuf, u64, u64)> = self.quota_matches.iter()
+                                .map(|(path, &limit)| {
+                                    let used = self.scan_root.as_ref().and_then(|r| find_node(r, path)).map_or(0, |n| n.size);
+                                    (path, used, limit)
+                                })
+                                .collect();
+                            let ratio = |used: u64, limit: u64| used as f64 / limit.max(1) as f64;
+                            rows.sort_by(|a, b| ratio(b.1, b.2).total_cmp(&ratio(a.1, a.2)));
+                            let over = rows.iter().filter(|r| ratio(r.1, r.2) >= QUOTA_WARN).count();
+                            let unmatched = self.quotas.len().saturating_sub(rows.len());
+                            let mut clear_clicked = false;
+                            ui.horizontal(|ui| {
+                                let mut summary = format!(
+                                    "{} quotas on scanned folders, {} at {:.0}% or more.",
+                                    format_count(rows.len() as u64), format_count(over as u64), QUOTA_WARN * 100.0,
+                                );
+                                if unmatched > 0 {
+                                    summary += &format!(" {} match no folder in this scan.", format_count(unmatched as u64));
+                                }
+                                ui.label(summary);
+                                if let Some(ref csv) = self.quota_csv {
+                                    ui.weak(csv.to_string_lossy());
+                                }
+                                clear_clicked = ui.button("Clear").clicked();
+                            });
+                            ui.separator();
 
-                    if filtered.is_empty() {
-                        ui.label("No matching file types.");
-                    } else {
-                        // Render as a treemap of extensions
-                        let ext_rect = ui.available_rect_before_wrap();
-                        let painter = ui.painter_at(ext_rect);
-                        let _response = ui.allocate_rect(ext_rect, egui::Sense::hover());
-
-                        let sizes: Vec<f64> = filtered.iter().map(|e| e.1 as f64).collect();
-                        let rects = treemap::layout(
-                            ext_rect.min.x, ext_rect.min.y,
-                            ext_rect.width(), ext_rect.height(),
-                            &sizes,
-                        );
+                            if !self.search_text.is_empty() {
+                                let q = self.search_text.to_lowercase();
+                                rows.retain(|r| r.0.to_string_lossy().to_lowercase().contains(&q));
+                            }
 
-                        for tr in &rects {
-                            let ext = &filtered[tr.index];
-                            let rect = egui::Rect::from_min_size(
-                                egui::pos2(tr.x, tr.y),
-                                egui::vec2(tr.w, tr.h),
-                            );
-                            let inner = rect.shrink(1.0);
-                            let ci = tr.index;
-                            let (r, g, b) = theme.base_rgb(ci);
-                            let col = egui::Color32::from_rgb(r, g, b);
-                            painter.rect_filled(inner, 2.0, col);
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 4.0;
+                                let w = ui.available_width();
+                                ui.add_sized([w * 0.46, 18.0], egui::Label::new("Folder"));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Used"));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Quota"));
+                                ui.add_sized([w * 0.26, 18.0], egui::Label::new("Use"));
+                            });
+                            ui.separator();
 
-                            // Draw text if block is big enough
-                            if inner.width() > 40.0 && inner.height() > 18.0 {
-                                let text_clip = inner.intersect(ext_rect);
-                                let text_painter = painter.with_clip_rect(text_clip);
-                                let text_col = text_color_for(col);
-                                let pct = (ext.1 as f64 / total_size as f64) * 100.0;
+                            let row_h = 22.0;
+                            egui::ScrollArea::vertical().auto_shrink(false).show_rows(ui, row_h, rows.len(), |ui, row_range| {
+                                for &(path, used, limit) in &rows[row_range] {
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 4.0;
+                                        let w = ui.available_width();
+                                        let path_str = path.to_string_lossy().to_string();
+                                        let resp = ui.add_sized([w * 0.46, 18.0],
+                                            egui::SelectableLabel::new(self.selection.contains(path), &path_str));
+                                        if resp.clicked() {
+                                            report_click = Some((path.clone(), ui.input(|i| i.modifiers.command)));
+                                        }
+                                        resp.context_menu(|ui| {
+                                            let facts = self.path_facts(path, true, used);
+                                            if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                report_action = Some((action, facts));
+                                            }
+                                        });
+                                        ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_size(used)));
+                                        ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_size(limit)));
+                                        let r = ratio(used, limit);
+                                        let (bar, _) = ui.allocate_exact_size(egui::vec2(w * 0.16, 10.0), egui::Sense::hover());
+                                        ui.painter().rect_filled(bar, 2.0, egui::Color32::from_gray(60));
+                                        let fill = egui::Rect::from_min_size(bar.min, egui::vec2(bar.width() * r.min(1.0) as f32, bar.height()));
+                                        ui.painter().rect_filled(fill, 2.0, quota_color(r));
+                                        let pct = egui::RichText::new(format!("{:.0}%", r * 100.0));
+                                        ui.label(if r >= QUOTA_WARN { pct.color(quota_color(r)).strong() } else { pct });
+                                    });
+                                }
+                            });
+                            if clear_clicked {
+                                self.clear_quotas();
+                            }
+                        }
+                    }
+                    ReportKind::AppCaches => {
+                        if let Some(ref caches) = self.cached_app_caches {
+                            let total: u64 = caches.iter().map(|c| c.size).sum();
+                            let selected: Vec<&AppCache> = caches.iter()
+                                .filter(|c| self.app_cache_selected.contains(&c.path))
+                                .collect();
+                            let selected_size: u64 = selected.iter().map(|c| c.size).sum();
+                            let mut clear_clicked = false;
+                            let mut refresh_clicked = false;
+                            ui.horizontal(|ui| {
+                                ui.label(format!(
+                                    "{} cache folders. {} total.",
+                                    format_count(caches.len() as u64),
+                                    format_size(total),
+                                ));
+                                let clear_label = format!("Clear Selected ({})", format_size(selected_size));
+                                clear_clicked = ui.add_enabled(!selected.is_empty(), egui::Button::new(clear_label)).clicked();
+                                refresh_clicked = ui.button("Refresh").clicked();
+                            });
+                            if clear_clicked {
+                                let items = selected.iter()
+                                    .map(|c| PlannedRemoval {
+                                        path: c.path.clone(),
+                                        size: c.size,
+                                        method: DeleteMethod::CacheCleared,
+                                        reason: format!("{} cache", c.app),
+                                    })
+                                    .collect();
+                                self.pending_cache_clear = Some(Manifest { title: "Clear App Caches".to_string(), items, from_file: false });
+                            }
+                            ui.separator();
 
-                                // Extension name
-                                let font_size = (inner.height() * 0.3).clamp(11.0, 24.0);
-                                let max_chars = ((inner.width() - 6.0) / (font_size * 0.55)) as usize;
-                                let label = truncate_str(&ext.0, max_chars);
-                                text_painter.text(
-                                    inner.min + egui::vec2(4.0, 4.0),
-                                    egui::Align2::LEFT_TOP,
-                                    label,
-                                    egui::FontId::proportional(font_size),
-                                    text_col,
-                                );
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 4.0;
+                                let w = ui.available_width();
+                                ui.add_sized([w * 0.04, 18.0], egui::Label::new(""));
+                                ui.add_sized([w * 0.14, 18.0], egui::Label::new("App"));
+                                ui.add_sized([w * 0.12, 18.0], egui::Label::new("Size"));
+                                ui.add_sized([w * 0.08, 18.0], egui::Label::new("Files"));
+                                ui.add_sized([w * 0.58, 18.0], egui::Label::new("Guidance"));
+                            });
+                            ui.separator();
 
-                                // Size and count
-                                if inner.height() > 36.0 {
-                                    let info = format!("{} ({:.1}%, {} files)",
-                                        format_size(ext.1), pct, format_count(ext.2));
-                                    let info_size = (font_size * 0.7).clamp(9.0, 14.0);
-                                    text_painter.text(
-                                        inner.min + egui::vec2(4.0, font_size + 6.0),
-                                        egui::Align2::LEFT_TOP,
-                                        info,
-                                        egui::FontId::proportional(info_size),
-                                        text_col.gamma_multiply(0.7),
-                                    );
+                            if caches.is_empty() {
+                                ui.label("No known app caches found.");
+                            }
+                            let mut toggled: Option<PathBuf> = None;
+                            egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                                for cache in caches {
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 4.0;
+                                        let w = ui.available_width();
+                                        let mut checked = self.app_cache_selected.contains(&cache.path);
+                                        if ui.add_sized([w * 0.04, 18.0], egui::Checkbox::without_text(&mut checked)).changed() {
+                                            toggled = Some(cache.path.clone());
+                                        }
+                                        let app_text = if cache.running {
+                                            egui::RichText::new(format!("{} (running)", cache.app))
+                                                .color(egui::Color32::from_rgb(220, 180, 50))
+                                        } else {
+                                            egui::RichText::new(cache.app)
+                                        };
+                                        ui.add_sized([w * 0.14, 18.0], egui::Label::new(app_text))
+                                            .on_hover_text(cache.path.to_string_lossy());
+                                        ui.add_sized([w * 0.12, 18.0], egui::Label::new(format_size(cache.size)));
+                                        ui.add_sized([w * 0.08, 18.0], egui::Label::new(format_count(cache.file_count)));
+                                        ui.add_sized([w * 0.58, 18.0], egui::Label::new(
+                                            egui::RichText::new(cache.guidance).weak()));
+                                    });
+                                }
+                            });
+                            if let Some(path) = toggled {
+                                if !self.app_cache_selected.remove(&path) {
+                                    self.app_cache_selected.insert(path);
+                                }
+                            }
+                            if refresh_clicked {
+                                self.cached_app_caches = None;
+                            }
+                        } else {
+                            ui.vertical_centered(|ui| {
+                                ui.add_space(ui.available_height() / 3.0);
+                                if self.tool_job.as_ref().is_some_and(|j| j.kind == ToolKind::Caches) {
+                                    ui.heading("Measuring app caches...");
+                                    ui.spinner();
+                                } else {
+                                    ui.label("Browser, chat, and media app caches live outside most scans.");
+                                    ui.add_space(8.0);
+                                    if ui.button("Measure App Caches").clicked() {
+                                        self.start_tool_job(ToolKind::Caches, |progress| {
+                                            match measure_app_caches(progress) {
+                                                Some(caches) => ToolResult::AppCaches(caches),
+                                                None => ToolResult::Cancelled,
+                                            }
+                                        });
+                                    }
+                                }
+                            });
+                        }
+                    }
+                    ReportKind::System => {
+                        if let Some(ref items) = self.cached_system_items {
+                            let total: u64 = items.iter().filter_map(|i| i.size).sum();
+                            let mut refresh_clicked = false;
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{} reclaimable through system cleanup tools.", format_size(total)));
+                                refresh_clicked = ui.button("Refresh").clicked();
+                            });
+                            ui.separator();
+                            egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                                for item in items {
+                                    ui.group(|ui| {
+                                        ui.set_min_width(ui.available_width());
+                                        ui.horizontal(|ui| {
+                                            ui.strong(item.name);
+                                            let size_text = match item.size {
+                                                Some(size) => format_size(size),
+                                                None => "?".to_string(),
+                                            };
+                                            ui.label(size_text);
+                                            if let Some(ref path) = item.path {
+                                                ui.weak(path.to_string_lossy());
+                                            }
+                                        });
+                                        ui.label(egui::RichText::new(&item.note).weak());
+                                        if !item.actions.is_empty() {
+                                            ui.horizontal(|ui| {
+                                                for action in &item.actions {
+                                                    if ui.button(action.label).clicked() {
+                                                        action.launch();
+                                                    }
+                                                }
+                                            });
+                                        }
+                                    });
                                 }
+                            });
+                            if refresh_clicked {
+                                self.cached_system_items = None;
                             }
+                        } else {
+                            ui.vertical_centered(|ui| {
+                                ui.add_space(ui.available_height() / 3.0);
+                                if self.tool_job.as_ref().is_some_and(|j| j.kind == ToolKind::System) {
+                                    ui.heading("Measuring system storage...");
+                                    ui.spinner();
+                                } else {
+                                    ui.label("Update caches and old Windows installations hold space that only system tools can reclaim.");
+                                    ui.add_space(8.0);
+                                    if ui.button("Measure System Storage").clicked() {
+                                        self.start_tool_job(ToolKind::System, |progress| {
+                                            match measure_system(progress) {
+                                                Some(items) => ToolResult::System(items),
+                                                None => ToolResult::Cancelled,
+                                            }
+                                        });
+                                    }
+                                }
+                            });
                         }
                     }
                 }
+                if let Some((path, toggle)) = report_click {
+                    self.selection.click(path, toggle);
+                }
+                if let Some((action, facts)) = report_action {
+                    self.run_node_action(ctx, action, facts);
+                }
             }
 
-            ViewMode::Duplicates => {
-                if self.dup_receiver.is_some() && self.cached_duplicates.is_none() {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(ui.available_height() / 3.0);
-                        ui.heading("Analyzing duplicates...");
-                        ui.spinner();
-                    });
-                } else if let Some(ref dups) = self.cached_duplicates {
-                    let total_waste: u64 = dups.iter()
-                        .map(|g| g.size * (g.paths.len() as u64 - 1))
-                        .sum();
-                    let total_groups = dups.len();
-
-                    // Summary header
+            ViewMode::Diff => {
+                let mut diff_action: Option<(NodeAction, NodeFacts)> = None;
+                let action_env = self.action_env();
+                if let Some(ref report) = self.diff_report {
+                    // Summary: per-folder totals, then one toggle per difference kind
                     ui.horizontal(|ui| {
                         ui.label(format!(
-                            "{} duplicate groups. {} wasted.",
-                            format_count(total_groups as u64),
-                            format_size(total_waste),
+                            "{} vs {}:",
+                            report.left_root.to_string_lossy(),
+                            report.right_root.to_string_lossy(),
                         ));
+                        if !report.folders.is_empty() {
+                            let text = format!("{} Folders", format_count(report.folders.len() as u64));
+                            if ui.selectable_label(self.diff_folders, text)
+                                .on_hover_text("Size change of each folder")
+                                .clicked()
+                            {
+                                self.diff_folders = !self.diff_folders;
+                            }
+                        }
+                        for kind in DIFF_KINDS {
+                            if report.count(kind) == 0 {
+                                continue;
+                            }
+                            let text = egui::RichText::new(format!(
+                                "{} {}",
+                                format_count(report.count(kind) as u64),
+                                report.kind_label(kind),
+                            )).color(diff_kind_color(kind));
+                            let selected = !self.diff_folders && self.diff_filter == Some(kind);
+                            if ui.selectable_label(selected, text).clicked() {
+                                self.diff_filter = if selected { None } else { Some(kind) };
+                                self.diff_folders = false;
+                            }
+                        }
+                        ui.label(format!("Net {}", format_change(report.growth())));
+                        if self.diff_growth.is_some() && ui.button("Show on Map")
+                            .on_hover_text("Color the map by growth: red grew, green shrank")
+                            .clicked()
+                        {
+                            self.view_mode = ViewMode::Treemap;
+                            self.color_mode = ColorMode::Growth;
+                        }
+                        if ui.button("Export JSON...").clicked() {
+                            let out = rfd::FileDialog::new()
+                                .set_title("Export diff")
+                                .add_filter("JSON", &["json"])
+                                .set_file_name("diff.json")
+                                .save_file();
+                            if let Some(out) = out {
+                                if let Err(e) = std::fs::write(&out, report.to_json()) {
+                                    self.info_message = Some(format!("Could not export diff: {}", e));
+                                }
+                            }
+                        }
                     });
                     ui.separator();
 
-                    let mut filtered: Vec<&DuplicateGroup> = dups.iter().collect();
-                    if !self.search_text.is_empty() {
-                        let q = self.search_text.to_lowercase();
-                        filtered.retain(|g| g.paths.iter().any(|p| p.to_lowercase().contains(&q)));
-                    }
-
-                    if filtered.is_empty() && !self.search_text.is_empty() {
-                        ui.label("No matching duplicates.");
+                    let query = self.search_text.to_lowercase();
+                    let matches = |path: &str| query.is_empty() || path.to_lowercase().contains(&query);
+                    let (sort, asc) = (self.diff_sort, self.diff_sort_asc);
+                    // Clickable column header: Path sorts A-Z first, sizes largest first
+                    let mut clicked_sort = None;
+                    let mut header = |ui: &mut egui::Ui, width: f32, label: &str, col: DiffSort| {
+                        let arrow = match sort {
+                            Some(c) if c == col => if asc { " ^" } else { " v" },
+                            _ => "",
+                        };
+                        if ui.add_sized([width, 18.0], egui::SelectableLabel::new(false, format!("{}{}", label, arrow))).clicked() {
+                            clicked_sort = Some(col);
+                        }
+                    };
+                    let size_cell = |present: bool, size: u64| {
+                        if present { format_size(size) } else { "-".to_string() }
+                    };
+                    let unchanged = ui.visuals().weak_text_color();
+                    let change_text = |left: u64, right: u64| {
+                        let change = right as i64 - left as i64;
+                        let col = match change {
+                            0 => unchanged,
+                            c if c > 0 => egui::Color32::from_rgb(220, 90, 70),
+                            _ => egui::Color32::from_rgb(80, 180, 100),
+                        };
+                        egui::RichText::new(format_change(change)).color(col)
+                    };
+                    let row_h = 22.0;
+
+                    if self.diff_folders {
+                        let mut folders: Vec<&crate::compare::FolderDelta> = report.folders.iter()
+                            .filter(|f| matches(&f.rel_path))
+                            .collect();
+                        if let Some(sort) = sort {
+                            sort_diff_rows(&mut folders, sort, asc, |f| (f.rel_path.as_str(), f.left_size, f.right_size));
+                        }
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            let w = ui.available_width();
+                            header(ui, w * 0.44, "Folder", DiffSort::Path);
+                            header(ui, w * 0.12, &capitalize(&report.left_label), DiffSort::Left);
+                            header(ui, w * 0.12, &capitalize(&report.right_label), DiffSort::Right);
+                            header(ui, w * 0.14, "Change", DiffSort::Change);
+                            ui.add_sized([w * 0.14, 18.0], egui::Label::new("Files"));
+                        });
+                        ui.separator();
+                        if folders.is_empty() {
+                            ui.label("No matching folders.");
+                        } else {
+                            egui::ScrollArea::vertical().auto_shrink(false).show_rows(
+                                ui, row_h, folders.len(), |ui, row_range| {
+                                for folder in &folders[row_range] {
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 4.0;
+                                        let w = ui.available_width();
+                                        let full = report.right_root.join(&folder.rel_path);
+                                        let resp = ui.add_sized([w * 0.44, 18.0], egui::SelectableLabel::new(
+                                            false, format!("[D] {}", folder.rel_path)));
+                                        resp.context_menu(|ui| {
+                                            let facts = self.path_facts(&full, true, folder.right_size);
+                                            if let Some(action) = action_buttons(ui, &facts, &action_env) {
+                                                diff_action = Some((action, facts));
+                                            }
+                                        });
+                                        ui.add_sized([w * 0.12, 18.0], egui::Label::new(size_cell(folder.left_size > 0, folder.left_size)));
+                                        ui.add_sized([w * 0.12, 18.0], egui::Label::new(size_cell(folder.right_size > 0, folder.right_size)));
+                                        ui.add_sized([w * 0.14, 18.0], egui::Label::new(change_text(folder.left_size, folder.right_size)));
+                                        let files = match (folder.added_files, folder.removed_files) {
+                                            (0, 0) => String::new(),
+                                            (a, 0) => format!("+{}", format_count(a)),
+                                            (0, r) => format!("-{}", format_count(r)),
+                                            (a, r) => format!("+{} -{}", format_count(a), format_count(r)),
+                                        };
+                                        ui.add_sized([w * 0.14, 18.0], egui::Label::new(files))
+                                            .on_hover_text("Files new / gone below this folder");
+                                    });
+                                }
+                            });
+                        }
                     } else {
-                        egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
-                            for (gi, group) in filtered.iter().enumerate() {
-                                let waste = group.size * (group.paths.len() as u64 - 1);
-                                let ci = gi % 20;
-                                let (r, g, b) = self.theme.base_rgb(ci);
-                                let col = egui::Color32::from_rgb(r, g, b);
+                        let mut filtered: Vec<&crate::compare::DiffEntry> = report.entries.iter()
+                            .filter(|e| self.diff_filter.is_none_or(|k| e.kind == k) && matches(&e.rel_path))
+                            .collect();
+                        if let Some(sort) = sort {
+                            sort_diff_rows(&mut filtered, sort, asc, |e| (e.rel_path.as_str(), e.left_size, e.right_size));
+                        }
 
-                                ui.horizontal(|ui| {
-                                    ui.colored_label(col, format!(
-                                        "{} x {} (wastes {})",
-                                        group.paths.len(),
-                                        format_size(group.size),
-                                        format_size(waste),
-                                    ));
-                                });
+                        // Column headers; Status goes back to the report's order
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            let w = ui.available_width();
+                            if ui.add_sized([w * 0.16, 18.0], egui::SelectableLabel::new(false, "Status")).clicked() {
+                                self.diff_sort = None;
+                            }
+                            header(ui, w * 0.42, "Path", DiffSort::Path);
+                            header(ui, w * 0.12, &capitalize(&report.left_label), DiffSort::Left);
+                            header(ui, w * 0.12, &capitalize(&report.right_label), DiffSort::Right);
+                            header(ui, w * 0.14, "Change", DiffSort::Change);
+                        });
+                        ui.separator();
 
-                                for path in &group.paths {
+                        if report.entries.is_empty() {
+                            ui.label("No differences found.");
+                        } else if filtered.is_empty() {
+                            ui.label("No matching differences.");
+                        } else {
+                            egui::ScrollArea::vertical().auto_shrink(false).show_rows(
+                                ui, row_h, filtered.len(), |ui, row_range| {
+                                for entry in &filtered[row_range] {
                                     ui.horizontal(|ui| {
-                                        ui.add_space(16.0);
-                                        let resp = ui.add(egui::Label::new(
-                                            egui::RichText::new(path).weak()
-                                        ).sense(egui::Sense::click()));
+                                        ui.spacing_mut().item_spacing.x = 4.0;
+                                        let w = ui.available_width();
+                                        ui.add_sized([w * 0.16, 18.0], egui::Label::new(
+                                            egui::RichText::new(report.kind_label(entry.kind)).color(diff_kind_color(entry.kind))));
+                                        let icon = if entry.is_dir { "D" } else { "F" };
+                                        let resp = ui.add_sized([w * 0.42, 18.0], egui::SelectableLabel::new(
+                                            false, format!("[{}] {}", icon, entry.rel_path)));
                                         resp.context_menu(|ui| {
-                                            if ui.button("Open in Explorer").clicked() {
-                                                let _ = std::process::Command::new("explorer")
-                                                    .arg("/select,")
-                                                    .arg(path)
-                                                    .spawn();
-                                                ui.close_menu();
-                                            }
-                                            if ui.button("Copy Path").clicked() {
-                                                ctx.copy_text(path.clone());
-                                                ui.close_menu();
-                                            }
-                                            if ui.button("Delete to Recycle Bin").clicked() {
-                                                self.pending_delete = Some(PathBuf::from(path));
-                                                ui.close_menu();
+                                            let sides = [
+                                                (entry.kind != DiffKind::OnlyRight, &report.left_root, &report.left_label),
+                                                (entry.kind != DiffKind::OnlyLeft, &report.right_root, &report.right_label),
+                                            ];
+                                            for (present, root, label) in sides {
+                                                if !present {
+                                                    continue;
+                                                }
+                                                let full = root.join(&entry.rel_path);
+                                                if ui.button(format!("Open {} in Explorer", label)).clicked() {
+                                                    reveal_in_explorer(&full);
+                                                    ui.close_menu();
+                                                }
+                                                if ui.button(format!("Copy {} Path", capitalize(label))).clicked() {
+                                                    ctx.copy_text(full.to_string_lossy().to_string());
+                                                    ui.close_menu();
+                                                }
                                             }
                                         });
+                                        ui.add_sized([w * 0.12, 18.0], egui::Label::new(
+                                            size_cell(entry.kind != DiffKind::OnlyRight, entry.left_size)));
+                                        ui.add_sized([w * 0.12, 18.0], egui::Label::new(
+                                            size_cell(entry.kind != DiffKind::OnlyLeft, entry.right_size)));
+                                        ui.add_sized([w * 0.14, 18.0], egui::Label::new(change_text(entry.left_size, entry.right_size)));
                                     });
                                 }
-                                ui.add_space(4.0);
-                                ui.separator();
+                            });
+                        }
+                    }
+                    if let Some(col) = clicked_sort {
+                        if self.diff_sort == Some(col) {
+                            self.diff_sort_asc = !self.diff_sort_asc;
+                        } else {
+                            self.diff_sort = Some(col);
+                            self.diff_sort_asc = col == DiffSort::Path;
+                        }
+                    }
+                }
+                if let Some((action, facts)) = diff_action {
+                    self.run_node_action(ctx, action, facts);
+                }
+            }
+
+            ViewMode::Queue => {
+                let done: Vec<(u64, u64, Option<u64>)> = self.scan_queue.iter()
+                    .filter_map(|q| match q.state {
+                        QueueState::Done { size, file_count, free, .. } => Some((size, file_count, free)),
+                        _ => None,
+                    })
+                    .collect();
+                let busy = self.queue_busy();
+                let mut open_idx: Option<usize> = None;
+                let mut clear_clicked = false;
+                ui.horizontal(|ui| {
+                    let total: u64 = done.iter().map(|d| d.0).sum();
+                    let files: u64 = done.iter().map(|d| d.1).sum();
+                    let free: u64 = done.iter().filter_map(|d| d.2).sum();
+                    ui.strong(format!(
+                        "{} of {} scans done: {} in {} files, {} free",
+                        done.len(),
+                        self.scan_queue.len(),
+                        format_size(total),
+                        format_count(files),
+                        format_size(free),
+                    ));
+                    if busy {
+                        if ui.button("Cancel All").clicked() {
+                            for q in &self.scan_queue {
+                                q.progress.cancel.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    } else if ui.button("Clear").clicked() {
+                        clear_clicked = true;
+                    }
+                });
+                ui.separator();
+
+                let max_size = done.iter().map(|d| d.0 + d.2.unwrap_or(0)).max().unwrap_or(0).max(1);
+                egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                    for (i, job) in self.scan_queue.iter().enumerate() {
+                        ui.group(|ui| {
+                            ui.set_min_width(ui.available_width());
+                            ui.horizontal(|ui| {
+                                ui.heading(job.path.to_string_lossy());
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    match job.state {
+                                        QueueState::Pending => {
+                                            if ui.small_button("Remove").clicked() {
+                                                job.progress.cancel.store(true, Ordering::Relaxed);
+                                            }
+                                            ui.weak("Waiting");
+                                        }
+                                        QueueState::Running => {
+                                            if ui.small_button("Cancel").clicked() {
+                                                job.progress.cancel.store(true, Ordering::Relaxed);
+                                            }
+                                            ui.label(format!(
+                                                "{} files, {}",
+                                                format_count(job.progress.files_scanned.load(Ordering::Relaxed)),
+                                                format_size(job.progress.bytes_scanned.load(Ordering::Relaxed)),
+                                            ));
+                                            ui.spinner();
+                                        }
+                                        QueueState::Done { .. } => {
+                                            if ui.button("Open").clicked() {
+                                                open_idx = Some(i);
+                                            }
+                                        }
+                                        QueueState::Cancelled => {
+                                            ui.weak("Cancelled");
+                                        }
+                                    }
+                                });
+                            });
+                            if let QueueState::Done { size, file_count, free, secs, .. } = job.state {
+                                // Used (scanned) vs free, scaled against the largest root
+                                let bar_height = 14.0;
+                                let (bar_rect, _) = ui.allocate_exact_size(
+                                    egui::vec2(ui.available_width(), bar_height),
+                                    egui::Sense::hover(),
+                                );
+                                let full_w = bar_rect.width() * ((size + free.unwrap_or(0)) as f32 / max_size as f32);
+                                let used_w = bar_rect.width() * (size as f32 / max_size as f32);
+                                ui.painter().rect_filled(
+                                    egui::Rect::from_min_size(bar_rect.min, egui::vec2(full_w, bar_height)),
+                                    3.0, egui::Color32::from_gray(60),
+                                );
+                                ui.painter().rect_filled(
+                                    egui::Rect::from_min_size(bar_rect.min, egui::vec2(used_w, bar_height)),
+                                    3.0, egui::Color32::from_rgb(60, 140, 220),
+                                );
+                                let free_text = free.map(|f| format!(", {} free", format_size(f))).unwrap_or_default();
+                                ui.label(format!(
+                                    "{} in {} files{} (scanned in {})",
+                                    format_size(size),
+                                    format_count(file_count),
+                                    free_text,
+                                    format_duration(secs),
+                                ));
                             }
                         });
+                        ui.add_space(2.0);
+                    }
+                });
+
+                if let Some(idx) = open_idx {
+                    self.open_queued_scan(idx);
+                }
+                if clear_clicked {
+                    self.scan_queue.clear();
+                    self.queue_tx = None;
+                    self.queue_events = None;
+                    if self.view_mode == ViewMode::Queue {
+                        self.view_mode = ViewMode::Treemap;
                     }
-                } else {
-                    ui.label("No duplicate data available. Scan a drive first.");
                 }
             }
 
@@ -2133,6 +8843,7 @@ impl eframe::App for SpaceViewApp {
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_view_state();
         save_prefs(&self.current_prefs());
     }
 }
@@ -2148,66 +8859,152 @@ impl eframe::App for SpaceViewApp {
 // Headers are drawn AFTER children so they're never obscured.
 // All text is clipped to its containing rect via painter.with_clip_rect().
 
-/// Top-level entry: transform root nodes from world to screen, then recurse.
-fn render_nodes(
-    painter: &egui::Painter,
-    nodes: &[LayoutNode],
-    camera: &Camera,
+/// Per-frame rendering parameters shared by every node in the traversal.
+struct RenderCtx<'a> {
+    painter: &'a egui::Painter,
     viewport: egui::Rect,
     theme: ColorTheme,
     color_mode: ColorMode,
+    age_ramp: AgeRamp,
+    high_contrast: bool,
+    label_density: f32,
+    metrics: CellMetrics,
     time_range: (u64, u64),
-    ext_colors: &std::collections::HashMap<String, usize>,
-    selected_ext: Option<&str>,
-) {
+    ext_colors: &'a std::collections::HashMap<String, usize>,
+    selected_ext: Option<&'a str>,
+    /// Age band filter, only applied in Age mode
+    age_band: Option<usize>,
+    selection: &'a Selection,
+    /// Imported quota limits by folder path
+    quotas: &'a std::collections::HashMap<PathBuf, u64>,
+    /// Change since the compared snapshot, for Growth mode
+    growth: Option<&'a GrowthMap>,
+    /// Heat overlay, when on
+    heat: Option<&'a HeatMap>,
+    /// Depth being highlighted from the legend, with pulse strength 0..1
+    flash: Option<(usize, f32)>,
+    /// "+N more" badges drawn this frame, for click handling afterwards
+    badges: &'a std::cell::RefCell<Vec<HiddenBadge>>,
+}
+
+#[derive(Clone)]
+struct HiddenList {
+    dir_name: String,
+    /// (name, size, path), largest first
+    items: Vec<(String, u64, PathBuf)>,
+}
+
+/// A "+N more" badge on a directory whose children are partly below the size cull.
+struct HiddenBadge {
+    rect: egui::Rect,
+    dir: PathBuf,
+    /// Content rect the children were laid out in, to recompute which were culled
+    content: egui::Rect,
+}
+
+/// Whether this frame's wheel input came from a precision touchpad rather than a
+/// notched mouse wheel: pixel deltas, or fractional line deltas (Windows reports
+/// touchpad scrolling as sub-notch WM_MOUSEWHEEL steps).
+fn is_touchpad_scroll(events: &[egui::Event]) -> bool {
+    events.iter().any(|e| match e {
+        egui::Event::MouseWheel { unit: egui::MouseWheelUnit::Point, .. } => true,
+        egui::Event::MouseWheel { unit: egui::MouseWheelUnit::Line, delta, .. } => {
+            delta.x.fract() != 0.0 || delta.y.fract() != 0.0
+        }
+        _ => false,
+    })
+}
+
+/// View picked by a shortcut: Alt+1..5, or plain 1..5 away from the Map where digits
+/// are zoom bookmarks; Ctrl+Tab / Ctrl+Shift+Tab cycle through `TAB_VIEWS`.
+fn view_shortcut(input: &mut egui::InputState, current: ViewMode) -> Option<ViewMode> {
+    for (key, view) in BOOKMARK_KEYS.iter().zip(TAB_VIEWS) {
+        // Alt first: a plain-key pattern also matches Alt+key
+        if input.consume_key(egui::Modifiers::ALT, *key)
+            || (current != ViewMode::Treemap && input.consume_key(egui::Modifiers::NONE, *key))
+        {
+            return Some(view);
+        }
+    }
+    let step = if input.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Tab) {
+        TAB_VIEWS.len() - 1
+    } else if input.consume_key(egui::Modifiers::COMMAND, egui::Key::Tab) {
+        1
+    } else {
+        return None;
+    };
+    // From Reports, Diff or Queue the cycle starts again at the Map
+    Some(match TAB_VIEWS.iter().position(|v| *v == current) {
+        Some(pos) => TAB_VIEWS[(pos + step) % TAB_VIEWS.len()],
+        None => TAB_VIEWS[0],
+    })
+}
+
+/// Shortcut grid rows for the bound keymap actions (About and welcome screens).
+fn keymap_rows(ui: &mut egui::Ui, keymap: &Keymap) {
+    for action in ACTIONS {
+        let bound = keymap.describe(action);
+        if !bound.is_empty() {
+            ui.label(bound);
+            ui.label(action.label());
+            ui.end_row();
+        }
+    }
+}
+
+/// Top-level entry: transform root nodes from world to screen, then recurse.
+fn render_nodes(rc: &RenderCtx, nodes: &[LayoutNode], camera: &Camera) {
     for node in nodes {
-        let screen_rect = camera.world_to_screen(node.world_rect, viewport);
-        render_node(painter, node, screen_rect, viewport, theme, color_mode, time_range, ext_colors, selected_ext);
+        let screen_rect = camera.world_to_screen(node.world_rect, rc.viewport);
+        render_node(rc, node, screen_rect);
     }
 }
 
 /// Core recursive render. `screen_rect` is the allocated screen area for this node
 /// (computed by the parent via treemap::layout, NOT from world_rect for children).
-fn render_node(
-    painter: &egui::Painter,
-    node: &LayoutNode,
-    screen_rect: egui::Rect,
-    viewport: egui::Rect,
-    theme: ColorTheme,
-    color_mode: ColorMode,
-    time_range: (u64, u64),
-    ext_colors: &std::collections::HashMap<String, usize>,
-    selected_ext: Option<&str>,
-) {
+fn render_node(rc: &RenderCtx, node: &LayoutNode, screen_rect: egui::Rect) {
+    let RenderCtx {
+        painter, viewport, theme, color_mode, age_ramp, high_contrast, label_density, metrics, time_range,
+        ext_colors, selected_ext, age_band, selection, quotas, growth, heat, flash, badges,
+    } = *rc;
+    let heat_of = heat.and_then(|h| h.heat(&node.path));
+    let growth_of = |path: &Path| growth.and_then(|g| g.delta(path));
+    let largest_growth = growth.map_or(0, |g| g.largest);
+    let border = if high_contrast {
+        egui::Stroke::new(HIGH_CONTRAST_BORDER_PX, egui::Color32::BLACK)
+    } else {
+        egui::Stroke::new(1.0, egui::Color32::from_gray(30))
+    };
+    // Secondary text (sizes) is dimmed, except in high contrast mode
+    let secondary = |c: egui::Color32| if high_contrast { c } else { c.gamma_multiply(0.6) };
+
     // Viewport culling
     if !screen_rect.intersects(viewport) {
         return;
     }
     // Size culling
-    if screen_rect.width() < MIN_SCREEN_PX || screen_rect.height() < MIN_SCREEN_PX {
+    if metrics.too_small(screen_rect) {
         return;
     }
 
     if node.is_dir && node.has_children {
-        let inner = screen_rect.shrink(BORDER_PX);
-        let hh = HEADER_PX.min(inner.height());
+        let inner = screen_rect.shrink(metrics.border_px);
+        let hh = metrics.header_px.min(inner.height());
 
         // Phase 1: body fill + border stroke
         let col = match color_mode {
             ColorMode::Depth | ColorMode::Extension => body_color(node.color_index, theme),
-            ColorMode::Age => age_body_color(node.modified, time_range),
+            ColorMode::Age => age_body_color(node.modified, time_range, age_ramp),
+            ColorMode::Growth => growth_body_color(growth_of(&node.path), largest_growth),
         };
         painter.rect_filled(inner, 1.0, col);
-        painter.rect_stroke(inner, 1.0, egui::Stroke::new(1.0, egui::Color32::from_gray(30)), egui::StrokeKind::Outside);
+        painter.rect_stroke(inner, 1.0, border, egui::StrokeKind::Outside);
 
         // Phase 2: children in screen-space content area
         if node.children_expanded && !node.children.is_empty() {
-            let content = egui::Rect::from_min_max(
-                egui::pos2(inner.min.x + PAD_PX, inner.min.y + hh),
-                egui::pos2(inner.max.x - PAD_PX, inner.max.y - PAD_PX),
-            );
-            if content.width() > MIN_SCREEN_PX && content.height() > MIN_SCREEN_PX {
-                let sizes: Vec<f64> = node.children.iter().map(|c| c.size as f64).collect();
+            let content = metrics.content_rect(inner);
+            if content.width() > metrics.min_px && content.height() > metrics.min_px {
+                let sizes: Vec<f64> = node.children.iter().map(|c| c.weight as f64).collect();
                 let rects = treemap::layout(
                     content.min.x,
                     content.min.y,
@@ -2215,12 +9012,30 @@ fn render_node(
                     content.height(),
                     &sizes,
                 );
+                let mut hidden = 0;
                 for tr in &rects {
                     let child_rect = egui::Rect::from_min_size(
                         egui::pos2(tr.x, tr.y),
                         egui::vec2(tr.w, tr.h),
                     );
-                    render_node(painter, &node.children[tr.index], child_rect, viewport, theme, color_mode, time_range, ext_colors, selected_ext);
+                    if metrics.too_small(child_rect) {
+                        hidden += 1;
+                    } else {
+                        render_node(rc, &node.children[tr.index], child_rect);
+                    }
+                }
+                if hidden > 0 && content.width() >= 60.0 && content.height() >= 24.0 {
+                    let text = format!("+{} more", format_count(hidden));
+                    let galley = painter.layout_no_wrap(text, egui::FontId::proportional(9.0), egui::Color32::WHITE);
+                    let rect = egui::Rect::from_min_size(
+                        content.max - galley.size() - egui::vec2(6.0, 4.0),
+                        galley.size() + egui::vec2(6.0, 4.0),
+                    );
+                    if rect.intersects(viewport) {
+                        painter.rect_filled(rect, 3.0, egui::Color32::from_black_alpha(170));
+                        painter.galley(rect.min + egui::vec2(3.0, 2.0), galley, egui::Color32::WHITE);
+                        badges.borrow_mut().push(HiddenBadge { rect, dir: node.path.clone(), content });
+                    }
                 }
             }
         }
@@ -2232,67 +9047,109 @@ fn render_node(
             if clipped.width() > 0.0 && clipped.height() > 0.0 {
                 let hdr_col = match color_mode {
                     ColorMode::Depth | ColorMode::Extension => header_color(node.color_index, theme),
-                    ColorMode::Age => age_header_color(node.modified, time_range),
+                    ColorMode::Age => age_header_color(node.modified, time_range, age_ramp),
+                    ColorMode::Growth => growth_header_color(growth_of(&node.path), largest_growth),
                 };
                 painter.rect_filled(clipped, 1.0, hdr_col);
 
-                if hh >= 14.0 && inner.width() > 30.0 {
+                let lod = label_lod(egui::vec2(inner.width(), hh), &DIR_LOD, label_density);
+                if lod >= LabelLod::Name {
                     let text_painter = painter.with_clip_rect(clipped);
-                    let font_size = (hh - 4.0).clamp(9.0, 13.0);
-                    let size_text = if node.file_count > 0 && inner.width() > 180.0 {
-                        format!("{} ({})", format_size(node.size), format_count(node.file_count))
+                    // Grows with a taller header (HiDPI / TV setups)
+                    let font_size = (hh - 4.0).max(9.0);
+                    let size_text = if let Some(&limit) = quotas.get(&node.path) {
+                        format!("{} of {} ({:.0}%)", format_size(node.size), format_size(limit),
+                            node.size as f64 / limit.max(1) as f64 * 100.0)
+                    } else if lod >= LabelLod::Detail && node.file_count > 0 {
+                        format!("{} ({})", format_size(node.weight), format_count(node.file_count))
                     } else {
-                        format_size(node.size)
+                        format_size(node.weight)
                     };
-                    let show_size = inner.width() > 100.0;
+                    let show_size = lod >= LabelLod::Size;
+                    let size_font = egui::FontId::proportional(font_size - 1.0);
                     let size_reserve = if show_size {
-                        size_text.len() as f32 * (font_size - 1.0) * 0.55 + 12.0
+                        text_width(&text_painter, &size_text, &size_font) + 12.0
                     } else {
                         0.0
                     };
                     let name_width = inner.width() - 8.0 - size_reserve;
-                    let max_chars = (name_width / (font_size * 0.55)).max(0.0) as usize;
-                    let label = truncate_str(&node.name, max_chars);
+                    let name = if lod >= LabelLod::Detail && !node.children.is_empty() {
+                        format!("{}  -  {}", node.name, largest_child_names(node, 3))
+                    } else {
+                        node.name.clone()
+                    };
+                    let font = egui::FontId::proportional(font_size);
+                    let label = truncate_middle(&text_painter, &name, &font, name_width);
                     text_painter.text(
                         clipped.min + egui::vec2(3.0, 1.0),
                         egui::Align2::LEFT_TOP,
                         label,
-                        egui::FontId::proportional(font_size),
-                        text_color_for(hdr_col),
+                        font,
+                        text_color_for(hdr_col, high_contrast),
                     );
                     if show_size {
                         text_painter.text(
                             egui::pos2(clipped.max.x - 3.0, clipped.min.y + 1.0),
                             egui::Align2::RIGHT_TOP,
                             size_text,
-                            egui::FontId::proportional(font_size - 1.0),
-                            text_color_for(hdr_col).gamma_multiply(0.6),
+                            size_font,
+                            secondary(text_color_for(hdr_col, high_contrast)),
                         );
                     }
                 }
+                // Quota use as a strip along the bottom of the header
+                if let Some(&limit) = quotas.get(&node.path) {
+                    let ratio = node.size as f64 / limit.max(1) as f64;
+                    let strip = egui::Rect::from_min_size(
+                        egui::pos2(header.min.x, header.max.y - QUOTA_STRIP_PX),
+                        egui::vec2(header.width() * ratio.min(1.0) as f32, QUOTA_STRIP_PX),
+                    );
+                    painter.with_clip_rect(clipped).rect_filled(strip, 0.0, quota_color(ratio));
+                }
             }
         }
+        if quotas.get(&node.path).is_some_and(|&limit| node.size as f64 >= limit as f64 * QUOTA_WARN) {
+            painter.rect_stroke(inner, 1.0, egui::Stroke::new(2.0, quota_color(1.0)), egui::StrokeKind::Inside);
+        }
+        if let Some(heat) = heat_of {
+            // Only the header is tinted, so hot subfolders still show through
+            let header = egui::Rect::from_min_size(inner.min, egui::vec2(inner.width(), hh)).intersect(viewport);
+            draw_heat(painter, inner, header, heat);
+        }
+        if node.attrs & ATTR_LINK != 0 {
+            draw_link_outline(painter, inner, viewport);
+        }
+        let content = metrics.content_rect(inner);
+        if content.height() >= 16.0 {
+            draw_attr_badges(painter, content.right_top() + egui::vec2(-2.0, 2.0), content.width() - 4.0, node.attrs);
+        }
     } else {
         // Files / empty dirs: single pass
         let inner = screen_rect.shrink(1.0);
-        let is_free_space = node.name == "<Free Space>";
-        let base_col = if is_free_space {
+        let is_free_space = is_space_tile(&node.name);
+        let base_col = if node.name == "<Free Space>" {
             egui::Color32::from_rgb(60, 140, 60)
+        } else if node.name == UNKNOWN_SPACE {
+            UNKNOWN_SPACE_COLOR
         } else {
             match color_mode {
                 ColorMode::Depth => {
                     if node.is_dir { dir_color(node.color_index, theme) }
                     else { file_color(node.color_index, theme) }
                 }
-                ColorMode::Age => age_color(node.modified, time_range),
+                ColorMode::Age => age_color(node.modified, time_range, age_ramp),
+                ColorMode::Growth => growth_color(growth_of(&node.path), largest_growth),
                 ColorMode::Extension => {
                     if node.is_dir { dir_color(node.color_index, theme) }
                     else { ext_file_color(&node.name, ext_colors, theme) }
                 }
             }
         };
-        // Apply dimming for extension filter
-        let col = if let Some(filter_ext) = selected_ext {
+        // Apply dimming for extension filter and the age legend's band
+        let outside_band = age_band.is_some_and(|band| !is_free_space && age_band_of(node.modified, time_range) != Some(band));
+        let col = if outside_band {
+            base_col.gamma_multiply(0.25)
+        } else if let Some(filter_ext) = selected_ext {
             if is_free_space {
                 base_col.gamma_multiply(0.25)
             } else {
@@ -2307,40 +9164,108 @@ fn render_node(
         };
         painter.rect_filled(inner, 1.0, col);
 
-        // Cushion shading: darken edges for 3D effect
-        if inner.width() > 6.0 && inner.height() > 6.0 {
+        if high_contrast {
+            painter.rect_stroke(inner, 1.0, border, egui::StrokeKind::Inside);
+        } else if inner.width() > 6.0 && inner.height() > 6.0 {
+            // Cushion shading: darken edges for 3D effect
             draw_cushion(painter, inner);
         }
 
-        if inner.width() > 35.0 && inner.height() > 14.0 {
+        let lod = label_lod(inner.size(), &FILE_LOD, label_density);
+        if lod >= LabelLod::Name {
             let text_clip = inner.intersect(viewport);
             if text_clip.width() > 0.0 && text_clip.height() > 0.0 {
                 let text_painter = painter.with_clip_rect(text_clip);
-                let text_col = text_color_for(col);
+                let text_col = text_color_for(col, high_contrast);
                 let font_size = 11.0f32.min(inner.height() - 3.0);
-                let max_chars = ((inner.width() - 6.0) / (font_size * 0.55)) as usize;
-                let label = truncate_str(&node.name, max_chars);
+                let font = egui::FontId::proportional(font_size);
+                let label = truncate_middle(&text_painter, &node.name, &font, inner.width() - 6.0);
 
                 text_painter.text(
                     inner.min + egui::vec2(3.0, 2.0),
                     egui::Align2::LEFT_TOP,
                     label,
-                    egui::FontId::proportional(font_size),
+                    font,
                     text_col,
                 );
 
-                if inner.height() > 28.0 {
+                if lod >= LabelLod::Size {
                     text_painter.text(
                         inner.min + egui::vec2(3.0, font_size + 3.0),
                         egui::Align2::LEFT_TOP,
-                        format_size(node.size),
+                        format_size(node.weight),
                         egui::FontId::proportional(9.0),
-                        text_col.gamma_multiply(0.6),
+                        secondary(text_col),
                     );
                 }
             }
         }
+        // Links and empty special folders; the label keeps the top-left corner
+        if inner.height() >= 30.0 {
+            draw_attr_badges(painter, inner.right_bottom() - egui::vec2(2.0, 14.0), inner.width() - 4.0, node.attrs);
+        }
+        if let Some(heat) = heat_of {
+            draw_heat(painter, inner, inner, heat);
+        }
+        if node.attrs & ATTR_LINK != 0 {
+            draw_link_outline(painter, inner, viewport);
+        }
     }
+
+    if let Some((_, strength)) = flash.filter(|(depth, _)| *depth == node.depth) {
+        painter.rect_stroke(
+            screen_rect.shrink(1.0),
+            1.0,
+            egui::Stroke::new(3.0, egui::Color32::WHITE.gamma_multiply(strength)),
+            egui::StrokeKind::Inside,
+        );
+    }
+
+    if !selection.is_empty() && selection.contains(&node.path) {
+        painter.rect_stroke(
+            screen_rect.shrink(1.0),
+            1.0,
+            egui::Stroke::new(2.0, SELECTION_COLOR),
+            egui::StrokeKind::Inside,
+        );
+    }
+}
+
+// ===================== Label LOD =====================
+
+/// How much text a block gets, chosen from its on-screen size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum LabelLod {
+    Hidden,
+    /// Name only
+    Name,
+    /// Name + size
+    Size,
+    /// Name + size + file count, plus the largest children in directory headers
+    Detail,
+}
+
+/// Minimum on-screen (width, height) for Name, Size and Detail at density 1.0.
+/// Directory heights are the header strip, so only widths really vary.
+const DIR_LOD: [(f32, f32); 3] = [(30.0, 14.0), (100.0, 14.0), (260.0, 14.0)];
+/// Files stop at Size: there is nothing more to say inside a file block.
+const FILE_LOD: [(f32, f32); 2] = [(35.0, 14.0), (35.0, 28.0)];
+
+/// Highest tier whose minimum fits `size`. Density > 1 labels smaller blocks by
+/// shrinking the width thresholds; heights stay put so text always fits its line.
+fn label_lod(size: egui::Vec2, tiers: &[(f32, f32)], density: f32) -> LabelLod {
+    const LODS: [LabelLod; 3] = [LabelLod::Name, LabelLod::Size, LabelLod::Detail];
+    tiers.iter().zip(LODS)
+        .take_while(|((w, h), _)| size.x * density > *w && size.y >= *h)
+        .last()
+        .map_or(LabelLod::Hidden, |(_, lod)| lod)
+}
+
+/// "a, b, c": names of the largest laid-out children.
+fn largest_child_names(node: &LayoutNode, n: usize) -> String {
+    let mut children: Vec<&LayoutNode> = node.children.iter().collect();
+    children.sort_by_key(|c| std::cmp::Reverse(c.size));
+    children.iter().take(n).map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
 }
 
 // ===================== Minimap Rendering =====================
@@ -2372,7 +9297,7 @@ fn render_minimap_node(
     if node.is_dir && node.has_children && node.children_expanded && !node.children.is_empty() {
         // Just recurse into children
         let inner = screen_rect.shrink(0.5);
-        let sizes: Vec<f64> = node.children.iter().map(|c| c.size as f64).collect();
+        let sizes: Vec<f64> = node.children.iter().map(|c| c.weight as f64).collect();
         let rects = treemap::layout(inner.min.x, inner.min.y, inner.width(), inner.height(), &sizes);
         for tr in &rects {
             let child_rect = egui::Rect::from_min_size(
@@ -2384,6 +9309,8 @@ fn render_minimap_node(
         // Leaf or unexpanded: solid color block
         let col = if node.name == "<Free Space>" {
             egui::Color32::from_rgb(60, 140, 60)
+        } else if node.name == UNKNOWN_SPACE {
+            UNKNOWN_SPACE_COLOR
         } else {
             let (r, g, b) = theme.base_rgb(node.color_index);
             egui::Color32::from_rgb(r, g, b)
@@ -2401,10 +9328,11 @@ fn screen_hit_test(
     camera: &Camera,
     viewport: egui::Rect,
     screen_pos: egui::Pos2,
+    metrics: &CellMetrics,
 ) -> Option<HoveredInfo> {
     for node in nodes {
         let screen_rect = camera.world_to_screen(node.world_rect, viewport);
-        if let Some(hit) = hit_test_node(node, screen_rect, viewport, screen_pos) {
+        if let Some(hit) = hit_test_node(node, screen_rect, screen_pos, metrics) {
             return Some(hit);
         }
     }
@@ -2415,26 +9343,21 @@ fn screen_hit_test(
 fn hit_test_node(
     node: &LayoutNode,
     screen_rect: egui::Rect,
-    viewport: egui::Rect,
     pos: egui::Pos2,
+    metrics: &CellMetrics,
 ) -> Option<HoveredInfo> {
     if !screen_rect.contains(pos) {
         return None;
     }
-    if screen_rect.width() < MIN_SCREEN_PX || screen_rect.height() < MIN_SCREEN_PX {
+    if metrics.too_small(screen_rect) {
         return None;
     }
 
     // Check children first (deeper = more specific)
     if node.is_dir && node.has_children && node.children_expanded && !node.children.is_empty() {
-        let inner = screen_rect.shrink(BORDER_PX);
-        let hh = HEADER_PX.min(inner.height());
-        let content = egui::Rect::from_min_max(
-            egui::pos2(inner.min.x + PAD_PX, inner.min.y + hh),
-            egui::pos2(inner.max.x - PAD_PX, inner.max.y - PAD_PX),
-        );
-        if content.width() > MIN_SCREEN_PX && content.height() > MIN_SCREEN_PX && content.contains(pos) {
-            let sizes: Vec<f64> = node.children.iter().map(|c| c.size as f64).collect();
+        let content = metrics.content_rect(screen_rect.shrink(metrics.border_px));
+        if content.width() > metrics.min_px && content.height() > metrics.min_px && content.contains(pos) {
+            let sizes: Vec<f64> = node.children.iter().map(|c| c.weight as f64).collect();
             let rects = treemap::layout(
                 content.min.x,
                 content.min.y,
@@ -2447,7 +9370,7 @@ fn hit_test_node(
                     egui::pos2(tr.x, tr.y),
                     egui::vec2(tr.w, tr.h),
                 );
-                if let Some(deeper) = hit_test_node(&node.children[tr.index], child_rect, viewport, pos) {
+                if let Some(deeper) = hit_test_node(&node.children[tr.index], child_rect, pos, metrics) {
                     return Some(deeper);
                 }
             }
@@ -2456,9 +9379,12 @@ fn hit_test_node(
 
     Some(HoveredInfo {
         name: node.name.clone(),
+        path: node.path.clone(),
         size: node.size,
+        allocated: node.allocated,
         file_count: node.file_count,
         is_dir: node.is_dir,
+        attrs: node.attrs,
         world_rect: node.world_rect,
         has_children: node.has_children,
         screen_rect,
@@ -2467,6 +9393,185 @@ fn hit_test_node(
 
 // ===================== Tree Helpers =====================
 
+/// `size` as a whole percentage of `total` for breadcrumbs ("62%", "<1%").
+fn share_label(size: u64, total: u64) -> String {
+    let pct = size as f64 / total.max(1) as f64 * 100.0;
+    if size > 0 && pct < 1.0 {
+        "<1%".to_string()
+    } else {
+        format!("{:.0}%", pct)
+    }
+}
+
+/// Breadcrumb hover card: a bar split among the folder's largest children (the
+/// rest lumped together in grey), with a legend below.
+fn crumb_share_card(ui: &mut egui::Ui, node: &FileNode, theme: ColorTheme) {
+    ui.strong(format!("{} - {}", node.name, format_size(node.size)));
+    if node.children.is_empty() || node.size == 0 {
+        return;
+    }
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(CRUMB_BAR_WIDTH, 10.0), egui::Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(90));
+    let shown = &node.children[..node.children.len().min(CRUMB_BAR_CHILDREN)];
+    let mut x = rect.min.x;
+    for (i, child) in shown.iter().enumerate() {
+        let w = rect.width() * (child.size as f64 / node.size as f64) as f32;
+        let (r, g, b) = theme.base_rgb(i);
+        let segment = egui::Rect::from_min_max(egui::pos2(x, rect.min.y), egui::pos2((x + w).min(rect.max.x), rect.max.y));
+        painter.rect_filled(segment, 0.0, egui::Color32::from_rgb(r, g, b));
+        x += w;
+    }
+    for (i, child) in shown.iter().enumerate() {
+        let (r, g, b) = theme.base_rgb(i);
+        ui.horizontal(|ui| {
+            let (swatch, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+            ui.painter().rect_filled(swatch, 2.0, egui::Color32::from_rgb(r, g, b));
+            ui.label(format!("{} ({})", child.name, share_label(child.size, node.size)));
+        });
+    }
+    let rest = node.children.len() - shown.len();
+    if rest > 0 {
+        let rest_size: u64 = node.children[shown.len()..].iter().map(|c| c.size).sum();
+        ui.weak(format!("{} more ({})", format_count(rest as u64), share_label(rest_size, node.size)));
+    }
+}
+
+/// Scroll offset that puts row `index` of a `show_rows` list at the top.
+fn row_offset(ui: &egui::Ui, index: usize, row_h: f32) -> f32 {
+    index as f32 * (row_h + ui.spacing().item_spacing.y)
+}
+
+/// Layout node with exactly this path, descending only through ancestors.
+fn find_layout_node<'a>(nodes: &'a [LayoutNode], path: &std::path::Path) -> Option<&'a LayoutNode> {
+    let node = nodes.iter().find(|n| path.starts_with(&n.path) && !n.path.as_os_str().is_empty())?;
+    if node.path == path {
+        Some(node)
+    } else {
+        find_layout_node(&node.children, path)
+    }
+}
+
+/// Children of `dir` that fall below the size cull when laid out in `content`.
+fn hidden_children(dir: &LayoutNode, content: egui::Rect, metrics: &CellMetrics) -> Vec<(String, u64, PathBuf)> {
+    let sizes: Vec<f64> = dir.children.iter().map(|c| c.size as f64).collect();
+    treemap::layout(content.min.x, content.min.y, content.width(), content.height(), &sizes)
+        .iter()
+        .filter(|tr| metrics.too_small(egui::Rect::from_min_size(egui::pos2(tr.x, tr.y), egui::vec2(tr.w, tr.h))))
+        .map(|tr| {
+            let c = &dir.children[tr.index];
+            (c.name.clone(), c.size, c.path.clone())
+        })
+        .collect()
+}
+
+/// Node (file or folder) with exactly this path, descending only through ancestors.
+fn find_node<'a>(node: &'a FileNode, path: &std::path::Path) -> Option<&'a FileNode> {
+    if node.path == path {
+        return Some(node);
+    }
+    let child = node.children.iter().find(|c| path.starts_with(&c.path))?;
+    find_node(child, path)
+}
+
+/// Node with exactly this path, descending only through ancestors.
+fn find_node_mut<'a>(node: &'a mut FileNode, path: &std::path::Path) -> Option<&'a mut FileNode> {
+    if node.path == path {
+        return Some(node);
+    }
+    let child = node.children.iter_mut().find(|c| c.is_dir && path.starts_with(&c.path))?;
+    find_node_mut(child, path)
+}
+
+/// Whether the scan kept no contents for a folder `depth` levels below the root:
+/// past the depth limit, or measured without a listing once the node budget ran out.
+fn summarized(node: &FileNode, depth: usize, max_depth: usize) -> bool {
+    (max_depth > 0 && depth >= max_depth) || (node.children.is_empty() && node.file_count > 0)
+}
+
+/// The deepest folder of the tree holding `path` whose contents the scan listed, and
+/// its depth in the tree. `offset` is the tree depth of the scan's own roots.
+fn listed_folder<'a>(root: &'a FileNode, path: &Path, max_depth: usize, offset: usize) -> Option<(&'a FileNode, usize)> {
+    if offset == 0 && !path.starts_with(&root.path) {
+        return None;
+    }
+    let (mut node, mut depth) = (root, 0);
+    // Longest match: an all-drives root can hold `/` and `/home` side by side
+    while let Some(child) = node.children.iter()
+        .filter(|c| c.is_dir && path.starts_with(&c.path))
+        .max_by_key(|c| c.path.as_os_str().len())
+    {
+        if summarized(child, depth + 1 - offset, max_depth) {
+            break;
+        }
+        node = child;
+        depth += 1;
+    }
+    Some((node, depth))
+}
+
+/// Free space and unknown tiles for a drive holding `scanned` bytes of the scan.
+/// Whole drives: whatever capacity is neither free nor scanned gets its own tile, so
+/// the map accounts for all of it. Unknown sorts before free space so the treemap
+/// puts free space in the bottom-right corner.
+fn space_tiles(drive: &DriveSpace, scanned: u64, whole_drive: bool) -> Vec<FileNode> {
+    let used = drive.total.saturating_sub(drive.available);
+    let unknown = Some(used.saturating_sub(scanned)).filter(|&gap| whole_drive && gap >= UNKNOWN_MIN_BYTES);
+    let tiles = [(UNKNOWN_SPACE, unknown), ("<Free Space>", Some(drive.available))];
+    tiles.into_iter()
+        .filter_map(|(name, size)| Some((name, size?)))
+        .map(|(name, size)| FileNode {
+            name: name.to_string(),
+            path: PathBuf::new(),
+            size,
+            is_dir: false,
+            file_count: 0,
+            modified: 0,
+            attrs: 0,
+            allocated: size,
+            children: Vec::new(),
+        })
+        .collect()
+}
+
+/// Replace the folder at `subtree.path` with a fresh scan of it, carrying the change in
+/// sizes and file count up through its ancestors. False if the folder isn't in the tree.
+fn graft_subtree(node: &mut FileNode, subtree: FileNode) -> bool {
+    if node.path == subtree.path {
+        *node = subtree;
+        return true;
+    }
+    // Longest match: an all-drives root can hold `/` and `/home` side by side
+    let Some(child) = node.children.iter_mut()
+        .filter(|c| c.is_dir && subtree.path.starts_with(&c.path))
+        .max_by_key(|c| c.path.as_os_str().len())
+    else {
+        return false;
+    };
+    let (old_size, old_allocated, old_count) = (child.size, child.allocated, child.file_count);
+    if !graft_subtree(child, subtree) {
+        return false;
+    }
+    let (new_size, new_allocated, new_count, new_modified) = (child.size, child.allocated, child.file_count, child.modified);
+    node.size = (node.size + new_size).saturating_sub(old_size);
+    node.allocated = (node.allocated + new_allocated).saturating_sub(old_allocated);
+    node.file_count = (node.file_count + new_count).saturating_sub(old_count);
+    node.modified = node.modified.max(new_modified);
+    node.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+    true
+}
+
+/// Clone every file (not directory) below `nodes`.
+fn collect_files_owned(nodes: &[FileNode], out: &mut Vec<FileNode>) {
+    for node in nodes {
+        if node.is_dir {
+            collect_files_owned(&node.children, out);
+        } else {
+            out.push(node.clone());
+        }
+    }
+}
+
 fn find_dir_by_path<'a>(root: &'a FileNode, path: &[String]) -> Option<&'a FileNode> {
     let mut current = root;
     for segment in path {
@@ -2475,6 +9580,45 @@ fn find_dir_by_path<'a>(root: &'a FileNode, path: &[String]) -> Option<&'a FileN
     Some(current)
 }
 
+/// Derive the per-scan caches (largest files, extension stats, time range) on the
+/// scan thread so the UI thread only has to swap them in.
+fn finish_scan(result: Option<FileNode>) -> ScanResult {
+    let (largest, extensions, time_range) = if let Some(ref root) = result {
+        // Compute time range on scan thread (not UI thread)
+        let time_range = compute_time_range(root);
+
+        // Collect all files once, derive both largest and extension stats
+        let mut all_files: Vec<(String, u64, String)> = Vec::new();
+        collect_all_files(root, &mut all_files);
+
+        let ext_list = extension_stats(&all_files);
+
+        // Largest 1000 files
+        all_files.sort_by_key(|f| std::cmp::Reverse(f.1));
+        all_files.truncate(LARGEST_FILES);
+
+        (Some(all_files), Some(ext_list), time_range)
+    } else {
+        (None, None, (0, 0))
+    };
+    (result, largest, extensions, time_range)
+}
+
+/// (extension, bytes, files) over `files` from collect_all_files, largest first.
+fn extension_stats(files: &[(String, u64, String)]) -> Vec<(String, u64, u64)> {
+    let mut ext_map: std::collections::HashMap<String, (u64, u64)> = std::collections::HashMap::new();
+    for (name, size, _) in files {
+        let entry = ext_map.entry(extension_key(name)).or_insert((0, 0));
+        entry.0 += size;
+        entry.1 += 1;
+    }
+    let mut ext_list: Vec<(String, u64, u64)> = ext_map.into_iter()
+        .map(|(ext, (size, count))| (ext, size, count))
+        .collect();
+    ext_list.sort_by_key(|e| std::cmp::Reverse(e.1));
+    ext_list
+}
+
 /// Compute (min, max) modified timestamps across all files in the tree.
 fn compute_time_range(node: &FileNode) -> (u64, u64) {
     let mut min_t = u64::MAX;
@@ -2495,7 +9639,7 @@ fn compute_time_range_recursive(node: &FileNode, min_t: &mut u64, max_t: &mut u6
 }
 
 /// Tiered duplicate detection: group by size, then partial hash (first 4KB), then full hash.
-fn find_duplicates(root: &FileNode) -> Vec<DuplicateGroup> {
+fn find_duplicates(root: &FileNode, limit: &RateLimiter) -> Vec<DuplicateGroup> {
     use std::collections::HashMap;
 
     // Step 1: Collect all files with paths, grouped by size
@@ -2513,7 +9657,7 @@ fn find_duplicates(root: &FileNode) -> Vec<DuplicateGroup> {
     for (size, paths) in candidates {
         let mut by_partial: HashMap<u64, Vec<String>> = HashMap::new();
         for path in &paths {
-            if let Ok(hash) = hash_file_partial(path) {
+            if let Ok(hash) = hash_file_partial(path, limit) {
                 by_partial.entry(hash).or_default().push(path.clone());
             }
         }
@@ -2531,7 +9675,7 @@ fn find_duplicates(root: &FileNode) -> Vec<DuplicateGroup> {
 
             let mut by_full: HashMap<u64, Vec<String>> = HashMap::new();
             for path in &partial_group {
-                if let Ok(hash) = hash_file_full(path) {
+                if let Ok(hash) = hash_file_full(path, limit) {
                     by_full.entry(hash).or_default().push(path.clone());
                 }
             }
@@ -2553,52 +9697,114 @@ fn find_duplicates(root: &FileNode) -> Vec<DuplicateGroup> {
     results
 }
 
+/// Aggregate duplicate groups by folder pair. In each group the file in the folder
+/// with the shortest path counts as the original (`D:\Photos`); every other copy adds
+/// its size to (its folder, original folder). Largest pairs first.
+fn duplicate_folder_pairs(groups: &[DuplicateGroup]) -> Vec<FolderPairDup> {
+    let parent = |p: &String| {
+        std::path::Path::new(p).parent().map(|d| d.to_string_lossy().to_string()).unwrap_or_default()
+    };
+    let mut pairs: std::collections::HashMap<(String, String), (u64, u64)> = std::collections::HashMap::new();
+    for group in groups {
+        let mut dirs: Vec<String> = group.paths.iter().map(parent).collect();
+        dirs.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        let Some((original, copies)) = dirs.split_first() else { continue };
+        for copy in copies {
+            let entry = pairs.entry((copy.clone(), original.clone())).or_insert((0, 0));
+            entry.0 += group.size;
+            entry.1 += 1;
+        }
+    }
+    let mut out: Vec<FolderPairDup> = pairs.into_iter()
+        .map(|((copy_dir, original_dir), (bytes, files))| FolderPairDup { copy_dir, original_dir, bytes, files })
+        .collect();
+    out.sort_by_key(|p| std::cmp::Reverse(p.bytes));
+    out
+}
+
+/// Every copy in `groups` but the one in the shortest folder path (the original, as
+/// in `duplicate_folder_pairs`), as a dry run sending them to the Recycle Bin.
+fn extra_copies_manifest(groups: &[&DuplicateGroup]) -> Manifest {
+    let parent_len = |p: &String| std::path::Path::new(p).parent().map_or(0, |d| d.as_os_str().len());
+    let mut items = Vec::new();
+    for group in groups {
+        let mut paths: Vec<&String> = group.paths.iter().collect();
+        paths.sort_by(|a, b| parent_len(a).cmp(&parent_len(b)).then_with(|| a.cmp(b)));
+        let Some((original, copies)) = paths.split_first() else { continue };
+        items.extend(copies.iter().map(|copy| PlannedRemoval {
+            path: PathBuf::from(copy),
+            size: group.size,
+            method: DeleteMethod::RecycleBin,
+            reason: format!("copy of {}", original),
+        }));
+    }
+    Manifest { title: "Remove Extra Copies".to_string(), items, from_file: false }
+}
+
+/// The demo tree has no contents to hash: its planted copies are the files that
+/// share a size, which no other demo file does.
+fn demo_duplicates(root: &FileNode) -> Vec<DuplicateGroup> {
+    let mut by_size = std::collections::HashMap::new();
+    collect_file_paths(root, &mut by_size);
+    let mut results: Vec<DuplicateGroup> = by_size.into_iter()
+        .filter(|(_, paths)| paths.len() >= 2)
+        .map(|(size, paths)| DuplicateGroup { size, paths })
+        .collect();
+    results.sort_by_key(|g| std::cmp::Reverse(g.size * (g.paths.len() as u64 - 1)));
+    results
+}
+
 fn collect_file_paths(node: &FileNode, by_size: &mut std::collections::HashMap<u64, Vec<String>>) {
     for child in &node.children {
         if child.is_dir {
             collect_file_paths(child, by_size);
-        } else if child.name != "<Free Space>" && child.size > 0 {
+        } else if !is_space_tile(&child.name) && child.name != SMALL_FILES && child.size > 0 {
             by_size.entry(child.size).or_default()
                 .push(child.path.to_string_lossy().to_string());
         }
     }
 }
 
-fn hash_file_partial(path: &str) -> std::io::Result<u64> {
-    use std::hash::{Hash, Hasher};
-    let mut file = std::fs::File::open(path)?;
-    let mut buf = [0u8; 4096];
-    let n = std::io::Read::read(&mut file, &mut buf)?;
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    buf[..n].hash(&mut hasher);
-    Ok(hasher.finish())
+fn collect_all_files(node: &FileNode, files: &mut Vec<(String, u64, String)>) {
+    for child in &node.children {
+        collect_file_or_files(child, files);
+    }
 }
 
-fn hash_file_full(path: &str) -> std::io::Result<u64> {
-    use std::hash::{Hash, Hasher};
-    let mut file = std::fs::File::open(path)?;
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    let mut buf = [0u8; 8192];
-    loop {
-        let n = std::io::Read::read(&mut file, &mut buf)?;
-        if n == 0 { break; }
-        buf[..n].hash(&mut hasher);
+/// `node` itself if it is a real file, else every file below it.
+fn collect_file_or_files(node: &FileNode, files: &mut Vec<(String, u64, String)>) {
+    if node.is_dir {
+        collect_all_files(node, files);
+    } else if !is_space_tile(&node.name) && node.name != SMALL_FILES && node.name != NOT_SCANNED {
+        files.push((node.name.clone(), node.size, node.path.to_string_lossy().to_string()));
     }
-    Ok(hasher.finish())
 }
 
-fn collect_all_files(node: &FileNode, files: &mut Vec<(String, u64, String)>) {
+/// Every real file below `node` whose `extension_key` is `ext`.
+fn collect_files_of_type<'a>(node: &'a FileNode, ext: &str, files: &mut Vec<&'a FileNode>) {
     for child in &node.children {
         if child.is_dir {
-            collect_all_files(child, files);
-        } else if child.name != "<Free Space>" {
-            files.push((child.name.clone(), child.size, child.path.to_string_lossy().to_string()));
+            collect_files_of_type(child, ext, files);
+        } else if !is_space_tile(&child.name) && child.name != SMALL_FILES && child.name != NOT_SCANNED
+            && extension_key(&child.name) == ext
+        {
+            files.push(child);
         }
     }
 }
 
 // ===================== Colors =====================
 
+fn diff_kind_color(kind: DiffKind) -> egui::Color32 {
+    match kind {
+        DiffKind::OnlyLeft => egui::Color32::from_rgb(220, 60, 50),
+        DiffKind::OnlyRight => egui::Color32::from_rgb(60, 140, 220),
+        DiffKind::SizeChanged => egui::Color32::from_rgb(220, 180, 50),
+        DiffKind::TimeChanged => egui::Color32::from_rgb(180, 140, 220),
+        DiffKind::ContentChanged => egui::Color32::from_rgb(220, 120, 40),
+    }
+}
+
 fn dir_color(ci: usize, theme: ColorTheme) -> egui::Color32 {
     let (r, g, b) = theme.base_rgb(ci);
     egui::Color32::from_rgb(r, g, b)
@@ -2623,11 +9829,15 @@ fn body_color(ci: usize, theme: ColorTheme) -> egui::Color32 {
 
 /// Get the color index for a file based on its extension.
 fn ext_color_index(name: &str, ext_colors: &std::collections::HashMap<String, usize>) -> Option<usize> {
-    let ext = name.rsplit('.').next()
+    ext_colors.get(&extension_key(name)).copied()
+}
+
+/// Key a file is grouped under in the extension stats: ".mkv" (lowercase) or "(no ext)".
+fn extension_key(name: &str) -> String {
+    name.rsplit('.').next()
         .filter(|e| e.len() < 10 && *e != name)
         .map(|e| format!(".{}", e.to_lowercase()))
-        .unwrap_or_else(|| "(no ext)".to_string());
-    ext_colors.get(&ext).copied()
+        .unwrap_or_else(|| "(no ext)".to_string())
 }
 
 /// File color for extension mode. Uses theme colors indexed by extension rank.
@@ -2640,51 +9850,107 @@ fn ext_file_color(name: &str, ext_colors: &std::collections::HashMap<String, usi
     }
 }
 
-/// Map a file's modified timestamp to a red-to-green gradient.
-/// Old files = red/warm. Recent files = green/cool.
-fn age_color(modified: u64, time_range: (u64, u64)) -> egui::Color32 {
+/// Position of a modified timestamp on the age gradient (old = 0.0, new = 1.0), or
+/// None when the date or the range is unknown.
+fn age_t(modified: u64, time_range: (u64, u64)) -> Option<f64> {
     if modified == 0 || time_range.0 >= time_range.1 {
-        return egui::Color32::from_rgb(128, 128, 128); // unknown = gray
+        return None;
     }
     // Log scale: spreads out recent files instead of clustering at green.
     // age_secs = how old this file is (0 = newest). Log compresses the old end
     // and expands the new end, so "1 week ago" vs "1 month ago" is visible
     // even when the oldest file is 15 years old.
-    let age_secs = (time_range.1 - modified) as f64;
+    let age_secs = time_range.1.saturating_sub(modified) as f64;
     let max_age = (time_range.1 - time_range.0) as f64;
-    let t = 1.0 - (age_secs + 1.0).ln() / (max_age + 1.0).ln();
-    let t = t.clamp(0.0, 1.0) as f32;
-    // Red (old) -> Yellow (mid) -> Green (new)
-    let (r, g, b) = if t < 0.5 {
-        // Red to Yellow
-        let s = t * 2.0;
-        (220.0, 60.0 + 160.0 * s, 50.0)
-    } else {
-        // Yellow to Green
-        let s = (t - 0.5) * 2.0;
-        (220.0 - 160.0 * s, 220.0, 50.0 + 30.0 * s)
-    };
-    egui::Color32::from_rgb(r as u8, g as u8, b as u8)
+    Some((1.0 - (age_secs + 1.0).ln() / (max_age + 1.0).ln()).clamp(0.0, 1.0))
+}
+
+/// Inverse of `age_t`: the timestamp at gradient position t.
+fn age_at(t: f64, time_range: (u64, u64)) -> u64 {
+    let max_age = time_range.1.saturating_sub(time_range.0) as f64;
+    let age_secs = ((1.0 - t) * (max_age + 1.0).ln()).exp() - 1.0;
+    time_range.1.saturating_sub(age_secs.round() as u64)
+}
+
+/// Age legend band (0 = oldest) holding a timestamp.
+fn age_band_of(modified: u64, time_range: (u64, u64)) -> Option<usize> {
+    age_t(modified, time_range).map(|t| ((t * AGE_BANDS as f64) as usize).min(AGE_BANDS - 1))
+}
+
+/// Map a file's modified timestamp onto the age gradient.
+fn age_color(modified: u64, time_range: (u64, u64), ramp: AgeRamp) -> egui::Color32 {
+    match age_t(modified, time_range) {
+        Some(t) => ramp.rgb(t as f32),
+        None => egui::Color32::from_rgb(128, 128, 128), // unknown = gray
+    }
 }
 
 /// Darker version of age color for directory bodies.
-fn age_body_color(modified: u64, time_range: (u64, u64)) -> egui::Color32 {
-    let col = age_color(modified, time_range);
+fn age_body_color(modified: u64, time_range: (u64, u64), ramp: AgeRamp) -> egui::Color32 {
+    let col = age_color(modified, time_range, ramp);
     let dim = |c: u8| (c as f32 * 0.35) as u8;
     egui::Color32::from_rgb(dim(col.r()), dim(col.g()), dim(col.b()))
 }
 
 /// Header version of age color.
-fn age_header_color(modified: u64, time_range: (u64, u64)) -> egui::Color32 {
-    let col = age_color(modified, time_range);
+fn age_header_color(modified: u64, time_range: (u64, u64), ramp: AgeRamp) -> egui::Color32 {
+    let col = age_color(modified, time_range, ramp);
+    let darken = |c: u8| (c as f32 * 0.80) as u8;
+    egui::Color32::from_rgb(darken(col.r()), darken(col.g()), darken(col.b()))
+}
+
+/// Growth mode color: red for grown, green for shrunk, stronger on a log scale up
+/// to the largest folder change. Unchanged items are gray.
+fn growth_color(delta: Option<i64>, largest: u64) -> egui::Color32 {
+    const NEUTRAL: f32 = 110.0;
+    let Some(delta) = delta.filter(|d| *d != 0) else { return egui::Color32::from_gray(NEUTRAL as u8) };
+    let t = ((delta.unsigned_abs() as f64).ln_1p() / (largest.max(2) as f64).ln_1p()).clamp(0.2, 1.0) as f32;
+    let (r, g, b) = if delta > 0 { (225, 55, 45) } else { (55, 185, 90) };
+    let mix = |c: u8| (NEUTRAL + (c as f32 - NEUTRAL) * t) as u8;
+    egui::Color32::from_rgb(mix(r), mix(g), mix(b))
+}
+
+/// Darker version of growth color for directory bodies.
+fn growth_body_color(delta: Option<i64>, largest: u64) -> egui::Color32 {
+    let col = growth_color(delta, largest);
+    let dim = |c: u8| (c as f32 * 0.35) as u8;
+    egui::Color32::from_rgb(dim(col.r()), dim(col.g()), dim(col.b()))
+}
+
+/// Header version of growth color.
+fn growth_header_color(delta: Option<i64>, largest: u64) -> egui::Color32 {
+    let col = growth_color(delta, largest);
     let darken = |c: u8| (c as f32 * 0.80) as u8;
     egui::Color32::from_rgb(darken(col.r()), darken(col.g()), darken(col.b()))
 }
 
+/// Dashed outline for a link: the data lives somewhere else. Only the part near the
+/// viewport is dashed, so a block zoomed far past the screen stays cheap.
+fn draw_link_outline(painter: &egui::Painter, rect: egui::Rect, viewport: egui::Rect) {
+    if rect.width() < 8.0 || rect.height() < 8.0 {
+        return;
+    }
+    let r = rect.shrink(1.0).intersect(viewport.expand(4.0));
+    let corners = [r.left_top(), r.right_top(), r.right_bottom(), r.left_bottom(), r.left_top()];
+    painter.extend(egui::Shape::dashed_line(&corners, egui::Stroke::new(1.5, LINK_COLOR), 5.0, 3.0));
+}
+
+/// Heat overlay glow: an orange outline around `rect` and a tint over `tint`, both
+/// stronger the hotter (0..1) the folder is.
+fn draw_heat(painter: &egui::Painter, rect: egui::Rect, tint: egui::Rect, heat: f32) {
+    painter.rect_filled(tint, 1.0, HEAT_COLOR.gamma_multiply(0.15 + 0.45 * heat));
+    painter.rect_stroke(
+        rect,
+        1.0,
+        egui::Stroke::new(1.0 + 3.0 * heat, HEAT_COLOR.gamma_multiply(0.4 + 0.6 * heat)),
+        egui::StrokeKind::Inside,
+    );
+}
+
 /// Draw cushion shading: darken edges to create a 3D raised effect.
 fn draw_cushion(painter: &egui::Painter, rect: egui::Rect) {
-    let w = (rect.width() * 0.15).min(6.0).max(1.0);
-    let h = (rect.height() * 0.15).min(6.0).max(1.0);
+    let w = (rect.width() * 0.15).clamp(1.0, 6.0);
+    let h = (rect.height() * 0.15).clamp(1.0, 6.0);
     let dark = egui::Color32::from_rgba_premultiplied(0, 0, 0, 30);
     let light = egui::Color32::from_rgba_premultiplied(255, 255, 255, 18);
 
@@ -2710,25 +9976,85 @@ fn draw_cushion(painter: &egui::Painter, rect: egui::Rect) {
     );
 }
 
-fn text_color_for(bg: egui::Color32) -> egui::Color32 {
-    let lum = 0.299 * bg.r() as f64 + 0.587 * bg.g() as f64 + 0.114 * bg.b() as f64;
-    if lum > 150.0 {
-        egui::Color32::from_gray(20)
+/// Label color for a block: whichever of dark/light text has the higher WCAG
+/// contrast ratio. High contrast mode uses pure black/white (always >= 4.5:1).
+fn text_color_for(bg: egui::Color32, high_contrast: bool) -> egui::Color32 {
+    let (dark, light) = if high_contrast {
+        (egui::Color32::BLACK, egui::Color32::WHITE)
     } else {
-        egui::Color32::from_gray(235)
-    }
+        (egui::Color32::from_gray(20), egui::Color32::from_gray(235))
+    };
+    let bg_lum = relative_luminance(bg);
+    let contrast = |fg: egui::Color32| {
+        let fg_lum = relative_luminance(fg);
+        (fg_lum.max(bg_lum) + 0.05) / (fg_lum.min(bg_lum) + 0.05)
+    };
+    if contrast(dark) >= contrast(light) { dark } else { light }
+}
+
+/// WCAG 2 relative luminance of an sRGB color (0.0 black ..= 1.0 white).
+fn relative_luminance(c: egui::Color32) -> f32 {
+    let channel = |v: u8| {
+        let v = v as f32 / 255.0;
+        if v <= 0.03928 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+    };
+    0.2126 * channel(c.r()) + 0.7152 * channel(c.g()) + 0.0722 * channel(c.b())
 }
 
 // ===================== Helpers =====================
 
-fn truncate_str(s: &str, max_chars: usize) -> String {
-    if max_chars < 4 {
-        return String::new();
-    }
-    if s.len() <= max_chars {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_chars - 3])
+/// Width of `text` in points at `font`, from the actual glyph advances.
+fn text_width(painter: &egui::Painter, text: &str, font: &egui::FontId) -> f32 {
+    painter.fonts(|fonts| text.chars().map(|c| fonts.glyph_width(font, c)).sum())
+}
+
+/// Fit `text` into `max_width` points, eliding the middle with "…" so both the start
+/// and the end (usually the extension) stay readable. Cuts only between grapheme
+/// clusters, so CJK, emoji and combining sequences are never split. Empty if fewer
+/// than two graphemes would fit.
+fn truncate_middle(painter: &egui::Painter, text: &str, font: &egui::FontId, max_width: f32) -> String {
+    use unicode_segmentation::UnicodeSegmentation;
+    const ELLIPSIS: &str = "\u{2026}";
+
+    painter.fonts(|fonts| {
+        let width = |g: &str| g.chars().map(|c| fonts.glyph_width(font, c)).sum::<f32>();
+        let graphemes: Vec<(&str, f32)> = text.graphemes(true).map(|g| (g, width(g))).collect();
+        if graphemes.iter().map(|(_, w)| w).sum::<f32>() <= max_width {
+            return text.to_string();
+        }
+        let mut budget = max_width - width(ELLIPSIS);
+        // Half the room for the head, the rest for the tail, leftovers back to the head
+        let (mut head, mut tail) = (0, graphemes.len());
+        let half = budget / 2.0;
+        let mut head_width = 0.0;
+        while head < tail && head_width + graphemes[head].1 <= half {
+            head_width += graphemes[head].1;
+            head += 1;
+        }
+        budget -= head_width;
+        while tail > head && graphemes[tail - 1].1 <= budget {
+            budget -= graphemes[tail - 1].1;
+            tail -= 1;
+        }
+        while head < tail && graphemes[head].1 <= budget {
+            budget -= graphemes[head].1;
+            head += 1;
+        }
+        if head + (graphemes.len() - tail) < 2 {
+            return String::new();
+        }
+        let mut out: String = graphemes[..head].iter().map(|(g, _)| *g).collect();
+        out += ELLIPSIS;
+        out.extend(graphemes[tail..].iter().map(|(g, _)| *g));
+        out
+    })
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
     }
 }
 
@@ -2751,7 +10077,13 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
-fn format_count(n: u64) -> String {
+/// A size change with its sign: `+1.20 GB`, `-300.0 MB`.
+fn format_change(delta: i64) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_size(delta.unsigned_abs()))
+}
+
+pub fn format_count(n: u64) -> String {
     if n >= 1_000_000 {
         format!("{:.1}M", n as f64 / 1_000_000.0)
     } else if n >= 1_000 {
@@ -2772,15 +10104,149 @@ fn format_duration(secs: f64) -> String {
     }
 }
 
-/// Find the path of a node by name and size in the file tree.
-fn find_path_for_node(root: &FileNode, name: &str, size: u64) -> Option<PathBuf> {
-    if root.name == name && root.size == size {
-        return Some(root.path.clone());
+/// Coarse age for status text: "5m", "3h", "2d".
+/// Network profile listing stats: ("Listing avg 42 ms", tooltip with the slowest folders).
+fn listing_latency(prog: &ScanProgress) -> Option<(String, String)> {
+    let listings = prog.listings.load(Ordering::Relaxed);
+    if listings == 0 {
+        return None;
     }
-    for child in &root.children {
-        if let Some(p) = find_path_for_node(child, name, size) {
-            return Some(p);
+    let avg_ms = prog.listing_micros.load(Ordering::Relaxed) as f64 / listings as f64 / 1000.0;
+    let mut tip = format!("{} folders listed. Slowest:", format_count(listings));
+    if let Ok(slowest) = prog.slowest_listings.lock() {
+        for (micros, dir) in slowest.iter() {
+            tip += &format!("\n{:.0} ms  {}", *micros as f64 / 1000.0, dir.to_string_lossy());
         }
     }
-    None
+    Some((format!("Listing avg {:.0} ms", avg_ms), tip))
+}
+
+/// Widget sizes for the touch layout: finger-sized buttons, checkboxes, sliders
+/// and scroll bars, and larger text. Off: egui's defaults.
+fn touch_style(style: &mut egui::Style, touch: bool) {
+    let defaults = egui::Style::default();
+    style.spacing = defaults.spacing;
+    style.text_styles = defaults.text_styles;
+    if !touch {
+        return;
+    }
+    let spacing = &mut style.spacing;
+    spacing.interact_size = egui::vec2(TOUCH_TARGET_PX * 1.5, TOUCH_TARGET_PX);
+    spacing.button_padding = egui::vec2(12.0, 8.0);
+    spacing.item_spacing = egui::vec2(12.0, 8.0);
+    spacing.icon_width = 24.0;
+    spacing.icon_width_inner = 14.0;
+    spacing.icon_spacing = 8.0;
+    spacing.slider_width *= TOUCH_TEXT_SCALE;
+    spacing.scroll.bar_width = 16.0;
+    for font in style.text_styles.values_mut() {
+        font.size *= TOUCH_TEXT_SCALE;
+    }
+}
+
+/// Status label ("Drives: 1 of 3 done") and one line per drive for an all-drives
+/// scan, None for any other.
+fn drive_progress(prog: &ScanProgress) -> Option<(String, Vec<String>)> {
+    let drives = prog.drives.lock().ok()?;
+    if drives.is_empty() {
+        return None;
+    }
+    let done = drives.iter().filter(|d| d.done).count();
+    let lines = drives.iter()
+        .map(|d| format!(
+            "{}  {} files, {}{}",
+            d.root.to_string_lossy(),
+            format_count(d.files),
+            format_size(d.bytes),
+            if d.done { " - done" } else { "" },
+        ))
+        .collect();
+    Some((format!("Drives: {} of {} done", done, drives.len()), lines))
+}
+
+/// (year, month 1-12, day) in UTC for seconds since 1970 (Howard Hinnant's algorithm).
+pub(crate) fn civil_date(secs: u64) -> (i64, u32, u32) {
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn iso_date(secs: u64) -> String {
+    let (y, m, d) = civil_date(secs);
+    format!("{}-{:02}-{:02}", y, m, d)
+}
+
+/// The ATTR_BADGES set in `attrs`, right-aligned at `top_right`, as many as fit in `width`.
+fn draw_attr_badges(painter: &egui::Painter, top_right: egui::Pos2, width: f32, attrs: u16) {
+    let mut x = top_right.x;
+    for (bit, text, color) in ATTR_BADGES {
+        if attrs & bit == 0 {
+            continue;
+        }
+        let galley = painter.layout_no_wrap(text.to_string(), egui::FontId::proportional(9.0), egui::Color32::WHITE);
+        let size = galley.size() + egui::vec2(6.0, 2.0);
+        if top_right.x - (x - size.x) > width {
+            break;
+        }
+        let rect = egui::Rect::from_min_size(egui::pos2(x - size.x, top_right.y), size);
+        painter.rect_filled(rect, 3.0, color);
+        painter.galley(rect.min + egui::vec2(3.0, 1.0), galley, egui::Color32::WHITE);
+        x = rect.min.x - 2.0;
+    }
+}
+
+/// Green below the quota warning level, red at or above it.
+fn quota_color(ratio: f64) -> egui::Color32 {
+    if ratio >= QUOTA_WARN {
+        egui::Color32::from_rgb(220, 60, 50)
+    } else {
+        egui::Color32::from_rgb(80, 170, 80)
+    }
+}
+
+/// `2024-03-14 09:05` in UTC.
+fn iso_date_time(secs: u64) -> String {
+    format!("{} {:02}:{:02}", iso_date(secs), secs % 86_400 / 3600, secs % 3600 / 60)
+}
+
+/// A legend date only as precise as its distance from now needs: "2009" for years
+/// back, "Mar 2017" for months, "Mar 14" for days, "5h ago" within the last day.
+/// The age gradient is logarithmic, so the newer edges are often that close.
+fn legend_date(secs: u64) -> String {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let ago = now_secs().saturating_sub(secs);
+    let (y, m, d) = civil_date(secs);
+    let month = MONTHS[(m - 1) as usize];
+    if ago >= 2 * 365 * 86_400 {
+        y.to_string()
+    } else if ago >= 60 * 86_400 {
+        format!("{} {}", month, y)
+    } else if ago >= 86_400 {
+        format!("{} {}", month, d)
+    } else {
+        format!("{} ago", format_age(ago))
+    }
+}
+
+fn format_age(secs: u64) -> String {
+    if secs >= 86_400 {
+        format!("{}d", secs / 86_400)
+    } else if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}m", secs / 60)
+    }
+}
+
+pub(crate) fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
@@ -0,0 +1,143 @@
+use crate::paths::long_path;
+use crate::scanner::ScanProgress;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+/// What `zip_folder` wrote.
+pub struct ZipSummary {
+    pub files: u64,
+    /// Entries left out: files that couldn't be read (locked, access denied), folders
+    /// that couldn't be listed, and links
+    pub skipped: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+/// `<folder>.zip` next to the folder, or `<folder> (2).zip` etc. if that exists.
+pub fn zip_destination(dir: &Path) -> PathBuf {
+    let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "Archive".to_string());
+    let parent = dir.parent().unwrap_or(dir);
+    let mut dest = parent.join(format!("{}.zip", name));
+    let mut n = 2;
+    while dest.exists() {
+        dest = parent.join(format!("{} ({}).zip", name, n));
+        n += 1;
+    }
+    dest
+}
+
+/// Deflate everything below `dir` into a new archive at `dest`, the folder itself as
+/// the top-level entry. Counts files and bytes into `progress`. Ok(None) if cancelled;
+/// the partial archive is removed.
+pub fn zip_folder(dir: &Path, dest: &Path, progress: &ScanProgress) -> Result<Option<ZipSummary>, String> {
+    let file = std::fs::File::create(long_path(dest)).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(std::io::BufWriter::new(file));
+    let top = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut summary = ZipSummary { files: 0, skipped: 0, bytes_in: 0, bytes_out: 0 };
+    let complete = match add_dir(&mut zip, dir, &top, progress, &mut summary) {
+        Ok(true) => zip.finish().map(|_| true).map_err(|e| e.to_string()),
+        other => {
+            // Close the file first so Windows lets us delete it
+            drop(zip);
+            other
+        }
+    };
+    if !matches!(complete, Ok(true)) {
+        let _ = std::fs::remove_file(long_path(dest));
+        return complete.map(|_| None);
+    }
+    summary.bytes_out = std::fs::metadata(long_path(dest)).map(|m| m.len()).unwrap_or(0);
+    Ok(Some(summary))
+}
+
+/// Ok(false) if cancelled.
+fn add_dir<W: std::io::Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    dir: &Path,
+    name: &str,
+    progress: &ScanProgress,
+    summary: &mut ZipSummary,
+) -> Result<bool, String> {
+    // Directory entries keep empty folders; names always use `/`
+    zip.add_directory(format!("{}/", name), stamped(SimpleFileOptions::default(), std::fs::metadata(long_path(dir)).ok()))
+        .map_err(|e| e.to_string())?;
+    let Ok(entries) = std::fs::read_dir(long_path(dir)) else {
+        summary.skipped += 1;
+        return Ok(true);
+    };
+    for entry in entries {
+        if progress.cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        let Ok((entry, file_type)) = entry.and_then(|e| e.file_type().map(|t| (e, t))) else {
+            summary.skipped += 1;
+            continue;
+        };
+        // Links and junctions would pull in content from outside the folder
+        if file_type.is_symlink() {
+            summary.skipped += 1;
+            continue;
+        }
+        let path = dir.join(entry.file_name());
+        let entry_name = format!("{}/{}", name, entry.file_name().to_string_lossy());
+        if file_type.is_dir() {
+            if !add_dir(zip, &path, &entry_name, progress, summary)? {
+                return Ok(false);
+            }
+            continue;
+        }
+        let Ok(mut src) = std::fs::File::open(long_path(&path)) else {
+            summary.skipped += 1;
+            continue;
+        };
+        let meta = src.metadata().ok();
+        let size = meta.as_ref().map_or(0, |m| m.len());
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(size >= u32::MAX as u64);
+        let options = stamped(options, meta);
+        zip.start_file(entry_name, options).map_err(|e| e.to_string())?;
+        std::io::copy(&mut src, zip).map_err(|e| format!("{}: {}", path.to_string_lossy(), e))?;
+        summary.files += 1;
+        summary.bytes_in += size;
+        progress.files_scanned.fetch_add(1, Ordering::Relaxed);
+        progress.bytes_scanned.fetch_add(size, Ordering::Relaxed);
+    }
+    Ok(true)
+}
+
+/// Entry options carrying the modified time from `meta`, when it has one.
+fn stamped(options: SimpleFileOptions, meta: Option<std::fs::Metadata>) -> SimpleFileOptions {
+    match meta.and_then(|m| m.modified().ok()).and_then(dos_time) {
+        Some(time) => options.last_modified_time(time),
+        None => options,
+    }
+}
+
+/// Zip entries store a zone-less DOS date; this writes UTC, which is what tools show
+/// for archives made on another machine anyway.
+fn dos_time(time: std::time::SystemTime) -> Option<DateTime> {
+    let secs = time.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64;
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    DateTime::from_date_and_time(
+        year.try_into().ok()?,
+        month as u8,
+        day as u8,
+        (rem / 3600) as u8,
+        (rem % 3600 / 60) as u8,
+        (rem % 60) as u8,
+    ).ok()
+}
@@ -0,0 +1,173 @@
+use crate::compare::{DiffEntry, DiffKind, DiffReport};
+use crate::hashing::sha256_file;
+use crate::scanner::{FileNode, ScanOptions, ScanProgress, is_offline, scan_directory};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+const HEADER: &str = "# SpaceView integrity baseline v1";
+
+/// One hashed file in a baseline. Paths are relative to the baseline root.
+/// An empty `sha256` marks a file that was not hashed: it could not be read, or it
+/// was an offline (cloud / archive) placeholder that reading would recall.
+pub struct BaselineEntry {
+    pub rel_path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+pub struct Baseline {
+    pub root: PathBuf,
+    pub created: u64,
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Files recorded without a hash: unreadable, or offline placeholders.
+    pub fn unhashed(&self) -> usize {
+        self.entries.iter().filter(|e| e.sha256.is_empty()).count()
+    }
+}
+
+/// Walk `root` and SHA-256 every file. Returns None if cancelled.
+pub fn create_baseline(root: &Path, progress: Arc<ScanProgress>) -> Option<Baseline> {
+    let tree = scan_directory(root, progress.clone(), &ScanOptions::default())?;
+    let mut entries = Vec::new();
+    hash_tree(&tree, root, &progress, &mut entries)?;
+    entries.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    let created = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Some(Baseline { root: root.to_path_buf(), created, entries })
+}
+
+fn hash_tree(node: &FileNode, root: &Path, progress: &ScanProgress, out: &mut Vec<BaselineEntry>) -> Option<()> {
+    for child in &node.children {
+        if progress.cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        if child.is_dir {
+            hash_tree(child, root, progress, out)?;
+        } else {
+            // Keep unreadable files (locked, no access) so verify doesn't call them Added.
+            // Offline placeholders aren't read at all: that would recall the whole store.
+            progress.files_scanned.fetch_add(1, Ordering::Relaxed);
+            let rel = child.path.strip_prefix(root).unwrap_or(&child.path);
+            let sha256 = if is_offline(child.attrs) { String::new() } else { sha256_file(&child.path).unwrap_or_default() };
+            out.push(BaselineEntry {
+                rel_path: rel.to_string_lossy().to_string(),
+                size: child.size,
+                sha256,
+            });
+        }
+    }
+    Some(())
+}
+
+/// Write a baseline as tab-separated text: `sha256<TAB>size<TAB>relative path`.
+pub fn save_baseline(baseline: &Baseline, path: &Path) -> std::io::Result<()> {
+    let mut content = format!(
+        "{}\nroot={}\ncreated={}\n",
+        HEADER,
+        baseline.root.to_string_lossy(),
+        baseline.created,
+    );
+    for e in &baseline.entries {
+        content += &format!("{}\t{}\t{}\n", e.sha256, e.size, e.rel_path);
+    }
+    std::fs::write(path, content)
+}
+
+pub fn load_baseline(path: &Path) -> Result<Baseline, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut lines = content.lines();
+    if lines.next() != Some(HEADER) {
+        return Err("Not a SpaceView baseline file".to_string());
+    }
+    let mut baseline = Baseline { root: PathBuf::new(), created: 0, entries: Vec::new() };
+    for line in lines {
+        if let Some(root) = line.strip_prefix("root=") {
+            baseline.root = PathBuf::from(root);
+        } else if let Some(created) = line.strip_prefix("created=") {
+            baseline.created = created.parse().unwrap_or(0);
+        } else {
+            let mut parts = line.splitn(3, '\t');
+            if let (Some(hash), Some(size), Some(rel)) = (parts.next(), parts.next(), parts.next()) {
+                baseline.entries.push(BaselineEntry {
+                    rel_path: rel.to_string(),
+                    size: size.parse().unwrap_or(0),
+                    sha256: hash.to_string(),
+                });
+            }
+        }
+    }
+    Ok(baseline)
+}
+
+/// Re-walk the baseline root (or `root_override`) and report added, removed, and
+/// modified files. Files whose size is unchanged are re-hashed to catch silent corruption;
+/// ones stored without a hash, and offline placeholders, are only checked by size.
+pub fn verify_baseline(baseline: &Baseline, root_override: Option<&Path>, progress: Arc<ScanProgress>) -> Option<DiffReport> {
+    let root = root_override.unwrap_or(&baseline.root);
+    let tree = scan_directory(root, progress.clone(), &ScanOptions::default())?;
+    let mut current: HashMap<String, &FileNode> = HashMap::new();
+    collect_files(&tree, root, &mut current);
+
+    let mut entries = Vec::new();
+    for e in &baseline.entries {
+        if progress.cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        match current.remove(&e.rel_path) {
+            None => entries.push(DiffEntry {
+                rel_path: e.rel_path.clone(), kind: DiffKind::OnlyLeft, is_dir: false,
+                left_size: e.size, right_size: 0,
+            }),
+            Some(node) => {
+                progress.files_scanned.fetch_add(1, Ordering::Relaxed);
+                let changed = node.size != e.size
+                    || (!e.sha256.is_empty()
+                        && !is_offline(node.attrs)
+                        && sha256_file(&node.path).map(|h| h != e.sha256).unwrap_or(true));
+                if changed {
+                    entries.push(DiffEntry {
+                        rel_path: e.rel_path.clone(), kind: DiffKind::ContentChanged, is_dir: false,
+                        left_size: e.size, right_size: node.size,
+                    });
+                }
+            }
+        }
+    }
+    let mut added: Vec<DiffEntry> = current.into_iter()
+        .map(|(rel_path, node)| DiffEntry {
+            rel_path, kind: DiffKind::OnlyRight, is_dir: false, left_size: 0, right_size: node.size,
+        })
+        .collect();
+    added.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    entries.extend(added);
+
+    Some(DiffReport {
+        title: "Integrity Check".to_string(),
+        left_label: "baseline".to_string(),
+        right_label: "current".to_string(),
+        only_left_label: "Removed".to_string(),
+        only_right_label: "Added".to_string(),
+        left_root: root.to_path_buf(),
+        right_root: root.to_path_buf(),
+        entries,
+        folders: Vec::new(),
+    })
+}
+
+fn collect_files<'a>(node: &'a FileNode, root: &Path, out: &mut HashMap<String, &'a FileNode>) {
+    for child in &node.children {
+        if child.is_dir {
+            collect_files(child, root, out);
+        } else {
+            let rel = child.path.strip_prefix(root).unwrap_or(&child.path);
+            out.insert(rel.to_string_lossy().to_string(), child);
+        }
+    }
+}
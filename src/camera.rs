@@ -4,7 +4,7 @@ const MAX_ZOOM: f32 = 5_000.0;
 const MIN_ZOOM: f32 = 1.0;
 
 /// Continuous camera for world-space treemap viewing.
-/// Supports smooth scroll-zoom, click-drag pan, and snap-zoom animations.
+/// Supports smooth scroll-zoom, gesture (pinch) zoom, click-drag pan, and snap-zoom animations.
 pub struct Camera {
     pub center: egui::Pos2,
     pub zoom: f32,
@@ -179,9 +179,26 @@ impl Camera {
         }
 
         let factor = (1.0 + SCROLL_ZOOM_SPEED).powf(scroll_delta);
-        let new_zoom = (self.target_zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.zoom_target_about(factor, world_focus);
+        self.clamp_center(viewport);
+    }
+
+    /// Multiplicative zoom from a gesture (touchpad pinch, touch screen, Ctrl+scroll),
+    /// centered on a world point. Applied immediately: gestures already arrive smooth
+    /// and extra easing makes them feel laggy.
+    pub fn gesture_zoom(&mut self, factor: f32, world_focus: egui::Pos2, viewport: egui::Rect) {
+        if self.animating {
+            self.animating = false;
+        }
+        self.zoom_target_about(factor, world_focus);
+        self.zoom = self.target_zoom;
+        self.center = self.target_center;
+        self.clamp_center(viewport);
+    }
 
-        // Adjust center so that world_focus stays at the same screen position.
+    /// Scale target zoom by `factor`, keeping `world_focus` at the same screen position.
+    fn zoom_target_about(&mut self, factor: f32, world_focus: egui::Pos2) {
+        let new_zoom = (self.target_zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
         let old_zoom = self.target_zoom;
         let ratio = old_zoom / new_zoom;
         self.target_center = egui::pos2(
@@ -189,7 +206,6 @@ impl Camera {
             world_focus.y - (world_focus.y - self.target_center.y) * ratio,
         );
         self.target_zoom = new_zoom;
-        self.clamp_center(viewport);
     }
 
     /// Immediate pan by a world-space delta.
@@ -205,16 +221,21 @@ impl Camera {
 
     /// Animated snap-zoom so that `world_rect` fills the viewport.
     pub fn snap_to(&mut self, world_rect: egui::Rect, viewport: egui::Rect) {
-        self.anim_start_center = self.center;
-        self.anim_start_zoom = self.zoom;
-
-        self.target_center = world_rect.center();
         // Zoom so the rect fills the viewport (fit shorter axis)
         // zoom * vp_w * world_w = vp_w → zoom = 1/world_w
         let zoom_w = 1.0 / world_rect.width();
         // zoom * vp_w * world_h = vp_h → zoom = vp_h / (vp_w * world_h)
         let zoom_h = viewport.height() / (world_rect.height() * viewport.width());
-        self.target_zoom = zoom_w.min(zoom_h).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.snap_to_view(world_rect.center(), zoom_w.min(zoom_h), viewport);
+    }
+
+    /// Animated snap to an exact center + zoom (e.g. a saved bookmark).
+    pub fn snap_to_view(&mut self, center: egui::Pos2, zoom: f32, viewport: egui::Rect) {
+        self.anim_start_center = self.center;
+        self.anim_start_zoom = self.zoom;
+
+        self.target_center = center;
+        self.target_zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
 
         self.clamp_center(viewport);
 
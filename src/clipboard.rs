@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+/// Put files on the clipboard the way Explorer's Copy does (CF_HDROP), so they can be
+/// pasted into Explorer, mail or chat apps. Folders work too.
+#[cfg(target_os = "windows")]
+pub fn copy_files(paths: &[PathBuf]) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::GlobalFree;
+    use windows_sys::Win32::System::DataExchange::{CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData};
+    use windows_sys::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use windows_sys::Win32::UI::Shell::DROPFILES;
+    // Standard clipboard format id (Win32_System_Ole isn't enabled just for this)
+    const CF_HDROP: u32 = 15;
+
+    // DROPFILES header followed by NUL-terminated wide paths and a closing NUL
+    let mut list: Vec<u16> = Vec::new();
+    for path in paths {
+        list.extend(path.as_os_str().encode_wide());
+        list.push(0);
+    }
+    list.push(0);
+    let header = std::mem::size_of::<DROPFILES>();
+    let bytes = header + list.len() * 2;
+
+    unsafe {
+        let mem = GlobalAlloc(GMEM_MOVEABLE, bytes);
+        if mem.is_null() {
+            return Err("Out of memory copying files.".to_string());
+        }
+        let ptr = GlobalLock(mem) as *mut u8;
+        if ptr.is_null() {
+            GlobalFree(mem);
+            return Err("Out of memory copying files.".to_string());
+        }
+        let mut drop_files: DROPFILES = std::mem::zeroed();
+        drop_files.pFiles = header as u32;
+        drop_files.fWide = 1;
+        std::ptr::copy_nonoverlapping(&drop_files as *const DROPFILES as *const u8, ptr, header);
+        std::ptr::copy_nonoverlapping(list.as_ptr() as *const u8, ptr.add(header), list.len() * 2);
+        GlobalUnlock(mem);
+
+        if OpenClipboard(std::ptr::null_mut()) == 0 {
+            GlobalFree(mem);
+            return Err("The clipboard is in use by another program.".to_string());
+        }
+        EmptyClipboard();
+        // On success the clipboard owns the memory
+        let placed = !SetClipboardData(CF_HDROP, mem).is_null();
+        CloseClipboard();
+        if !placed {
+            GlobalFree(mem);
+            return Err("Could not copy files to the clipboard.".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn copy_files(_paths: &[PathBuf]) -> Result<(), String> {
+    Err("Copying files to the clipboard is only available on Windows.".to_string())
+}
@@ -0,0 +1,447 @@
+use crate::scanner::{FileNode, is_space_tile};
+use crate::snapshot::load_snapshot;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Modified-time differences at or below this are ignored (FAT/exFAT store 2s resolution).
+const MTIME_TOLERANCE_SECS: u64 = 2;
+/// Heat: growth is measured against at least this size, so a folder that was tiny
+/// (or new) needs real growth to glow, not just a few files.
+const HEAT_BASE_BYTES: u64 = 64 << 20;
+/// Heat: growing by this share of its size per day makes a folder glow fully.
+const HEAT_FULL_RATE: f64 = 0.25;
+/// Heat below this isn't drawn.
+const HEAT_MIN: f32 = 0.05;
+/// Heat: weight of the growth rate; the rest is the bytes added per day.
+const HEAT_RATE_WEIGHT: f64 = 0.75;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffKind {
+    /// Present only on the left side (source / baseline / older scan).
+    OnlyLeft,
+    /// Present only on the right side (backup / current state / newer scan).
+    OnlyRight,
+    SizeChanged,
+    TimeChanged,
+    /// Content hash differs (integrity verification).
+    ContentChanged,
+}
+
+pub const DIFF_KINDS: [DiffKind; 5] = [
+    DiffKind::OnlyLeft,
+    DiffKind::OnlyRight,
+    DiffKind::SizeChanged,
+    DiffKind::TimeChanged,
+    DiffKind::ContentChanged,
+];
+
+impl DiffKind {
+    /// Stable machine-readable name, used in JSON output.
+    pub fn key(self) -> &'static str {
+        match self {
+            DiffKind::OnlyLeft => "only_left",
+            DiffKind::OnlyRight => "only_right",
+            DiffKind::SizeChanged => "size_changed",
+            DiffKind::TimeChanged => "time_changed",
+            DiffKind::ContentChanged => "content_changed",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DiffEntry {
+    pub rel_path: String,
+    pub kind: DiffKind,
+    pub is_dir: bool,
+    pub left_size: u64,
+    pub right_size: u64,
+}
+
+/// A folder whose total changed between the two trees. Folders on only one side
+/// are listed once, not with every subfolder, like `compare_trees` reports them.
+#[derive(Clone, Debug)]
+pub struct FolderDelta {
+    pub rel_path: String,
+    pub left_size: u64,
+    pub right_size: u64,
+    /// Files anywhere below that are only on the right / only on the left
+    pub added_files: u64,
+    pub removed_files: u64,
+}
+
+impl FolderDelta {
+    pub fn change(&self) -> i64 {
+        self.right_size as i64 - self.left_size as i64
+    }
+}
+
+/// Size change of every item of the newer tree that differs from the older one, by
+/// its path in the newer tree, for coloring the map. New items count their whole size.
+pub struct GrowthMap {
+    deltas: HashMap<PathBuf, i64>,
+    /// Largest change of any folder below the root, for scaling the colors
+    pub largest: u64,
+}
+
+impl GrowthMap {
+    /// None for items that didn't change
+    pub fn delta(&self, path: &Path) -> Option<i64> {
+        self.deltas.get(path).copied()
+    }
+}
+
+/// How fast each folder is growing, 0..1, for the Heat overlay. The growth rate
+/// relative to the folder's size counts most, so a small runaway folder glows as
+/// brightly as a big one; the bytes added per day decide the rest.
+pub struct HeatMap {
+    heat: HashMap<PathBuf, f32>,
+    /// When the snapshot the growth is measured from was taken
+    pub since: u64,
+    /// Most bytes any folder added per day
+    pub fastest: u64,
+}
+
+impl HeatMap {
+    /// None for folders that didn't grow enough to show
+    pub fn heat(&self, path: &Path) -> Option<f32> {
+        self.heat.get(path).copied()
+    }
+}
+
+/// Heat of every folder of `current` that grew since the snapshot at `old`, with
+/// `now` the time `current` was scanned.
+pub fn heat_map(old: &Path, current: &FileNode, now: u64) -> Result<HeatMap, String> {
+    let (info, old_root) = load_snapshot(old)?;
+    if !info.complete {
+        return Err("snapshot of an unfinished scan".to_string());
+    }
+    let days = (now.saturating_sub(info.created) as f64 / 86_400.0).max(1.0 / 24.0);
+    let mut growth = Vec::new();
+    heat_dirs(Some(&old_root), current, days, &mut growth);
+    let fastest = growth.iter().map(|g| g.2).fold(0.0, f64::max);
+    let heat = growth.into_iter()
+        .filter_map(|(path, rate, per_day)| {
+            let rate_part = (rate / HEAT_FULL_RATE).min(1.0).sqrt();
+            let bytes_part = (1.0 + per_day).ln() / (1.0 + fastest).ln().max(1.0);
+            let heat = (HEAT_RATE_WEIGHT * rate_part + (1.0 - HEAT_RATE_WEIGHT) * bytes_part) as f32;
+            (heat >= HEAT_MIN).then_some((path, heat))
+        })
+        .collect();
+    Ok(HeatMap { heat, since: info.created, fastest: fastest as u64 })
+}
+
+/// Collect (path, growth per day relative to size, bytes per day) of each grown
+/// folder below `right`. `left` is the same folder in the snapshot, if it existed.
+fn heat_dirs(left: Option<&FileNode>, right: &FileNode, days: f64, out: &mut Vec<(PathBuf, f64, f64)>) {
+    let left_map: HashMap<String, &FileNode> = left.map_or_else(HashMap::new, |l| {
+        l.children.iter().filter(|c| c.is_dir).map(|c| (match_key(&c.name), c)).collect()
+    });
+    for r in right.children.iter().filter(|c| c.is_dir && !c.path.as_os_str().is_empty()) {
+        let l = left_map.get(&match_key(&r.name)).copied();
+        let old = l.map_or(0, |l| l.size);
+        if r.size > old {
+            let per_day = (r.size - old) as f64 / days;
+            out.push((r.path.clone(), per_day / old.max(HEAT_BASE_BYTES) as f64, per_day));
+        }
+        heat_dirs(l, r, days, out);
+    }
+}
+
+/// Result of comparing two trees, shown in the Diff view.
+pub struct DiffReport {
+    pub title: String,
+    pub left_label: String,
+    pub right_label: String,
+    pub only_left_label: String,
+    pub only_right_label: String,
+    pub left_root: PathBuf,
+    pub right_root: PathBuf,
+    pub entries: Vec<DiffEntry>,
+    /// Per-folder totals, for comparisons of two scans (empty otherwise)
+    pub folders: Vec<FolderDelta>,
+}
+
+impl DiffReport {
+    /// Human label for a diff kind in the context of this report.
+    pub fn kind_label(&self, kind: DiffKind) -> String {
+        match kind {
+            DiffKind::OnlyLeft => self.only_left_label.clone(),
+            DiffKind::OnlyRight => self.only_right_label.clone(),
+            DiffKind::SizeChanged => "Size differs".to_string(),
+            DiffKind::TimeChanged => "Modified differs".to_string(),
+            DiffKind::ContentChanged => "Modified".to_string(),
+        }
+    }
+
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.entries.iter().filter(|e| e.kind == kind).count()
+    }
+
+    /// Net bytes gained from left to right (negative when space was freed).
+    pub fn growth(&self) -> i64 {
+        self.entries.iter().map(|e| e.right_size as i64 - e.left_size as i64).sum()
+    }
+
+    /// The report as JSON: labels, per-kind counts, net growth, and one object per entry.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{{");
+        let _ = writeln!(out, "  \"title\": {},", json_str(&self.title));
+        let _ = writeln!(out, "  \"left\": {{\"label\": {}, \"root\": {}}},",
+            json_str(&self.left_label), json_str(&self.left_root.to_string_lossy()));
+        let _ = writeln!(out, "  \"right\": {{\"label\": {}, \"root\": {}}},",
+            json_str(&self.right_label), json_str(&self.right_root.to_string_lossy()));
+        let counts: Vec<String> = DIFF_KINDS.iter()
+            .map(|k| format!("\"{}\": {}", k.key(), self.count(*k)))
+            .collect();
+        let _ = writeln!(out, "  \"counts\": {{{}}},", counts.join(", "));
+        let _ = writeln!(out, "  \"growth\": {},", self.growth());
+        let _ = writeln!(out, "  \"entries\": [");
+        for (i, e) in self.entries.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "    {{\"path\": {}, \"kind\": \"{}\", \"status\": {}, \"dir\": {}, \"left_size\": {}, \"right_size\": {}}}{}",
+                json_str(&e.rel_path),
+                e.kind.key(),
+                json_str(&self.kind_label(e.kind)),
+                e.is_dir,
+                e.left_size,
+                e.right_size,
+                if i + 1 < self.entries.len() { "," } else { "" },
+            );
+        }
+        let _ = writeln!(out, "  ]");
+        let _ = writeln!(out, "}}");
+        out
+    }
+}
+
+/// Quote and escape a string as a JSON string literal.
+pub fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Diff two saved snapshots, older on the left. Both must be complete scans.
+pub fn compare_snapshots(old: &Path, new: &Path) -> Result<DiffReport, String> {
+    let load = |path: &Path| -> Result<_, String> {
+        let (info, root) = load_snapshot(path).map_err(|e| format!("{}: {}", path.to_string_lossy(), e))?;
+        if !info.complete {
+            return Err(format!("{}: snapshot of an unfinished scan", path.to_string_lossy()));
+        }
+        Ok((info, root))
+    };
+    let (old_info, old_root) = load(old)?;
+    let (new_info, new_root) = load(new)?;
+    Ok(DiffReport {
+        title: "Snapshot Diff".to_string(),
+        left_label: "older".to_string(),
+        right_label: "newer".to_string(),
+        only_left_label: "Removed".to_string(),
+        only_right_label: "Added".to_string(),
+        entries: compare_trees(&old_root, &new_root),
+        folders: folder_deltas(&old_root, &new_root),
+        left_root: old_info.root,
+        right_root: new_info.root,
+    })
+}
+
+/// Diff a saved snapshot against the tree on screen, which is on the right, and map
+/// the growth onto the current tree. The snapshot must be a complete scan.
+pub fn compare_with_snapshot(old: &Path, current: &FileNode) -> Result<(DiffReport, GrowthMap), String> {
+    let (info, old_root) = load_snapshot(old)?;
+    if !info.complete {
+        return Err("snapshot of an unfinished scan".to_string());
+    }
+    let report = DiffReport {
+        title: "Changes Since Snapshot".to_string(),
+        left_label: "snapshot".to_string(),
+        right_label: "now".to_string(),
+        only_left_label: "Deleted".to_string(),
+        only_right_label: "New".to_string(),
+        entries: compare_trees(&old_root, current),
+        folders: folder_deltas(&old_root, current),
+        left_root: info.root,
+        right_root: current.path.clone(),
+    };
+    Ok((report, growth_map(&old_root, current)))
+}
+
+/// Every folder below the roots whose size or files changed, largest growth first.
+pub fn folder_deltas(left: &FileNode, right: &FileNode) -> Vec<FolderDelta> {
+    let mut out = Vec::new();
+    delta_dirs(left, right, "", &mut out);
+    out.sort_by_key(|d| std::cmp::Reverse(d.change()));
+    out
+}
+
+/// Collect the changed folders below this pair; returns the files added and removed below it.
+fn delta_dirs(left: &FileNode, right: &FileNode, prefix: &str, out: &mut Vec<FolderDelta>) -> (u64, u64) {
+    let mut left_map: HashMap<String, &FileNode> = left.children.iter()
+        .filter(|c| !is_space_tile(&c.name))
+        .map(|c| (match_key(&c.name), c))
+        .collect();
+    let (mut added, mut removed) = (0, 0);
+    for r in right.children.iter().filter(|c| !is_space_tile(&c.name)) {
+        let rel = join_rel(prefix, &r.name);
+        match left_map.remove(&match_key(&r.name)) {
+            Some(l) if l.is_dir && r.is_dir => {
+                let (a, d) = delta_dirs(l, r, &rel, out);
+                if l.size != r.size || a > 0 || d > 0 {
+                    out.push(FolderDelta { rel_path: rel, left_size: l.size, right_size: r.size, added_files: a, removed_files: d });
+                }
+                added += a;
+                removed += d;
+            }
+            Some(l) if !l.is_dir && !r.is_dir => {}
+            // File on one side, folder on the other: both halves
+            Some(l) => {
+                removed += one_side(l, rel.clone(), false, out);
+                added += one_side(r, rel, true, out);
+            }
+            None => added += one_side(r, rel, true, out),
+        }
+    }
+    for l in left_map.into_values() {
+        removed += one_side(l, join_rel(prefix, &l.name), false, out);
+    }
+    (added, removed)
+}
+
+/// List a folder that is only on one side; returns its files.
+fn one_side(node: &FileNode, rel_path: String, on_right: bool, out: &mut Vec<FolderDelta>) -> u64 {
+    if node.is_dir {
+        let (size, files) = (node.size, node.file_count);
+        out.push(if on_right {
+            FolderDelta { rel_path, left_size: 0, right_size: size, added_files: files, removed_files: 0 }
+        } else {
+            FolderDelta { rel_path, left_size: size, right_size: 0, added_files: 0, removed_files: files }
+        });
+    }
+    node.file_count
+}
+
+/// Map how each item of `right` changed since `left`.
+pub fn growth_map(left: &FileNode, right: &FileNode) -> GrowthMap {
+    let mut map = GrowthMap { deltas: HashMap::new(), largest: 0 };
+    growth_dirs(left, right, &mut map);
+    map
+}
+
+fn growth_dirs(left: &FileNode, right: &FileNode, map: &mut GrowthMap) {
+    let left_map: HashMap<String, &FileNode> = left.children.iter()
+        .map(|c| (match_key(&c.name), c))
+        .collect();
+    // Aggregates have no path to key them by
+    for r in right.children.iter().filter(|c| !c.path.as_os_str().is_empty()) {
+        match left_map.get(&match_key(&r.name)).filter(|l| l.is_dir == r.is_dir) {
+            Some(l) => {
+                if l.size != r.size {
+                    record_growth(map, r, r.size as i64 - l.size as i64);
+                }
+                if r.is_dir {
+                    growth_dirs(l, r, map);
+                }
+            }
+            None => all_new(r, map),
+        }
+    }
+}
+
+fn all_new(node: &FileNode, map: &mut GrowthMap) {
+    if node.path.as_os_str().is_empty() {
+        return;
+    }
+    record_growth(map, node, node.size as i64);
+    for child in &node.children {
+        all_new(child, map);
+    }
+}
+
+fn record_growth(map: &mut GrowthMap, node: &FileNode, delta: i64) {
+    if node.is_dir {
+        map.largest = map.largest.max(delta.unsigned_abs());
+    }
+    map.deltas.insert(node.path.clone(), delta);
+}
+
+/// Walk two scanned trees side by side and report every difference.
+/// A directory missing on one side is reported once (with its total size), not per file.
+pub fn compare_trees(left: &FileNode, right: &FileNode) -> Vec<DiffEntry> {
+    let mut entries = Vec::new();
+    compare_dirs(left, right, "", &mut entries);
+    entries.sort_by(|a, b| {
+        let ka = DIFF_KINDS.iter().position(|k| *k == a.kind);
+        let kb = DIFF_KINDS.iter().position(|k| *k == b.kind);
+        ka.cmp(&kb).then_with(|| a.rel_path.cmp(&b.rel_path))
+    });
+    entries
+}
+
+/// Name key for matching entries across trees. Windows paths are case-insensitive.
+pub fn match_key(name: &str) -> String {
+    if cfg!(windows) {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    }
+}
+
+fn join_rel(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}{}{}", prefix, std::path::MAIN_SEPARATOR, name)
+    }
+}
+
+fn compare_dirs(left: &FileNode, right: &FileNode, prefix: &str, out: &mut Vec<DiffEntry>) {
+    let mut right_map: HashMap<String, &FileNode> = right.children.iter()
+        .filter(|c| !is_space_tile(&c.name))
+        .map(|c| (match_key(&c.name), c))
+        .collect();
+
+    for l in left.children.iter().filter(|c| !is_space_tile(&c.name)) {
+        let rel = join_rel(prefix, &l.name);
+        match right_map.remove(&match_key(&l.name)) {
+            Some(r) if l.is_dir && r.is_dir => compare_dirs(l, r, &rel, out),
+            Some(r) if !l.is_dir && !r.is_dir => {
+                if l.size != r.size {
+                    out.push(DiffEntry { rel_path: rel, kind: DiffKind::SizeChanged, is_dir: false, left_size: l.size, right_size: r.size });
+                } else if l.modified.abs_diff(r.modified) > MTIME_TOLERANCE_SECS {
+                    out.push(DiffEntry { rel_path: rel, kind: DiffKind::TimeChanged, is_dir: false, left_size: l.size, right_size: r.size });
+                }
+            }
+            // File on one side, directory on the other: report both halves
+            Some(r) => {
+                out.push(DiffEntry { rel_path: rel.clone(), kind: DiffKind::OnlyLeft, is_dir: l.is_dir, left_size: l.size, right_size: 0 });
+                out.push(DiffEntry { rel_path: rel, kind: DiffKind::OnlyRight, is_dir: r.is_dir, left_size: 0, right_size: r.size });
+            }
+            None => {
+                out.push(DiffEntry { rel_path: rel, kind: DiffKind::OnlyLeft, is_dir: l.is_dir, left_size: l.size, right_size: 0 });
+            }
+        }
+    }
+
+    for r in right.children.iter().filter(|c| !is_space_tile(&c.name)) {
+        if right_map.contains_key(&match_key(&r.name)) {
+            out.push(DiffEntry { rel_path: join_rel(prefix, &r.name), kind: DiffKind::OnlyRight, is_dir: r.is_dir, left_size: 0, right_size: r.size });
+        }
+    }
+}
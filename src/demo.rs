@@ -0,0 +1,269 @@
+use crate::scanner::{
+    ATTR_CASE_SENSITIVE, ATTR_COMPRESSED, ATTR_GIT, ATTR_LINK, ATTR_OFFLINE, ATTR_SPARSE, ATTR_SYNCED, ATTR_SYSTEM,
+    FileNode, SMALL_FILES,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+// A made-up disk for trying SpaceView without scanning anything: profiles, repos,
+// caches, old archives, system files and a few copies, so every view and report
+// has something to show. Built from a fixed seed and a fixed "now", so it comes
+// out the same on every machine, for screenshots and for checking layouts.
+
+/// Root path of the sample tree. Nothing exists there; the app keeps it read-only.
+pub const DEMO_ROOT: &str = "Demo";
+
+/// The time the sample dates count back from (October 2025).
+const DEMO_NOW: u64 = 1_760_000_000;
+const DAY: u64 = 86_400;
+const KB: u64 = 1024;
+const MB: u64 = 1024 * KB;
+const GB: u64 = 1024 * MB;
+const DEMO_CLUSTER: u64 = 4 * KB;
+
+/// Linear congruential generator; the demo only needs repeatable variety.
+struct Rng {
+    state: u64,
+    /// File sizes handed out so far. Sizes are kept unique, because the demo's
+    /// duplicates are matched on size alone and only the planted copies should match.
+    sizes: HashSet<u64>,
+}
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        self.state >> 33
+    }
+
+    /// Uniform in `lo..hi`
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next() % (hi - lo).max(1)
+    }
+
+    /// A size in `lo..hi` no other file has
+    fn size(&mut self, lo: u64, hi: u64) -> u64 {
+        let mut size = self.range(lo, hi);
+        while !self.sizes.insert(size) {
+            size += 1;
+        }
+        size
+    }
+
+    /// A modified time up to `max_days` back
+    fn date(&mut self, max_days: u64) -> u64 {
+        DEMO_NOW - self.range(0, max_days * DAY)
+    }
+
+    /// `count` files named `<prefix>_0001.<ext>` and so on
+    fn files(&mut self, prefix: &str, ext: &str, count: usize, lo: u64, hi: u64, max_days: u64) -> Vec<FileNode> {
+        (1..=count)
+            .map(|i| {
+                let size = self.size(lo, hi);
+                let modified = self.date(max_days);
+                file(&format!("{}_{:04}.{}", prefix, i, ext), size, modified)
+            })
+            .collect()
+    }
+
+    /// One named file
+    fn one(&mut self, name: &str, lo: u64, hi: u64, max_days: u64) -> FileNode {
+        let size = self.size(lo, hi);
+        let modified = self.date(max_days);
+        file(name, size, modified)
+    }
+
+    /// The `<Small Files>` block a scan with a minimum file size leaves
+    fn small_files(&mut self, count: u64, max_days: u64) -> FileNode {
+        FileNode {
+            name: SMALL_FILES.to_string(),
+            size: count * self.range(2 * KB, 12 * KB),
+            file_count: count,
+            modified: self.date(max_days),
+            ..file("", 0, 0)
+        }
+    }
+}
+
+fn file(name: &str, size: u64, modified: u64) -> FileNode {
+    FileNode {
+        name: name.to_string(),
+        path: PathBuf::new(),
+        size,
+        is_dir: false,
+        file_count: 1,
+        modified,
+        attrs: 0,
+        allocated: 0,
+        children: Vec::new(),
+    }
+}
+
+fn dir(name: &str, children: Vec<FileNode>) -> FileNode {
+    FileNode { name: name.to_string(), is_dir: true, file_count: 0, children, ..file(name, 0, 0) }
+}
+
+fn with_attrs(mut node: FileNode, attrs: u16) -> FileNode {
+    node.attrs |= attrs;
+    node
+}
+
+/// The sample tree, finished like a scan: paths filled in, folder totals summed,
+/// children largest first.
+pub fn demo_tree() -> FileNode {
+    let mut rng = Rng { state: 0x5EED_5EED, sizes: HashSet::new() };
+    let r = &mut rng;
+
+    let installer = r.size(80 * MB, 90 * MB);
+    let photos_zip = r.size(600 * MB, 700 * MB);
+    let build = r.size(1200 * MB, 1300 * MB);
+
+    let alex = dir("alex", vec![
+        dir("Documents", vec![
+            dir("Taxes", [r.files("return", "pdf", 8, 200 * KB, 3 * MB, 2500), vec![r.small_files(40, 2500)]].concat()),
+            dir("Work", [r.files("report", "docx", 35, 40 * KB, 4 * MB, 700), r.files("budget", "xlsx", 12, 30 * KB, 2 * MB, 700)].concat()),
+            r.one("Thesis final FINAL v3.docx", 8 * MB, 12 * MB, 1800),
+        ]),
+        dir("Pictures", vec![
+            dir("Vacation 2023", r.files("IMG", "jpg", 160, 2 * MB, 7 * MB, 800)),
+            dir("Phone Backup", [r.files("PXL", "heic", 220, MB, 4 * MB, 400), r.files("VID", "mp4", 14, 40 * MB, 400 * MB, 400)].concat()),
+            dir("Raw", r.files("DSC", "cr2", 60, 20 * MB, 32 * MB, 1200)),
+        ]),
+        dir("Videos", r.files("Recording", "mp4", 9, 300 * MB, 2 * GB, 900)),
+        dir("Downloads", vec![
+            r.one("ubuntu-24.04-desktop-amd64.iso", 5 * GB, 6 * GB, 500),
+            file("VSCodeSetup.exe", installer, DEMO_NOW - 90 * DAY),
+            file("VSCodeSetup (1).exe", installer, DEMO_NOW - 30 * DAY),
+            file("photos.zip", photos_zip, DEMO_NOW - 200 * DAY),
+            r.one("driver_pack.zip", 300 * MB, 500 * MB, 1000),
+            r.small_files(310, 1500),
+        ]),
+        dir("AppData", vec![dir("Local", vec![
+            dir("Temp", [r.files("tmp", "tmp", 90, 100 * KB, 20 * MB, 60), vec![r.small_files(900, 60)]].concat()),
+            dir("Google", vec![dir("Chrome", vec![dir("User Data", vec![dir("Default", vec![
+                dir("Cache", vec![dir("Cache_Data", [r.files("f", "bin", 240, 200 * KB, 3 * MB, 30), vec![r.small_files(2400, 30)]].concat())]),
+            ])])])]),
+            dir("npm-cache", vec![dir("_cacache", vec![r.small_files(5200, 300)])]),
+        ])]),
+        with_attrs(dir("OneDrive", vec![
+            dir("Shared", r.files("slides", "pptx", 18, 2 * MB, 60 * MB, 300)),
+            with_attrs(r.one("family-archive.zip", 3 * GB, 4 * GB, 600), ATTR_OFFLINE),
+            file("photos.zip", photos_zip, DEMO_NOW - 180 * DAY),
+        ]), ATTR_SYNCED),
+    ]);
+
+    let sam = dir("sam", vec![
+        dir("Music", vec![
+            dir("Albums", r.files("track", "flac", 140, 20 * MB, 45 * MB, 3000)),
+            dir("Podcasts", r.files("episode", "mp3", 60, 30 * MB, 90 * MB, 200)),
+        ]),
+        dir("Documents", [r.files("letter", "docx", 22, 20 * KB, MB, 2000), vec![r.small_files(75, 2000)]].concat()),
+        dir("AppData", vec![dir("Local", vec![dir("Temp", vec![r.small_files(420, 20)])])]),
+    ]);
+
+    let website = with_attrs(dir("website", vec![
+        dir(".git", vec![dir("objects", vec![dir("pack", r.files("pack", "pack", 3, 20 * MB, 80 * MB, 120))])]),
+        dir("node_modules", vec![
+            dir("typescript", vec![dir("lib", r.files("lib", "js", 30, 200 * KB, 9 * MB, 90))]),
+            dir("@esbuild", vec![r.one("esbuild.exe", 9 * MB, 11 * MB, 90)]),
+            r.small_files(18_000, 90),
+        ]),
+        dir("src", [r.files("component", "tsx", 48, 2 * KB, 40 * KB, 60), r.files("style", "css", 12, KB, 30 * KB, 60)].concat()),
+        dir("dist", r.files("bundle", "js", 6, 300 * KB, 3 * MB, 10)),
+    ]), ATTR_GIT);
+
+    let game = with_attrs(dir("game", vec![
+        dir(".git", vec![dir("lfs", vec![dir("objects", r.files("obj", "bin", 40, 10 * MB, 120 * MB, 400))])]),
+        dir("Assets", vec![
+            dir("Textures", r.files("tex", "png", 180, 500 * KB, 16 * MB, 400)),
+            dir("Audio", r.files("sfx", "wav", 90, 300 * KB, 30 * MB, 400)),
+            dir("Models", r.files("mesh", "fbx", 45, MB, 60 * MB, 400)),
+        ]),
+        dir("Library", vec![dir("ShaderCache", vec![r.small_files(6400, 14)]), dir("Artifacts", r.files("artifact", "bin", 120, 500 * KB, 12 * MB, 14))]),
+        dir("Builds", vec![
+            dir("v0.9", vec![file("game.pak", build, DEMO_NOW - 240 * DAY)]),
+            dir("v1.0", vec![file("game.pak", build, DEMO_NOW - 120 * DAY), r.one("game.exe", 30 * MB, 40 * MB, 120)]),
+        ]),
+        with_attrs(r.one("dev-vm.vhdx", 20 * GB, 24 * GB, 5), ATTR_SPARSE),
+    ]), ATTR_GIT);
+
+    // A WSL-style checkout: names that differ only in case need the folder flag
+    let kernel = with_attrs(dir("linux", vec![
+        dir(".git", vec![dir("objects", vec![dir("pack", r.files("pack", "pack", 2, 900 * MB, 1200 * MB, 30))])]),
+        dir("include", vec![dir("uapi", vec![dir("netfilter", vec![
+            r.one("xt_DSCP.h", 900, 1100, 900),
+            r.one("xt_dscp.h", 700, 900, 900),
+            r.one("xt_MARK.h", 300, 500, 900),
+            r.one("xt_mark.h", 400, 600, 900),
+        ])])]),
+        dir("drivers", vec![r.small_files(14_000, 900)]),
+        r.one("Makefile", 60 * KB, 80 * KB, 30),
+    ]), ATTR_GIT | ATTR_CASE_SENSITIVE);
+
+    let archive = dir("Archive", vec![
+        with_attrs(dir("Backups 2019", r.files("backup", "zip", 12, 400 * MB, 3 * GB, 2400)), ATTR_COMPRESSED),
+        dir("Old Photos", r.files("scan", "tif", 80, 10 * MB, 40 * MB, 3600)),
+        with_attrs(file("Projects (shortcut)", 0, DEMO_NOW - 1000 * DAY), ATTR_LINK),
+    ]);
+
+    let games = dir("Games", vec![dir("Steam", vec![dir("steamapps", vec![dir("common", vec![
+        dir("Space Sim", r.files("content", "pak", 14, 2 * GB, 5 * GB, 150)),
+        dir("Puzzle Quest", [r.files("data", "pak", 4, 300 * MB, 900 * MB, 700), vec![r.small_files(800, 700)]].concat()),
+    ])])])]);
+
+    let windows = with_attrs(dir("Windows", vec![
+        dir("System32", [r.files("lib", "dll", 300, 100 * KB, 30 * MB, 200), vec![r.small_files(9000, 200)]].concat()),
+        dir("WinSxS", vec![r.small_files(26_000, 400)]),
+        dir("Installer", r.files("patch", "msp", 40, 20 * MB, 300 * MB, 800)),
+    ]), ATTR_SYSTEM);
+
+    let mut root = dir(DEMO_ROOT, vec![
+        dir("Users", vec![alex, sam]),
+        dir("Projects", vec![website, game, kernel]),
+        archive,
+        games,
+        windows,
+        with_attrs(r.one("pagefile.sys", 8 * GB, 9 * GB, 1), ATTR_SYSTEM),
+        with_attrs(r.one("hiberfil.sys", 6 * GB, 7 * GB, 3), ATTR_SYSTEM),
+    ]);
+    finish(&mut root, Path::new(""), false);
+    root
+}
+
+/// Size on disk of a demo file: 4 KB clusters, compressed files (or files in a
+/// compressed folder) at about half, sparse ones mostly unallocated, offline ones none.
+fn demo_allocated(node: &FileNode, compressed: bool) -> u64 {
+    let stored = if node.attrs & ATTR_OFFLINE != 0 {
+        0
+    } else if compressed {
+        node.size / 100 * 45
+    } else if node.attrs & ATTR_SPARSE != 0 {
+        node.size / 8
+    } else {
+        node.size
+    };
+    // `<Small Files>` stands for many files, each wasting half a cluster on average
+    stored.div_ceil(DEMO_CLUSTER) * DEMO_CLUSTER + node.file_count.saturating_sub(1) * DEMO_CLUSTER / 2
+}
+
+/// Fill in paths below `parent` and total up folders like the scanner does.
+/// `compressed`: the parent is NTFS-compressed, so new files in it are too.
+fn finish(node: &mut FileNode, parent: &Path, compressed: bool) {
+    let compressed = compressed || node.attrs & ATTR_COMPRESSED != 0;
+    // Aggregates keep their empty path
+    if !node.name.starts_with('<') {
+        node.path = parent.join(&node.name);
+    }
+    if !node.is_dir {
+        node.allocated = demo_allocated(node, compressed);
+        return;
+    }
+    let path = node.path.clone();
+    for child in &mut node.children {
+        finish(child, &path, compressed);
+    }
+    node.size = node.children.iter().map(|c| c.size).sum();
+    node.allocated = node.children.iter().map(|c| c.allocated).sum();
+    node.file_count = node.children.iter().map(|c| c.file_count).sum();
+    node.modified = node.children.iter().map(|c| c.modified).max().unwrap_or(0);
+    node.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+}
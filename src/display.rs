@@ -0,0 +1,76 @@
+use eframe::egui;
+
+/// Height of the strip at the top of a window that must stay on screen so it can be dragged.
+const TITLE_BAR_PX: f32 = 40.0;
+
+/// Work areas (monitor minus taskbar) of the connected monitors in physical pixels,
+/// primary first. None where monitors can't be enumerated.
+#[cfg(target_os = "windows")]
+pub fn monitor_work_areas() -> Option<Vec<egui::Rect>> {
+    use windows_sys::Win32::Foundation::{BOOL, LPARAM, RECT};
+    use windows_sys::Win32::Graphics::Gdi::{EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO};
+
+    const MONITORINFOF_PRIMARY: u32 = 1;
+
+    unsafe extern "system" fn collect(monitor: HMONITOR, _: HDC, _: *mut RECT, data: LPARAM) -> BOOL {
+        let areas = &mut *(data as *mut Vec<(bool, egui::Rect)>);
+        let mut info: MONITORINFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if GetMonitorInfoW(monitor, &mut info) != 0 {
+            let r = info.rcWork;
+            areas.push((
+                info.dwFlags & MONITORINFOF_PRIMARY != 0,
+                egui::Rect::from_min_max(
+                    egui::pos2(r.left as f32, r.top as f32),
+                    egui::pos2(r.right as f32, r.bottom as f32),
+                ),
+            ));
+        }
+        1
+    }
+
+    let mut areas: Vec<(bool, egui::Rect)> = Vec::new();
+    let ok = unsafe {
+        EnumDisplayMonitors(
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            Some(collect),
+            &mut areas as *mut Vec<(bool, egui::Rect)> as LPARAM,
+        )
+    };
+    if ok == 0 || areas.is_empty() {
+        return None;
+    }
+    areas.sort_by_key(|(primary, _)| !primary);
+    Some(areas.into_iter().map(|(_, rect)| rect).collect())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn monitor_work_areas() -> Option<Vec<egui::Rect>> {
+    None
+}
+
+/// Move (and if needed shrink) a window rect so it sits on a connected monitor.
+/// A window whose title bar is fully on one monitor is returned unchanged. Otherwise
+/// it is clamped into the monitor it overlaps most, or the primary if it is off-screen.
+pub fn fit_to_monitors(window: egui::Rect, areas: &[egui::Rect]) -> egui::Rect {
+    let title_bar = egui::Rect::from_min_size(window.min, egui::vec2(window.width(), TITLE_BAR_PX));
+    if areas.is_empty() || areas.iter().any(|a| a.contains_rect(title_bar)) {
+        return window;
+    }
+    let overlap = |a: &egui::Rect| {
+        let i = a.intersect(window);
+        if i.is_positive() { i.area() } else { 0.0 }
+    };
+    let area = areas.iter()
+        .copied()
+        .max_by(|a, b| overlap(a).total_cmp(&overlap(b)))
+        .filter(|a| overlap(a) > 0.0)
+        .unwrap_or(areas[0]);
+    let size = window.size().min(area.size());
+    let min = egui::pos2(
+        window.min.x.clamp(area.min.x, area.max.x - size.x),
+        window.min.y.clamp(area.min.y, area.max.y - size.y),
+    );
+    egui::Rect::from_min_size(min, size)
+}
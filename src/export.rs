@@ -0,0 +1,236 @@
+use crate::app::{CellMetrics, format_count, format_size};
+use crate::scanner::FileNode;
+use crate::treemap;
+use eframe::egui;
+use std::fmt::Write;
+
+/// Children drawn per folder in diagrams; the rest are folded into one "N more" node.
+const DIAGRAM_MAX_CHILDREN: usize = 12;
+
+/// Diagram node label: name plus size and share of the diagram root.
+fn diagram_label(node: &FileNode, root_size: u64) -> (String, String) {
+    let pct = if root_size > 0 { node.size as f64 * 100.0 / root_size as f64 } else { 0.0 };
+    (node.name.clone(), format!("{} ({:.1}%)", format_size(node.size), pct))
+}
+
+/// Walk the top `levels` of the tree, calling `emit(id, parent_id, name, detail, is_dir)`
+/// for every node shown. Ids are sequential from 0 (the root).
+fn walk_diagram(root: &FileNode, levels: usize, mut emit: impl FnMut(usize, Option<usize>, &str, &str, bool)) {
+    let mut next_id = 0;
+    let mut stack: Vec<(&FileNode, Option<usize>, usize)> = vec![(root, None, 0)];
+    while let Some((node, parent, depth)) = stack.pop() {
+        let id = next_id;
+        next_id += 1;
+        let (name, detail) = diagram_label(node, root.size);
+        emit(id, parent, &name, &detail, node.is_dir);
+        if depth >= levels {
+            continue;
+        }
+        // Largest first; pushed in reverse so the stack pops them in order
+        let mut children: Vec<&FileNode> = node.children.iter().collect();
+        children.sort_by_key(|c| std::cmp::Reverse(c.size));
+        if children.len() > DIAGRAM_MAX_CHILDREN {
+            let rest = &children[DIAGRAM_MAX_CHILDREN..];
+            let size: u64 = rest.iter().map(|c| c.size).sum();
+            let more_id = next_id;
+            next_id += 1;
+            let pct = if root.size > 0 { size as f64 * 100.0 / root.size as f64 } else { 0.0 };
+            emit(more_id, Some(id), &format!("{} more", rest.len()), &format!("{} ({:.1}%)", format_size(size), pct), false);
+            children.truncate(DIAGRAM_MAX_CHILDREN);
+        }
+        for child in children.into_iter().rev() {
+            stack.push((child, Some(id), depth + 1));
+        }
+    }
+}
+
+/// Graphviz dot of the top `levels` below `root`, left to right, folders as boxes.
+pub fn tree_to_dot(root: &FileNode, levels: usize) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = String::from("digraph SpaceView {\n  rankdir=LR;\n  node [fontname=\"Segoe UI\", fontsize=10];\n");
+    walk_diagram(root, levels, |id, parent, name, detail, is_dir| {
+        let shape = if is_dir { "box" } else { "note" };
+        let _ = writeln!(out, "  n{} [shape={}, label=\"{}\\n{}\"];", id, shape, escape(name), escape(detail));
+        if let Some(parent) = parent {
+            let _ = writeln!(out, "  n{} -> n{};", parent, id);
+        }
+    });
+    out += "}\n";
+    out
+}
+
+/// Mermaid flowchart of the top `levels` below `root`.
+pub fn tree_to_mermaid(root: &FileNode, levels: usize) -> String {
+    // Mermaid has no backslash escapes; quotes and markup use entity codes
+    let escape = |s: &str| s.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;");
+    let mut out = String::from("graph LR\n");
+    walk_diagram(root, levels, |id, parent, name, detail, is_dir| {
+        let (open, close) = if is_dir { ("[", "]") } else { ("(", ")") };
+        let node = format!("n{}{}\"{}<br/>{}\"{}", id, open, escape(name), escape(detail), close);
+        match parent {
+            Some(parent) => {
+                let _ = writeln!(out, "  n{} --> {}", parent, node);
+            }
+            None => {
+                let _ = writeln!(out, "  {}", node);
+            }
+        }
+    });
+    out
+}
+
+// ===================== SVG =====================
+
+/// Which part of a block a color is for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvgPart {
+    /// Folder header strip
+    Header,
+    /// Folder background behind its children
+    Body,
+    /// File, empty folder, or a folder drawn without children (depth limit)
+    Leaf,
+}
+
+/// Treemap as SVG: one `<rect>` (+ label when it fits) per block, down to `max_depth`
+/// levels, laid out exactly like the screen (squarified, same cell metrics).
+/// `colors(node, depth, part)` returns (fill, text) so the export matches the current colors.
+pub fn treemap_to_svg(
+    root: &FileNode,
+    size: egui::Vec2,
+    max_depth: usize,
+    metrics: &CellMetrics,
+    colors: &dyn Fn(&FileNode, usize, SvgPart) -> (egui::Color32, egui::Color32),
+) -> String {
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"Segoe UI, sans-serif\">\n",
+        w = size.x,
+        h = size.y,
+    );
+    let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, size);
+    svg_node(&mut out, root, rect, 0, max_depth, metrics, colors);
+    out += "</svg>\n";
+    out
+}
+
+fn svg_node(
+    out: &mut String,
+    node: &FileNode,
+    rect: egui::Rect,
+    depth: usize,
+    max_depth: usize,
+    metrics: &CellMetrics,
+    colors: &dyn Fn(&FileNode, usize, SvgPart) -> (egui::Color32, egui::Color32),
+) {
+    let _ = writeln!(out, "<g><title>{} ({})</title>", xml_escape(&node.path.to_string_lossy()), format_size(node.size));
+    if node.is_dir && !node.children.is_empty() && depth < max_depth {
+        let inner = rect.shrink(metrics.border_px);
+        let (body, _) = colors(node, depth, SvgPart::Body);
+        svg_rect(out, inner, body, true);
+        let content = metrics.content_rect(inner);
+        if content.width() > metrics.min_px && content.height() > metrics.min_px {
+            let sizes: Vec<f64> = node.children.iter().map(|c| c.size as f64).collect();
+            for tr in treemap::layout(content.min.x, content.min.y, content.width(), content.height(), &sizes) {
+                let child_rect = egui::Rect::from_min_size(egui::pos2(tr.x, tr.y), egui::vec2(tr.w, tr.h));
+                if !metrics.too_small(child_rect) {
+                    svg_node(out, &node.children[tr.index], child_rect, depth + 1, max_depth, metrics, colors);
+                }
+            }
+        }
+        // Header last so it sits on top, as on screen
+        let header = egui::Rect::from_min_size(inner.min, egui::vec2(inner.width(), metrics.header_px.min(inner.height())));
+        let (fill, text) = colors(node, depth, SvgPart::Header);
+        svg_rect(out, header, fill, false);
+        let font = (header.height() - 4.0).clamp(0.0, 12.0);
+        svg_label(out, header, &format!("{}  {}", node.name, format_size(node.size)), font, text, header.height() * 0.5 + font * 0.35);
+    } else {
+        let inner = rect.shrink(1.0);
+        let (fill, text) = colors(node, depth, SvgPart::Leaf);
+        svg_rect(out, inner, fill, false);
+        let font = 11.0f32.min(inner.height() - 3.0);
+        svg_label(out, inner, &node.name, font, text, font + 1.0);
+        if inner.height() > font * 2.0 + 6.0 {
+            svg_label(out, inner, &format_size(node.size), 9.0, text, font + 12.0);
+        }
+    }
+    out.push_str("</g>\n");
+}
+
+fn svg_rect(out: &mut String, rect: egui::Rect, fill: egui::Color32, border: bool) {
+    let stroke = if border { " stroke=\"#1e1e1e\" stroke-width=\"1\"" } else { "" };
+    let _ = writeln!(
+        out,
+        "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#{:02x}{:02x}{:02x}\"{}/>",
+        rect.min.x, rect.min.y, rect.width(), rect.height(), fill.r(), fill.g(), fill.b(), stroke,
+    );
+}
+
+/// Left-aligned label at `baseline` below the rect top, cut to roughly fit the width.
+/// SVG viewers measure text themselves, so the cut uses an average glyph width.
+fn svg_label(out: &mut String, rect: egui::Rect, text: &str, font: f32, color: egui::Color32, baseline: f32) {
+    if font < 6.0 || rect.width() < 24.0 || baseline > rect.height() {
+        return;
+    }
+    let max_chars = ((rect.width() - 6.0) / (font * 0.55)) as usize;
+    let label: String = if text.chars().count() > max_chars {
+        text.chars().take(max_chars.saturating_sub(1)).chain(std::iter::once('…')).collect()
+    } else {
+        text.to_string()
+    };
+    let _ = writeln!(
+        out,
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.0}\" fill=\"#{:02x}{:02x}{:02x}\">{}</text>",
+        rect.min.x + 3.0, rect.min.y + baseline, font, color.r(), color.g(), color.b(), xml_escape(&label),
+    );
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// ===================== Print =====================
+
+/// Printable page: the treemap on a landscape page, then the `top` largest folders
+/// (at any depth) as a table whose header repeats on every page. Calls `print()` on load.
+pub fn print_html(root: &FileNode, svg: &str, top: usize) -> String {
+    let mut folders: Vec<&FileNode> = Vec::new();
+    let mut stack: Vec<&FileNode> = root.children.iter().filter(|c| c.is_dir).collect();
+    while let Some(node) = stack.pop() {
+        folders.push(node);
+        stack.extend(node.children.iter().filter(|c| c.is_dir));
+    }
+    folders.sort_by_key(|f| std::cmp::Reverse(f.size));
+    folders.truncate(top);
+
+    let title = xml_escape(&root.path.to_string_lossy());
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>SpaceView - {title}</title>\n<style>\n\
+         @page {{ size: landscape; margin: 12mm; }}\n\
+         body {{ font-family: 'Segoe UI', sans-serif; font-size: 10pt; margin: 0; }}\n\
+         h1 {{ font-size: 14pt; margin: 0 0 4mm; }}\n\
+         svg {{ width: 100%; height: auto; max-height: 160mm; }}\n\
+         table {{ border-collapse: collapse; width: 100%; page-break-before: always; }}\n\
+         thead {{ display: table-header-group; }}\n\
+         tr {{ page-break-inside: avoid; }}\n\
+         th, td {{ border-bottom: 1px solid #ccc; padding: 2px 6px; text-align: left; }}\n\
+         td.num, th.num {{ text-align: right; white-space: nowrap; }}\n\
+         </style></head>\n<body onload=\"window.print()\">\n<h1>{title} &mdash; {size}, {files} files</h1>\n",
+        size = format_size(root.size),
+        files = format_count(root.file_count),
+    );
+    // Page 1: the treemap, scaled to the page width by CSS
+    out += svg;
+    out += "<table>\n<thead><tr><th>Folder</th><th class=\"num\">Size</th><th class=\"num\">Files</th><th class=\"num\">% of total</th></tr></thead>\n<tbody>\n";
+    for folder in folders {
+        let rel = folder.path.strip_prefix(&root.path).unwrap_or(&folder.path);
+        let pct = if root.size > 0 { folder.size as f64 * 100.0 / root.size as f64 } else { 0.0 };
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}%</td></tr>",
+            xml_escape(&rel.to_string_lossy()), format_size(folder.size), format_count(folder.file_count), pct,
+        );
+    }
+    out += "</tbody></table>\n</body></html>\n";
+    out
+}
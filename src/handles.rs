@@ -0,0 +1,142 @@
+use std::path::Path;
+#[cfg(target_os = "windows")]
+use std::path::PathBuf;
+
+/// Files under a folder handed to the Restart Manager at most. Large trees are
+/// checked partially; the delete itself still reports anything missed.
+pub const MAX_FILES_CHECKED: usize = 10_000;
+
+/// What the in-use check found.
+pub struct InUse {
+    /// Executable names of processes holding files open, sorted and deduplicated
+    pub names: Vec<String>,
+    /// The folder holds more than MAX_FILES_CHECKED files and only that many were checked
+    pub partial: bool,
+}
+
+/// Processes holding files open under `path` (file or folder). No names if nothing
+/// is in use or the check isn't supported.
+pub fn processes_using(path: &Path) -> InUse {
+    let own = std::process::id();
+    let (pids, partial) = pids_using(path);
+    let pids: Vec<sysinfo::Pid> = pids
+        .into_iter()
+        .filter(|&pid| pid != own)
+        .map(sysinfo::Pid::from_u32)
+        .collect();
+    if pids.is_empty() {
+        return InUse { names: Vec::new(), partial };
+    }
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&pids), true);
+    let mut names: Vec<String> = pids.iter()
+        .map(|pid| match sys.process(*pid) {
+            Some(p) => p.name().to_string_lossy().to_string(),
+            None => format!("PID {}", pid),
+        })
+        .collect();
+    names.sort_by_key(|n| n.to_lowercase());
+    names.dedup();
+    InUse { names, partial }
+}
+
+/// Ask the Restart Manager which processes have any of the files open. True as
+/// well when only the first MAX_FILES_CHECKED files were asked about.
+#[cfg(target_os = "windows")]
+fn pids_using(path: &Path) -> (Vec<u32>, bool) {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS};
+    use windows_sys::Win32::System::RestartManager::{
+        CCH_RM_SESSION_KEY, RM_PROCESS_INFO, RmEndSession, RmGetList, RmRegisterResources, RmStartSession,
+    };
+
+    let mut files = Vec::new();
+    let partial = !collect_files(path, &mut files);
+    if files.is_empty() {
+        return (Vec::new(), partial);
+    }
+    let wide: Vec<Vec<u16>> = files.iter()
+        .map(|f| f.as_os_str().encode_wide().chain(Some(0)).collect())
+        .collect();
+    let ptrs: Vec<*const u16> = wide.iter().map(|w| w.as_ptr()).collect();
+
+    let mut pids = Vec::new();
+    unsafe {
+        let mut session = 0u32;
+        let mut key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+        if RmStartSession(&mut session, 0, key.as_mut_ptr()) != ERROR_SUCCESS {
+            return (pids, partial);
+        }
+        let registered = RmRegisterResources(
+            session, ptrs.len() as u32, ptrs.as_ptr(), 0, std::ptr::null(), 0, std::ptr::null(),
+        );
+        if registered == ERROR_SUCCESS {
+            let mut infos: Vec<RM_PROCESS_INFO> = Vec::new();
+            // The list can grow between the sizing call and the real one
+            for _ in 0..3 {
+                let mut needed = 0u32;
+                let mut count = infos.len() as u32;
+                let mut reasons = 0u32;
+                let err = RmGetList(session, &mut needed, &mut count, infos.as_mut_ptr(), &mut reasons);
+                if err == ERROR_MORE_DATA {
+                    infos = vec![std::mem::zeroed(); needed as usize];
+                    continue;
+                }
+                if err == ERROR_SUCCESS {
+                    pids.extend(infos[..count as usize].iter().map(|i| i.Process.dwProcessId));
+                }
+                break;
+            }
+        }
+        RmEndSession(session);
+    }
+    (pids, partial)
+}
+
+/// The files under `path` in their `\\?\` form, so deep ones aren't missed. Links and
+/// junctions aren't followed, which keeps the walk inside the folder and out of
+/// loops. False if MAX_FILES_CHECKED cut the list short.
+#[cfg(target_os = "windows")]
+fn collect_files(path: &Path, out: &mut Vec<PathBuf>) -> bool {
+    use crate::paths::long_path;
+
+    if out.len() >= MAX_FILES_CHECKED {
+        return false;
+    }
+    if !std::fs::symlink_metadata(long_path(path)).is_ok_and(|m| m.is_dir()) {
+        out.push(long_path(path));
+        return true;
+    }
+    let Ok(rd) = std::fs::read_dir(long_path(path)) else { return true };
+    for entry in rd.flatten() {
+        if entry.file_type().is_ok_and(|t| t.is_symlink()) {
+            continue;
+        }
+        if !collect_files(&path.join(entry.file_name()), out) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Scan every process's open file descriptors for targets under `path`.
+#[cfg(target_os = "linux")]
+fn pids_using(path: &Path) -> (Vec<u32>, bool) {
+    let Ok(procs) = std::fs::read_dir("/proc") else { return (Vec::new(), false) };
+    let mut pids = Vec::new();
+    for entry in procs.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else { continue };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else { continue };
+        let in_use = fds.flatten()
+            .any(|fd| std::fs::read_link(fd.path()).is_ok_and(|target| target.starts_with(path)));
+        if in_use {
+            pids.push(pid);
+        }
+    }
+    (pids, false)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn pids_using(_path: &Path) -> (Vec<u32>, bool) {
+    (Vec::new(), false)
+}
@@ -0,0 +1,105 @@
+use crate::paths::long_path;
+use crate::scanner::ScanProgress;
+use crate::throttle::RateLimiter;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+/// Fast in-process hash of the first 4KB. Only comparable within one run.
+/// Reads are paced by `limit` (bytes per second).
+pub fn hash_file_partial(path: &str, limit: &RateLimiter) -> std::io::Result<u64> {
+    use std::hash::{Hash, Hasher};
+    let mut file = std::fs::File::open(long_path(Path::new(path)))?;
+    let mut buf = [0u8; 4096];
+    let n = file.read(&mut buf)?;
+    limit.take(n as u64);
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    buf[..n].hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Fast in-process hash of the whole file. Only comparable within one run.
+pub fn hash_file_full(path: &str, limit: &RateLimiter) -> std::io::Result<u64> {
+    use std::hash::{Hash, Hasher};
+    let mut file = std::fs::File::open(long_path(Path::new(path)))?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 { break; }
+        limit.take(n as u64);
+        buf[..n].hash(&mut hasher);
+    }
+    Ok(hasher.finish())
+}
+
+/// SHA-256 of the whole file as lowercase hex. Stable across runs and versions,
+/// so it can be persisted (integrity baselines).
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(long_path(path))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 { break; }
+        hasher.update(&buf[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Checksums offered for a single file (context menu > Compute Hash).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Checksum {
+    Sha256,
+    Blake3,
+}
+
+pub const CHECKSUMS: [Checksum; 2] = [Checksum::Sha256, Checksum::Blake3];
+
+impl Checksum {
+    pub fn label(self) -> &'static str {
+        match self {
+            Checksum::Sha256 => "SHA-256",
+            Checksum::Blake3 => "BLAKE3",
+        }
+    }
+}
+
+/// Checksum of the whole file as lowercase hex, counting bytes read into `progress`.
+/// Ok(None) if cancelled.
+pub fn checksum_file(path: &Path, kind: Checksum, progress: &ScanProgress) -> std::io::Result<Option<String>> {
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(long_path(path))?;
+    let mut sha = Sha256::new();
+    let mut blake = blake3::Hasher::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        if progress.cancel.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let n = file.read(&mut buf)?;
+        if n == 0 { break; }
+        match kind {
+            Checksum::Sha256 => sha.update(&buf[..n]),
+            Checksum::Blake3 => {
+                blake.update(&buf[..n]);
+            }
+        }
+        progress.bytes_scanned.fetch_add(n as u64, Ordering::Relaxed);
+    }
+    Ok(Some(match kind {
+        Checksum::Sha256 => to_hex(&sha.finalize()),
+        Checksum::Blake3 => blake.finalize().to_hex().to_string(),
+    }))
+}
+
+/// SHA-256 of an in-memory buffer as lowercase hex.
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    to_hex(&Sha256::digest(data))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,109 @@
+use crate::app::data_dir;
+use crate::hashing::sha256_hex;
+use crate::scanner::FileNode;
+use crate::snapshot::save_snapshot;
+use std::path::{Path, PathBuf};
+
+// Auto-saved snapshots live in %APPDATA%\SpaceView\history\<root key>\<created>.svsnap,
+// one folder per scan root so retention can count per root. The root's real path is
+// in each snapshot's header.
+
+/// Snapshots kept per root unless the user picks another number.
+pub const DEFAULT_SNAPSHOT_KEEP: usize = 10;
+
+pub fn history_dir() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("history"))
+}
+
+/// Folder name for one root: a short hash, since paths don't make valid names.
+fn root_key(root: &Path) -> String {
+    let text = root.to_string_lossy();
+    let text = text.trim_end_matches(['/', '\\']);
+    // Windows paths are case-insensitive; C:\Data and c:\data share a history
+    let text = if cfg!(windows) { text.to_lowercase() } else { text.to_string() };
+    sha256_hex(text.as_bytes())[..16].to_string()
+}
+
+/// History folder of one scan root. It may not exist yet.
+pub fn root_history(root: &Path) -> Option<PathBuf> {
+    history_dir().map(|h| h.join(root_key(root)))
+}
+
+/// The newest history snapshot of `root` saved at or before `before` (seconds since
+/// epoch), by the time in its name.
+pub fn snapshot_before(root: &Path, before: u64) -> Option<PathBuf> {
+    let dir = root_history(root)?;
+    snapshots_in(&dir).into_iter().rev().find(|p| {
+        p.file_stem().and_then(|s| s.to_str()).and_then(|s| s.parse::<u64>().ok()).is_some_and(|t| t <= before)
+    })
+}
+
+/// Save a completed scan into the history, then prune that root to its `keep`
+/// newest snapshots.
+pub fn save_to_history(root: &FileNode, keep: usize) -> std::io::Result<()> {
+    let Some(dir) = root_history(&root.path) else { return Ok(()) };
+    std::fs::create_dir_all(&dir)?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    // Zero-padded so names sort by age
+    save_snapshot(root, true, &dir.join(format!("{:012}.svsnap", now)))?;
+    prune_dir(&dir, keep);
+    Ok(())
+}
+
+/// Snapshot files in one root's folder, oldest first.
+fn snapshots_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|p| p.extension().is_some_and(|e| e == "svsnap"));
+    files.sort();
+    files
+}
+
+/// Remove all but the `keep` newest snapshots. Returns (files removed, bytes freed).
+fn prune_dir(dir: &Path, keep: usize) -> (usize, u64) {
+    let files = snapshots_in(dir);
+    let excess = files.len().saturating_sub(keep.max(1));
+    let (mut removed, mut freed) = (0, 0);
+    for path in &files[..excess] {
+        let size = std::fs::metadata(path).map_or(0, |m| m.len());
+        if std::fs::remove_file(path).is_ok() {
+            removed += 1;
+            freed += size;
+        }
+    }
+    (removed, freed)
+}
+
+/// Apply a (lowered) retention count to every root at once.
+pub fn prune_all(keep: usize) -> (usize, u64) {
+    let Some(history) = history_dir() else { return (0, 0) };
+    let Ok(entries) = std::fs::read_dir(history) else { return (0, 0) };
+    entries.flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| prune_dir(&e.path(), keep))
+        .fold((0, 0), |(n, b), (dn, db)| (n + dn, b + db))
+}
+
+/// Delete every auto-saved snapshot.
+pub fn clear_history() -> std::io::Result<()> {
+    match history_dir() {
+        Some(history) if history.exists() => std::fs::remove_dir_all(history),
+        _ => Ok(()),
+    }
+}
+
+/// Files and bytes below `path` (a single file counts as one).
+pub fn disk_usage(path: &Path) -> (u64, u64) {
+    let Ok(meta) = std::fs::symlink_metadata(path) else { return (0, 0) };
+    if !meta.is_dir() {
+        return (1, meta.len());
+    }
+    let Ok(entries) = std::fs::read_dir(path) else { return (0, 0) };
+    entries.flatten()
+        .map(|e| disk_usage(&e.path()))
+        .fold((0, 0), |(f, b), (df, db)| (f + df, b + db))
+}
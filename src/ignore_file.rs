@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+
+// Per-folder ignore files: a `.spaceviewignore` in a scanned folder lists, in
+// gitignore syntax, what below it every scan leaves out, so a project can keep its
+// build outputs out of everyone's totals. Rules apply to the folder holding the file
+// and everything under it; a deeper file's rules win over a shallower one's.
+
+/// Name of the ignore file looked for in each scanned folder.
+pub const IGNORE_FILE: &str = ".spaceviewignore";
+
+/// One pattern line.
+#[derive(Clone, Debug)]
+struct Rule {
+    /// Lowercased on Windows, where names don't differ by case
+    pattern: Vec<u8>,
+    /// `!pattern`: brings back what an earlier rule ignored
+    negated: bool,
+    /// `pattern/`: folders only
+    dir_only: bool,
+    /// A `/` at the start or in the middle: matched against the path below the
+    /// ignore file's folder. Otherwise against the name, at any depth.
+    anchored: bool,
+}
+
+/// The rules of one ignore file.
+#[derive(Clone, Debug)]
+pub struct IgnoreRules {
+    /// Folder holding the ignore file
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    /// Parse ignore file text for the folder `base`. Blank lines and `#` comments
+    /// are skipped; `\#` and `\!` start a pattern with those characters.
+    pub fn parse(base: &Path, text: &str) -> Self {
+        let rules = text.lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').filter(|r| r.starts_with(['#', '!'])).unwrap_or(line)),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let line = line.strip_prefix('/').unwrap_or(line);
+                if line.is_empty() {
+                    return None;
+                }
+                let pattern = if cfg!(windows) { line.to_lowercase() } else { line.to_string() };
+                Some(Rule { pattern: pattern.into_bytes(), negated, dir_only, anchored })
+            })
+            .collect();
+        Self { base: base.to_path_buf(), rules }
+    }
+
+    /// The ignore file in `dir`, if it has one with any rules.
+    pub fn load(dir: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(dir.join(IGNORE_FILE)).ok()?;
+        Some(Self::parse(dir, &text)).filter(|r| !r.rules.is_empty())
+    }
+
+    /// What these rules say about `path`: ignored, brought back by a `!` rule, or
+    /// nothing (None) if no rule matches. The last matching rule decides.
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        let mut rel = relative.components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if cfg!(windows) {
+            rel = rel.to_lowercase();
+        }
+        let name = rel.rsplit('/').next().unwrap_or(&rel);
+        self.rules.iter().rev()
+            .find(|rule| {
+                (is_dir || !rule.dir_only)
+                    && glob_match(&rule.pattern, if rule.anchored { rel.as_bytes() } else { name.as_bytes() })
+            })
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// Whether `path` is ignored by the ignore files in effect, outermost first. The
+/// deepest file with a matching rule decides.
+pub fn is_ignored(rules: &[&IgnoreRules], path: &Path, is_dir: bool) -> bool {
+    rules.iter().rev().find_map(|r| r.decide(path, is_dir)).unwrap_or(false)
+}
+
+/// Ignore files in the folders above `root`, outermost first, so scanning a
+/// subfolder leaves out the same things as scanning its parent.
+pub fn ancestor_rules(root: &Path) -> Vec<IgnoreRules> {
+    let mut rules: Vec<IgnoreRules> = root.ancestors().skip(1).filter_map(IgnoreRules::load).collect();
+    rules.reverse();
+    rules
+}
+
+/// gitignore globs: `*` and `?` stop at `/`, `[a-z]` / `[!a-z]` are classes, `\`
+/// escapes, a `**/` folder matches any number of folders and a trailing `**` everything
+/// inside. Iterative with one resume point per kind of star, so a pattern from an untrusted
+/// ignore file can't make a scan backtrack exponentially.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Where to resume when the last `*` has to take one more character, and when
+    // the last `**/` has to take one more folder: (pattern after it, text position)
+    let mut star: Option<(usize, usize)> = None;
+    let mut dirs: Option<(usize, usize)> = None;
+    loop {
+        if p < pattern.len() {
+            let (token, next) = glob_token(pattern, p);
+            let matched = match token {
+                GlobToken::Rest => return true,
+                GlobToken::Star => {
+                    star = Some((next, t));
+                    p = next;
+                    continue;
+                }
+                GlobToken::AnyDirs => {
+                    dirs = Some((next, t));
+                    star = None;
+                    p = next;
+                    continue;
+                }
+                GlobToken::Byte(b) => text.get(t) == Some(&b),
+                GlobToken::Any => text.get(t).is_some_and(|&c| c != b'/'),
+                GlobToken::Class(class) => text.get(t).is_some_and(|&c| c != b'/' && class_match(class, c)),
+            };
+            if matched {
+                p = next;
+                t += 1;
+                continue;
+            }
+        } else if t == text.len() {
+            return true;
+        }
+        // A `*` never takes a `/`; past one, only a `**/` can move on
+        if let Some((sp, st)) = star.filter(|&(_, st)| text.get(st).is_some_and(|&c| c != b'/')) {
+            star = Some((sp, st + 1));
+            (p, t) = (sp, st + 1);
+        } else if let Some((dp, dt)) = dirs {
+            let Some(slash) = text[dt..].iter().position(|&c| c == b'/') else { return false };
+            dirs = Some((dp, dt + slash + 1));
+            star = None;
+            (p, t) = (dp, dt + slash + 1);
+        } else {
+            return false;
+        }
+    }
+}
+
+enum GlobToken<'a> {
+    Byte(u8),
+    /// `?`
+    Any,
+    /// The inside of `[...]`
+    Class(&'a [u8]),
+    /// `*`
+    Star,
+    /// `**/`
+    AnyDirs,
+    /// `**` at the end: everything
+    Rest,
+}
+
+/// The pattern element starting at `p`, and where the next one starts.
+fn glob_token(pattern: &[u8], p: usize) -> (GlobToken<'_>, usize) {
+    let rest = &pattern[p..];
+    // As in git, `**/` is only special as a whole folder name; elsewhere it is two `*`
+    if rest.starts_with(b"**/") && (p == 0 || pattern[p - 1] == b'/') {
+        return (GlobToken::AnyDirs, p + 3);
+    }
+    if rest == b"**" {
+        return (GlobToken::Rest, p + 2);
+    }
+    match rest[0] {
+        b'*' => (GlobToken::Star, p + 1),
+        b'?' => (GlobToken::Any, p + 1),
+        // A `]` right after the `[` is part of the class
+        b'[' => match rest.iter().skip(2).position(|&c| c == b']') {
+            Some(i) => (GlobToken::Class(&rest[1..i + 2]), p + i + 3),
+            None => (GlobToken::Byte(b'['), p + 1),
+        },
+        b'\\' if rest.len() > 1 => (GlobToken::Byte(rest[1]), p + 2),
+        c => (GlobToken::Byte(c), p + 1),
+    }
+}
+
+/// Whether `c` is in a `[...]` class: single bytes and `a-z` ranges, `!` or `^` first negates.
+fn class_match(class: &[u8], c: u8) -> bool {
+    let (negated, class) = match class.split_first() {
+        Some((b'!' | b'^', inner)) => (true, inner),
+        _ => (false, class),
+    };
+    let mut hit = false;
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == b'-' {
+            hit |= (class[i]..=class[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            hit |= class[i] == c;
+            i += 1;
+        }
+    }
+    hit != negated
+}
@@ -0,0 +1,245 @@
+use eframe::egui;
+use std::path::Path;
+
+/// Treemap commands that can be bound to keys or mouse buttons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    ZoomIn,
+    ZoomOut,
+    ZoomToRoot,
+    OpenInExplorer,
+    CopyPath,
+    ToggleSelect,
+    ClearSelection,
+    Delete,
+}
+
+pub const ACTIONS: [Action; 8] = [
+    Action::ZoomIn,
+    Action::ZoomOut,
+    Action::ZoomToRoot,
+    Action::OpenInExplorer,
+    Action::CopyPath,
+    Action::ToggleSelect,
+    Action::ClearSelection,
+    Action::Delete,
+];
+
+impl Action {
+    /// Name used in keymap.txt.
+    pub fn id(self) -> &'static str {
+        match self {
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::ZoomToRoot => "zoom_to_root",
+            Action::OpenInExplorer => "open_in_explorer",
+            Action::CopyPath => "copy_path",
+            Action::ToggleSelect => "toggle_select",
+            Action::ClearSelection => "clear_selection",
+            Action::Delete => "delete",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::ZoomIn => "Zoom into folder",
+            Action::ZoomOut => "Zoom out",
+            Action::ZoomToRoot => "Zoom out to root",
+            Action::OpenInExplorer => "Open in Explorer",
+            Action::CopyPath => "Copy path",
+            Action::ToggleSelect => "Add/remove from selection",
+            Action::ClearSelection => "Clear selection",
+            Action::Delete => "Delete to Recycle Bin",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trigger {
+    Key(egui::Key),
+    Click(egui::PointerButton),
+    DoubleClick(egui::PointerButton),
+}
+
+/// A trigger plus the exact modifiers that must be held. Ctrl means Cmd on macOS.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binding {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub trigger: Trigger,
+}
+
+impl Binding {
+    const fn plain(trigger: Trigger) -> Self {
+        Self { ctrl: false, shift: false, alt: false, trigger }
+    }
+
+    const fn ctrl(trigger: Trigger) -> Self {
+        Self { ctrl: true, shift: false, alt: false, trigger }
+    }
+
+    /// Parse "Ctrl+Shift+MiddleClick", "Backspace", "DoubleClick", ...
+    pub fn parse(text: &str) -> Option<Self> {
+        let (mut ctrl, mut shift, mut alt) = (false, false, false);
+        let mut trigger = None;
+        for part in text.split('+').map(str::trim) {
+            let lower = part.to_lowercase();
+            match lower.as_str() {
+                "ctrl" | "cmd" => ctrl = true,
+                "shift" => shift = true,
+                "alt" => alt = true,
+                _ => trigger = Some(parse_mouse(&lower).or_else(|| egui::Key::from_name(part).map(Trigger::Key))?),
+            }
+        }
+        Some(Self { ctrl, shift, alt, trigger: trigger? })
+    }
+
+    /// Inverse of `parse`, for the defaults file and UI hints.
+    pub fn describe(&self) -> String {
+        let mut out = String::new();
+        if self.ctrl {
+            out += "Ctrl+";
+        }
+        if self.shift {
+            out += "Shift+";
+        }
+        if self.alt {
+            out += "Alt+";
+        }
+        out += &match self.trigger {
+            Trigger::Key(key) => key.name().to_string(),
+            Trigger::Click(button) => format!("{}Click", button_name(button)),
+            Trigger::DoubleClick(button) => format!("Double{}Click", button_name(button)),
+        };
+        out
+    }
+
+    fn modifiers_match(&self, m: &egui::Modifiers) -> bool {
+        self.ctrl == m.command && self.shift == m.shift && self.alt == m.alt
+    }
+}
+
+/// "Click", "RightClick", "DoubleMiddleClick", "Mouse4Click" (lowercased).
+fn parse_mouse(lower: &str) -> Option<Trigger> {
+    let rest = lower.strip_suffix("click")?;
+    let (double, rest) = match rest.strip_prefix("double") {
+        Some(r) => (true, r),
+        None => (false, rest),
+    };
+    let button = match rest {
+        "" | "left" => egui::PointerButton::Primary,
+        "right" => egui::PointerButton::Secondary,
+        "middle" => egui::PointerButton::Middle,
+        "mouse4" => egui::PointerButton::Extra1,
+        "mouse5" => egui::PointerButton::Extra2,
+        _ => return None,
+    };
+    Some(if double { Trigger::DoubleClick(button) } else { Trigger::Click(button) })
+}
+
+fn button_name(button: egui::PointerButton) -> &'static str {
+    match button {
+        egui::PointerButton::Primary => "",
+        egui::PointerButton::Secondary => "Right",
+        egui::PointerButton::Middle => "Middle",
+        egui::PointerButton::Extra1 => "Mouse4",
+        egui::PointerButton::Extra2 => "Mouse5",
+    }
+}
+
+/// Keyboard and mouse bindings for treemap actions.
+/// Loaded from `keymap.txt` (`action = Binding, Binding`), falling back to defaults.
+pub struct Keymap {
+    pub bindings: Vec<(Binding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use egui::{Key, PointerButton};
+        Self {
+            bindings: vec![
+                (Binding::plain(Trigger::DoubleClick(PointerButton::Primary)), Action::ZoomIn),
+                (Binding::plain(Trigger::Key(Key::Backspace)), Action::ZoomOut),
+                (Binding::plain(Trigger::Key(Key::Escape)), Action::ZoomOut),
+                (Binding::plain(Trigger::Key(Key::Home)), Action::ZoomToRoot),
+                (Binding::plain(Trigger::DoubleClick(PointerButton::Secondary)), Action::ZoomToRoot),
+                (Binding::plain(Trigger::Click(PointerButton::Middle)), Action::OpenInExplorer),
+                (Binding::ctrl(Trigger::Click(PointerButton::Primary)), Action::ToggleSelect),
+                (Binding::plain(Trigger::Key(Key::Delete)), Action::Delete),
+            ],
+        }
+    }
+}
+
+impl Keymap {
+    /// Load overrides from `path`. An action listed in the file replaces all of its
+    /// default bindings (`none` unbinds it). Writes a commented defaults file if missing.
+    pub fn load(path: Option<&Path>) -> Self {
+        let mut keymap = Self::default();
+        let Some(path) = path else { return keymap };
+        let Ok(content) = std::fs::read_to_string(path) else {
+            keymap.save_defaults(path);
+            return keymap;
+        };
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((id, value)) = line.split_once('=') else { continue };
+            let Some(action) = ACTIONS.into_iter().find(|a| a.id() == id.trim()) else { continue };
+            keymap.bindings.retain(|(_, a)| *a != action);
+            for text in value.split(',').map(str::trim) {
+                if let Some(binding) = Binding::parse(text) {
+                    keymap.bindings.push((binding, action));
+                }
+            }
+        }
+        keymap
+    }
+
+    fn save_defaults(&self, path: &Path) {
+        let mut content = String::from(
+            "# SpaceView treemap bindings. Uncomment a line to change it.\n\
+             # Modifiers: Ctrl, Shift, Alt. Mouse: Click, RightClick, MiddleClick, Mouse4Click, Mouse5Click,\n\
+             # and Double- versions (DoubleClick, DoubleRightClick). Keys use egui names (Home, F2, A).\n\
+             # Separate multiple bindings with commas; `none` unbinds.\n",
+        );
+        for action in ACTIONS {
+            let bound = self.describe(action);
+            content += &format!("# {} = {}\n", action.id(), if bound.is_empty() { "none" } else { &bound });
+        }
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = std::fs::write(path, content);
+    }
+
+    /// All bindings for an action, e.g. "Backspace, Escape". Empty if unbound.
+    pub fn describe(&self, action: Action) -> String {
+        self.bindings.iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(b, _)| b.describe())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Actions triggered this frame. Mouse bindings only fire on `response` (the treemap).
+    pub fn triggered(&self, input: &egui::InputState, response: &egui::Response) -> Vec<Action> {
+        let mut out = Vec::new();
+        for (binding, action) in &self.bindings {
+            if !binding.modifiers_match(&input.modifiers) || out.contains(action) {
+                continue;
+            }
+            let fired = match binding.trigger {
+                Trigger::Key(key) => input.key_pressed(key),
+                Trigger::Click(button) => response.clicked_by(button),
+                Trigger::DoubleClick(button) => response.double_clicked_by(button),
+            };
+            if fired {
+                out.push(*action);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,88 @@
+use crate::app::data_dir;
+use std::io::Write;
+use std::path::PathBuf;
+
+// Everything removed through SpaceView, one line per item in
+// %APPDATA%\SpaceView\deletions.log when the persistent log is on:
+// `<unix secs>\t<bytes>\t<method>\t<path>`. The path goes last so it may hold tabs.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeleteMethod {
+    RecycleBin,
+    Shredded,
+    /// Contents of an app cache folder emptied from the App Caches report
+    CacheCleared,
+}
+
+impl DeleteMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            DeleteMethod::RecycleBin => "Recycle Bin",
+            DeleteMethod::Shredded => "Shredded",
+            DeleteMethod::CacheCleared => "Cache cleared",
+        }
+    }
+
+    fn from_label(s: &str) -> Option<Self> {
+        [DeleteMethod::RecycleBin, DeleteMethod::Shredded, DeleteMethod::CacheCleared]
+            .into_iter()
+            .find(|m| m.label() == s)
+    }
+}
+
+pub struct Deletion {
+    /// Seconds since 1970
+    pub time: u64,
+    pub path: PathBuf,
+    /// Size the scan had for the item (bytes actually freed for cache clears)
+    pub size: u64,
+    pub method: DeleteMethod,
+}
+
+impl Deletion {
+    pub fn now(path: PathBuf, size: u64, method: DeleteMethod) -> Self {
+        let time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self { time, path, size, method }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(4, '\t');
+        let time = parts.next()?.parse().ok()?;
+        let size = parts.next()?.parse().ok()?;
+        let method = DeleteMethod::from_label(parts.next()?)?;
+        let path = PathBuf::from(parts.next()?);
+        Some(Self { time, path, size, method })
+    }
+}
+
+fn ledger_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("deletions.log"))
+}
+
+/// The persistent log, oldest first. Unreadable lines are skipped.
+pub fn load_ledger() -> Vec<Deletion> {
+    let Some(text) = ledger_path().and_then(|p| std::fs::read_to_string(p).ok()) else { return Vec::new() };
+    text.lines().filter_map(Deletion::parse).collect()
+}
+
+pub fn append_ledger(entries: &[Deletion]) -> std::io::Result<()> {
+    let Some(path) = ledger_path() else { return Ok(()) };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    for e in entries {
+        writeln!(file, "{}\t{}\t{}\t{}", e.time, e.size, e.method.label(), e.path.to_string_lossy())?;
+    }
+    Ok(())
+}
+
+pub fn clear_ledger() -> std::io::Result<()> {
+    match ledger_path() {
+        Some(path) if path.exists() => std::fs::remove_file(path),
+        _ => Ok(()),
+    }
+}
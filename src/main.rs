@@ -1,12 +1,48 @@
 #![windows_subsystem = "windows"]
 
+mod agent;
+mod anonymize;
 mod app;
+mod archive;
+mod baseline;
 mod camera;
+mod cli;
+mod clipboard;
+mod compare;
+mod demo;
+mod display;
+mod export;
+mod handles;
+mod hashing;
+mod history;
+mod ignore_file;
+mod keymap;
+mod ledger;
+mod manifest;
+mod memory;
+mod migrate;
+mod node_actions;
+mod notify;
+mod paths;
+mod power;
+mod quota;
+mod remote_fs;
+mod reports;
 mod scanner;
+mod selection;
+mod shred;
+mod snapshot;
+mod throttle;
 mod treemap;
+mod view_state;
+mod watcher;
 mod world_layout;
 
 fn main() -> eframe::Result<()> {
+    if let Some(code) = cli::run() {
+        std::process::exit(code);
+    }
+
     let icon = eframe::icon_data::from_png_bytes(include_bytes!("../assets/icon.png"))
         .expect("Failed to load icon");
 
@@ -24,7 +60,8 @@ fn main() -> eframe::Result<()> {
         vp = vp.with_inner_size([1024.0, 700.0]);
     }
 
-    // Restore saved window position (monitor placement)
+    // Restore saved window position (monitor placement). The app pulls it back on
+    // screen in its first frame if that monitor is gone.
     if let (Some(x), Some(y)) = (prefs.window_x, prefs.window_y) {
         vp = vp.with_position([x, y]);
     }
@@ -0,0 +1,100 @@
+use crate::ledger::DeleteMethod;
+use crate::quota::csv_fields;
+use std::path::PathBuf;
+
+// Dry runs of the bulk removals (all files of a type, app caches, extra duplicate
+// copies): the exact list of what would go, reviewed in the app and either carried
+// out or saved as CSV for change-control approval. An approved CSV can be loaded
+// back and run as is. One row per item: `action,path,bytes,reason`, where action is
+// `recycle` (send to the Recycle Bin) or `clear-contents` (empty a cache folder).
+
+/// One item a bulk removal would take.
+#[derive(Clone, Debug)]
+pub struct PlannedRemoval {
+    pub path: PathBuf,
+    /// Size the scan had for the item. A file to recycle must still have it when the
+    /// manifest runs, so an approved list never takes a file that changed since. 0 (a
+    /// cloud placeholder or a further hard link, as the scan counts them) isn't checked.
+    pub size: u64,
+    pub method: DeleteMethod,
+    /// Why it is on the list, for the reviewer (`.log file`, `Chrome cache`, ...)
+    pub reason: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Manifest {
+    /// What produced it, as the review window's title
+    pub title: String,
+    pub items: Vec<PlannedRemoval>,
+    /// Loaded from a CSV rather than made from the scan, so its folders to empty
+    /// were never checked against the tree
+    pub from_file: bool,
+}
+
+/// Bulk removals only recycle files or empty cache folders.
+fn action_key(method: DeleteMethod) -> &'static str {
+    if method == DeleteMethod::CacheCleared { "clear-contents" } else { "recycle" }
+}
+
+impl Manifest {
+    pub fn total(&self) -> u64 {
+        self.items.iter().map(|i| i.size).sum()
+    }
+
+    /// Folders this manifest would empty.
+    pub fn folders_to_empty(&self) -> usize {
+        self.items.iter().filter(|i| i.method == DeleteMethod::CacheCleared).count()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
+        let mut csv = String::from("action,path,bytes,reason\n");
+        for item in &self.items {
+            csv += &format!(
+                "{},{},{},{}\n",
+                action_key(item.method), quote(&item.path.to_string_lossy()), item.size, quote(&item.reason),
+            );
+        }
+        csv
+    }
+
+    /// Read a manifest saved by `to_csv`. Blank lines and the header are skipped; any
+    /// other row that doesn't parse fails the whole file, since running part of an
+    /// approved list would not be what was approved.
+    pub fn parse_csv(title: String, text: &str) -> Result<Self, String> {
+        let mut items = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || (i == 0 && line.starts_with("action,")) {
+                continue;
+            }
+            let fields = csv_fields(line);
+            let method = match fields.first().map(|f| f.trim()) {
+                Some("recycle") => DeleteMethod::RecycleBin,
+                Some("clear-contents") => DeleteMethod::CacheCleared,
+                _ => return Err(format!("Line {}: unknown action.", i + 1)),
+            };
+            let path = fields.get(1).map(|p| PathBuf::from(p.trim())).filter(|p| p.is_absolute());
+            let size = fields.get(2).and_then(|s| s.trim().parse().ok());
+            let (Some(path), Some(size)) = (path, size) else {
+                return Err(format!("Line {}: expected an absolute path and a size in bytes.", i + 1));
+            };
+            let reason = fields.get(3).cloned().unwrap_or_default();
+            items.push(PlannedRemoval { path, size, method, reason });
+        }
+        if items.is_empty() {
+            return Err("The manifest lists nothing to remove.".to_string());
+        }
+        Ok(Self { title, items, from_file: true })
+    }
+}
+
+/// Whether a planned item is still what the list was made from: there, a folder
+/// for a cache to empty, and a file to recycle still a file of its planned size.
+/// A folder or link where a file was planned is never recycled.
+pub fn unchanged(item: &PlannedRemoval) -> bool {
+    let Ok(metadata) = std::fs::symlink_metadata(&item.path) else { return false };
+    match item.method {
+        DeleteMethod::CacheCleared => metadata.is_dir(),
+        _ => metadata.is_file() && (item.size == 0 || metadata.len() == item.size),
+    }
+}
@@ -0,0 +1,16 @@
+use crate::scanner::FileNode;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate};
+
+/// Resident memory of this process in bytes (the working set on Windows).
+pub fn process_rss() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing().with_memory());
+    sys.process(pid).map(|p| p.memory())
+}
+
+/// Nodes in the tree, the root included. Each one costs a name, a path and a
+/// child vector, so this is what drives memory on huge volumes.
+pub fn node_count(node: &FileNode) -> u64 {
+    1 + node.children.iter().map(node_count).sum::<u64>()
+}
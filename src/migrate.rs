@@ -0,0 +1,99 @@
+use crate::app::{format_count, format_size};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// A folder to migrate, with what the scan measured for the script's comments.
+pub struct MigrationSource {
+    pub path: PathBuf,
+    pub size: u64,
+    pub files: u64,
+}
+
+/// File extension of the script for this platform: a robocopy batch file on
+/// Windows, an rsync shell script elsewhere.
+pub fn script_extension() -> &'static str {
+    if cfg!(windows) { "cmd" } else { "sh" }
+}
+
+/// A script that copies (or moves) each folder to `dest/<folder name>`, for an admin
+/// to review and run. Folders with the same name get `name (2)` etc. instead of
+/// being merged. `header` lines become comments at the top.
+pub fn migration_script(sources: &[MigrationSource], dest: &Path, moving: bool, header: &[String]) -> String {
+    let mut used = std::collections::HashSet::new();
+    let targets: Vec<PathBuf> = sources.iter()
+        .map(|s| {
+            let name = s.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "Folder".to_string());
+            let mut target = name.clone();
+            let mut n = 2;
+            while !used.insert(target.to_lowercase()) {
+                target = format!("{} ({})", name, n);
+                n += 1;
+            }
+            dest.join(target)
+        })
+        .collect();
+    if cfg!(windows) {
+        robocopy_script(sources, &targets, dest, moving, header)
+    } else {
+        rsync_script(sources, &targets, dest, moving, header)
+    }
+}
+
+fn robocopy_script(sources: &[MigrationSource], targets: &[PathBuf], dest: &Path, moving: bool, header: &[String]) -> String {
+    // `%` starts a variable in batch files, even inside quotes
+    let quote = |p: &Path| format!("\"{}\"", p.to_string_lossy().replace('%', "%%"));
+    let mut out = String::from("@echo off\r\n");
+    for line in header {
+        let _ = write!(out, "rem {}\r\n", line.replace('%', "%%"));
+    }
+    out += "rem Robocopy exit codes below 8 mean success.\r\n";
+    out += "setlocal\r\nchcp 65001 >nul\r\nset \"LOG=%~dpn0.log\"\r\nset FAILED=\r\n";
+    let _ = write!(out, "if not exist {} mkdir {}\r\n", quote(dest), quote(dest));
+    // /MOVE deletes each file and folder only after it was copied
+    let mode = if moving { " /MOVE" } else { "" };
+    for (source, target) in sources.iter().zip(targets) {
+        let _ = write!(
+            out,
+            "\r\nrem {} ({} files)\r\nrobocopy {} {} /E /COPY:DAT /DCOPY:T /R:1 /W:1 /NP{} /LOG+:\"%LOG%\"\r\n\
+             if errorlevel 8 (echo Failed: {} & set FAILED=1)\r\n",
+            format_size(source.size),
+            format_count(source.files),
+            quote(&source.path),
+            quote(target),
+            mode,
+            quote(&source.path),
+        );
+    }
+    out += "\r\nif defined FAILED (echo Some folders failed. See %LOG% & exit /b 1)\r\necho Done. Log: %LOG%\r\n";
+    out
+}
+
+fn rsync_script(sources: &[MigrationSource], targets: &[PathBuf], dest: &Path, moving: bool, header: &[String]) -> String {
+    let quote = |text: &str| format!("'{}'", text.replace('\'', "'\\''"));
+    let mut out = String::from("#!/bin/sh\n");
+    for line in header {
+        let _ = writeln!(out, "# {}", line);
+    }
+    out += "set -u\nfailed=0\n";
+    let _ = writeln!(out, "mkdir -p {}", quote(&dest.to_string_lossy()));
+    for (source, target) in sources.iter().zip(targets) {
+        let path = source.path.to_string_lossy();
+        // Trailing slashes: copy the folder's contents into the named target
+        let src = quote(&format!("{}/", path.trim_end_matches('/')));
+        let dst = quote(&format!("{}/", target.to_string_lossy()));
+        let failed = quote(&format!("Failed: {}", path));
+        let _ = write!(out, "\n# {} ({} files)\n", format_size(source.size), format_count(source.files));
+        if moving {
+            // rsync removes only files; the emptied folders go once everything arrived
+            let _ = write!(
+                out,
+                "if rsync -a --remove-source-files {} {}; then\n    find {} -depth -type d -empty -delete\nelse\n    echo {} >&2; failed=1\nfi\n",
+                src, dst, quote(&path), failed,
+            );
+        } else {
+            let _ = writeln!(out, "rsync -a {} {} || {{ echo {} >&2; failed=1; }}", src, dst, failed);
+        }
+    }
+    out += "\nif [ \"$failed\" -ne 0 ]; then echo 'Some folders failed.' >&2; exit 1; fi\necho Done.\n";
+    out
+}
@@ -0,0 +1,225 @@
+use crate::scanner::{ATTR_LINK, FileNode, is_all_drives, is_offline, is_space_tile};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+// The per-item context menu every view shares. A view describes the item it was
+// opened on (NodeFacts) and the app's state (ActionEnv); `action_buttons` shows
+// just the actions that make sense for that combination and reports the pick,
+// which the app carries out in one place.
+
+/// What kind of block a menu was opened on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeKind {
+    File,
+    Folder,
+    /// Free space / unknown space tiles: a size, nothing on disk
+    SpaceTile,
+    /// Aggregates without a path of their own (small files, not scanned, all drives)
+    Virtual,
+}
+
+/// The facts about one item that decide which actions it gets.
+pub struct NodeFacts {
+    pub kind: NodeKind,
+    /// Empty for space tiles and virtual nodes
+    pub path: PathBuf,
+    pub size: u64,
+    pub file_count: u64,
+    /// A folder whose contents are in the tree (not summarized by a depth limit)
+    pub has_children: bool,
+    /// Scanner ATTR_* bits
+    pub attrs: u16,
+    pub selected: bool,
+    /// A folder currently shown flattened (every file inside as its direct child)
+    pub flattened: bool,
+}
+
+impl NodeFacts {
+    /// Classify an item the way the scanner builds them: space tiles by name,
+    /// aggregates by their empty path, the all-drives root by its virtual one.
+    pub fn new(name: &str, path: &Path, is_dir: bool, size: u64, file_count: u64, has_children: bool) -> Self {
+        let kind = if is_space_tile(name) {
+            NodeKind::SpaceTile
+        } else if path.as_os_str().is_empty() || is_all_drives(path) {
+            NodeKind::Virtual
+        } else if is_dir {
+            NodeKind::Folder
+        } else {
+            NodeKind::File
+        };
+        Self { kind, path: path.to_path_buf(), size, file_count, has_children, attrs: 0, selected: false, flattened: false }
+    }
+
+    pub fn from_node(node: &FileNode) -> Self {
+        Self::new(&node.name, &node.path, node.is_dir, node.size, node.file_count, !node.children.is_empty())
+            .with_attrs(node.attrs)
+    }
+
+    pub fn with_attrs(mut self, attrs: u16) -> Self {
+        self.attrs = attrs;
+        self
+    }
+
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn with_flattened(mut self, flattened: bool) -> Self {
+        self.flattened = flattened;
+        self
+    }
+
+    fn on_disk(&self) -> bool {
+        matches!(self.kind, NodeKind::File | NodeKind::Folder)
+    }
+
+    /// Reading the data would download it (cloud placeholder) or recall it from
+    /// archive storage, or there is no data to read (a link).
+    fn data_elsewhere(&self) -> bool {
+        is_offline(self.attrs) || self.attrs & ATTR_LINK != 0
+    }
+}
+
+/// App state that also limits the actions: a remote tree is read-only, and a
+/// scan in progress can't be extended.
+pub struct ActionEnv {
+    pub remote: bool,
+    pub scanning: bool,
+    pub shred_enabled: bool,
+    /// A tool job is running: actions that change files are shown greyed out
+    pub busy: bool,
+}
+
+/// Hover text for a destructive button greyed out while a tool job runs.
+pub const BUSY_HINT: &str = "Wait for the running job to finish";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeAction {
+    Open,
+    CopyPath,
+    CopyFile,
+    ComputeHash,
+    TypeBreakdown,
+    Flatten,
+    ScanContents,
+    Rescan,
+    Compress,
+    ToggleSelection,
+    Delete,
+    Shred,
+}
+
+/// Menu order. Consecutive actions with a different `group` get a separator.
+const ACTIONS: [NodeAction; 12] = [
+    NodeAction::Open,
+    NodeAction::CopyPath,
+    NodeAction::CopyFile,
+    NodeAction::ComputeHash,
+    NodeAction::TypeBreakdown,
+    NodeAction::Flatten,
+    NodeAction::ScanContents,
+    NodeAction::Rescan,
+    NodeAction::Compress,
+    NodeAction::ToggleSelection,
+    NodeAction::Delete,
+    NodeAction::Shred,
+];
+
+impl NodeAction {
+    fn label(self, facts: &NodeFacts) -> &'static str {
+        match self {
+            NodeAction::Open => "Open in Explorer",
+            NodeAction::CopyPath => "Copy Path",
+            NodeAction::CopyFile => "Copy File",
+            NodeAction::ComputeHash => "Compute Hash...",
+            NodeAction::TypeBreakdown => "Type Breakdown...",
+            NodeAction::Flatten if facts.flattened => "Unflatten",
+            NodeAction::Flatten => "Flatten",
+            NodeAction::ScanContents => "Scan Contents",
+            NodeAction::Rescan => "Rescan This Folder",
+            NodeAction::Compress => "Compress to .zip...",
+            NodeAction::ToggleSelection if facts.selected => "Remove from Selection",
+            NodeAction::ToggleSelection => "Add to Selection",
+            NodeAction::Delete => "Delete to Recycle Bin",
+            NodeAction::Shred => "Shred (overwrite and delete)...",
+        }
+    }
+
+    fn hint(self) -> Option<&'static str> {
+        match self {
+            NodeAction::Flatten => Some("Show every file inside directly, without subfolder frames"),
+            NodeAction::ScanContents => {
+                Some("The depth limit kept only this folder's total. Scan just this folder and add its contents.")
+            }
+            NodeAction::Rescan => Some("Scan just this folder again and update its sizes, after changes made outside SpaceView."),
+            _ => None,
+        }
+    }
+
+    fn group(self) -> u8 {
+        match self {
+            NodeAction::Open | NodeAction::CopyPath | NodeAction::CopyFile | NodeAction::ComputeHash => 0,
+            NodeAction::TypeBreakdown
+            | NodeAction::Flatten
+            | NodeAction::ScanContents
+            | NodeAction::Rescan
+            | NodeAction::Compress
+            | NodeAction::ToggleSelection => 1,
+            NodeAction::Delete | NodeAction::Shred => 2,
+        }
+    }
+
+    /// Removes or rewrites files, so it waits while another tool job runs.
+    pub fn destructive(self) -> bool {
+        matches!(self, NodeAction::Compress | NodeAction::Delete | NodeAction::Shred)
+    }
+
+    /// Whether this action applies to the item in the current state.
+    pub fn applies(self, facts: &NodeFacts, env: &ActionEnv) -> bool {
+        let file = facts.kind == NodeKind::File;
+        let folder = facts.kind == NodeKind::Folder;
+        let local = !env.remote;
+        match self {
+            NodeAction::Open | NodeAction::CopyPath | NodeAction::ToggleSelection => facts.on_disk(),
+            NodeAction::CopyFile => file && local,
+            NodeAction::ComputeHash => file && local && !facts.data_elsewhere(),
+            NodeAction::TypeBreakdown => folder && facts.has_children,
+            NodeAction::Flatten => folder && facts.has_children && !env.scanning,
+            NodeAction::ScanContents => folder && !facts.has_children && facts.file_count > 0 && local && !env.scanning,
+            NodeAction::Rescan => folder && facts.has_children && local && !env.scanning,
+            NodeAction::Compress => folder && local && facts.path.parent().is_some(),
+            NodeAction::Delete => facts.on_disk() && local,
+            NodeAction::Shred => file && local && env.shred_enabled && !facts.data_elsewhere(),
+        }
+    }
+}
+
+/// The actions that apply to an item, in menu order.
+pub fn available_actions(facts: &NodeFacts, env: &ActionEnv) -> Vec<NodeAction> {
+    ACTIONS.into_iter().filter(|a| a.applies(facts, env)).collect()
+}
+
+/// Add the item's actions to an open context menu, grouped by separators, and
+/// close the menu on a click. Returns the action picked this frame.
+pub fn action_buttons(ui: &mut egui::Ui, facts: &NodeFacts, env: &ActionEnv) -> Option<NodeAction> {
+    let mut picked = None;
+    let mut group = None;
+    for action in available_actions(facts, env) {
+        if group.is_some_and(|g| g != action.group()) {
+            ui.separator();
+        }
+        group = Some(action.group());
+        let enabled = !(env.busy && action.destructive());
+        let mut button = ui.add_enabled(enabled, egui::Button::new(action.label(facts)))
+            .on_disabled_hover_text(BUSY_HINT);
+        if let Some(hint) = action.hint() {
+            button = button.on_hover_text(hint);
+        }
+        if button.clicked() {
+            picked = Some(action);
+            ui.close_menu();
+        }
+    }
+    picked
+}
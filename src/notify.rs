@@ -0,0 +1,492 @@
+use crate::app::{civil_date, format_size, now_secs};
+use crate::compare::{json_str, match_key};
+use crate::scanner::{FileNode, is_space_tile};
+use base64::Engine;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A folder must gain at least this much before its percentage growth counts, so a
+/// 4 KB folder that doubled doesn't raise an alert.
+const MIN_PERCENT_GROWTH_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Grown folders listed in the alert text; the JSON has all of them.
+const MAX_LISTED_FOLDERS: usize = 10;
+
+/// How the SMTP connection is secured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SmtpSecurity {
+    /// TLS from the first byte (usually port 465).
+    Tls,
+    /// Plain connection upgraded with STARTTLS (usually port 587).
+    StartTls,
+    /// No encryption, for internal relays on port 25.
+    None,
+}
+
+/// Where and when `--monitor` sends alerts. Loaded from notify.txt.
+pub struct NotifySettings {
+    /// Slack, Teams and Discord incoming webhooks all accept the posted JSON.
+    pub webhook_url: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: SmtpSecurity,
+    pub smtp_user: String,
+    pub smtp_password: String,
+    pub smtp_from: String,
+    pub smtp_to: Vec<String>,
+    /// Alert when the scanned volume has less free space than this (0 = off).
+    pub min_free_percent: f64,
+    /// Alert when the folder grew by more than this since the last check (0 = off).
+    pub max_growth_mb: u64,
+    /// Alert when the folder is larger than this (0 = off).
+    pub max_size_gb: u64,
+    /// Alert when any folder inside grew by more than this since the last check (0 = off).
+    pub folder_growth_mb: u64,
+    /// Alert when any folder inside grew by more than this percentage (0 = off).
+    pub folder_growth_percent: f64,
+}
+
+impl Default for NotifySettings {
+    fn default() -> Self {
+        Self {
+            webhook_url: String::new(),
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_security: SmtpSecurity::StartTls,
+            smtp_user: String::new(),
+            smtp_password: String::new(),
+            smtp_from: String::new(),
+            smtp_to: Vec::new(),
+            min_free_percent: 10.0,
+            max_growth_mb: 0,
+            max_size_gb: 0,
+            folder_growth_mb: 0,
+            folder_growth_percent: 0.0,
+        }
+    }
+}
+
+const TEMPLATE: &str = "\
+# SpaceView notifications for `spaceview --monitor`. Uncomment and fill in a line to use it.
+# Alerts go to every configured channel when a threshold is crossed (0 turns a threshold off).
+# min_free_percent = 10
+# max_growth_mb = 0
+# max_size_gb = 0
+#
+# Per-folder growth, diffed against the previous --state snapshot. Only the deepest
+# folders that crossed a threshold are reported (percentages need 10 MB of growth).
+# folder_growth_mb = 0
+# folder_growth_percent = 0
+#
+# Webhook (Slack, Teams or Discord incoming webhook URL):
+# webhook_url = https://hooks.slack.com/services/...
+#
+# Email. smtp_security is starttls (port 587), tls (port 465) or none (port 25 relays).
+# With none nothing is encrypted, so no login is sent: leave smtp_user empty.
+# smtp_host = smtp.example.com
+# smtp_port = 587
+# smtp_security = starttls
+# smtp_user = alerts@example.com
+# smtp_password =
+# smtp_from = alerts@example.com
+# smtp_to = admin@example.com, oncall@example.com
+";
+
+pub fn notify_settings_path() -> Option<PathBuf> {
+    crate::app::data_dir().map(|d| d.join("notify.txt"))
+}
+
+impl NotifySettings {
+    /// Load settings from `path`, writing the commented template if it is missing.
+    pub fn load(path: &Path) -> Self {
+        let mut settings = Self::default();
+        let Ok(content) = std::fs::read_to_string(path) else {
+            write_template(path);
+            return settings;
+        };
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else { continue };
+            let value = value.trim();
+            match key.trim() {
+                "webhook_url" => settings.webhook_url = value.to_string(),
+                "smtp_host" => settings.smtp_host = value.to_string(),
+                "smtp_port" => settings.smtp_port = value.parse().unwrap_or(settings.smtp_port),
+                "smtp_security" => {
+                    settings.smtp_security = match value.to_lowercase().as_str() {
+                        "tls" | "ssl" => SmtpSecurity::Tls,
+                        "none" => SmtpSecurity::None,
+                        _ => SmtpSecurity::StartTls,
+                    }
+                }
+                "smtp_user" => settings.smtp_user = value.to_string(),
+                "smtp_password" => settings.smtp_password = value.to_string(),
+                "smtp_from" => settings.smtp_from = value.to_string(),
+                "smtp_to" => {
+                    settings.smtp_to = value.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect()
+                }
+                "min_free_percent" => settings.min_free_percent = value.parse().unwrap_or(settings.min_free_percent),
+                "max_growth_mb" => settings.max_growth_mb = value.parse().unwrap_or(settings.max_growth_mb),
+                "max_size_gb" => settings.max_size_gb = value.parse().unwrap_or(settings.max_size_gb),
+                "folder_growth_mb" => settings.folder_growth_mb = value.parse().unwrap_or(settings.folder_growth_mb),
+                "folder_growth_percent" => {
+                    settings.folder_growth_percent = value.parse().unwrap_or(settings.folder_growth_percent)
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    /// Messages for every threshold `summary` crosses.
+    pub fn alerts(&self, summary: &ScanSummary) -> Vec<String> {
+        let mut alerts = Vec::new();
+        if let (Some(free), Some(total)) = (summary.free, summary.total) {
+            let percent = if total > 0 { free as f64 * 100.0 / total as f64 } else { 100.0 };
+            if self.min_free_percent > 0.0 && percent < self.min_free_percent {
+                alerts.push(format!("Only {:.1}% free ({}) on the volume", percent, format_size(free)));
+            }
+        }
+        if let Some(growth) = summary.growth {
+            if self.max_growth_mb > 0 && growth > (self.max_growth_mb * 1024 * 1024) as i64 {
+                alerts.push(format!("Grew by {} since the last check", format_size(growth as u64)));
+            }
+        }
+        if self.max_size_gb > 0 && summary.size > self.max_size_gb * 1024 * 1024 * 1024 {
+            alerts.push(format!("Folder is {}, over the {} GB limit", format_size(summary.size), self.max_size_gb));
+        }
+        for grown in summary.grown_folders.iter().take(MAX_LISTED_FOLDERS) {
+            alerts.push(format!("{} grew by {}", grown.path.to_string_lossy(), grown.describe()));
+        }
+        if summary.grown_folders.len() > MAX_LISTED_FOLDERS {
+            alerts.push(format!("{} more folders grew past the limit", summary.grown_folders.len() - MAX_LISTED_FOLDERS));
+        }
+        alerts
+    }
+
+    pub fn checks_folders(&self) -> bool {
+        self.folder_growth_mb > 0 || self.folder_growth_percent > 0.0
+    }
+
+    /// Folders of `new` that grew past `folder_growth_mb` or `folder_growth_percent`
+    /// since `old`, largest growth first. A folder is left out when one of its
+    /// subfolders already explains it, so a single busy folder raises one alert
+    /// instead of one per parent.
+    pub fn grown_folders(&self, old: &FileNode, new: &FileNode) -> Vec<FolderGrowth> {
+        let mut grown = Vec::new();
+        if self.checks_folders() {
+            self.collect_grown(Some(old), new, &mut grown);
+        }
+        grown.sort_by_key(|g| std::cmp::Reverse(g.growth()));
+        grown
+    }
+
+    fn crosses(&self, old_size: u64, new_size: u64) -> bool {
+        let growth = new_size.saturating_sub(old_size);
+        let by_size = self.folder_growth_mb > 0 && growth > self.folder_growth_mb * 1024 * 1024;
+        let by_percent = self.folder_growth_percent > 0.0
+            && growth >= MIN_PERCENT_GROWTH_BYTES
+            && (old_size == 0 || growth as f64 * 100.0 / old_size as f64 > self.folder_growth_percent);
+        by_size || by_percent
+    }
+
+    /// Returns whether `new` or a folder inside it was reported.
+    fn collect_grown(&self, old: Option<&FileNode>, new: &FileNode, out: &mut Vec<FolderGrowth>) -> bool {
+        // Subfolders are checked even when this one didn't grow: a cleanup
+        // elsewhere can hide a folder that did
+        let old_size = old.map_or(0, |o| o.size);
+        let old_children: HashMap<String, &FileNode> = old
+            .map(|o| o.children.iter().filter(|c| c.is_dir).map(|c| (match_key(&c.name), c)).collect())
+            .unwrap_or_default();
+        let mut inner = false;
+        for child in new.children.iter().filter(|c| c.is_dir && !is_space_tile(&c.name)) {
+            inner |= self.collect_grown(old_children.get(&match_key(&child.name)).copied(), child, out);
+        }
+        if inner || !self.crosses(old_size, new.size) {
+            return inner;
+        }
+        out.push(FolderGrowth { path: new.path.clone(), old_size, new_size: new.size });
+        true
+    }
+
+    pub fn has_email(&self) -> bool {
+        !self.smtp_host.is_empty() && !self.smtp_to.is_empty()
+    }
+}
+
+fn write_template(path: &Path) {
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(path, TEMPLATE);
+}
+
+/// Make sure notify.txt exists (so it can be opened for editing) and return its path.
+pub fn ensure_settings_file() -> Option<PathBuf> {
+    let path = notify_settings_path()?;
+    if !path.exists() {
+        write_template(&path);
+    }
+    Some(path)
+}
+
+/// A folder that grew past a per-folder threshold between two `--monitor` runs.
+pub struct FolderGrowth {
+    pub path: PathBuf,
+    /// 0 when the folder is new
+    pub old_size: u64,
+    pub new_size: u64,
+}
+
+impl FolderGrowth {
+    pub fn growth(&self) -> u64 {
+        self.new_size.saturating_sub(self.old_size)
+    }
+
+    /// `1.2 GB (+35%)`, or `1.2 GB (new)`.
+    fn describe(&self) -> String {
+        if self.old_size == 0 {
+            format!("{} (new)", format_size(self.growth()))
+        } else {
+            format!("{} (+{:.0}%)", format_size(self.growth()), self.growth() as f64 * 100.0 / self.old_size as f64)
+        }
+    }
+}
+
+/// Result of one `--monitor` check.
+pub struct ScanSummary {
+    pub root: PathBuf,
+    pub size: u64,
+    pub file_count: u64,
+    /// Free and total bytes of the volume holding `root`, when known.
+    pub free: Option<u64>,
+    pub total: Option<u64>,
+    /// Net bytes gained since the previous check, when there was one.
+    pub growth: Option<i64>,
+    /// Folders over the per-folder growth thresholds since the previous check.
+    pub grown_folders: Vec<FolderGrowth>,
+    pub alerts: Vec<String>,
+}
+
+impl ScanSummary {
+    /// One-paragraph plain-text summary, used as the webhook text and email body.
+    pub fn text(&self) -> String {
+        let mut text = format!(
+            "SpaceView: {} is {} ({} files)",
+            self.root.to_string_lossy(),
+            format_size(self.size),
+            self.file_count,
+        );
+        if let (Some(free), Some(total)) = (self.free, self.total) {
+            text += &format!(", {} free of {}", format_size(free), format_size(total));
+        }
+        if let Some(growth) = self.growth {
+            let sign = if growth < 0 { "-" } else { "+" };
+            text += &format!(", {}{} since the last check", sign, format_size(growth.unsigned_abs()));
+        }
+        for alert in &self.alerts {
+            text += &format!("\n- {}", alert);
+        }
+        text
+    }
+
+    /// JSON payload. `text` (Slack, Teams) and `content` (Discord) carry the readable
+    /// summary; the other fields are for scripts.
+    pub fn to_json(&self) -> String {
+        let opt = |v: Option<String>| v.unwrap_or_else(|| "null".to_string());
+        let alerts: Vec<String> = self.alerts.iter().map(|a| json_str(a)).collect();
+        let grown: Vec<String> = self.grown_folders.iter()
+            .map(|g| format!(
+                "{{\"path\": {}, \"old_size\": {}, \"new_size\": {}}}",
+                json_str(&g.path.to_string_lossy()), g.old_size, g.new_size,
+            ))
+            .collect();
+        let text = json_str(&self.text());
+        format!(
+            "{{\"text\": {}, \"content\": {}, \"root\": {}, \"size\": {}, \"file_count\": {}, \"free\": {}, \"total\": {}, \"growth\": {}, \"grown_folders\": [{}], \"alerts\": [{}]}}\n",
+            text,
+            text,
+            json_str(&self.root.to_string_lossy()),
+            self.size,
+            self.file_count,
+            opt(self.free.map(|v| v.to_string())),
+            opt(self.total.map(|v| v.to_string())),
+            opt(self.growth.map(|v| v.to_string())),
+            grown.join(", "),
+            alerts.join(", "),
+        )
+    }
+}
+
+/// Send `summary` to every configured channel. Returns one error per failed channel.
+pub fn send(settings: &NotifySettings, summary: &ScanSummary) -> Vec<String> {
+    let mut errors = Vec::new();
+    if !settings.webhook_url.is_empty() {
+        if let Err(e) = post_webhook(&settings.webhook_url, summary) {
+            errors.push(format!("Webhook: {}", e));
+        }
+    }
+    if settings.has_email() {
+        if let Err(e) = send_email(settings, summary) {
+            errors.push(format!("Email: {}", e));
+        }
+    }
+    errors
+}
+
+fn post_webhook(url: &str, summary: &ScanSummary) -> Result<(), String> {
+    ureq::post(url)
+        .set("Content-Type", "application/json")
+        .timeout(SMTP_TIMEOUT)
+        .send_string(&summary.to_json())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// ===================== SMTP =====================
+
+fn send_email(settings: &NotifySettings, summary: &ScanSummary) -> Result<(), String> {
+    if settings.smtp_security == SmtpSecurity::None && !settings.smtp_user.is_empty() {
+        return Err("Not sending the login unencrypted: use smtp_security = starttls or tls, or clear smtp_user".to_string());
+    }
+    let tcp = connect(&settings.smtp_host, settings.smtp_port)?;
+    tcp.set_read_timeout(Some(SMTP_TIMEOUT)).map_err(|e| e.to_string())?;
+    tcp.set_write_timeout(Some(SMTP_TIMEOUT)).map_err(|e| e.to_string())?;
+    let subject = match summary.alerts.first() {
+        Some(alert) => format!("SpaceView alert: {}", alert),
+        None => format!("SpaceView report: {}", summary.root.to_string_lossy()),
+    };
+    match settings.smtp_security {
+        SmtpSecurity::Tls => {
+            let mut stream = tls_stream(&settings.smtp_host, tcp)?;
+            expect_reply(&mut stream, 220)?;
+            smtp_session(&mut stream, settings, &subject, &summary.text())
+        }
+        SmtpSecurity::StartTls => {
+            let mut tcp = tcp;
+            expect_reply(&mut tcp, 220)?;
+            smtp_command(&mut tcp, "EHLO spaceview", 250)?;
+            smtp_command(&mut tcp, "STARTTLS", 220)?;
+            let mut stream = tls_stream(&settings.smtp_host, tcp)?;
+            smtp_session(&mut stream, settings, &subject, &summary.text())
+        }
+        SmtpSecurity::None => {
+            let mut tcp = tcp;
+            expect_reply(&mut tcp, 220)?;
+            smtp_session(&mut tcp, settings, &subject, &summary.text())
+        }
+    }
+}
+
+/// Connect with SMTP_TIMEOUT per address, so an unreachable host can't hang the monitor.
+fn connect(host: &str, port: u16) -> Result<TcpStream, String> {
+    let mut last_error = format!("{} did not resolve", host);
+    for addr in (host, port).to_socket_addrs().map_err(|e| e.to_string())? {
+        match TcpStream::connect_timeout(&addr, SMTP_TIMEOUT) {
+            Ok(tcp) => return Ok(tcp),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+fn tls_stream(host: &str, tcp: TcpStream) -> Result<rustls::StreamOwned<rustls::ClientConnection, TcpStream>, String> {
+    let roots = rustls::RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let name = rustls::pki_types::ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+    let conn = rustls::ClientConnection::new(Arc::new(config), name).map_err(|e| e.to_string())?;
+    Ok(rustls::StreamOwned::new(conn, tcp))
+}
+
+/// EHLO, optional AUTH PLAIN, then the message. Expects the greeting already read.
+fn smtp_session(stream: &mut (impl Read + Write), settings: &NotifySettings, subject: &str, body: &str) -> Result<(), String> {
+    smtp_command(stream, "EHLO spaceview", 250)?;
+    if !settings.smtp_user.is_empty() {
+        let token = base64::engine::general_purpose::STANDARD
+            .encode(format!("\0{}\0{}", settings.smtp_user, settings.smtp_password));
+        smtp_command(stream, &format!("AUTH PLAIN {}", token), 235)?;
+    }
+    let from = if settings.smtp_from.is_empty() { &settings.smtp_user } else { &settings.smtp_from };
+    smtp_command(stream, &format!("MAIL FROM:<{}>", from), 250)?;
+    for to in &settings.smtp_to {
+        smtp_command(stream, &format!("RCPT TO:<{}>", to), 250)?;
+    }
+    smtp_command(stream, "DATA", 354)?;
+    // Relays reject or spam-flag mail without Date and Message-ID
+    let now = now_secs();
+    let domain = from.rsplit_once('@').map_or("spaceview.invalid", |(_, d)| d);
+    let mut message = format!(
+        "Date: {}\r\nMessage-ID: <{}.{}@{}>\r\nFrom: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n",
+        rfc5322_date(now),
+        now,
+        std::process::id(),
+        domain,
+        from,
+        settings.smtp_to.join(", "),
+        subject.replace(['\r', '\n'], " "),
+    );
+    for line in body.lines() {
+        // Dot-stuffing: a lone "." would end the message early
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message += line;
+        message += "\r\n";
+    }
+    message += ".";
+    smtp_command(stream, &message, 250)?;
+    let _ = smtp_command(stream, "QUIT", 221);
+    Ok(())
+}
+
+/// `Fri, 16 Oct 2026 09:05:00 +0000`.
+fn rfc5322_date(secs: u64) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let (y, m, d) = civil_date(secs);
+    format!(
+        "{}, {} {} {} {:02}:{:02}:{:02} +0000",
+        DAYS[(secs / 86_400 % 7) as usize], d, MONTHS[(m - 1) as usize], y,
+        secs % 86_400 / 3600, secs % 3600 / 60, secs % 60,
+    )
+}
+
+fn smtp_command(stream: &mut (impl Read + Write), line: &str, expected: u16) -> Result<(), String> {
+    stream.write_all(format!("{}\r\n", line).as_bytes()).map_err(|e| e.to_string())?;
+    stream.flush().map_err(|e| e.to_string())?;
+    expect_reply(stream, expected)
+}
+
+/// Read one (possibly multi-line) reply and check its code. Reads byte by byte so
+/// nothing past the reply is consumed before a STARTTLS upgrade.
+fn expect_reply(stream: &mut impl Read, expected: u16) -> Result<(), String> {
+    loop {
+        let mut line = Vec::new();
+        let mut byte = [0u8; 1];
+        while line.last() != Some(&b'\n') {
+            match stream.read(&mut byte) {
+                Ok(0) => return Err("Connection closed".to_string()),
+                Ok(_) => line.push(byte[0]),
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        let line = String::from_utf8_lossy(&line).trim_end().to_string();
+        let code: u16 = line.get(..3).and_then(|c| c.parse().ok()).ok_or_else(|| format!("Bad reply: {}", line))?;
+        // "250-..." continues, "250 ..." ends the reply
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return if code == expected { Ok(()) } else { Err(line) };
+        }
+    }
+}
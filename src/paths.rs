@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+/// Longest path the shell and legacy Win32 APIs accept without the `\\?\` prefix.
+#[cfg(target_os = "windows")]
+const MAX_PATH: usize = 260;
+
+/// Extended-length form of an absolute path for file APIs, lifting the 260-char
+/// MAX_PATH limit: `C:\a` becomes `\\?\C:\a` and `\\server\share` becomes
+/// `\\?\UNC\server\share`. Relative or already-prefixed paths are returned as-is,
+/// as are all paths on other platforms. Don't store or display the result.
+#[cfg(target_os = "windows")]
+pub fn long_path(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if text.starts_with(r"\\?\") || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+    // Verbatim paths skip normalization, so `/` and `.` segments must already be resolved
+    let text = text.replace('/', "\\");
+    match text.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", text)),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// A form of `path` that Explorer accepts. Explorer doesn't understand `\\?\`, so
+/// over-long paths are shortened to their 8.3 alias when the volume has one.
+#[cfg(target_os = "windows")]
+pub fn shell_path(path: &Path) -> PathBuf {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetShortPathNameW;
+
+    if path.as_os_str().len() < MAX_PATH {
+        return path.to_path_buf();
+    }
+    let wide: Vec<u16> = long_path(path).as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut buf = vec![0u16; 32_768];
+    let len = unsafe { GetShortPathNameW(wide.as_ptr(), buf.as_mut_ptr(), buf.len() as u32) } as usize;
+    if len == 0 || len >= buf.len() {
+        return path.to_path_buf();
+    }
+    let short = String::from_utf16_lossy(&buf[..len]);
+    // Strip the prefix again; the short form usually fits in MAX_PATH
+    match short.strip_prefix(r"\\?\UNC\") {
+        Some(unc) => PathBuf::from(format!(r"\\{}", unc)),
+        None => PathBuf::from(short.strip_prefix(r"\\?\").unwrap_or(&short)),
+    }
+}
+
+/// The 8.3 alias of the last component of `path`, or None when it has none (the
+/// volume doesn't generate them, or they were stripped) or on other platforms.
+#[cfg(target_os = "windows")]
+pub fn short_name(path: &Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetShortPathNameW;
+
+    let wide: Vec<u16> = long_path(path).as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut buf = vec![0u16; 32_768];
+    let len = unsafe { GetShortPathNameW(wide.as_ptr(), buf.as_mut_ptr(), buf.len() as u32) } as usize;
+    if len == 0 || len >= buf.len() {
+        return None;
+    }
+    let short = PathBuf::from(String::from_utf16_lossy(&buf[..len]));
+    let short = short.file_name()?.to_string_lossy().to_string();
+    // Without an alias the long name comes back
+    (Some(short.as_str()) != path.file_name().and_then(|n| n.to_str())).then_some(short)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn short_name(_path: &Path) -> Option<String> {
+    None
+}
+
+/// Open the file manager (Explorer, Finder, or the desktop's own) with `path` selected.
+#[cfg(target_os = "windows")]
+pub fn reveal_in_explorer(path: &Path) {
+    let _ = std::process::Command::new("explorer").arg("/select,").arg(shell_path(path)).spawn();
+}
+
+#[cfg(target_os = "macos")]
+pub fn reveal_in_explorer(path: &Path) {
+    let _ = std::process::Command::new("open").arg("-R").arg(path).spawn();
+}
+
+/// Asks the desktop's file manager over D-Bus (`org.freedesktop.FileManager1`,
+/// served by Nautilus, Dolphin, Nemo, Thunar and others). Without one, the parent
+/// folder opens with nothing selected. Runs on its own thread since the call waits
+/// for the reply.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn reveal_in_explorer(path: &Path) {
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        let shown = std::process::Command::new("dbus-send")
+            .args([
+                "--session",
+                "--print-reply",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ])
+            .arg(format!("array:string:{}", file_uri(&path)))
+            .arg("string:")
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success());
+        if !shown {
+            open_in_explorer(path.parent().unwrap_or(&path));
+        }
+    });
+}
+
+/// `file://` URI for an absolute path, percent-encoding everything but unreserved
+/// characters and `/`.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn file_uri(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    let mut uri = String::from("file://");
+    for &b in path.as_os_str().as_bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", b));
+        }
+    }
+    uri
+}
+
+/// Open a folder in the file manager, or a file in its default app.
+#[cfg(target_os = "windows")]
+pub fn open_in_explorer(path: &Path) {
+    let _ = std::process::Command::new("explorer").arg(shell_path(path)).spawn();
+}
+
+#[cfg(target_os = "macos")]
+pub fn open_in_explorer(path: &Path) {
+    let _ = std::process::Command::new("open").arg(path).spawn();
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn open_in_explorer(path: &Path) {
+    let _ = std::process::Command::new("xdg-open").arg(path).spawn();
+}
+
+/// Send files to the Recycle Bin in one shell operation, without a progress window.
+/// Where an item can't be recycled (no Recycle Bin on a network share or some USB
+/// drives, or too big for it) the shell asks before deleting it for good.
+/// Returns how many are gone afterwards; locked or kept files aren't counted.
+#[cfg(target_os = "windows")]
+pub fn recycle_files(paths: &[PathBuf]) -> Result<usize, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::{
+        SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FOF_WANTNUKEWARNING,
+        FO_DELETE, SHFILEOPSTRUCTW,
+    };
+
+    // pFrom is a list of NUL-terminated paths ending in an extra NUL. The shell
+    // doesn't take `\\?\` paths, so over-long ones go by their 8.3 alias.
+    let mut from: Vec<u16> = Vec::new();
+    for path in paths {
+        from.extend(shell_path(path).as_os_str().encode_wide());
+        from.push(0);
+    }
+    from.push(0);
+    let mut op: SHFILEOPSTRUCTW = unsafe { std::mem::zeroed() };
+    op.wFunc = FO_DELETE;
+    op.pFrom = from.as_ptr();
+    // FOF_WANTNUKEWARNING overrides FOF_NOCONFIRMATION for items that would be destroyed
+    op.fFlags = (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT | FOF_WANTNUKEWARNING) as u16;
+    // A non-zero result only says some file failed, so count what actually went
+    unsafe { SHFileOperationW(&mut op) };
+    Ok(paths.iter().filter(|p| !p.exists()).count())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn recycle_files(_paths: &[PathBuf]) -> Result<usize, String> {
+    Err("The Recycle Bin is only available on Windows.".to_string())
+}
@@ -0,0 +1,30 @@
+/// True while the machine runs on battery. Desktops without one report AC power.
+#[cfg(target_os = "windows")]
+pub fn on_battery() -> bool {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    let mut power: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    // ACLineStatus: 0 offline, 1 online, 255 unknown
+    unsafe { GetSystemPowerStatus(&mut power) != 0 && power.ACLineStatus == 0 }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn on_battery() -> bool {
+    false
+}
+
+/// True while a fullscreen game, video or presentation has the screen
+/// (the same check Windows uses to hold back notifications).
+#[cfg(target_os = "windows")]
+pub fn fullscreen_app() -> bool {
+    use windows_sys::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+    let mut state = 0;
+    unsafe { SHQueryUserNotificationState(&mut state) == 0 }
+        && matches!(state, QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn fullscreen_app() -> bool {
+    false
+}
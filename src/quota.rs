@@ -0,0 +1,111 @@
+use crate::scanner::FileNode;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Utilization at which a folder is flagged as nearly full.
+pub const QUOTA_WARN: f64 = 0.9;
+
+/// One `path,quota` row of an imported quota CSV.
+pub struct Quota {
+    /// As written in the CSV
+    pub path: PathBuf,
+    pub limit: u64,
+}
+
+/// Parse `path,quota` rows. Quotas are bytes or a number with a unit (`500 MB`,
+/// `1.5TB`, binary multiples). Paths may be quoted; extra columns are ignored. A
+/// first line whose quota doesn't parse is taken as a header. Returns the quotas and
+/// how many other lines were skipped.
+pub fn parse_quota_csv(text: &str) -> (Vec<Quota>, usize) {
+    let mut quotas = Vec::new();
+    let mut skipped = 0;
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = csv_fields(line);
+        let parsed = match (fields.first(), fields.get(1)) {
+            (Some(path), Some(limit)) if !path.trim().is_empty() => {
+                parse_quota_size(limit).map(|limit| Quota { path: PathBuf::from(path.trim()), limit })
+            }
+            _ => None,
+        };
+        match parsed {
+            Some(quota) => quotas.push(quota),
+            None if i == 0 => {}
+            None => skipped += 1,
+        }
+    }
+    (quotas, skipped)
+}
+
+/// Split one CSV line on commas, honoring double quotes (`""` is a literal quote).
+pub fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// `1073741824`, `1 GB`, `1.5TB`, `500MiB` to bytes. Units are binary, like the
+/// sizes SpaceView shows.
+fn parse_quota_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let value: f64 = text[..split].parse().ok()?;
+    let shift = match text[split..].trim().to_ascii_uppercase().trim_end_matches("IB").trim_end_matches('B') {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        "P" => 50,
+        _ => return None,
+    };
+    Some((value * (1u64 << shift) as f64) as u64)
+}
+
+/// How paths are compared: trailing separators dropped, and case-insensitive with
+/// either slash on Windows.
+fn quota_key(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if cfg!(windows) {
+        text.replace('/', "\\").trim_end_matches('\\').to_lowercase()
+    } else {
+        text.trim_end_matches('/').to_string()
+    }
+}
+
+/// Map each quota to the folder in the tree it applies to, keyed by the tree's own
+/// path. Quotas on folders outside the scan are left out.
+pub fn match_quotas(root: &FileNode, quotas: &[Quota]) -> HashMap<PathBuf, u64> {
+    let by_key: HashMap<String, u64> = quotas.iter().map(|q| (quota_key(&q.path), q.limit)).collect();
+    let mut matched = HashMap::new();
+    match_dirs(root, &by_key, &mut matched);
+    matched
+}
+
+fn match_dirs(node: &FileNode, by_key: &HashMap<String, u64>, matched: &mut HashMap<PathBuf, u64>) {
+    if !node.is_dir {
+        return;
+    }
+    if let Some(&limit) = by_key.get(&quota_key(&node.path)) {
+        matched.insert(node.path.clone(), limit);
+    }
+    for child in &node.children {
+        match_dirs(child, by_key, matched);
+    }
+}
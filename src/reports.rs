@@ -0,0 +1,876 @@
+use crate::paths::{long_path, short_name};
+use crate::scanner::{
+    ATTR_CASE_SENSITIVE, ATTR_COMPRESSED, ATTR_SPARSE, FileNode, SMALL_FILES, ScanOptions, ScanProgress, is_offline,
+    scan_directory,
+};
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Working-tree files at least this large are flagged as Git LFS candidates.
+const LFS_CANDIDATE_BYTES: u64 = 50 * 1024 * 1024;
+/// Loose objects above this size suggest running `git gc`.
+const LOOSE_GC_BYTES: u64 = 50 * 1024 * 1024;
+
+// ===================== Git Repositories =====================
+
+pub struct GitRepoInfo {
+    pub path: PathBuf,
+    /// Whole repo folder including `.git`.
+    pub total_size: u64,
+    pub git_size: u64,
+    /// `.git/objects/pack`
+    pub pack_size: u64,
+    /// `.git/objects/xx/` loose object folders
+    pub loose_size: u64,
+    pub has_lfs: bool,
+    /// Largest working-tree files over LFS_CANDIDATE_BYTES (name, size).
+    pub large_files: Vec<(String, u64)>,
+}
+
+impl GitRepoInfo {
+    pub fn working_size(&self) -> u64 {
+        self.total_size.saturating_sub(self.git_size)
+    }
+
+    /// Short maintenance hints, most important first.
+    pub fn suggestions(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if self.loose_size > LOOSE_GC_BYTES || (self.loose_size > 10 * 1024 * 1024 && self.loose_size > self.pack_size / 4) {
+            out.push("git gc (many loose objects)".to_string());
+        }
+        if !self.large_files.is_empty() && !self.has_lfs {
+            out.push(format!("Git LFS ({} large files)", self.large_files.len()));
+        }
+        if self.git_size > self.working_size() * 2 && self.git_size > 100 * 1024 * 1024 {
+            out.push("history 2x+ checkout: shallow clone or prune".to_string());
+        }
+        out
+    }
+}
+
+/// Find every directory containing a `.git` folder. Nested repos (submodules,
+/// vendored checkouts) are reported separately. Sorted by `.git` size, largest first.
+pub fn find_git_repos(root: &FileNode) -> Vec<GitRepoInfo> {
+    let mut repos = Vec::new();
+    find_git_repos_recursive(root, &mut repos);
+    repos.sort_by_key(|r| std::cmp::Reverse(r.git_size));
+    repos
+}
+
+fn find_git_repos_recursive(node: &FileNode, repos: &mut Vec<GitRepoInfo>) {
+    if let Some(git) = node.children.iter().find(|c| c.is_dir && c.name == ".git") {
+        let objects = git.children.iter().find(|c| c.is_dir && c.name == "objects");
+        let pack_size = objects
+            .and_then(|o| o.children.iter().find(|c| c.name == "pack"))
+            .map(|p| p.size)
+            .unwrap_or(0);
+        let loose_size = objects
+            .map(|o| o.children.iter()
+                .filter(|c| c.is_dir && c.name.len() == 2 && c.name.chars().all(|ch| ch.is_ascii_hexdigit()))
+                .map(|c| c.size)
+                .sum())
+            .unwrap_or(0);
+        let has_lfs = git.children.iter().any(|c| c.is_dir && c.name == "lfs");
+
+        let mut large_files = Vec::new();
+        for child in node.children.iter().filter(|c| c.name != ".git") {
+            collect_large_files(child, &mut large_files);
+        }
+        large_files.sort_by_key(|f| std::cmp::Reverse(f.1));
+
+        repos.push(GitRepoInfo {
+            path: node.path.clone(),
+            total_size: node.size,
+            git_size: git.size,
+            pack_size,
+            loose_size,
+            has_lfs,
+            large_files,
+        });
+    }
+    for child in node.children.iter().filter(|c| c.is_dir && c.name != ".git") {
+        find_git_repos_recursive(child, repos);
+    }
+}
+
+fn collect_large_files(node: &FileNode, out: &mut Vec<(String, u64)>) {
+    if node.is_dir {
+        // Nested repos report their own large files
+        if node.children.iter().any(|c| c.is_dir && c.name == ".git") {
+            return;
+        }
+        for child in &node.children {
+            collect_large_files(child, out);
+        }
+    } else if node.size >= LFS_CANDIDATE_BYTES {
+        out.push((node.name.clone(), node.size));
+    }
+}
+
+// ===================== Matching Folders =====================
+
+/// Folders smaller than this aren't reported; small look-alikes (empty build
+/// output, `__pycache__`) would bury the copies worth cleaning up.
+const MATCHING_FOLDER_MIN_BYTES: u64 = 1024 * 1024;
+
+/// Folders with the same fingerprint: the same names and sizes all the way down.
+pub struct MatchingFolders {
+    pub paths: Vec<PathBuf>,
+    /// Size of each copy
+    pub size: u64,
+    pub file_count: u64,
+}
+
+impl MatchingFolders {
+    /// Bytes held by all copies but one.
+    pub fn extra(&self) -> u64 {
+        self.size * (self.paths.len() as u64).saturating_sub(1)
+    }
+}
+
+/// Group folders by a structural fingerprint, a hash of their sorted child names
+/// and sizes (recursively), to catch copy-pasted folders without reading any file
+/// content. Copies inside an already reported pair are left out. Largest extra
+/// bytes first.
+pub fn find_matching_folders(root: &FileNode) -> Vec<MatchingFolders> {
+    let mut by_print: HashMap<u64, Vec<&FileNode>> = HashMap::new();
+    fingerprint(root, &mut by_print);
+
+    let mut groups: Vec<Vec<&FileNode>> = by_print.into_values().filter(|g| g.len() > 1).collect();
+    // Outer folders first, so their matching subfolders can be skipped
+    groups.sort_by_key(|g| std::cmp::Reverse(g[0].size));
+    let mut reported: HashSet<&Path> = HashSet::new();
+    let mut out = Vec::new();
+    for group in groups {
+        let covered = group.iter().all(|n| n.path.ancestors().skip(1).any(|a| reported.contains(a)));
+        if covered {
+            continue;
+        }
+        reported.extend(group.iter().map(|n| n.path.as_path()));
+        out.push(MatchingFolders {
+            paths: group.iter().map(|n| n.path.clone()).collect(),
+            size: group[0].size,
+            file_count: group[0].file_count,
+        });
+    }
+    out.sort_by_key(|g| std::cmp::Reverse(g.extra()));
+    out
+}
+
+/// Fingerprint of `node`, recording every folder worth reporting in `by_print`.
+fn fingerprint<'a>(node: &'a FileNode, by_print: &mut HashMap<u64, Vec<&'a FileNode>>) -> u64 {
+    let mut children: Vec<(&str, u64, bool, u64)> = node.children.iter()
+        .map(|c| {
+            let print = if c.is_dir { fingerprint(c, by_print) } else { 0 };
+            (c.name.as_str(), c.size, c.is_dir, print)
+        })
+        .collect();
+    children.sort_unstable();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    children.hash(&mut hasher);
+    let print = hasher.finish();
+    // Folders never expanded have no children and would all look alike
+    if node.is_dir && !node.children.is_empty() && node.size >= MATCHING_FOLDER_MIN_BYTES {
+        by_print.entry(print).or_default().push(node);
+    }
+    print
+}
+
+// ===================== Archive Candidates =====================
+
+/// Cold folders smaller than this aren't worth moving.
+const ARCHIVE_MIN_BYTES: u64 = 100 * 1024 * 1024;
+
+/// A folder in which nothing has changed since the cutoff.
+pub struct ArchiveCandidate {
+    pub path: PathBuf,
+    pub size: u64,
+    pub file_count: u64,
+    /// Newest file inside (seconds since 1970)
+    pub modified: u64,
+}
+
+/// The largest cold subtrees below `root`: folders of at least ARCHIVE_MIN_BYTES
+/// whose newest file is older than `cutoff` (seconds since 1970). A cold folder is
+/// reported whole, its subfolders aren't; folders without dates are skipped.
+/// Largest first, so the top rows free the most space on the primary drive.
+pub fn find_archive_candidates(root: &FileNode, cutoff: u64) -> Vec<ArchiveCandidate> {
+    let mut out = Vec::new();
+    find_archive_candidates_recursive(root, cutoff, &mut out);
+    out.sort_by_key(|c| std::cmp::Reverse(c.size));
+    out
+}
+
+fn find_archive_candidates_recursive(node: &FileNode, cutoff: u64, out: &mut Vec<ArchiveCandidate>) {
+    for child in node.children.iter().filter(|c| c.is_dir && c.size >= ARCHIVE_MIN_BYTES) {
+        if child.modified != 0 && child.modified < cutoff {
+            out.push(ArchiveCandidate {
+                path: child.path.clone(),
+                size: child.size,
+                file_count: child.file_count,
+                modified: child.modified,
+            });
+        } else {
+            find_archive_candidates_recursive(child, cutoff, out);
+        }
+    }
+}
+
+// ===================== User Profiles =====================
+
+/// One folder under `Users` (or `home`), split into the parts people ask about.
+pub struct UserProfile {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub desktop: u64,
+    pub documents: u64,
+    pub downloads: u64,
+    /// AppData's Local / LocalLow / Roaming, or a Unix home's dot-folders
+    pub app_data: Vec<(String, u64)>,
+}
+
+impl UserProfile {
+    pub fn app_data_size(&self) -> u64 {
+        self.app_data.iter().map(|(_, size)| size).sum()
+    }
+
+    /// Everything not in one of the named parts.
+    pub fn other(&self) -> u64 {
+        self.size.saturating_sub(self.desktop + self.documents + self.downloads + self.app_data_size())
+    }
+}
+
+/// The profiles folder of the scan: the root itself if it is `Users` / `home`,
+/// else such a folder directly below it (scanning `C:\` or `/`).
+fn profiles_folder(root: &FileNode) -> Option<&FileNode> {
+    let is_profiles = |n: &FileNode| n.is_dir && (n.name.eq_ignore_ascii_case("Users") || n.name == "home");
+    if is_profiles(root) {
+        return Some(root);
+    }
+    root.children.iter().find(|c| is_profiles(c))
+}
+
+/// Break down each user profile in the scan, largest first. Empty when the scan
+/// holds no profiles folder.
+pub fn find_user_profiles(root: &FileNode) -> Vec<UserProfile> {
+    let Some(users) = profiles_folder(root) else { return Vec::new() };
+    let child = |node: &FileNode, name: &str| {
+        node.children.iter().find(|c| c.is_dir && c.name.eq_ignore_ascii_case(name)).map_or(0, |c| c.size)
+    };
+    let mut out: Vec<UserProfile> = users.children.iter()
+        .filter(|p| p.is_dir)
+        .map(|profile| {
+            let app_data = match profile.children.iter().find(|c| c.is_dir && c.name.eq_ignore_ascii_case("AppData")) {
+                Some(app_data) => ["Local", "LocalLow", "Roaming"].iter()
+                    .map(|name| (name.to_string(), child(app_data, name)))
+                    .filter(|(_, size)| *size > 0)
+                    .collect(),
+                None => profile.children.iter()
+                    .filter(|c| c.is_dir && c.name.starts_with('.'))
+                    .map(|c| (c.name.clone(), c.size))
+                    .collect(),
+            };
+            UserProfile {
+                name: profile.name.clone(),
+                path: profile.path.clone(),
+                size: profile.size,
+                desktop: child(profile, "Desktop"),
+                documents: child(profile, "Documents"),
+                downloads: child(profile, "Downloads"),
+                app_data,
+            }
+        })
+        .collect();
+    out.sort_by_key(|p| std::cmp::Reverse(p.size));
+    out
+}
+
+// ===================== Case & Short Names =====================
+
+/// Rough NTFS cost of one 8.3 alias: a second $FILE_NAME attribute in the file's
+/// MFT record plus a second entry in the folder's index.
+pub const SHORT_NAME_BYTES: u64 = 200;
+
+/// Characters allowed in an 8.3 name besides letters and digits.
+const SHORT_NAME_CHARS: &str = "!#$%&'()-@^_`{}~";
+
+pub struct NameIssues {
+    /// Case-sensitive folders (path, size, files). New subfolders inherit the flag,
+    /// so only the topmost one of each subtree is listed.
+    pub case_sensitive: Vec<(PathBuf, u64, u64)>,
+    /// Folders holding names that differ only by case, with those names.
+    pub case_clashes: Vec<(PathBuf, Vec<String>)>,
+    /// Names that need an 8.3 alias, per top-level folder (the root itself for its
+    /// own files). Most first.
+    pub long_names: Vec<(PathBuf, u64)>,
+    /// Whether a sampled long name actually has an alias: Some(false) when the
+    /// volume doesn't generate them. None when not checked.
+    pub short_names_on: Option<bool>,
+}
+
+impl NameIssues {
+    pub fn long_name_count(&self) -> u64 {
+        self.long_names.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// Whether NTFS would give `name` an 8.3 alias: anything that isn't a 1-8 character
+/// base with an optional 1-3 character extension of allowed characters, or that mixes
+/// case within either part.
+pub fn needs_short_name(name: &str) -> bool {
+    let fits = |part: &str, max: usize| {
+        !part.is_empty()
+            && part.len() <= max
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || SHORT_NAME_CHARS.contains(c))
+            && !(part.chars().any(|c| c.is_ascii_lowercase()) && part.chars().any(|c| c.is_ascii_uppercase()))
+    };
+    match name.split_once('.') {
+        Some((base, ext)) => !(fits(base, 8) && fits(ext, 3)),
+        None => !fits(name, 8),
+    }
+}
+
+/// Case-sensitive folders, names differing only by case, and (with `short_names`)
+/// how many names need an 8.3 alias. `probe` asks the file system whether one of
+/// those names really has an alias, so it is off for remote trees.
+pub fn find_name_issues(root: &FileNode, short_names: bool, probe: bool) -> NameIssues {
+    let mut issues = NameIssues { case_sensitive: Vec::new(), case_clashes: Vec::new(), long_names: Vec::new(), short_names_on: None };
+    find_case_issues(root, &mut issues);
+    issues.case_sensitive.sort_by_key(|(_, size, _)| std::cmp::Reverse(*size));
+
+    if short_names {
+        let mut sample = None;
+        let mut loose = 0;
+        for child in root.children.iter().filter(|c| !c.path.as_os_str().is_empty()) {
+            if child.is_dir {
+                let count = count_long_names(child, &mut sample);
+                if count > 0 {
+                    issues.long_names.push((child.path.clone(), count));
+                }
+            } else if needs_short_name(&child.name) {
+                loose += 1;
+                sample.get_or_insert(child.path.as_path());
+            }
+        }
+        if loose > 0 {
+            issues.long_names.push((root.path.clone(), loose));
+        }
+        issues.long_names.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        if probe && cfg!(windows) {
+            issues.short_names_on = sample.map(|path| short_name(path).is_some());
+        }
+    }
+    issues
+}
+
+fn find_case_issues(node: &FileNode, issues: &mut NameIssues) {
+    let mut by_lower: HashMap<String, Vec<&str>> = HashMap::new();
+    for child in node.children.iter().filter(|c| !c.path.as_os_str().is_empty()) {
+        by_lower.entry(child.name.to_lowercase()).or_default().push(&child.name);
+    }
+    let mut clashes: Vec<String> = by_lower.into_values()
+        .filter(|names| names.len() > 1)
+        .flatten()
+        .map(String::from)
+        .collect();
+    if !clashes.is_empty() {
+        clashes.sort();
+        issues.case_clashes.push((node.path.clone(), clashes));
+    }
+    for child in node.children.iter().filter(|c| c.is_dir) {
+        if child.attrs & ATTR_CASE_SENSITIVE != 0 && node.attrs & ATTR_CASE_SENSITIVE == 0 {
+            issues.case_sensitive.push((child.path.clone(), child.size, child.file_count));
+        }
+        find_case_issues(child, issues);
+    }
+}
+
+/// Names in `node` (itself included) that need an 8.3 alias. Files grouped into
+/// aggregates by Scan Options aren't seen. `sample` gets the first such path.
+fn count_long_names<'a>(node: &'a FileNode, sample: &mut Option<&'a Path>) -> u64 {
+    let mut count = 0;
+    if needs_short_name(&node.name) {
+        count += 1;
+        sample.get_or_insert(node.path.as_path());
+    }
+    for child in node.children.iter().filter(|c| !c.path.as_os_str().is_empty()) {
+        if child.is_dir {
+            count += count_long_names(child, sample);
+        } else if needs_short_name(&child.name) {
+            count += 1;
+            sample.get_or_insert(child.path.as_path());
+        }
+    }
+    count
+}
+
+// ===================== Cluster Slack =====================
+
+/// Folders listed in the Cluster Slack report.
+pub const SLACK_REPORT_ROWS: usize = 200;
+
+/// A folder's own files and what rounding them up to whole clusters costs.
+pub struct SlackFolder {
+    pub path: PathBuf,
+    /// Files directly in the folder (or summarized under it)
+    pub file_count: u64,
+    pub size: u64,
+    /// Allocated minus logical bytes
+    pub slack: u64,
+    /// Part of `slack` is estimated, for files the tree only has as a total
+    pub estimated: bool,
+}
+
+/// Bytes lost to rounding one file of `size` up to whole clusters. Empty files
+/// take no cluster.
+fn file_slack(size: u64, cluster: u64) -> u64 {
+    size.div_ceil(cluster) * cluster - size
+}
+
+/// Slack of `count` files totalling `size` whose individual sizes are unknown (Small
+/// Files aggregates, folders past the depth limit). Files averaging under a cluster
+/// mostly take exactly one; larger ones lose half a cluster each on average.
+fn estimated_slack(size: u64, count: u64, cluster: u64) -> u64 {
+    if count == 0 {
+        return 0;
+    }
+    if size / count < cluster {
+        (count * cluster).saturating_sub(size)
+    } else {
+        count * cluster / 2
+    }
+}
+
+/// Per-folder slack for `cluster`-byte clusters: the folders whose own files waste
+/// the most, most first, up to SLACK_REPORT_ROWS, plus the slack of the whole tree.
+/// Compressed, sparse and offline files are skipped since their allocation doesn't
+/// follow their size.
+pub fn find_cluster_slack(root: &FileNode, cluster: u64) -> (Vec<SlackFolder>, u64) {
+    let mut out = Vec::new();
+    find_cluster_slack_recursive(root, cluster.max(1), &mut out);
+    let total = out.iter().map(|f| f.slack).sum();
+    out.sort_by_key(|f| std::cmp::Reverse(f.slack));
+    out.truncate(SLACK_REPORT_ROWS);
+    (out, total)
+}
+
+fn find_cluster_slack_recursive(node: &FileNode, cluster: u64, out: &mut Vec<SlackFolder>) {
+    let mut folder = SlackFolder { path: node.path.clone(), file_count: 0, size: 0, slack: 0, estimated: false };
+    if node.children.is_empty() {
+        // Summarized by the depth limit: only the totals are known
+        folder.file_count = node.file_count;
+        folder.size = node.size;
+        folder.slack = estimated_slack(node.size, node.file_count, cluster);
+        folder.estimated = true;
+    }
+    for child in &node.children {
+        if child.is_dir {
+            find_cluster_slack_recursive(child, cluster, out);
+        } else if child.path.as_os_str().is_empty() {
+            if child.name == SMALL_FILES {
+                folder.file_count += child.file_count;
+                folder.size += child.size;
+                folder.slack += estimated_slack(child.size, child.file_count, cluster);
+                folder.estimated = true;
+            }
+        } else if child.attrs & (ATTR_COMPRESSED | ATTR_SPARSE) == 0 && !is_offline(child.attrs) {
+            folder.file_count += 1;
+            folder.size += child.size;
+            folder.slack += file_slack(child.size, cluster);
+        }
+    }
+    if folder.slack > 0 {
+        out.push(folder);
+    }
+}
+
+// ===================== App Caches =====================
+
+const BROWSER_GUIDANCE: &str = "Safe to clear. Close the browser first. Pages load slower until the cache refills.";
+const CHROMIUM_CACHE_DIRS: [&str; 4] = ["Cache", "Code Cache", "GPUCache", "Service Worker/CacheStorage"];
+
+pub struct AppCache {
+    pub app: &'static str,
+    pub path: PathBuf,
+    pub guidance: &'static str,
+    pub size: u64,
+    pub file_count: u64,
+    /// The owning app was running when measured. Clearing may skip locked files.
+    pub running: bool,
+}
+
+/// A well-known cache folder: (app, process name, path, guidance).
+type CacheCandidate = (&'static str, &'static str, PathBuf, &'static str);
+
+/// Cache folders inside every Chromium profile (Default, Profile 1, ...) under `user_data`.
+fn chromium_cache_dirs(user_data: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let Ok(rd) = std::fs::read_dir(user_data) else { return out };
+    for profile in rd.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        for rel in CHROMIUM_CACHE_DIRS {
+            let dir = rel.split('/').fold(profile.clone(), |p, c| p.join(c));
+            if dir.is_dir() {
+                out.push(dir);
+            }
+        }
+    }
+    out
+}
+
+/// Firefox keeps its disk cache in `<profile>/cache2`.
+fn firefox_cache_dirs(profiles: &Path) -> Vec<PathBuf> {
+    let Ok(rd) = std::fs::read_dir(profiles) else { return Vec::new() };
+    rd.flatten()
+        .map(|e| e.path().join("cache2"))
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn cache_candidates() -> Vec<CacheCandidate> {
+    let local = std::env::var("LOCALAPPDATA").map(PathBuf::from).unwrap_or_default();
+    let roaming = std::env::var("APPDATA").map(PathBuf::from).unwrap_or_default();
+    let mut out: Vec<CacheCandidate> = Vec::new();
+
+    for dir in chromium_cache_dirs(&local.join("Google").join("Chrome").join("User Data")) {
+        out.push(("Chrome", "chrome.exe", dir, BROWSER_GUIDANCE));
+    }
+    for dir in chromium_cache_dirs(&local.join("Microsoft").join("Edge").join("User Data")) {
+        out.push(("Edge", "msedge.exe", dir, BROWSER_GUIDANCE));
+    }
+    for dir in firefox_cache_dirs(&local.join("Mozilla").join("Firefox").join("Profiles")) {
+        out.push(("Firefox", "firefox.exe", dir, BROWSER_GUIDANCE));
+    }
+    out.push(("Spotify", "Spotify.exe", local.join("Spotify").join("Storage"),
+        "Streaming cache. Safe to clear. Offline downloads are stored separately."));
+    for sub in ["Cache", "Code Cache", "GPUCache"] {
+        out.push(("Discord", "Discord.exe", roaming.join("discord").join(sub),
+            "Safe to clear. Quit Discord (tray icon) first."));
+    }
+    for sub in ["Cache", "Code Cache", "GPUCache", "Service Worker/CacheStorage"] {
+        let dir = sub.split('/').fold(roaming.join("Microsoft").join("Teams"), |p, c| p.join(c));
+        out.push(("Teams (classic)", "Teams.exe", dir,
+            "Safe to clear. Quit Teams first. You stay signed in."));
+    }
+    let new_teams = local.join("Packages").join("MSTeams_8wekyb3d8bbwe").join("LocalCache")
+        .join("Microsoft").join("MSTeams").join("EBWebView");
+    for dir in chromium_cache_dirs(&new_teams) {
+        out.push(("Teams", "ms-teams.exe", dir, "Safe to clear. Quit Teams first. You stay signed in."));
+    }
+    for sub in ["Media Cache Files", "Media Cache"] {
+        out.push(("Adobe", "Adobe Premiere Pro.exe", roaming.join("Adobe").join("Common").join(sub),
+            "Safe to clear when no project is open. Premiere/After Effects rebuild conformed media on demand."));
+    }
+    out
+}
+
+#[cfg(not(target_os = "windows"))]
+fn cache_candidates() -> Vec<CacheCandidate> {
+    let home = std::env::var("HOME").map(PathBuf::from).unwrap_or_default();
+    let cache = home.join(".cache");
+    let config = home.join(".config");
+    let mut out: Vec<CacheCandidate> = Vec::new();
+
+    for dir in chromium_cache_dirs(&cache.join("google-chrome")) {
+        out.push(("Chrome", "chrome", dir, BROWSER_GUIDANCE));
+    }
+    for dir in chromium_cache_dirs(&cache.join("microsoft-edge")) {
+        out.push(("Edge", "msedge", dir, BROWSER_GUIDANCE));
+    }
+    for dir in firefox_cache_dirs(&cache.join("mozilla").join("firefox")) {
+        out.push(("Firefox", "firefox", dir, BROWSER_GUIDANCE));
+    }
+    out.push(("Spotify", "spotify", cache.join("spotify"),
+        "Streaming cache. Safe to clear. Offline downloads are stored separately."));
+    for sub in ["Cache", "Code Cache", "GPUCache"] {
+        out.push(("Discord", "Discord", config.join("discord").join(sub),
+            "Safe to clear. Quit Discord first."));
+    }
+    out
+}
+
+/// Lowercased names of running processes.
+fn running_process_names() -> std::collections::HashSet<String> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    sys.processes().values()
+        .map(|p| p.name().to_string_lossy().to_lowercase())
+        .collect()
+}
+
+/// Measure every known cache folder that exists. Returns None if cancelled.
+pub fn measure_app_caches(progress: Arc<ScanProgress>) -> Option<Vec<AppCache>> {
+    let running = running_process_names();
+    let mut out = Vec::new();
+    for (app, process, path, guidance) in cache_candidates() {
+        if !path.is_dir() {
+            continue;
+        }
+        let node = scan_directory(&path, progress.clone(), &ScanOptions::default())?;
+        if node.size == 0 {
+            continue;
+        }
+        out.push(AppCache {
+            app,
+            path,
+            guidance,
+            size: node.size,
+            file_count: node.file_count,
+            running: running.contains(&process.to_lowercase()),
+        });
+    }
+    out.sort_by_key(|c| std::cmp::Reverse(c.size));
+    Some(out)
+}
+
+/// Delete everything inside `dir` but keep the folder itself, so the app can
+/// recreate its cache. Locked files are skipped. Returns (bytes freed, files skipped).
+pub fn clear_dir_contents(dir: &Path) -> (u64, u64) {
+    let mut freed = 0;
+    let mut skipped = 0;
+    clear_dir_contents_recursive(dir, &mut freed, &mut skipped);
+    (freed, skipped)
+}
+
+fn clear_dir_contents_recursive(dir: &Path, freed: &mut u64, skipped: &mut u64) {
+    let Ok(rd) = std::fs::read_dir(long_path(dir)) else { return };
+    for entry in rd.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            clear_dir_contents_recursive(&path, freed, skipped);
+            let _ = std::fs::remove_dir(&path);
+        } else {
+            match std::fs::remove_file(&path) {
+                Ok(()) => *freed += meta.len(),
+                Err(_) => *skipped += 1,
+            }
+        }
+    }
+}
+
+// ===================== System =====================
+
+/// A supported cleanup mechanism, launched as `program args...`.
+pub struct CleanupAction {
+    pub label: &'static str,
+    pub program: &'static str,
+    pub args: &'static [&'static str],
+}
+
+impl CleanupAction {
+    pub fn launch(&self) {
+        let _ = std::process::Command::new(self.program).args(self.args).spawn();
+    }
+}
+
+/// One line of the System report. `size` is None when the folder can't be read.
+pub struct SystemItem {
+    pub name: &'static str,
+    pub path: Option<PathBuf>,
+    pub size: Option<u64>,
+    pub note: String,
+    pub actions: Vec<CleanupAction>,
+}
+
+#[cfg(target_os = "windows")]
+const STORAGE_SETTINGS: CleanupAction = CleanupAction {
+    label: "Temporary files settings",
+    program: "explorer",
+    args: &["ms-settings:storagesense"],
+};
+#[cfg(target_os = "windows")]
+const DISK_CLEANUP: CleanupAction = CleanupAction {
+    label: "Disk Cleanup",
+    program: "cleanmgr",
+    args: &[],
+};
+
+/// Measure a system folder. Missing folders count as 0; unreadable ones return the reason.
+fn measure_system_dir(path: &Path, progress: &Arc<ScanProgress>) -> Result<u64, String> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    if std::fs::read_dir(path).is_err() {
+        return Err("Access denied. Run SpaceView as administrator to measure.".to_string());
+    }
+    scan_directory(path, progress.clone(), &ScanOptions::default())
+        .map(|n| n.size)
+        .ok_or_else(|| "Cancelled".to_string())
+}
+
+fn system_item(name: &'static str, path: PathBuf, guidance: &str, actions: Vec<CleanupAction>, progress: &Arc<ScanProgress>) -> SystemItem {
+    let (size, note) = match measure_system_dir(&path, progress) {
+        Ok(size) => (Some(size), guidance.to_string()),
+        Err(reason) => (None, reason),
+    };
+    SystemItem { name, path: Some(path), size, note, actions }
+}
+
+/// One volume's shadow copy storage as listed by `vssadmin list shadowstorage`.
+#[cfg(target_os = "windows")]
+struct ShadowStorage {
+    volume: String,
+    used: u64,
+    /// Size cap as printed ("10 GB", "UNBOUNDED")
+    max: String,
+}
+
+/// Parse `vssadmin list shadowstorage` (English output). Each association block starts
+/// with "For volume: (C:)\\?\Volume{...}\" and carries Used / Maximum lines.
+#[cfg(target_os = "windows")]
+fn parse_shadow_storage(text: &str) -> Vec<ShadowStorage> {
+    let mut out: Vec<ShadowStorage> = Vec::new();
+    for line in text.lines() {
+        let Some((key, val)) = line.trim().split_once(": ") else { continue };
+        // "1.23 GB (0%)" -> "1.23 GB"
+        let amount = val.split(" (").next().unwrap_or(val).trim();
+        match key {
+            "For volume" => {
+                let volume = val.strip_prefix('(').and_then(|v| v.split_once(')')).map_or(val, |(v, _)| v);
+                out.push(ShadowStorage { volume: volume.to_string(), used: 0, max: String::new() });
+            }
+            "Used Shadow Copy Storage space" => {
+                if let Some(last) = out.last_mut() {
+                    last.used = parse_vss_size(amount).unwrap_or(0);
+                }
+            }
+            "Maximum Shadow Copy Storage space" => {
+                if let Some(last) = out.last_mut() {
+                    last.max = amount.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// "1.23 GB", "512 KB", "0 bytes" in binary units.
+#[cfg(target_os = "windows")]
+fn parse_vss_size(text: &str) -> Option<u64> {
+    let (num, unit) = text.split_once(' ')?;
+    let num: f64 = num.replace(',', "").parse().ok()?;
+    let scale = match unit.trim().to_ascii_uppercase().as_str() {
+        "B" | "BYTES" => 1u64,
+        "KB" => 1 << 10,
+        "MB" => 1 << 20,
+        "GB" => 1 << 30,
+        "TB" => 1 << 40,
+        "PB" => 1 << 50,
+        _ => return None,
+    };
+    Some((num * scale as f64) as u64)
+}
+
+/// Previous Versions / restore point storage: invisible to a file walk (it lives in
+/// System Volume Information), so ask the Volume Shadow Copy service via vssadmin.
+#[cfg(target_os = "windows")]
+fn shadow_copy_item() -> SystemItem {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let name = "Shadow copies (Previous Versions)";
+    let actions = vec![CleanupAction { label: "System Protection", program: "SystemPropertiesProtection", args: &[] }];
+    let output = std::process::Command::new("vssadmin")
+        .args(["list", "shadowstorage"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+    // Without admin rights vssadmin prints an error and no associations
+    let text = output.map(|out| String::from_utf8_lossy(&out.stdout).to_string()).unwrap_or_default();
+    let volumes = parse_shadow_storage(&text);
+    if volumes.is_empty() {
+        let none = text.contains("No items found");
+        let note = if none {
+            "No shadow copy storage in use. System Protection is off on every drive."
+        } else {
+            "Access denied. Run SpaceView as administrator to measure."
+        };
+        return SystemItem { name, path: None, size: none.then_some(0), note: note.to_string(), actions };
+    }
+    let used: u64 = volumes.iter().map(|v| v.used).sum();
+    let per_volume: Vec<String> = volumes.iter()
+        .map(|v| format!("{} {} of {} max", v.volume, crate::app::format_size(v.used), v.max))
+        .collect();
+    let example = &volumes[0].volume;
+    let note = format!(
+        "Previous Versions are using {} ({}). Restore points and earlier versions of files live here, \
+         where no scan can see them. Lower the cap in System Protection > Configure, or as administrator: \
+         vssadmin resize shadowstorage /for={v} /on={v} /maxsize=5%",
+        crate::app::format_size(used), per_volume.join(", "), v = example,
+    );
+    SystemItem { name, path: None, size: Some(used), note, actions }
+}
+
+#[cfg(target_os = "windows")]
+fn system_items(progress: &Arc<ScanProgress>) -> Vec<SystemItem> {
+    let windir = std::env::var("SystemRoot").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("C:\\Windows"));
+    let drive = std::env::var("SystemDrive").map(|d| PathBuf::from(format!("{}\\", d))).unwrap_or_else(|_| PathBuf::from("C:\\"));
+    vec![
+        system_item(
+            "Windows Update downloads",
+            windir.join("SoftwareDistribution").join("Download"),
+            "Downloaded update packages. Windows removes them after install; clear leftovers with Disk Cleanup > Windows Update Cleanup.",
+            vec![DISK_CLEANUP, STORAGE_SETTINGS],
+            progress,
+        ),
+        system_item(
+            "Delivery Optimization cache",
+            windir.join("ServiceProfiles").join("NetworkService").join("AppData").join("Local")
+                .join("Microsoft").join("Windows").join("DeliveryOptimization").join("Cache"),
+            "Update files shared with other PCs. Clear via Temporary files > Delivery Optimization Files, or cap it in settings.",
+            vec![
+                STORAGE_SETTINGS,
+                CleanupAction { label: "Delivery Optimization settings", program: "explorer", args: &["ms-settings:delivery-optimization"] },
+            ],
+            progress,
+        ),
+        system_item(
+            "Previous Windows installation",
+            drive.join("Windows.old"),
+            "Kept about 10 days after a feature update for rollback. Remove via Temporary files > Previous Windows installation(s).",
+            vec![STORAGE_SETTINGS, DISK_CLEANUP],
+            progress,
+        ),
+        system_item(
+            "Windows upgrade staging",
+            drive.join("$Windows.~BT"),
+            "Left over from feature upgrades. Remove via Temporary files > Temporary Windows installation files.",
+            vec![STORAGE_SETTINGS],
+            progress,
+        ),
+        shadow_copy_item(),
+    ]
+}
+
+#[cfg(not(target_os = "windows"))]
+fn system_items(progress: &Arc<ScanProgress>) -> Vec<SystemItem> {
+    vec![
+        system_item(
+            "APT package cache",
+            PathBuf::from("/var/cache/apt/archives"),
+            "Downloaded .deb packages. Clear with `sudo apt clean`.",
+            Vec::new(),
+            progress,
+        ),
+        system_item(
+            "systemd journal",
+            PathBuf::from("/var/log/journal"),
+            "System logs. Shrink with `sudo journalctl --vacuum-size=200M`.",
+            Vec::new(),
+            progress,
+        ),
+    ]
+}
+
+/// Measure OS-managed storage that users can't safely delete by hand but can
+/// reclaim through the supported cleanup tools.
+pub fn measure_system(progress: Arc<ScanProgress>) -> Option<Vec<SystemItem>> {
+    let items = system_items(&progress);
+    if progress.cancel.load(std::sync::atomic::Ordering::Relaxed) {
+        return None;
+    }
+    Some(items)
+}
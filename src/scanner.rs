@@ -1,7 +1,11 @@
+use crate::ignore_file::{IGNORE_FILE, IgnoreRules, ancestor_rules, is_ignored};
+use crate::paths::long_path;
+use crate::throttle::RateLimiter;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Debug)]
 pub struct FileNode {
@@ -10,35 +14,583 @@ pub struct FileNode {
     pub size: u64,
     pub is_dir: bool,
     pub file_count: u64,
-    pub modified: u64, // seconds since epoch (0 = unknown)
+    /// Seconds since epoch (0 = unknown). Folders carry their newest descendant's time,
+    /// not their own mtime, so Age mode shows a folder with fresh files as recent.
+    pub modified: u64,
+    pub attrs: u16,    // ATTR_* bits
+    /// Size on disk: less than `size` for compressed and sparse files, more from
+    /// cluster rounding, 0 for data stored elsewhere. Folders sum their contents.
+    /// Trees from sources that don't report it carry `size` here.
+    pub allocated: u64,
     pub children: Vec<FileNode>,
 }
 
-/// Get free space for the drive containing `path`.
-pub fn get_free_space(path: &Path) -> Option<u64> {
+/// Sparse file: its logical size can be far above what it occupies on disk.
+pub const ATTR_SPARSE: u16 = 1;
+/// Data moved to offline storage by an HSM / archive system.
+pub const ATTR_OFFLINE: u16 = 2;
+/// Cloud placeholder (OneDrive Files On-Demand etc.): reading it downloads the data.
+pub const ATTR_RECALL: u16 = 4;
+/// Symbolic link or junction. Not followed, so it shows as a tiny file, unless
+/// `ScanOptions::follow_links` walked it: then it marks the folder it reached.
+pub const ATTR_LINK: u16 = 8;
+/// NTFS-compressed: takes less room on disk than its size.
+pub const ATTR_COMPRESSED: u16 = 16;
+/// Marked as a system file or folder.
+pub const ATTR_SYSTEM: u16 = 32;
+/// Folder managed by a cloud sync client such as OneDrive.
+pub const ATTR_SYNCED: u16 = 64;
+/// Folder holding a `.git` directory (or a worktree's `.git` file).
+pub const ATTR_GIT: u16 = 128;
+/// NTFS folder with per-directory case sensitivity on (WSL creates these), so it
+/// can hold names that differ only by case.
+pub const ATTR_CASE_SENSITIVE: u16 = 256;
+/// File with more than one hard link (WinSxS, package caches). Only looked for when
+/// `ScanOptions::count_links_once` is on.
+pub const ATTR_HARDLINK: u16 = 512;
+
+/// Whether a file's data is stored somewhere other than the local disk.
+pub fn is_offline(attrs: u16) -> bool {
+    attrs & (ATTR_OFFLINE | ATTR_RECALL) != 0
+}
+
+/// Human-readable names of the set ATTR_* bits, for tooltips.
+pub fn attr_labels(attrs: u16) -> Vec<&'static str> {
+    [
+        (ATTR_SPARSE, "Sparse"),
+        (ATTR_OFFLINE, "Offline"),
+        (ATTR_RECALL, "Cloud (not downloaded)"),
+        (ATTR_LINK, "Link"),
+        (ATTR_COMPRESSED, "Compressed"),
+        (ATTR_SYSTEM, "System"),
+        (ATTR_SYNCED, "Cloud-synced"),
+        (ATTR_GIT, "Git repository"),
+        (ATTR_CASE_SENSITIVE, "Case-sensitive"),
+        (ATTR_HARDLINK, "Hard link"),
+    ]
+        .into_iter()
+        .filter(|(bit, _)| attrs & bit != 0)
+        .map(|(_, label)| label)
+        .collect()
+}
+
+/// Map the Windows file attributes of an entry (file, folder or link) to ATTR_* bits.
+#[cfg(target_os = "windows")]
+fn file_attrs(metadata: &std::fs::Metadata) -> u16 {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+    const FILE_ATTRIBUTE_SPARSE_FILE: u32 = 0x200;
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    const FILE_ATTRIBUTE_COMPRESSED: u32 = 0x800;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x4_0000;
+    const FILE_ATTRIBUTE_PINNED: u32 = 0x8_0000;
+    const FILE_ATTRIBUTE_UNPINNED: u32 = 0x10_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x40_0000;
+    let raw = metadata.file_attributes();
+    let is_link = metadata.file_type().is_symlink();
+    let mut attrs = 0;
+    if is_link {
+        attrs |= ATTR_LINK;
+    }
+    if raw & FILE_ATTRIBUTE_SYSTEM != 0 {
+        attrs |= ATTR_SYSTEM;
+    }
+    if raw & FILE_ATTRIBUTE_COMPRESSED != 0 {
+        attrs |= ATTR_COMPRESSED;
+    }
+    // Folders under a sync root are cloud-filter reparse points (not links) and
+    // carry the pin state the user picked in Explorer
+    if metadata.is_dir()
+        && (raw & (FILE_ATTRIBUTE_PINNED | FILE_ATTRIBUTE_UNPINNED | FILE_ATTRIBUTE_RECALL_ON_OPEN) != 0
+            || raw & FILE_ATTRIBUTE_REPARSE_POINT != 0 && !is_link)
+    {
+        attrs |= ATTR_SYNCED;
+    }
+    if raw & FILE_ATTRIBUTE_SPARSE_FILE != 0 {
+        attrs |= ATTR_SPARSE;
+    }
+    if raw & FILE_ATTRIBUTE_OFFLINE != 0 {
+        attrs |= ATTR_OFFLINE;
+    }
+    if raw & (FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS) != 0 {
+        attrs |= ATTR_RECALL;
+    }
+    attrs
+}
+
+#[cfg(not(target_os = "windows"))]
+fn file_attrs(metadata: &std::fs::Metadata) -> u16 {
+    if metadata.file_type().is_symlink() { ATTR_LINK } else { 0 }
+}
+
+/// Whether NTFS per-directory case sensitivity is on for `dir`. Needs a handle, so
+/// it costs one open per folder and only runs with `case_sensitive_dirs`; file
+/// systems without the flag just answer false.
+#[cfg(target_os = "windows")]
+fn is_case_sensitive_dir(dir: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_FLAG_BACKUP_SEMANTICS, FILE_READ_ATTRIBUTES, FileCaseSensitiveInfo, GetFileInformationByHandleEx,
+    };
+    const FILE_CS_FLAG_CASE_SENSITIVE_DIR: u32 = 0x1;
+    // Folders only open with backup semantics; attribute access works even without read rights
+    let Ok(handle) = std::fs::OpenOptions::new()
+        .access_mode(FILE_READ_ATTRIBUTES)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(long_path(dir))
+    else {
+        return false;
+    };
+    // FILE_CASE_SENSITIVE_INFO is a single ULONG of flags
+    let mut flags: u32 = 0;
+    let ok = unsafe {
+        GetFileInformationByHandleEx(
+            handle.as_raw_handle(),
+            FileCaseSensitiveInfo,
+            &mut flags as *mut u32 as *mut std::ffi::c_void,
+            std::mem::size_of::<u32>() as u32,
+        )
+    };
+    ok != 0 && flags & FILE_CS_FLAG_CASE_SENSITIVE_DIR != 0
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_case_sensitive_dir(_dir: &Path) -> bool {
+    false
+}
+
+/// Volume and file ID of a file with more than one hard link, None for a file with
+/// just one. The directory listing doesn't carry the link count on Windows, so this
+/// opens the file (attributes only, no data read or recalled).
+#[cfg(target_os = "windows")]
+fn hard_link_id(path: &Path, _metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        BY_HANDLE_FILE_INFORMATION, FILE_READ_ATTRIBUTES, GetFileInformationByHandle,
+    };
+    let file = std::fs::OpenOptions::new().access_mode(FILE_READ_ATTRIBUTES).open(long_path(path)).ok()?;
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return None;
+    }
+    let index = (info.nFileIndexHigh as u64) << 32 | info.nFileIndexLow as u64;
+    (info.nNumberOfLinks > 1).then_some((info.dwVolumeSerialNumber as u64, index))
+}
+
+#[cfg(unix)]
+fn hard_link_id(_path: &Path, metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn hard_link_id(_path: &Path, _metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Bytes a file of `size` takes on disk: 0 for data stored elsewhere (offline,
+/// cloud-only), otherwise whole clusters (`cluster` 0 = no rounding) of what the file
+/// system stores, which is less than `size` for compressed and sparse files.
+fn allocated_size(path: &Path, metadata: &std::fs::Metadata, size: u64, attrs: u16, cluster: u64) -> u64 {
+    if is_offline(attrs) || size == 0 {
+        return 0;
+    }
+    let stored = stored_size(path, metadata, attrs).unwrap_or(size);
+    if cluster > 0 { stored.div_ceil(cluster) * cluster } else { stored }
+}
+
+/// Bytes the file system stores for a compressed or sparse file (None for others:
+/// they store their size). Costs a call per such file.
+#[cfg(target_os = "windows")]
+fn stored_size(path: &Path, _metadata: &std::fs::Metadata, attrs: u16) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{GetLastError, NO_ERROR};
+    use windows_sys::Win32::Storage::FileSystem::{GetCompressedFileSizeW, INVALID_FILE_SIZE};
+    if attrs & (ATTR_COMPRESSED | ATTR_SPARSE) == 0 {
+        return None;
+    }
+    let wide: Vec<u16> = long_path(path).as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut high = 0u32;
+    let low = unsafe { GetCompressedFileSizeW(wide.as_ptr(), &mut high) };
+    // INVALID_FILE_SIZE is also a valid low half; only the error code tells them apart
+    if low == INVALID_FILE_SIZE && unsafe { GetLastError() } != NO_ERROR {
+        return None;
+    }
+    Some((high as u64) << 32 | low as u64)
+}
+
+/// Unix reports the blocks actually allocated, already in whole blocks.
+#[cfg(unix)]
+fn stored_size(_path: &Path, metadata: &std::fs::Metadata, _attrs: u16) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.blocks() * 512)
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn stored_size(_path: &Path, _metadata: &std::fs::Metadata, _attrs: u16) -> Option<u64> {
+    None
+}
+
+/// Capacity of the drive (mount) that holds a path.
+#[derive(Clone, Debug)]
+pub struct DriveSpace {
+    pub mount_point: PathBuf,
+    pub total: u64,
+    pub available: u64,
+}
+
+/// Allocation unit of the volume holding `path`: the NTFS/FAT cluster size on
+/// Windows, the file system block size elsewhere. None when it can't be read.
+#[cfg(target_os = "windows")]
+pub fn cluster_size(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{GetDiskFreeSpaceW, GetVolumePathNameW};
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut volume = vec![0u16; 1024];
+    // `C:\`, a mount folder, or `\\server\share\`
+    if unsafe { GetVolumePathNameW(wide.as_ptr(), volume.as_mut_ptr(), volume.len() as u32) } == 0 {
+        return None;
+    }
+    let (mut sectors, mut bytes, mut free, mut total) = (0u32, 0u32, 0u32, 0u32);
+    let ok = unsafe { GetDiskFreeSpaceW(volume.as_ptr(), &mut sectors, &mut bytes, &mut free, &mut total) };
+    (ok != 0 && sectors > 0 && bytes > 0).then(|| sectors as u64 * bytes as u64)
+}
+
+#[cfg(unix)]
+pub fn cluster_size(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.blksize()).filter(|&b| b > 0)
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+pub fn cluster_size(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Find the drive containing `path` (longest matching mount point).
+pub fn drive_space(path: &Path) -> Option<DriveSpace> {
     use sysinfo::Disks;
     let disks = Disks::new_with_refreshed_list();
     // Don't use canonicalize: it adds \\?\ prefix on Windows which breaks starts_with
-    let mut best: Option<(usize, u64)> = None;
-    for disk in disks.list() {
-        let mp = disk.mount_point();
-        if path.starts_with(mp) {
-            let len = mp.to_string_lossy().len();
-            if best.is_none() || len > best.unwrap().0 {
-                best = Some((len, disk.available_space()));
+    disks.list().iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| DriveSpace {
+            mount_point: disk.mount_point().to_path_buf(),
+            total: disk.total_space(),
+            available: disk.available_space(),
+        })
+}
+
+/// Get free space for the drive containing `path`.
+pub fn get_free_space(path: &Path) -> Option<u64> {
+    drive_space(path).map(|d| d.available)
+}
+/// Name of the pseudo-node that stands in for a folder's files below `min_file_size`.
+/// Like `<Free Space>` it has an empty path, so file actions skip it.
+pub const SMALL_FILES: &str = "<Small Files>";
+
+/// Name of the placeholder left in a folder whose scan was cancelled part-way: the
+/// folder's totals only cover what was reached. Empty path and zero size, like `SMALL_FILES`.
+pub const NOT_SCANNED: &str = "<Not Scanned>";
+
+/// Name of the drive-root tile for space the scan couldn't see: capacity minus free
+/// space minus everything scanned (permissions, NTFS metadata, shadow copies).
+pub const UNKNOWN_SPACE: &str = "<Unknown>";
+
+/// Name and path of the virtual root of an all-drives scan, with one child per local
+/// drive. Not a real path: scans and rescans of it go through `scan_all_drives`.
+pub const ALL_DRIVES: &str = "<All Drives>";
+
+pub fn is_all_drives(path: &Path) -> bool {
+    path.as_os_str() == ALL_DRIVES
+}
+
+/// `<Free Space>` and `<Unknown>`: drive-level tiles with no files behind them.
+pub fn is_space_tile(name: &str) -> bool {
+    name == "<Free Space>" || name == UNKNOWN_SPACE
+}
+
+/// ATTR_LINK for a folder reached through a link (see `ScanProgress::resolve_link`),
+/// whose metadata is its target's.
+fn link_bit(entry: &std::fs::DirEntry) -> u16 {
+    if entry.file_type().is_ok_and(|t| t.is_symlink()) { ATTR_LINK } else { 0 }
+}
+
+/// `ignore_files`: the ignore file of the folder `dir` listed as `entries`, if it has one.
+fn own_rules(options: &ScanOptions, dir: &Path, entries: &[std::fs::DirEntry]) -> Option<IgnoreRules> {
+    let has_file = options.ignore_files && entries.iter().any(|e| e.file_name() == IGNORE_FILE);
+    has_file.then(|| IgnoreRules::load(dir)).flatten()
+}
+
+/// `ignore_files`: the ignore files above the scan root, outermost first.
+fn root_rules(options: &ScanOptions, root: &Path) -> Vec<IgnoreRules> {
+    if options.ignore_files { ancestor_rules(root) } else { Vec::new() }
+}
+
+fn not_scanned_node() -> FileNode {
+    FileNode {
+        name: NOT_SCANNED.to_string(),
+        path: PathBuf::new(),
+        size: 0,
+        is_dir: false,
+        file_count: 0,
+        modified: 0,
+        attrs: 0,
+        allocated: 0,
+        children: Vec::new(),
+    }
+}
+
+/// Whether the scan of `node` was cut short somewhere below it.
+pub fn is_partial(node: &FileNode) -> bool {
+    node.children.iter().any(|c| c.name == NOT_SCANNED || (c.is_dir && is_partial(c)))
+}
+
+/// Scan-time filters that trade detail for speed and memory on huge volumes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScanOptions {
+    /// Keep folders at most this many levels below the root (0 = unlimited).
+    /// Deeper folders are still measured but show up as leaves.
+    pub max_depth: usize,
+    /// Files smaller than this many bytes are summed into one `SMALL_FILES` node per folder.
+    pub min_file_size: u64,
+    /// Count the full size of offline / cloud-only files. Off by default: that data
+    /// isn't using local disk, so it would inflate the totals.
+    pub include_offline: bool,
+    /// Network volume profile: list folders on parallel threads and record how long
+    /// each listing takes. The UI also skips free-space queries and snapshots less often.
+    pub network: bool,
+    /// Include-only filter for targeted audits: keep just files whose name matches one
+    /// of these lowercase wildcard patterns (`*.log`). Empty = every file.
+    pub include_names: Vec<String>,
+    /// Include-only filter: keep just files at least this large (0 = every file).
+    pub include_min_size: u64,
+    /// Folders left out entirely, like SpaceView's own data folder. Set per scan,
+    /// not a user filter.
+    pub skip_dirs: Vec<PathBuf>,
+    /// User exclusions, lowercase wildcard patterns. Matching files and folders are
+    /// never read: not listed, measured or counted. A pattern with a `\` or `/`
+    /// matches the whole path (`c:\windows\winsxs`, `**/node_modules`), one without
+    /// just the name (`*.iso`, `node_modules`).
+    pub exclude: Vec<String>,
+    /// Tree node budget (0 = unlimited). Set per scan from Settings, not a user filter.
+    /// Once the tree holds this many nodes the rest of the scan is aggregated: folders
+    /// started after that are measured but keep no contents, as past the depth limit.
+    pub max_nodes: u64,
+    /// Count each hard-linked file once: the first link found gets the size, later
+    /// links to the same file count 0 bytes, so totals match the disk. Costs an open
+    /// per file on Windows.
+    pub count_links_once: bool,
+    /// Allocation unit of the scanned volume, for the size on disk. Read from the
+    /// volume when the scan starts if left at 0.
+    pub cluster: u64,
+    /// Walk into folder links (symlinks, junctions) instead of listing them as tiny
+    /// files. A link whose target holds or lies inside something the scan already
+    /// covers is still not followed, so loops end and nothing counts twice.
+    pub follow_links: bool,
+    /// Leave out what `.spaceviewignore` files in the scanned folders (or above the
+    /// root) list, so projects can keep their build outputs out of every scan.
+    pub ignore_files: bool,
+    /// Read each folder's NTFS case-sensitivity flag (ATTR_CASE_SENSITIVE). Costs an
+    /// open per folder on Windows, so it's off unless asked for.
+    pub case_sensitive_dirs: bool,
+}
+
+/// Scan threads a network-profile scan may run at once. Listing a remote folder is
+/// mostly waiting on round-trips, so many requests in flight hide the latency.
+const NETWORK_THREADS: usize = 16;
+/// Slowest folder listings kept for display.
+const SLOWEST_LISTINGS: usize = 5;
+/// How often a suspended scan thread wakes to check for a cancel (resume wakes it at once).
+const PAUSED_CANCEL_POLL: Duration = Duration::from_millis(250);
+
+/// UNC paths (`\\server\share`) are always on the network.
+pub fn is_network_path(path: &Path) -> bool {
+    let text = path.to_string_lossy();
+    text.starts_with(r"\\") && !text.starts_with(r"\\?\") && !text.starts_with(r"\\.\")
+}
+
+impl ScanOptions {
+    pub fn is_filtered(&self) -> bool {
+        self.max_depth > 0 || self.min_file_size > 0 || self.is_include_only()
+    }
+
+    /// Whether files outside the include-only filter are left out of the scan.
+    pub fn is_include_only(&self) -> bool {
+        !self.include_names.is_empty() || self.include_min_size > 0
+    }
+
+    /// Whether a file passes the include-only filter. Files that don't are skipped
+    /// entirely: not in the tree, the folder sizes or the file counts.
+    fn includes(&self, name: &str, size: u64) -> bool {
+        if size < self.include_min_size {
+            return false;
+        }
+        if self.include_names.is_empty() {
+            return true;
+        }
+        let name = name.to_lowercase();
+        self.include_names.iter().any(|p| wildcard_match(p.as_bytes(), name.as_bytes()))
+    }
+
+    /// Whether an entry matches one of the user exclusions (ignoring case).
+    fn excludes(&self, path: &Path, name: &str) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        let name = name.to_lowercase();
+        let mut full = None;
+        self.exclude.iter().any(|pattern| {
+            if pattern.contains(['/', '\\']) {
+                let full = full.get_or_insert_with(|| path.to_string_lossy().to_lowercase());
+                wildcard_match(pattern.as_bytes(), full.as_bytes())
+            } else {
+                wildcard_match(pattern.as_bytes(), name.as_bytes())
             }
+        })
+    }
+
+    /// These options with `cluster` read from the volume holding `root`, unless set.
+    fn for_volume(&self, root: &Path) -> Self {
+        Self { cluster: if self.cluster > 0 { self.cluster } else { cluster_size(root).unwrap_or(0) }, ..self.clone() }
+    }
+
+    /// Whether `dir` is one of `skip_dirs` (ignoring case on Windows).
+    fn skips(&self, dir: &Path) -> bool {
+        self.skip_dirs.iter().any(|d| {
+            if cfg!(windows) { d.as_os_str().eq_ignore_ascii_case(dir.as_os_str()) } else { d == dir }
+        })
+    }
+}
+
+/// Split `*.log; *.tmp` style text into the patterns `include_names` takes.
+pub fn parse_include_names(text: &str) -> Vec<String> {
+    text.split([';', ','])
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Split exclusions, one per line or separated by `;`, into the patterns `exclude`
+/// takes. Trailing separators are dropped, so `node_modules\` matches the folder.
+pub fn parse_exclusions(text: &str) -> Vec<String> {
+    text.split(['\n', ';'])
+        .map(|p| p.trim().trim_end_matches(['/', '\\']).to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// `*` matches any run of characters (path separators too, so `**/x` is any `x`
+/// folder), `?` any one, `\` and `/` each other; everything else matches itself.
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    let same = |a: u8, b: u8| a == b || (matches!(a, b'/' | b'\\') && matches!(b, b'/' | b'\\'));
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was and the text position it currently stands in for
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || same(pattern[p], text[t])) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            // Let the star absorb one more character
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
         }
     }
-    best.map(|(_, space)| space)
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Running total of a folder's files below the size threshold.
+#[derive(Default)]
+struct SmallFiles {
+    size: u64,
+    allocated: u64,
+    count: u64,
+    modified: u64,
 }
 
+impl SmallFiles {
+    fn add(&mut self, size: u64, allocated: u64, modified: u64) {
+        self.size += size;
+        self.allocated += allocated;
+        self.count += 1;
+        self.modified = self.modified.max(modified);
+    }
+
+    fn into_node(self) -> Option<FileNode> {
+        (self.count > 0).then(|| FileNode {
+            name: SMALL_FILES.to_string(),
+            path: PathBuf::new(),
+            size: self.size,
+            is_dir: false,
+            file_count: self.count,
+            modified: self.modified,
+            attrs: 0,
+            allocated: self.allocated,
+            children: Vec::new(),
+        })
+    }
+}
 
 pub struct ScanProgress {
     pub files_scanned: AtomicU64,
     pub bytes_scanned: AtomicU64,
     pub cancel: AtomicBool,
+    /// Set through `set_paused`, which also wakes the suspended threads on resume
     pub paused: AtomicBool,
+    /// Scan threads currently suspended in `wait_while_paused`
+    pub suspended: AtomicUsize,
+    pause_lock: Mutex<()>,
+    resumed: Condvar,
     pub scan_start: Instant,
+    /// Extra scan threads running (network profile)
+    pub workers: AtomicUsize,
+    /// Network profile: folders listed and their total listing time
+    pub listings: AtomicU64,
+    pub listing_micros: AtomicU64,
+    /// Network profile: (microseconds, folder) of the slowest listings, slowest first
+    pub slowest_listings: Mutex<Vec<(u64, PathBuf)>>,
+    /// Caps folder listings + entry stats per second (unlimited unless `with_ops_limit`)
+    pub io_limit: RateLimiter,
+    /// Nodes kept in the tree so far, counted as each folder finishes
+    pub nodes: AtomicU64,
+    /// The node budget ran out and later folders kept their totals only
+    pub aggregated: AtomicBool,
+    /// `count_links_once`: files already counted, by `hard_link_id`
+    seen_links: Mutex<HashSet<(u64, u64)>>,
+    /// `count_links_once`: bytes of the further links that counted as 0
+    pub link_bytes: AtomicU64,
+    /// `follow_links`: canonical folders the scan covers (its roots and the link
+    /// targets followed so far)
+    claimed: Mutex<Vec<PathBuf>>,
+    /// `follow_links`: folder links left unfollowed because they loop back or lead
+    /// somewhere already counted
+    pub links_skipped: AtomicU64,
+    /// `ignore_files`: entries an ignore file left out
+    pub ignored: AtomicU64,
+    /// All-drives scans: where each drive is, in `local_drive_roots` order
+    pub drives: Mutex<Vec<DriveProgress>>,
+}
+
+/// One drive of an all-drives scan. The counts come from its latest partial tree,
+/// so they move as each top-level folder finishes rather than file by file.
+#[derive(Clone, Debug)]
+pub struct DriveProgress {
+    pub root: PathBuf,
+    pub files: u64,
+    pub bytes: u64,
+    pub done: bool,
 }
 
 impl ScanProgress {
@@ -48,21 +600,151 @@ impl ScanProgress {
             bytes_scanned: AtomicU64::new(0),
             cancel: AtomicBool::new(false),
             paused: AtomicBool::new(false),
+            suspended: AtomicUsize::new(0),
+            pause_lock: Mutex::new(()),
+            resumed: Condvar::new(),
             scan_start: Instant::now(),
+            workers: AtomicUsize::new(0),
+            listings: AtomicU64::new(0),
+            listing_micros: AtomicU64::new(0),
+            slowest_listings: Mutex::new(Vec::new()),
+            io_limit: RateLimiter::unlimited(),
+            nodes: AtomicU64::new(0),
+            aggregated: AtomicBool::new(false),
+            seen_links: Mutex::new(HashSet::new()),
+            link_bytes: AtomicU64::new(0),
+            claimed: Mutex::new(Vec::new()),
+            links_skipped: AtomicU64::new(0),
+            ignored: AtomicU64::new(0),
+            drives: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Progress for a scan limited to `ops_per_sec` read operations (0 = unlimited),
+    /// for file servers where a full-speed walk would slow everyone else down.
+    pub fn with_ops_limit(ops_per_sec: u64) -> Self {
+        Self { io_limit: RateLimiter::new(ops_per_sec), ..Self::new() }
+    }
+
+    /// Whether the tree has used up `options.max_nodes`, flagging the scan as
+    /// aggregated the first time it has.
+    fn node_budget_spent(&self, options: &ScanOptions) -> bool {
+        let spent = options.max_nodes > 0 && self.nodes.load(Ordering::Relaxed) >= options.max_nodes;
+        if spent {
+            self.aggregated.store(true, Ordering::Relaxed);
+        }
+        spent
+    }
+
+    /// `follow_links`: mark the scan root as covered before walking it.
+    fn claim_root(&self, options: &ScanOptions, root: &Path) {
+        if let Some(root) = std::fs::canonicalize(long_path(root)).ok().filter(|_| options.follow_links) {
+            self.claimed.lock().unwrap_or_else(|e| e.into_inner()).push(root);
+        }
+    }
+
+    /// The metadata to scan an entry by. With `follow_links`, a link to a folder
+    /// nothing covers yet gets its target's, so it is walked as that folder, and the
+    /// target is claimed. Links that would loop or recount keep their own.
+    fn resolve_link(&self, options: &ScanOptions, path: &Path, metadata: std::fs::Metadata) -> std::fs::Metadata {
+        if !options.follow_links || !metadata.file_type().is_symlink() {
+            return metadata;
+        }
+        let target = std::fs::metadata(long_path(path)).ok().filter(|t| t.is_dir());
+        let Some((target, canonical)) = target.zip(std::fs::canonicalize(long_path(path)).ok()) else { return metadata };
+        let mut claimed = self.claimed.lock().unwrap_or_else(|e| e.into_inner());
+        if claimed.iter().any(|c| c.starts_with(&canonical) || canonical.starts_with(c)) {
+            self.links_skipped.fetch_add(1, Ordering::Relaxed);
+            return metadata;
+        }
+        claimed.push(canonical);
+        target
+    }
+
+    /// Whether an ignore file in `rules` leaves out the entry at `path`, counting it if so.
+    fn ignores(&self, rules: &[&IgnoreRules], path: &Path, entry: &std::fs::DirEntry) -> bool {
+        let ignored = !rules.is_empty() && is_ignored(rules, path, entry.file_type().is_ok_and(|t| t.is_dir()));
+        if ignored {
+            self.ignored.fetch_add(1, Ordering::Relaxed);
+        }
+        ignored
+    }
+
+    /// The size to count for a file of `size` bytes. With `count_links_once`, a
+    /// hard-linked file is marked ATTR_HARDLINK and counts 0 after its first link.
+    fn linked_size(&self, options: &ScanOptions, path: &Path, metadata: &std::fs::Metadata, size: u64, attrs: &mut u16) -> u64 {
+        if !options.count_links_once || size == 0 || *attrs & ATTR_LINK != 0 {
+            return size;
+        }
+        let Some(id) = hard_link_id(path, metadata) else { return size };
+        *attrs |= ATTR_HARDLINK;
+        let first = self.seen_links.lock().unwrap_or_else(|e| e.into_inner()).insert(id);
+        if first {
+            return size;
+        }
+        self.link_bytes.fetch_add(size, Ordering::Relaxed);
+        0
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        // Under the lock, so a thread between its check and its wait can't miss the wake-up
+        let _guard = self.pause_lock.lock();
+        self.paused.store(paused, Ordering::Relaxed);
+        self.resumed.notify_all();
+    }
+
+    /// Block the calling scan thread while paused. It sleeps on a condition variable
+    /// rather than spinning, and holds no directory handle: every listing is read into
+    /// memory before its entries are visited. Returns false if the scan was cancelled.
+    fn wait_while_paused(&self) -> bool {
+        if self.paused.load(Ordering::Relaxed) {
+            let mut guard = self.pause_lock.lock().unwrap_or_else(|e| e.into_inner());
+            self.suspended.fetch_add(1, Ordering::Relaxed);
+            while self.paused.load(Ordering::Relaxed) && !self.cancel.load(Ordering::Relaxed) {
+                guard = match self.resumed.wait_timeout(guard, PAUSED_CANCEL_POLL) {
+                    Ok((guard, _)) => guard,
+                    Err(e) => e.into_inner().0,
+                };
+            }
+            self.suspended.fetch_sub(1, Ordering::Relaxed);
+        }
+        !self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn record_listing(&self, dir: &Path, elapsed: std::time::Duration) {
+        let micros = elapsed.as_micros() as u64;
+        self.listings.fetch_add(1, Ordering::Relaxed);
+        self.listing_micros.fetch_add(micros, Ordering::Relaxed);
+        if let Ok(mut slowest) = self.slowest_listings.lock() {
+            if slowest.len() < SLOWEST_LISTINGS || slowest.last().is_some_and(|(m, _)| micros > *m) {
+                slowest.push((micros, dir.to_path_buf()));
+                slowest.sort_by_key(|(m, _)| std::cmp::Reverse(*m));
+                slowest.truncate(SLOWEST_LISTINGS);
+            }
         }
     }
 }
 
-/// Live scanning: sends partial tree snapshots after each top-level child directory completes.
-/// Gives ~20-30 live updates for a typical drive (one per top-level dir).
+/// Live scanning: calls `on_snapshot` with the partial tree after each top-level child
+/// directory completes. Gives ~20-30 live updates for a typical drive (one per top-level dir).
+/// On cancel the tree is still returned: everything reached so far, with a `NOT_SCANNED`
+/// placeholder in each folder that was cut short (see `is_partial`). It is also reported
+/// once more through `on_snapshot`. None only if cancelled before it started.
+///
+/// `resume` is a partial tree from an interrupted scan of the same root: its fully scanned
+/// top-level directories are kept as-is and skipped, everything else is walked again.
 pub fn scan_directory_live(
     root: &Path,
     progress: Arc<ScanProgress>,
-    snapshot_tx: std::sync::mpsc::Sender<FileNode>,
+    resume: Option<FileNode>,
+    options: &ScanOptions,
+    mut on_snapshot: impl FnMut(&FileNode),
 ) -> Option<FileNode> {
     if progress.cancel.load(Ordering::Relaxed) {
         return None;
     }
+    let options = &options.for_volume(root);
+    progress.claim_root(options, root);
 
     let mut node = FileNode {
         name: root
@@ -74,49 +756,82 @@ pub fn scan_directory_live(
         is_dir: true,
         file_count: 0,
         modified: 0,
+        attrs: 0,
+        allocated: 0,
         children: Vec::new(),
     };
 
-    let entries: Vec<_> = match std::fs::read_dir(root) {
+    // Directories finished before the interruption (top-level files are cheap to redo)
+    let mut done_dirs = std::collections::HashSet::new();
+    if let Some(partial) = resume.filter(|p| p.path == root) {
+        for child in partial.children.into_iter().filter(|c| c.is_dir && !is_partial(c)) {
+            progress.files_scanned.fetch_add(child.file_count, Ordering::Relaxed);
+            progress.bytes_scanned.fetch_add(child.size, Ordering::Relaxed);
+            node.size += child.size;
+            node.allocated += child.allocated;
+            node.file_count += child.file_count;
+            done_dirs.insert(child.name.clone());
+            node.children.push(child);
+        }
+    }
+
+    progress.io_limit.take(1);
+    // Read through the extended-length form, but keep plain paths in the tree
+    let entries: Vec<_> = match std::fs::read_dir(long_path(root)) {
         Ok(rd) => rd.filter_map(|e| e.ok()).collect(),
         Err(_) => return Some(node),
     };
+    let above = root_rules(options, root);
+    let own = own_rules(options, root, &entries);
+    let ignores: Vec<&IgnoreRules> = above.iter().chain(&own).collect();
 
+    let mut small = SmallFiles::default();
+    let mut cut_short = false;
     for entry in entries {
-        if progress.cancel.load(Ordering::Relaxed) {
-            return None;
-        }
-        while progress.paused.load(Ordering::Relaxed) {
-            std::thread::sleep(std::time::Duration::from_millis(50));
-            if progress.cancel.load(Ordering::Relaxed) {
-                return None;
-            }
+        if !progress.wait_while_paused() {
+            cut_short = true;
+            break;
         }
 
-        let path = entry.path();
+        let path = root.join(entry.file_name());
+        if options.excludes(&path, &entry.file_name().to_string_lossy()) || progress.ignores(&ignores, &path, &entry) {
+            continue;
+        }
+        progress.io_limit.take(1);
         let metadata = match entry.metadata() {
-            Ok(m) => m,
+            Ok(m) => progress.resolve_link(options, &path, m),
             Err(_) => continue,
         };
 
         if metadata.is_dir() {
             let name = entry.file_name().to_string_lossy().to_string();
-            if name == "System Volume Information" || name == "$Recycle.Bin" {
+            if name == "System Volume Information" || name == "$Recycle.Bin" || done_dirs.contains(&name) || options.skips(&path) {
                 continue;
             }
-            if let Some(child) = scan_directory(&path, progress.clone()) {
-                node.size += child.size;
-                node.file_count += child.file_count;
-                if child.size > 0 {
-                    node.children.push(child);
-                }
-                // Sort and send snapshot after each top-level dir
-                node.children.sort_by(|a, b| b.size.cmp(&a.size));
-                node.modified = node.children.iter().map(|c| c.modified).max().unwrap_or(0);
-                let _ = snapshot_tx.send(node.clone());
+            let attrs = file_attrs(&metadata) | link_bit(&entry);
+            let child = scan_directory_at(&path, progress.clone(), options, &ignores, 1, attrs);
+            node.size += child.size;
+            node.allocated += child.allocated;
+            node.file_count += child.file_count;
+            if keep_dir(&child) {
+                node.children.push(child);
             }
+            node.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+            node.modified = node.children.iter().map(|c| c.modified).max().unwrap_or(0);
+            if progress.cancel.load(Ordering::Relaxed) {
+                cut_short = true;
+                break;
+            }
+            // Send a snapshot after each top-level dir
+            on_snapshot(&node);
         } else {
-            let file_size = metadata.len();
+            let mut attrs = file_attrs(&metadata);
+            let file_size = if is_offline(attrs) && !options.include_offline { 0 } else { metadata.len() };
+            if options.is_include_only() && !options.includes(&entry.file_name().to_string_lossy(), file_size) {
+                continue;
+            }
+            let file_size = progress.linked_size(options, &path, &metadata, file_size, &mut attrs);
+            let allocated = allocated_size(&path, &metadata, file_size, attrs, options.cluster);
             let modified = metadata.modified().ok()
                 .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
                 .map(|d| d.as_secs())
@@ -125,7 +840,12 @@ pub fn scan_directory_live(
             progress.bytes_scanned.fetch_add(file_size, Ordering::Relaxed);
 
             node.size += file_size;
+            node.allocated += allocated;
             node.file_count += 1;
+            if file_size < options.min_file_size {
+                small.add(file_size, allocated, modified);
+                continue;
+            }
             node.children.push(FileNode {
                 name: entry.file_name().to_string_lossy().to_string(),
                 path,
@@ -133,21 +853,144 @@ pub fn scan_directory_live(
                 is_dir: false,
                 file_count: 0,
                 modified,
+                attrs,
+                allocated,
                 children: Vec::new(),
             });
         }
     }
+    node.children.extend(small.into_node());
+    progress.nodes.fetch_add(node.children.len() as u64, Ordering::Relaxed);
 
     node.modified = node.children.iter().map(|c| c.modified).max().unwrap_or(0);
-    node.children.sort_by(|a, b| b.size.cmp(&a.size));
+    node.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+    if cut_short {
+        node.children.push(not_scanned_node());
+        on_snapshot(&node);
+    }
     Some(node)
 }
 
-pub fn scan_directory(root: &Path, progress: Arc<ScanProgress>) -> Option<FileNode> {
+/// Mount points of the local drives an all-drives scan covers. Network shares and
+/// pseudo file systems (no capacity) are left out.
+pub fn local_drive_roots() -> Vec<PathBuf> {
+    use sysinfo::Disks;
+    let disks = Disks::new_with_refreshed_list();
+    let mut roots: Vec<PathBuf> = disks.list().iter()
+        .filter(|disk| disk.total_space() > 0 && !is_network_path(disk.mount_point()))
+        .map(|disk| disk.mount_point().to_path_buf())
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+/// Live scan of every drive in `roots` at once, one thread each, under an `ALL_DRIVES`
+/// root. Drive nodes are named by their mount point. A drive mounted inside another
+/// (`/home` under `/`) is skipped by the outer one's scan, so nothing counts twice.
+/// `on_snapshot` gets just the drive that changed whenever one sends a partial tree,
+/// so a tick costs one drive's copy rather than every drive's; `merge_drive` folds
+/// it into the combined tree. `resume` is an interrupted all-drives tree. None if
+/// cancelled before it started.
+pub fn scan_all_drives(
+    roots: &[PathBuf],
+    progress: Arc<ScanProgress>,
+    resume: Option<FileNode>,
+    options: &ScanOptions,
+    on_snapshot: impl FnMut(FileNode) + Send,
+) -> Option<FileNode> {
     if progress.cancel.load(Ordering::Relaxed) {
         return None;
     }
+    let mut resumed = resume.map(|r| r.children).unwrap_or_default();
+    *progress.drives.lock().unwrap_or_else(|e| e.into_inner()) = roots.iter()
+        .map(|root| DriveProgress { root: root.clone(), files: 0, bytes: 0, done: false })
+        .collect();
+    let on_snapshot = Mutex::new(on_snapshot);
+    let drives: Vec<FileNode> = std::thread::scope(|s| {
+        let handles: Vec<_> = roots.iter().enumerate().map(|(i, root)| {
+            let mut options = options.clone();
+            options.skip_dirs.extend(roots.iter().filter(|r| *r != root && r.starts_with(root)).cloned());
+            let resume = resumed.iter().position(|d| d.path == *root).map(|at| resumed.swap_remove(at));
+            let progress = progress.clone();
+            let on_snapshot = &on_snapshot;
+            s.spawn(move || {
+                let report = |node: &FileNode, done: bool| {
+                    if let Some(drive) = progress.drives.lock().unwrap_or_else(|e| e.into_inner()).get_mut(i) {
+                        *drive = DriveProgress { root: root.clone(), files: node.file_count, bytes: node.size, done };
+                    }
+                };
+                let drive = scan_directory_live(root, progress.clone(), resume, &options, |node| {
+                    report(node, false);
+                    (*on_snapshot.lock().unwrap_or_else(|e| e.into_inner()))(drive_node(node.clone(), root));
+                });
+                if let Some(ref node) = drive {
+                    report(node, true);
+                }
+                drive.map(|node| drive_node(node, root))
+            })
+        }).collect();
+        handles.into_iter().filter_map(|h| h.join().ok().flatten()).collect()
+    });
+    (!drives.is_empty()).then(|| all_drives_node(drives))
+}
+
+/// A drive's scan named by its mount point (`C:\`, `/home`), which also keeps paths
+/// rebuilt from a snapshot right: joining an absolute name replaces the root path.
+fn drive_node(mut node: FileNode, root: &Path) -> FileNode {
+    node.name = root.to_string_lossy().to_string();
+    node
+}
+
+/// Put a drive from a live all-drives snapshot into the combined tree in `all`, in
+/// place of its older copy, and total the drives again. Starts the combined tree if
+/// `all` holds none.
+pub fn merge_drive(all: &mut Option<FileNode>, drive: FileNode) -> &FileNode {
+    let mut drives = all.take().filter(|a| is_all_drives(&a.path)).map(|a| a.children).unwrap_or_default();
+    match drives.iter_mut().find(|d| d.path == drive.path) {
+        Some(old) => *old = drive,
+        None => drives.push(drive),
+    }
+    all.insert(all_drives_node(drives))
+}
+
+fn all_drives_node(mut drives: Vec<FileNode>) -> FileNode {
+    drives.sort_by_key(|d| std::cmp::Reverse(d.size));
+    FileNode {
+        name: ALL_DRIVES.to_string(),
+        path: PathBuf::from(ALL_DRIVES),
+        size: drives.iter().map(|d| d.size).sum(),
+        is_dir: true,
+        file_count: drives.iter().map(|d| d.file_count).sum(),
+        modified: drives.iter().map(|d| d.modified).max().unwrap_or(0),
+        attrs: 0,
+        allocated: drives.iter().map(|d| d.allocated).sum(),
+        children: drives,
+    }
+}
 
+/// Full recursive scan of `root`, filtered by `options`. None if cancelled.
+pub fn scan_directory(root: &Path, progress: Arc<ScanProgress>, options: &ScanOptions) -> Option<FileNode> {
+    let options = &options.for_volume(root);
+    progress.claim_root(options, root);
+    let above = root_rules(options, root);
+    let ignores: Vec<&IgnoreRules> = above.iter().collect();
+    let node = scan_directory_at(root, progress.clone(), options, &ignores, 0, 0);
+    (!progress.cancel.load(Ordering::Relaxed)).then_some(node)
+}
+
+/// Walk `root`, which sits `depth` levels below the scan root. On cancel, returns what
+/// it had, with a `NOT_SCANNED` placeholder if any of its own entries were skipped.
+/// `attrs` are the folder's own ATTR_* bits, from the parent's listing, and `ignores`
+/// the ignore files that apply to it, outermost first.
+fn scan_directory_at(
+    root: &Path,
+    progress: Arc<ScanProgress>,
+    options: &ScanOptions,
+    ignores: &[&IgnoreRules],
+    depth: usize,
+    attrs: u16,
+) -> FileNode {
     let mut node = FileNode {
         name: root
             .file_name()
@@ -158,46 +1001,81 @@ pub fn scan_directory(root: &Path, progress: Arc<ScanProgress>) -> Option<FileNo
         is_dir: true,
         file_count: 0,
         modified: 0,
+        attrs,
+        allocated: 0,
         children: Vec::new(),
     };
 
-    let entries: Vec<_> = match std::fs::read_dir(root) {
+    if progress.cancel.load(Ordering::Relaxed) {
+        node.children.push(not_scanned_node());
+        return node;
+    }
+
+    progress.io_limit.take(1);
+    let listing_start = Instant::now();
+    // Read through the extended-length form, but keep plain paths in the tree
+    let entries: Vec<_> = match std::fs::read_dir(long_path(root)) {
         Ok(rd) => rd.filter_map(|e| e.ok()).collect(),
-        Err(_) => return Some(node),
+        Err(_) => return node,
     };
+    if options.network {
+        progress.record_listing(root, listing_start.elapsed());
+    }
+    if entries.iter().any(|e| e.file_name() == ".git") {
+        node.attrs |= ATTR_GIT;
+    }
+    if options.case_sensitive_dirs && is_case_sensitive_dir(root) {
+        node.attrs |= ATTR_CASE_SENSITIVE;
+    }
+    // The folder's own ignore file applies to it and everything below
+    let own = own_rules(options, root, &entries);
+    let ignores: Vec<&IgnoreRules> = ignores.iter().copied().chain(&own).collect();
+    // Network profile: subfolders are scanned together after the loop
+    let mut remote_dirs = Vec::new();
+
+    // Past the depth limit or the node budget the folder is still measured, but its
+    // contents aren't kept
+    let keep_children = (options.max_depth == 0 || depth < options.max_depth) && !progress.node_budget_spent(options);
+    let mut small = SmallFiles::default();
+    let mut cut_short = false;
 
     for entry in entries {
-        if progress.cancel.load(Ordering::Relaxed) {
-            return None;
-        }
-        while progress.paused.load(Ordering::Relaxed) {
-            std::thread::sleep(std::time::Duration::from_millis(50));
-            if progress.cancel.load(Ordering::Relaxed) {
-                return None;
-            }
+        if !progress.wait_while_paused() {
+            cut_short = true;
+            break;
         }
 
-        let path = entry.path();
+        let path = root.join(entry.file_name());
+        if options.excludes(&path, &entry.file_name().to_string_lossy()) || progress.ignores(&ignores, &path, &entry) {
+            continue;
+        }
+        progress.io_limit.take(1);
         let metadata = match entry.metadata() {
-            Ok(m) => m,
+            Ok(m) => progress.resolve_link(options, &path, m),
             Err(_) => continue,
         };
 
         if metadata.is_dir() {
             // Skip system/hidden dirs that will just error out
             let name = entry.file_name().to_string_lossy().to_string();
-            if name == "System Volume Information" || name == "$Recycle.Bin" {
+            if name == "System Volume Information" || name == "$Recycle.Bin" || options.skips(&path) {
                 continue;
             }
-            if let Some(child) = scan_directory(&path, progress.clone()) {
-                node.size += child.size;
-                node.file_count += child.file_count;
-                if child.size > 0 {
-                    node.children.push(child);
-                }
+            let attrs = file_attrs(&metadata) | link_bit(&entry);
+            if options.network {
+                remote_dirs.push((path, attrs));
+            } else {
+                let child = scan_directory_at(&path, progress.clone(), options, &ignores, depth + 1, attrs);
+                add_child_dir(&mut node, child, keep_children);
             }
         } else {
-            let file_size = metadata.len();
+            let mut attrs = file_attrs(&metadata);
+            let file_size = if is_offline(attrs) && !options.include_offline { 0 } else { metadata.len() };
+            if options.is_include_only() && !options.includes(&entry.file_name().to_string_lossy(), file_size) {
+                continue;
+            }
+            let file_size = progress.linked_size(options, &path, &metadata, file_size, &mut attrs);
+            let allocated = allocated_size(&path, &metadata, file_size, attrs, options.cluster);
             let modified = metadata.modified().ok()
                 .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
                 .map(|d| d.as_secs())
@@ -206,7 +1084,17 @@ pub fn scan_directory(root: &Path, progress: Arc<ScanProgress>) -> Option<FileNo
             progress.bytes_scanned.fetch_add(file_size, Ordering::Relaxed);
 
             node.size += file_size;
+            node.allocated += allocated;
             node.file_count += 1;
+            // Directory modified is the newest child's modified time
+            node.modified = node.modified.max(modified);
+            if !keep_children {
+                continue;
+            }
+            if file_size < options.min_file_size {
+                small.add(file_size, allocated, modified);
+                continue;
+            }
             node.children.push(FileNode {
                 name: entry.file_name().to_string_lossy().to_string(),
                 path,
@@ -214,16 +1102,87 @@ pub fn scan_directory(root: &Path, progress: Arc<ScanProgress>) -> Option<FileNo
                 is_dir: false,
                 file_count: 0,
                 modified,
+                attrs,
+                allocated,
                 children: Vec::new(),
             });
         }
     }
+    node.children.extend(small.into_node());
 
-    // Set directory modified to the newest child's modified time
-    node.modified = node.children.iter().map(|c| c.modified).max().unwrap_or(0);
+    for child in scan_dirs_parallel(&remote_dirs, &progress, options, &ignores, depth + 1) {
+        add_child_dir(&mut node, child, keep_children);
+    }
+    // Subfolders that never got a thread before the cancel are dropped as empty
+    cut_short |= !remote_dirs.is_empty() && progress.cancel.load(Ordering::Relaxed);
+
+    progress.nodes.fetch_add(node.children.len() as u64, Ordering::Relaxed);
 
     // Sort children largest first
-    node.children.sort_by(|a, b| b.size.cmp(&a.size));
+    node.children.sort_by_key(|c| std::cmp::Reverse(c.size));
 
-    Some(node)
+    if cut_short {
+        node.children.push(not_scanned_node());
+    }
+    node
+}
+
+fn add_child_dir(node: &mut FileNode, child: FileNode, keep: bool) {
+    node.size += child.size;
+    node.allocated += child.allocated;
+    node.file_count += child.file_count;
+    node.modified = node.modified.max(child.modified);
+    if keep && keep_dir(&child) {
+        node.children.push(child);
+    }
+}
+
+/// Empty folders are left out of the tree. A cut-short one stays if the scan got
+/// anywhere in it, so its placeholder shows where the scan stopped, and so does one
+/// whose files are all hard links counted in another folder.
+fn keep_dir(child: &FileNode) -> bool {
+    child.size > 0
+        || (child.children.iter().any(|c| c.name != NOT_SCANNED) && is_partial(child))
+        || child.children.iter().any(|c| c.attrs & ATTR_HARDLINK != 0 || c.is_dir)
+}
+
+/// Scan `dirs`, handing each to a new thread while fewer than `NETWORK_THREADS`
+/// are running and scanning it inline otherwise. Results are in input order; a folder
+/// whose thread panicked is left out.
+fn scan_dirs_parallel(
+    dirs: &[(PathBuf, u16)],
+    progress: &Arc<ScanProgress>,
+    options: &ScanOptions,
+    ignores: &[&IgnoreRules],
+    depth: usize,
+) -> Vec<FileNode> {
+    enum Pending<'scope> {
+        Spawned(std::thread::ScopedJoinHandle<'scope, FileNode>),
+        Done(FileNode),
+    }
+
+    std::thread::scope(|scope| {
+        let pending: Vec<Pending> = dirs.iter()
+            .map(|&(ref dir, attrs)| {
+                let claimed = progress.workers
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < NETWORK_THREADS).then_some(n + 1))
+                    .is_ok();
+                if !claimed {
+                    return Pending::Done(scan_directory_at(dir, progress.clone(), options, ignores, depth, attrs));
+                }
+                let progress = progress.clone();
+                Pending::Spawned(scope.spawn(move || {
+                    let result = scan_directory_at(dir, progress.clone(), options, ignores, depth, attrs);
+                    progress.workers.fetch_sub(1, Ordering::AcqRel);
+                    result
+                }))
+            })
+            .collect();
+        pending.into_iter()
+            .filter_map(|p| match p {
+                Pending::Spawned(handle) => handle.join().ok(),
+                Pending::Done(result) => Some(result),
+            })
+            .collect()
+    })
 }
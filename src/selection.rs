@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+
+/// The items the user has picked, one set shared by every view: the Map outlines
+/// them, List, Top Files and Dupes highlight their rows, Types highlights their
+/// extensions. Paths are kept in the order they were picked.
+#[derive(Default)]
+pub struct Selection {
+    items: Vec<PathBuf>,
+}
+
+impl Selection {
+    pub fn contains(&self, path: &Path) -> bool {
+        self.items.iter().any(|p| p == path)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PathBuf> {
+        self.items.iter()
+    }
+
+    /// The most recently picked item, which a view scrolls to when it is opened.
+    pub fn latest(&self) -> Option<&Path> {
+        self.items.last().map(PathBuf::as_path)
+    }
+
+    /// Add or remove one item (Ctrl+click).
+    pub fn toggle(&mut self, path: PathBuf) {
+        if let Some(pos) = self.items.iter().position(|p| *p == path) {
+            self.items.remove(pos);
+        } else {
+            self.items.push(path);
+        }
+    }
+
+    /// Replace the selection with a single item (plain click in a list).
+    pub fn select_only(&mut self, path: PathBuf) {
+        self.items.clear();
+        self.items.push(path);
+    }
+
+    /// Plain click selects just the item, Ctrl+click adds or removes it.
+    pub fn click(&mut self, path: PathBuf, toggle: bool) {
+        if toggle {
+            self.toggle(path);
+        } else {
+            self.select_only(path);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
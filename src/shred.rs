@@ -0,0 +1,90 @@
+use crate::paths::long_path;
+use crate::scanner::ScanProgress;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+/// Passes offered for spinning disks: zeros, ones, then random data.
+pub const SHRED_PASSES_HDD: usize = 3;
+const CHUNK: usize = 1 << 20;
+
+/// Overwrite a file in place `passes` times, flushing each pass to the disk, then
+/// rename it to a meaningless name, truncate and delete it. Ok(false) if cancelled,
+/// in which case the file is left partly overwritten but not deleted.
+///
+/// On SSDs and copy-on-write or compressed volumes the overwrite may land in new
+/// blocks, so old data can survive; callers must say so before running this.
+pub fn shred_file(path: &Path, passes: usize, progress: &ScanProgress) -> Result<bool, String> {
+    let target = long_path(path);
+    let meta = std::fs::metadata(&target).map_err(|e| e.to_string())?;
+    if meta.is_dir() {
+        return Err("Only files can be shredded.".to_string());
+    }
+    // Read-only files can't be opened for writing
+    let mut perms = meta.permissions();
+    if perms.readonly() {
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        std::fs::set_permissions(&target, perms).map_err(|e| e.to_string())?;
+    }
+
+    let len = meta.len();
+    let mut file = std::fs::OpenOptions::new().write(true).open(&target).map_err(|e| e.to_string())?;
+    let mut rng = seed();
+    let mut buf = vec![0u8; CHUNK];
+    let passes = passes.max(1);
+    for pass in 0..passes {
+        // The last pass is always random; earlier ones alternate zeros and ones
+        let fill = if pass + 1 == passes { None } else if pass % 2 == 0 { Some(0x00) } else { Some(0xFF) };
+        if let Some(byte) = fill {
+            buf.fill(byte);
+        }
+        file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+        let mut left = len;
+        while left > 0 {
+            if progress.cancel.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+            let n = left.min(CHUNK as u64) as usize;
+            if fill.is_none() {
+                fill_random(&mut buf[..n], &mut rng);
+            }
+            file.write_all(&buf[..n]).map_err(|e| e.to_string())?;
+            progress.bytes_scanned.fetch_add(n as u64, Ordering::Relaxed);
+            left -= n as u64;
+        }
+        // Force this pass to the disk before the next one overwrites it in the cache
+        file.sync_all().map_err(|e| e.to_string())?;
+    }
+    drop(file);
+
+    // Hide the original name from the directory entry and the file system journal
+    let renamed = target.with_file_name(format!("{:016x}.tmp", xorshift(&mut rng)));
+    let victim = if std::fs::rename(&target, &renamed).is_ok() { renamed } else { target };
+    if let Ok(file) = std::fs::OpenOptions::new().write(true).open(&victim) {
+        let _ = file.set_len(0);
+    }
+    std::fs::remove_file(&victim).map_err(|e| e.to_string())?;
+    progress.files_scanned.fetch_add(1, Ordering::Relaxed);
+    Ok(true)
+}
+
+fn seed() -> u64 {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    (now.as_nanos() as u64 ^ ((std::process::id() as u64) << 32)) | 1
+}
+
+/// Fast non-cryptographic generator; overwrite data only needs to be unrelated to the file.
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn fill_random(buf: &mut [u8], state: &mut u64) {
+    for chunk in buf.chunks_mut(8) {
+        let bytes = xorshift(state).to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::scanner::{DriveSpace, FileNode, NOT_SCANNED, SMALL_FILES, is_space_tile};
+use std::io::{BufRead, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const HEADER: &str = "# SpaceView snapshot v1";
+
+/// Header fields of a saved scan tree.
+pub struct SnapshotInfo {
+    pub root: PathBuf,
+    /// Seconds since epoch when the snapshot was written.
+    pub created: u64,
+    /// False for a checkpoint of a scan that hasn't finished yet.
+    pub complete: bool,
+    /// Root totals (as of the snapshot, for partial scans).
+    pub size: u64,
+    pub file_count: u64,
+    /// When the tree was scanned, for snapshots saved from the app. Older ones and
+    /// checkpoints only have `created`.
+    pub scanned: Option<u64>,
+    /// The scanned drive's capacity and free space at scan time, if it was known
+    pub drive: Option<DriveSpace>,
+}
+
+impl SnapshotInfo {
+    fn new() -> Self {
+        Self { root: PathBuf::new(), created: 0, complete: true, size: 0, file_count: 0, scanned: None, drive: None }
+    }
+
+    pub fn scanned_at(&self) -> u64 {
+        self.scanned.unwrap_or(self.created)
+    }
+}
+
+/// Write a scanned tree as one line per node, depth-first:
+/// `depth \t kind \t size \t file_count \t modified \t name`, where kind is `d` or
+/// `f`, followed by the node's ATTR_* bits if it has any (`f4`, `d128`) and by
+/// `:<size on disk>` if that differs from the size (`f16:4096`).
+/// Goes through a temp file + rename so a crash mid-write never leaves a torn snapshot.
+pub fn save_snapshot(root: &FileNode, complete: bool, path: &Path) -> std::io::Result<()> {
+    write_file(path, |w| write_snapshot(w, root, complete))
+}
+
+/// Save a finished scan for opening again later, with when it was scanned and the
+/// drive's free space then (`scanned=` and `drive=` header lines).
+pub fn save_scan_snapshot(root: &FileNode, scanned: u64, drive: Option<&DriveSpace>, path: &Path) -> std::io::Result<()> {
+    write_file(path, |w| {
+        write_header(w, root, true)?;
+        writeln!(w, "scanned={}", scanned)?;
+        if let Some(drive) = drive {
+            writeln!(w, "drive={}\t{}\t{}", drive.total, drive.available, drive.mount_point.to_string_lossy())?;
+        }
+        write_node(w, root, 0)
+    })
+}
+
+fn write_file(path: &Path, write: impl FnOnce(&mut BufWriter<std::fs::File>) -> std::io::Result<()>) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    {
+        let mut w = BufWriter::new(std::fs::File::create(&tmp)?);
+        write(&mut w)?;
+        w.flush()?;
+    }
+    std::fs::rename(&tmp, path)
+}
+
+/// Write the snapshot text to any writer (a file, or a remote agent's socket buffer).
+pub fn write_snapshot(w: &mut impl Write, root: &FileNode, complete: bool) -> std::io::Result<()> {
+    write_header(w, root, complete)?;
+    write_node(w, root, 0)
+}
+
+fn write_header(w: &mut impl Write, root: &FileNode, complete: bool) -> std::io::Result<()> {
+    let created = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    writeln!(w, "{}", HEADER)?;
+    writeln!(w, "root={}", root.path.to_string_lossy())?;
+    writeln!(w, "created={}", created)?;
+    writeln!(w, "complete={}", complete as u8)
+}
+
+fn write_node(w: &mut impl Write, node: &FileNode, depth: usize) -> std::io::Result<()> {
+    let letter = if node.is_dir { 'd' } else { 'f' };
+    let mut kind = match node.attrs {
+        0 => letter.to_string(),
+        attrs => format!("{}{}", letter, attrs),
+    };
+    // Size on disk only where it differs; older readers ignore the bad attrs suffix
+    if node.allocated != node.size {
+        kind += &format!(":{}", node.allocated);
+    }
+    writeln!(
+        w,
+        "{}\t{}\t{}\t{}\t{}\t{}",
+        depth,
+        kind,
+        node.size,
+        node.file_count,
+        node.modified,
+        escape_name(&node.name),
+    )?;
+    for child in &node.children {
+        write_node(w, child, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Read only the header (cheap, for "resume scan?" prompts and pickers).
+pub fn read_snapshot_info(path: &Path) -> Result<SnapshotInfo, String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut lines = std::io::BufReader::new(file).lines();
+    if lines.next().and_then(|l| l.ok()).as_deref() != Some(HEADER) {
+        return Err("Not a SpaceView snapshot".to_string());
+    }
+    let mut info = SnapshotInfo::new();
+    for line in lines.map_while(Result::ok) {
+        if !parse_header_line(&line, &mut info) {
+            // First entry line is the root node
+            let parts: Vec<&str> = line.splitn(6, '\t').collect();
+            if let [_, _, size, file_count, ..] = parts[..] {
+                info.size = size.parse().unwrap_or(0);
+                info.file_count = file_count.parse().unwrap_or(0);
+            }
+            break;
+        }
+    }
+    Ok(info)
+}
+
+fn parse_header_line(line: &str, info: &mut SnapshotInfo) -> bool {
+    if let Some(root) = line.strip_prefix("root=") {
+        info.root = PathBuf::from(root);
+    } else if let Some(created) = line.strip_prefix("created=") {
+        info.created = created.parse().unwrap_or(0);
+    } else if let Some(complete) = line.strip_prefix("complete=") {
+        info.complete = complete != "0";
+    } else if let Some(scanned) = line.strip_prefix("scanned=") {
+        info.scanned = scanned.parse().ok();
+    } else if let Some(drive) = line.strip_prefix("drive=") {
+        let mut parts = drive.splitn(3, '\t');
+        if let (Some(total), Some(available), Some(mount)) = (parts.next(), parts.next(), parts.next()) {
+            info.drive = Some(DriveSpace {
+                mount_point: PathBuf::from(mount),
+                total: total.parse().unwrap_or(0),
+                available: available.parse().unwrap_or(0),
+            });
+        }
+    } else if line.contains('\t') || !line.contains('=') {
+        return false;
+    }
+    // Other `key=value` lines come from newer versions and are skipped
+    true
+}
+
+/// Load a snapshot back into a FileNode tree. Paths are rebuilt from the root.
+pub fn load_snapshot(path: &Path) -> Result<(SnapshotInfo, FileNode), String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    read_snapshot(std::io::BufReader::new(file))
+}
+
+/// Parse snapshot text from any reader.
+pub fn read_snapshot(reader: impl BufRead) -> Result<(SnapshotInfo, FileNode), String> {
+    let mut lines = reader.lines();
+    if lines.next().and_then(|l| l.ok()).as_deref() != Some(HEADER) {
+        return Err("Not a SpaceView snapshot".to_string());
+    }
+    let mut info = SnapshotInfo::new();
+    // Open directories from the root down to the current depth
+    let mut stack: Vec<FileNode> = Vec::new();
+    for line in lines {
+        let line = line.map_err(|e| e.to_string())?;
+        if stack.is_empty() && parse_header_line(&line, &mut info) {
+            continue;
+        }
+        let parts: Vec<&str> = line.splitn(6, '\t').collect();
+        let [depth, kind, size, file_count, modified, name] = parts[..] else {
+            return Err(format!("Malformed snapshot line: {}", line));
+        };
+        let depth: usize = depth.parse().map_err(|_| format!("Bad depth: {}", line))?;
+        if depth > stack.len() || (depth == 0 && !stack.is_empty()) {
+            return Err(format!("Unexpected depth: {}", line));
+        }
+        while stack.len() > depth {
+            pop_into_parent(&mut stack);
+        }
+        let name = unescape_name(name);
+        let path = match stack.last() {
+            // Placeholders and space tiles have no path on disk, as when scanned
+            Some(_) if name == SMALL_FILES || name == NOT_SCANNED || is_space_tile(&name) => PathBuf::new(),
+            Some(parent) => parent.path.join(&name),
+            None => info.root.clone(),
+        };
+        if depth == 0 {
+            info.size = size.parse().unwrap_or(0);
+            info.file_count = file_count.parse().unwrap_or(0);
+        }
+        let size = size.parse().unwrap_or(0);
+        let (kind, allocated) = kind.split_once(':').unwrap_or((kind, ""));
+        stack.push(FileNode {
+            name,
+            path,
+            size,
+            is_dir: kind.starts_with('d'),
+            file_count: file_count.parse().unwrap_or(0),
+            modified: modified.parse().unwrap_or(0),
+            attrs: kind.get(1..).and_then(|a| a.parse().ok()).unwrap_or(0),
+            allocated: allocated.parse().unwrap_or(size),
+            children: Vec::new(),
+        });
+    }
+    while stack.len() > 1 {
+        pop_into_parent(&mut stack);
+    }
+    let root = stack.pop().ok_or_else(|| "Snapshot has no entries".to_string())?;
+    Ok((info, root))
+}
+
+fn pop_into_parent(stack: &mut Vec<FileNode>) {
+    if let Some(node) = stack.pop() {
+        if let Some(parent) = stack.last_mut() {
+            parent.children.push(node);
+        }
+    }
+}
+
+fn escape_name(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
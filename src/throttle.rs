@@ -0,0 +1,38 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Paces work to at most `per_sec` units per second, shared across threads.
+/// Each `take` reserves the next slot and sleeps until it comes up. A rate of 0
+/// means unlimited, so callers can always go through a limiter.
+pub struct RateLimiter {
+    per_sec: u64,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(per_sec: u64) -> Self {
+        Self { per_sec, next: Mutex::new(Instant::now()) }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(0)
+    }
+
+    /// Wait until `units` more operations (or bytes) fit in the rate.
+    pub fn take(&self, units: u64) {
+        if self.per_sec == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let start = {
+            let Ok(mut next) = self.next.lock() else { return };
+            // Idle time doesn't bank up into a burst
+            let start = (*next).max(now);
+            *next = start + Duration::from_secs_f64(units as f64 / self.per_sec as f64);
+            start
+        };
+        if start > now {
+            std::thread::sleep(start - now);
+        }
+    }
+}
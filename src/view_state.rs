@@ -0,0 +1,81 @@
+use crate::app::data_dir;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Where the user left off in each scanned root, so reopening it returns there. One
+// line per root in %APPDATA%\SpaceView\view_state.txt, most recent first:
+// `<view>\t<color>\t<x>\t<y>\t<zoom>\t<list path>\t<root>`. The root goes last so
+// it may hold tabs; the list path is names joined with `/`, which no name contains.
+
+/// Roots remembered; the least recently left ones are dropped.
+const MAX_VIEW_STATES: usize = 50;
+
+#[derive(Clone, Debug)]
+pub struct ViewState {
+    pub root: PathBuf,
+    /// View and color mode keys, as the app names them
+    pub view: String,
+    pub color: String,
+    /// Treemap camera center. Y is a fraction of the world height, which follows
+    /// the window's shape.
+    pub center: (f32, f32),
+    pub zoom: f32,
+    /// Folder open in the List view, as names below the root
+    pub list_path: Vec<String>,
+}
+
+impl ViewState {
+    fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(7, '\t');
+        let view = parts.next()?.to_string();
+        let color = parts.next()?.to_string();
+        let x = parts.next()?.parse().ok()?;
+        let y = parts.next()?.parse().ok()?;
+        let zoom = parts.next()?.parse().ok()?;
+        let list = parts.next()?;
+        let root = PathBuf::from(parts.next()?);
+        let list_path = list.split('/').filter(|s| !s.is_empty()).map(String::from).collect();
+        Some(Self { root, view, color, center: (x, y), zoom, list_path })
+    }
+}
+
+/// Whether two scan roots are the same folder: trailing separators ignored, and
+/// case too on Windows.
+pub fn same_root(a: &Path, b: &Path) -> bool {
+    let key = |p: &Path| {
+        let text = p.to_string_lossy();
+        let text = text.trim_end_matches(['/', '\\']);
+        if cfg!(windows) { text.to_lowercase() } else { text.to_string() }
+    };
+    key(a) == key(b)
+}
+
+fn view_state_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("view_state.txt"))
+}
+
+/// Remembered roots, most recent first. Unreadable lines are skipped.
+pub fn load_view_states() -> Vec<ViewState> {
+    let Some(text) = view_state_path().and_then(|p| std::fs::read_to_string(p).ok()) else { return Vec::new() };
+    text.lines().filter_map(ViewState::parse).collect()
+}
+
+/// Put `state` first in `states`, replacing its root's old entry, and write them out.
+pub fn remember_view_state(states: &mut Vec<ViewState>, state: ViewState) -> std::io::Result<()> {
+    states.retain(|s| !same_root(&s.root, &state.root));
+    states.insert(0, state);
+    states.truncate(MAX_VIEW_STATES);
+    let Some(path) = view_state_path() else { return Ok(()) };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::File::create(path)?;
+    for s in states.iter() {
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            s.view, s.color, s.center.0, s.center.1, s.zoom, s.list_path.join("/"), s.root.to_string_lossy(),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+
+// Watching a scanned root for changes after the scan, so the tree follows files as
+// they are created, deleted and written without a manual rescan. One thread per root
+// blocks in ReadDirectoryChangesW over the whole subtree and reports the paths that
+// changed; the app settles them into folders to list again (`refresh_folders` in app.rs).
+
+/// Bytes of change records one wait can return. 64 KB is the most a network share
+/// takes; more changes than fit between two waits are reported as an overflow.
+#[cfg(target_os = "windows")]
+const WATCH_BUFFER: usize = 64 * 1024;
+
+// Only the Windows watch threads send these
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub enum WatchEvent {
+    /// Entries created, deleted, renamed, resized or written to
+    Changed(Vec<PathBuf>),
+    /// More changes came in than the buffer holds, so some were lost
+    Overflow,
+}
+
+/// Running watches; dropping it stops them.
+pub struct Watcher {
+    pub events: Receiver<WatchEvent>,
+    #[cfg(target_os = "windows")]
+    dirs: Vec<std::sync::Arc<std::fs::File>>,
+    #[cfg(target_os = "windows")]
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+/// Whether this platform can watch folders.
+pub fn watch_supported() -> bool {
+    cfg!(target_os = "windows")
+}
+
+/// Start watching `roots` and everything below them. Roots that can't be opened
+/// are left out; None if none could.
+#[cfg(target_os = "windows")]
+pub fn watch(roots: &[PathBuf]) -> Option<Watcher> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_FLAG_BACKUP_SEMANTICS, FILE_LIST_DIRECTORY, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
+    };
+
+    let (tx, events) = std::sync::mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut dirs = Vec::new();
+    for root in roots {
+        // Shared for everything, so the watch never keeps anyone from renaming or deleting
+        let dir = std::fs::OpenOptions::new()
+            .access_mode(FILE_LIST_DIRECTORY)
+            .share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(root);
+        let Ok(dir) = dir else { continue };
+        let dir = Arc::new(dir);
+        dirs.push(dir.clone());
+        let (root, tx, stop) = (root.clone(), tx.clone(), stop.clone());
+        std::thread::spawn(move || watch_thread(&root, &dir, &tx, &stop));
+    }
+    (!dirs.is_empty()).then_some(Watcher { events, dirs, stop })
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn watch(_roots: &[PathBuf]) -> Option<Watcher> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn watch_thread(
+    root: &std::path::Path,
+    dir: &std::fs::File,
+    tx: &std::sync::mpsc::Sender<WatchEvent>,
+    stop: &std::sync::atomic::AtomicBool,
+) {
+    use std::os::windows::ffi::OsStringExt;
+    use std::os::windows::io::AsRawHandle;
+    use std::sync::atomic::Ordering;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_NOTIFY_CHANGE_DIR_NAME, FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_LAST_WRITE,
+        FILE_NOTIFY_CHANGE_SIZE, FILE_NOTIFY_INFORMATION, ReadDirectoryChangesW,
+    };
+
+    // u32s keep the records DWORD-aligned, as the call requires
+    let mut buffer = vec![0u32; WATCH_BUFFER / 4];
+    let filter = FILE_NOTIFY_CHANGE_FILE_NAME | FILE_NOTIFY_CHANGE_DIR_NAME | FILE_NOTIFY_CHANGE_SIZE
+        | FILE_NOTIFY_CHANGE_LAST_WRITE;
+    while !stop.load(Ordering::Relaxed) {
+        let mut bytes = 0u32;
+        let ok = unsafe {
+            ReadDirectoryChangesW(
+                dir.as_raw_handle(),
+                buffer.as_mut_ptr().cast(),
+                WATCH_BUFFER as u32,
+                1,
+                filter,
+                &mut bytes,
+                std::ptr::null_mut(),
+                None,
+            )
+        };
+        // Fails once the watch is cancelled or the folder goes away
+        if ok == 0 || stop.load(Ordering::Relaxed) {
+            return;
+        }
+        let event = if bytes == 0 {
+            WatchEvent::Overflow
+        } else {
+            let mut changed = Vec::new();
+            let base = buffer.as_ptr().cast::<u8>();
+            let mut offset = 0usize;
+            loop {
+                // Each record: header, then the name relative to the root (UTF-16, not NUL-terminated)
+                let info = unsafe { base.add(offset).cast::<FILE_NOTIFY_INFORMATION>() };
+                let (next, name_len) = unsafe { ((*info).NextEntryOffset, (*info).FileNameLength) };
+                let name = unsafe {
+                    std::slice::from_raw_parts(std::ptr::addr_of!((*info).FileName).cast::<u16>(), name_len as usize / 2)
+                };
+                changed.push(root.join(std::ffi::OsString::from_wide(name)));
+                if next == 0 {
+                    break;
+                }
+                offset += next as usize;
+            }
+            WatchEvent::Changed(changed)
+        };
+        if tx.send(event).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for Watcher {
+    /// Wake each thread out of its wait. One between two waits stops at the next
+    /// change instead; the folder handles close once their threads are gone.
+    fn drop(&mut self) {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::IO::CancelIoEx;
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        for dir in &self.dirs {
+            unsafe { CancelIoEx(dir.as_raw_handle(), std::ptr::null()) };
+        }
+    }
+}
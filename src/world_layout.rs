@@ -1,6 +1,16 @@
-use crate::scanner::FileNode;
+use crate::scanner::{FileNode, is_space_tile};
 use crate::treemap;
 use eframe::egui;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Children laid out per folder. A folder with more (a flat folder of millions of
+/// files) shows its largest ones and one block for the rest: those would be far below
+/// a pixel anyway, and laying them all out would stall the frame.
+const MAX_LAYOUT_CHILDREN: usize = 10_000;
+/// An expansion laying out more children than this counts as several against the
+/// per-frame budget, so a few huge folders don't all expand in the same frame.
+const NODES_PER_EXPANSION: usize = 2_000;
 
 /// A node in the world-space layout tree.
 /// Each node corresponds to a FileNode and has a fixed world-space rect.
@@ -8,7 +18,13 @@ pub struct LayoutNode {
     pub world_rect: egui::Rect,
     pub depth: usize,
     pub name: String,
+    pub path: std::path::PathBuf,
     pub size: u64,
+    /// Size on disk (FileNode::allocated)
+    pub allocated: u64,
+    /// What the block's area stands for: `allocated` in the size-on-disk view,
+    /// `size` otherwise. Children are stored largest weight first.
+    pub weight: u64,
     pub file_count: u64,
     pub is_dir: bool,
     pub has_children: bool,
@@ -16,6 +32,7 @@ pub struct LayoutNode {
     pub child_index: usize,
     pub children_expanded: bool,
     pub modified: u64, // seconds since epoch (0 = unknown)
+    pub attrs: u16,    // scanner ATTR_* bits
     pub children: Vec<LayoutNode>,
 }
 
@@ -23,9 +40,33 @@ pub struct LayoutNode {
 pub struct WorldLayout {
     pub root_nodes: Vec<LayoutNode>,
     pub world_rect: egui::Rect,
+    sizing: Sizing,
     frame_counter: u64,
 }
 
+/// How a layout sizes its blocks, fixed when it is built.
+struct Sizing {
+    /// Blocks sized by size on disk instead of logical size
+    on_disk: bool,
+    /// Space tiles laid out inside a folder after its children (each drive of an
+    /// all-drives scan), by folder path. The folder's block grows to hold them.
+    nested_tiles: Vec<(PathBuf, Vec<FileNode>)>,
+}
+
+impl Sizing {
+    fn tiles_of(&self, node: &FileNode) -> &[FileNode] {
+        if !node.is_dir {
+            return &[];
+        }
+        self.nested_tiles.iter().find(|(path, _)| *path == node.path).map_or(&[], |(_, tiles)| &tiles[..])
+    }
+
+    fn weight(&self, node: &FileNode) -> u64 {
+        let own = if self.on_disk { node.allocated } else { node.size };
+        own + self.tiles_of(node).iter().map(|t| t.size).sum::<u64>()
+    }
+}
+
 /// Fraction of parent rect height used for directory headers at a given depth.
 /// Approximate. World_rects are only used for camera/expand/prune decisions, not rendering.
 fn header_fraction(_depth: usize) -> f32 {
@@ -44,19 +85,32 @@ pub fn content_rect(dir_rect: egui::Rect, depth: usize) -> egui::Rect {
 }
 
 impl WorldLayout {
-    /// Create a new world layout from a scanned file tree.
-    /// The root fills (0,0) to (1.0, aspect_ratio).
-    pub fn new(file_root: &FileNode, aspect_ratio: f32) -> Self {
+    /// Create a new world layout from a scanned file tree, with `tiles` (free space,
+    /// unknown) laid out after the root's children without being part of the tree.
+    /// `nested_tiles` do the same inside the folders they are keyed by.
+    /// The root fills (0,0) to (1.0, aspect_ratio). `on_disk` sizes blocks by
+    /// size on disk.
+    pub fn new(
+        file_root: &FileNode,
+        tiles: &[FileNode],
+        nested_tiles: Vec<(PathBuf, Vec<FileNode>)>,
+        aspect_ratio: f32,
+        on_disk: bool,
+    ) -> Self {
         let world_rect = egui::Rect::from_min_max(
             egui::pos2(0.0, 0.0),
             egui::pos2(1.0, aspect_ratio),
         );
 
-        let root_nodes = layout_children(file_root, world_rect, 0);
+        // Tiles come last, so child_index still points into file_root.children
+        let children: Vec<&FileNode> = file_root.children.iter().chain(tiles).collect();
+        let sizing = Sizing { on_disk, nested_tiles };
+        let root_nodes = layout_nodes(&children, world_rect, 0, &sizing);
 
         WorldLayout {
             root_nodes,
             world_rect,
+            sizing,
             frame_counter: 0,
         }
     }
@@ -73,6 +127,7 @@ impl WorldLayout {
             viewport,
             &mut expansions,
             max_expansions,
+            &self.sizing,
         );
     }
 
@@ -80,29 +135,76 @@ impl WorldLayout {
     /// Called every N frames.
     pub fn maybe_prune(&mut self, camera: &crate::camera::Camera, viewport: egui::Rect) {
         self.frame_counter += 1;
-        if self.frame_counter % 60 != 0 {
+        if !self.frame_counter.is_multiple_of(60) {
             return;
         }
         prune_recursive(&mut self.root_nodes, camera, viewport);
     }
 
     /// Build an ancestor chain from the root to the deepest node containing world_pos.
-    /// Returns Vec of (name, color_index, world_rect).
-    pub fn ancestor_chain(&self, world_pos: egui::Pos2) -> Vec<(&str, usize, egui::Rect)> {
+    pub fn ancestor_chain(&self, world_pos: egui::Pos2) -> Vec<&LayoutNode> {
         let mut chain = Vec::new();
         ancestor_chain_recursive(&self.root_nodes, world_pos, &mut chain);
         chain
     }
 
+    /// Lay out the folder at `path` and its ancestors again after its subtree in
+    /// `file_root` was replaced (a folder rescan). Blocks elsewhere keep their
+    /// expanded contents where their rect didn't move; moved ones collapse and
+    /// expand again as they come into view. `tiles` as in `new`.
+    pub fn refresh_path(&mut self, file_root: &FileNode, tiles: &[FileNode], path: &Path) {
+        let children: Vec<&FileNode> = file_root.children.iter().chain(tiles).collect();
+        let fresh = layout_nodes(&children, self.world_rect, 0, &self.sizing);
+        let old = std::mem::replace(&mut self.root_nodes, fresh);
+        // A rescanned root has nothing left to keep
+        if file_root.path != path {
+            refresh_level(&mut self.root_nodes, old, file_root, path, &self.sizing);
+        }
+    }
+
 }
 
 /// Lay out the children of `file_node` into `parent_rect` using squarified treemap.
-fn layout_children(file_node: &FileNode, parent_rect: egui::Rect, depth: usize) -> Vec<LayoutNode> {
-    if file_node.children.is_empty() {
+/// Tiles come last, so child_index still points into file_node.children.
+fn layout_children(file_node: &FileNode, parent_rect: egui::Rect, depth: usize, sizing: &Sizing) -> Vec<LayoutNode> {
+    let children: Vec<&FileNode> = file_node.children.iter().chain(sizing.tiles_of(file_node)).collect();
+    layout_nodes(&children, parent_rect, depth, sizing)
+}
+
+fn layout_nodes(children: &[&FileNode], parent_rect: egui::Rect, depth: usize, sizing: &Sizing) -> Vec<LayoutNode> {
+    if children.is_empty() {
         return Vec::new();
     }
+    let weight = |c: &FileNode| sizing.weight(c);
+    // Children are sorted by size; by size on disk (or with tiles added to drives)
+    // they need re-sorting, keeping each one's index into `children` for child_index.
+    // Trailing space tiles keep their place in the corner.
+    let mut order: Vec<usize> = (0..children.len()).collect();
+    let tree_children = children.iter().take_while(|c| !is_space_tile(&c.name)).count();
+    if sizing.on_disk || !sizing.nested_tiles.is_empty() {
+        order[..tree_children].sort_by_key(|&i| std::cmp::Reverse(weight(children[i])));
+    }
+    // Largest first, so the ones past the cap are the smallest
+    let mut rest = None;
+    if order.len() > MAX_LAYOUT_CHILDREN {
+        let hidden: Vec<&FileNode> = order.split_off(MAX_LAYOUT_CHILDREN - 1).into_iter().map(|i| children[i]).collect();
+        rest = Some(FileNode {
+            name: format!("<{} Smaller Items>", hidden.len()),
+            path: std::path::PathBuf::new(),
+            size: hidden.iter().map(|c| c.size).sum(),
+            is_dir: false,
+            file_count: hidden.iter().map(|c| if c.is_dir { c.file_count } else { 1 }).sum(),
+            modified: hidden.iter().map(|c| c.modified).max().unwrap_or(0),
+            attrs: 0,
+            allocated: hidden.iter().map(|c| c.allocated).sum(),
+            children: Vec::new(),
+        });
+        order.push(children.len());
+    }
+    // The rest block goes past the end of `children`
+    let child_at = |i: usize| children.get(i).copied().or(rest.as_ref()).expect("index into children or rest");
 
-    let sizes: Vec<f64> = file_node.children.iter().map(|c| c.size as f64).collect();
+    let sizes: Vec<f64> = order.iter().map(|&i| weight(child_at(i)) as f64).collect();
     let rects = treemap::layout(
         parent_rect.min.x,
         parent_rect.min.y,
@@ -113,12 +215,12 @@ fn layout_children(file_node: &FileNode, parent_rect: egui::Rect, depth: usize)
 
     let mut nodes = Vec::with_capacity(rects.len());
     for tr in &rects {
-        let child = &file_node.children[tr.index];
+        let child = child_at(order[tr.index]);
         let world_rect = egui::Rect::from_min_size(
             egui::pos2(tr.x, tr.y),
             egui::vec2(tr.w, tr.h),
         );
-        let has_children = child.is_dir && !child.children.is_empty();
+        let has_children = child.is_dir && (!child.children.is_empty() || !sizing.tiles_of(child).is_empty());
 
         // Color by depth: each nesting level gets its own palette color (SpaceMonger style)
         let color_index = depth;
@@ -127,14 +229,18 @@ fn layout_children(file_node: &FileNode, parent_rect: egui::Rect, depth: usize)
             world_rect,
             depth,
             name: child.name.clone(),
+            path: child.path.clone(),
             size: child.size,
+            allocated: child.allocated,
+            weight: weight(child),
             file_count: child.file_count,
             is_dir: child.is_dir,
             has_children,
             color_index,
-            child_index: tr.index,
+            child_index: order[tr.index],
             children_expanded: false,
             modified: child.modified,
+            attrs: child.attrs,
             children: Vec::new(),
         });
     }
@@ -147,8 +253,34 @@ fn layout_children_at_depth(
     file_node: &FileNode,
     parent_rect: egui::Rect,
     depth: usize,
+    sizing: &Sizing,
 ) -> Vec<LayoutNode> {
-    layout_children(file_node, parent_rect, depth)
+    layout_children(file_node, parent_rect, depth, sizing)
+}
+
+/// `refresh_path` below one folder: `nodes` were just laid out again for `file_node`,
+/// `old` are the blocks they replace.
+fn refresh_level(nodes: &mut [LayoutNode], old: Vec<LayoutNode>, file_node: &FileNode, path: &Path, sizing: &Sizing) {
+    // Aggregates and tiles have no path and no contents to keep
+    let mut old: HashMap<PathBuf, LayoutNode> = old.into_iter()
+        .filter(|n| n.children_expanded && !n.path.as_os_str().is_empty())
+        .map(|n| (n.path.clone(), n))
+        .collect();
+    for node in nodes.iter_mut() {
+        let Some(prev) = old.remove(&node.path) else { continue };
+        if path.starts_with(&node.path) {
+            let Some(child_file) = file_node.children.get(node.child_index) else { continue };
+            node.children = layout_children(child_file, content_rect(node.world_rect, node.depth), node.depth + 1, sizing);
+            node.children_expanded = true;
+            // The rescanned folder's own contents are all new
+            if node.path != path {
+                refresh_level(&mut node.children, prev.children, child_file, path, sizing);
+            }
+        } else if prev.world_rect == node.world_rect {
+            node.children = prev.children;
+            node.children_expanded = true;
+        }
+    }
 }
 
 /// Recursively expand nodes that are visible and large enough on screen.
@@ -159,6 +291,7 @@ fn expand_recursive(
     viewport: egui::Rect,
     expansions: &mut usize,
     max_expansions: usize,
+    sizing: &Sizing,
 ) {
     for node in nodes.iter_mut() {
         if *expansions >= max_expansions {
@@ -183,9 +316,9 @@ fn expand_recursive(
             // Find the corresponding FileNode child
             if let Some(child_file) = file_node.children.get(node.child_index) {
                 let cr = content_rect(node.world_rect, node.depth);
-                node.children = layout_children_at_depth(child_file, cr, node.depth + 1);
+                node.children = layout_children_at_depth(child_file, cr, node.depth + 1, sizing);
                 node.children_expanded = true;
-                *expansions += 1;
+                *expansions += 1 + node.children.len() / NODES_PER_EXPANSION;
             }
         }
 
@@ -199,6 +332,7 @@ fn expand_recursive(
                     viewport,
                     expansions,
                     max_expansions,
+                    sizing,
                 );
             }
         }
@@ -232,13 +366,13 @@ fn prune_recursive(
 fn ancestor_chain_recursive<'a>(
     nodes: &'a [LayoutNode],
     pos: egui::Pos2,
-    chain: &mut Vec<(&'a str, usize, egui::Rect)>,
+    chain: &mut Vec<&'a LayoutNode>,
 ) {
     for node in nodes {
         if !node.world_rect.contains(pos) {
             continue;
         }
-        chain.push((&node.name, node.color_index, node.world_rect));
+        chain.push(node);
         if node.children_expanded {
             ancestor_chain_recursive(&node.children, pos, chain);
         }